@@ -63,6 +63,17 @@ pub enum Error {
         /// The parsing error(s) that occured.
         errors: Cow<'static, str>,
     },
+    /// An external hook command failed.
+    HookFailed {
+        /// The command that failed.
+        command: Cow<'static, str>,
+        /// What the hook was run on.
+        ///
+        /// Something like "post source", "post HTML".
+        wher: Cow<'static, str>,
+        /// Additional data.
+        more: Cow<'static, str>,
+    },
 }
 
 impl Error {
@@ -85,6 +96,7 @@ impl Error {
             Error::FileNotFound { .. } => 3,
             Error::WrongFileState { .. } => 4,
             Error::FileParsingFailed { .. } => 5,
+            Error::HookFailed { .. } => 6,
         }
     }
 }
@@ -106,6 +118,7 @@ impl fmt::Display for Error {
             Error::FileNotFound { who, ref path } => write!(err_out, "File {} for {} not found.", path, who),
             Error::WrongFileState { what, ref path } => write!(err_out, "File {} is not {}.", path, what),
             Error::FileParsingFailed { ref desc, ref errors } => write!(err_out, "Failed to parse {}: {}.", desc, errors),
+            Error::HookFailed { ref command, ref wher, ref more } => write!(err_out, "Hook {:?} for {} failed: {}.", command, wher, more),
         }
     }
 }
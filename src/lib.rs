@@ -19,11 +19,14 @@ extern crate serde_derive;
 extern crate lazy_static;
 #[macro_use]
 extern crate bidir_map;
+extern crate tiny_http;
 extern crate jetscii;
 extern crate unicase;
 extern crate walkdir;
 extern crate chrono;
 extern crate comrak;
+extern crate syntect;
+extern crate mime_guess;
 #[cfg(target_os = "windows")]
 extern crate winapi;
 extern crate regex;
@@ -5,17 +5,25 @@ extern crate chrono;
 extern crate rayon;
 extern crate url;
 
-use rayon::iter::{IntoParallelRefIterator, ParallelIterator};
+use rayon::iter::{IntoParallelRefMutIterator, IntoParallelRefIterator, IndexedParallelIterator, ParallelIterator};
 use std::sync::mpsc::channel as mpsc_channel;
 use percent_encoding::percent_decode;
-use std::collections::BTreeMap;
-use std::io::{Write, stdout};
-use std::iter::FromIterator;
+use std::collections::{BTreeMap, BTreeSet};
+use std::io::{ErrorKind as IoErrorKind, Write, stdout};
+use std::iter::{self, FromIterator};
+use std::slice;
 use tabwriter::TabWriter;
 use std::process::exit;
-use std::mem::swap;
-use std::fs::File;
-use chrono::Utc;
+use std::cmp;
+use std::time::Duration;
+use std::path::PathBuf;
+use std::thread::{self, sleep};
+use std::fs::{self, File};
+use chrono::{TimeZone, Offset, Utc};
+
+
+/// How often to check the source for changes in watch mode.
+const WATCH_POLL_INTERVAL: Duration = Duration::from_millis(500);
 
 
 fn main() {
@@ -33,18 +41,81 @@ fn actual_main() -> i32 {
 }
 
 fn result_main() -> Result<(), bloguen::Error> {
-    let opts = bloguen::Options::parse();
+    let mut opts = bloguen::Options::parse();
+    let server = match opts.serve {
+        Some(port) => Some(bloguen::ops::PreviewServer::bind(("127.0.0.1", port))?),
+        None => None,
+    };
+    if !opts.watch {
+        build(&opts)?;
+        if let Some(server) = server {
+            println!();
+            println!("Serving {} at http://{}/", opts.output_dir.0, server.address());
+            server.serve(&opts.output_dir.1);
+        }
+        return Ok(());
+    }
+
+    let mut snapshot = bloguen::ops::SourceSnapshot::take(&opts.source_dir)?;
+    if let Err(err) = build(&opts) {
+        eprintln!("{}", err);
+    }
+
+    if let Some(server) = server {
+        let output_dir = opts.output_dir.1.clone();
+        println!();
+        println!("Serving {} at http://{}/", opts.output_dir.0, server.address());
+        thread::spawn(move || server.serve(&output_dir));
+    }
+
+    opts.incremental = true;
+    loop {
+        println!();
+        println!("Watching {} for changes...", opts.source_dir.0);
+
+        let new_snapshot = loop {
+            sleep(WATCH_POLL_INTERVAL);
+            match bloguen::ops::SourceSnapshot::take(&opts.source_dir) {
+                Ok(new_snapshot) => {
+                    if new_snapshot != snapshot {
+                        break new_snapshot;
+                    }
+                }
+                Err(err) => eprintln!("{}", err),
+            }
+        };
+
+        for path in snapshot.differences(&new_snapshot) {
+            println!("Changed: {}", path.strip_prefix(&opts.source_dir.1).unwrap_or(path).display());
+        }
+        println!();
+
+        snapshot = new_snapshot;
+        if let Err(err) = build(&opts) {
+            eprintln!("{}", err);
+        }
+    }
+}
 
+fn build(opts: &bloguen::Options) -> Result<(), bloguen::Error> {
     let mut descriptor = bloguen::ops::BlogueDescriptor::read(&opts.source_dir)?;
     println!("Blog name: {}", descriptor.name);
 
     let mut posts: Vec<_> = Result::from_iter(bloguen::ops::BloguePost::list(&opts.source_dir)?.into_iter().map(bloguen::ops::BloguePost::new))?;
     posts.sort_by(|lhs, rhs| lhs.number.cmp(&rhs.number).then_with(|| lhs.datetime.cmp(&rhs.datetime)).then_with(|| lhs.name.cmp(&rhs.name)));
+    let now = opts.now.unwrap_or_else(|| Utc::now().with_timezone(&Utc.fix()));
     println!("Found {} posts:", posts.len());
     {
         let mut out = TabWriter::new(stdout()).minwidth(1).padding(3);
         for p in &posts {
-            writeln!(out, "\t{}\t{}\t{}", p.number.0, p.datetime.format("%Y.%m.%d %r"), p.name).unwrap();
+            writeln!(out,
+                     "\t{}\t{}\t{}{}{}",
+                     p.number.0,
+                     p.datetime.format("%Y.%m.%d %r"),
+                     p.name,
+                     if p.draft { " (draft)" } else { "" },
+                     if p.datetime > now { " (scheduled)" } else { "" })
+                .unwrap();
         }
         out.flush().unwrap();
     }
@@ -59,6 +130,14 @@ fn result_main() -> Result<(), bloguen::Error> {
     } else {
         (None, None, None)
     };
+    let (mut tag_index_header, mut tag_index_center, mut tag_cloud_center, mut tag_index_footer) = if let Some(ref tidx) = descriptor.tag_index.as_ref() {
+        (Some(bloguen::util::read_file(&tidx.header_file, "tag index header")?),
+         Some(bloguen::util::read_file(&tidx.center_file, "tag index center")?),
+         Some(bloguen::util::read_file(&tidx.cloud_center_file, "tag cloud center")?),
+         Some(bloguen::util::read_file(&tidx.footer_file, "tag index footer")?))
+    } else {
+        (None, None, None, None)
+    };
     let global_language = descriptor.language.take().unwrap_or_else(|| match bloguen::util::default_language() {
         Some(l) => {
             match l.parse() {
@@ -94,10 +173,25 @@ fn result_main() -> Result<(), bloguen::Error> {
     if let Some(ref mut index_footer) = index_footer.as_mut() {
         bloguen::util::newline_pad(index_footer, 2, 1);
     }
+    if let Some(ref mut tag_index_header) = tag_index_header.as_mut() {
+        bloguen::util::newline_pad(tag_index_header, 0, 2);
+    }
+    if let Some(ref mut tag_index_center) = tag_index_center.as_mut() {
+        bloguen::util::newline_pad(tag_index_center, 1, 1);
+    }
+    if let Some(ref mut tag_cloud_center) = tag_cloud_center.as_mut() {
+        bloguen::util::newline_pad(tag_cloud_center, 1, 1);
+    }
+    if let Some(ref mut tag_index_footer) = tag_index_footer.as_mut() {
+        bloguen::util::newline_pad(tag_index_footer, 2, 1);
+    }
 
     for s in &mut descriptor.styles {
         s.load(&opts.source_dir)?;
     }
+    if let Some(stylesheet) = descriptor.highlight.as_ref().map(|h| h.stylesheet()).transpose()?.and_then(|s| s) {
+        descriptor.styles.push(stylesheet);
+    }
 
     for s in &mut descriptor.scripts {
         s.load(&opts.source_dir)?;
@@ -113,6 +207,16 @@ fn result_main() -> Result<(), bloguen::Error> {
         }
     }
 
+    if let Some(tidx) = descriptor.tag_index.as_mut() {
+        for s in &mut tidx.styles {
+            s.load(&opts.source_dir)?;
+        }
+
+        for s in &mut tidx.scripts {
+            s.load(&opts.source_dir)?;
+        }
+    }
+
     // println!("{}", post_header);
     // println!("{}", post_footer);
     // println!("{}", global_language);
@@ -120,19 +224,88 @@ fn result_main() -> Result<(), bloguen::Error> {
     // println!("{:#?}", descriptor);
 
 
-    let mut feed_files: BTreeMap<_, _> =
-        Result::from_iter(descriptor.feeds.iter().map(|(tp, fname)| descriptor.create_feed_output(&opts.output_dir, fname, tp).map(|f| (*tp, (f, fname)))))?;
-    for (tp, (ff, fname)) in &mut feed_files {
-        descriptor.generate_feed_head(ff, tp, fname, &global_language, &global_author)?;
+    let parse_template = |template: &str, name: &'static str| -> Result<bloguen::ops::FormatTemplate, bloguen::Error> {
+        let mut template = bloguen::ops::FormatTemplate::parse_with_includes(template, &opts.source_dir, name)?;
+        if descriptor.escape_html {
+            template.escape_html_by_default();
+        }
+        Ok(template)
+    };
+    let post_header = parse_template(&post_header, "post header")?;
+    let post_footer = parse_template(&post_footer, "post footer")?;
+    let index_header = index_header.map(|t| parse_template(&t, "index header")).transpose()?;
+    let index_center = index_center.map(|t| parse_template(&t, "index center")).transpose()?;
+    let index_footer = index_footer.map(|t| parse_template(&t, "index footer")).transpose()?;
+    let tag_index_header = tag_index_header.map(|t| parse_template(&t, "tag index header")).transpose()?;
+    let tag_index_center = tag_index_center.map(|t| parse_template(&t, "tag index center")).transpose()?;
+    let tag_cloud_center = tag_cloud_center.map(|t| parse_template(&t, "tag cloud center")).transpose()?;
+    let tag_index_footer = tag_index_footer.map(|t| parse_template(&t, "tag index footer")).transpose()?;
+
+    let mut templates = BTreeMap::new();
+    templates.insert("post_header".to_string(), bloguen::util::content_hash(&post_header));
+    templates.insert("post_footer".to_string(), bloguen::util::content_hash(&post_footer));
+    for (name, template) in &[("index_header", &index_header),
+                              ("index_center", &index_center),
+                              ("index_footer", &index_footer),
+                              ("tag_index_header", &tag_index_header),
+                              ("tag_index_center", &tag_index_center),
+                              ("tag_cloud_center", &tag_cloud_center),
+                              ("tag_index_footer", &tag_index_footer)] {
+        if let Some(template) = template.as_ref() {
+            templates.insert(name.to_string(), bloguen::util::content_hash(template));
+        }
     }
+    let mut manifest = bloguen::ops::BuildManifest::new(bloguen::util::content_hash(&(&descriptor, &global_language, &global_author)), templates);
+    let previous_manifest = if opts.incremental {
+        bloguen::ops::BuildManifest::read_or_default(&opts.output_dir)?
+    } else {
+        Default::default()
+    };
+    let rebuild_all = !manifest.globals_match(&previous_manifest);
 
 
-    let (idx_sender, idx_receiver) = mpsc_channel();
-    let (feed_sender, feed_receiver) = mpsc_channel();
+    let posts_metadata = posts.par_iter_mut()
+        .map(|p| {
+            let metadata = bloguen::ops::PostMetadata::read_or_default(&p.source_dir)?;
+            p.draft |= metadata.draft;
+            if let Some(title) = metadata.title.as_ref() {
+                p.name = title.clone();
+            }
+            if let Some(slug) = metadata.slug.as_ref() {
+                p.slug = slug.clone();
+            }
+            p.apply_permalink_pattern(&descriptor.permalink);
+            let independent_tags = bloguen::ops::TagName::load_additional_post_tags(&p.source_dir)?;
+            Ok((metadata, independent_tags))
+        })
+        .collect::<Result<Vec<_>, bloguen::Error>>()?;
+    bloguen::ops::BloguePost::check_unique_paths(&posts)?;
+    let published: Vec<_> = posts.iter().map(|p| p.is_published(&now, opts.drafts, opts.future)).collect();
+    let neighbours: Vec<_> = (0..posts.len())
+        .map(|i| {
+            (posts[..i].iter().zip(&published[..i]).rev().find(|&(_, &pb)| pb).map(|(p, _)| p),
+             posts[i + 1..].iter().zip(&published[i + 1..]).find(|&(_, &pb)| pb).map(|(p, _)| p))
+        })
+        .collect();
+    let posts_tags: Vec<Vec<_>> = posts_metadata.iter()
+        .map(|&(ref metadata, ref independent_tags)| metadata.tags.iter().chain(independent_tags).cloned().collect())
+        .collect();
+    let related_candidates: Vec<_> = posts.iter().zip(&posts_tags).zip(&published).filter(|&(_, &pb)| pb).map(|((p, tags), _)| (p, &tags[..])).collect();
+    let related: Vec<_> = posts.iter().zip(&posts_tags).map(|(p, tags)| p.related_posts(tags, &related_candidates)).collect();
+
+    let (manifest_sender, manifest_receiver) = mpsc_channel();
 
     posts.par_iter()
-        .try_for_each_with((idx_sender, feed_sender), |(idx_sender, feed_sender), p| {
-            let mut metadata = bloguen::ops::PostMetadata::read_or_default(&p.source_dir)?;
+        .zip(posts_metadata)
+        .zip(&published)
+        .zip(&neighbours)
+        .zip(&related)
+        .try_for_each_with(manifest_sender, |manifest_sender, ((((p, (mut metadata, independent_tags)), &published), &(previous_post, next_post)), related)| {
+            if !published {
+                remove_unpublished_outputs(p, &descriptor, &opts.output_dir)?;
+                return Ok(());
+            }
+
             let language = metadata.language.as_ref().unwrap_or(&global_language);
             let author = metadata.author.as_ref().unwrap_or(&global_author);
 
@@ -144,29 +317,77 @@ fn result_main() -> Result<(), bloguen::Error> {
                 s.load(&p.source_dir)?;
             }
 
-            let independent_tags = bloguen::ops::TagName::load_additional_post_tags(&p.source_dir)?;
+            let mut render = bloguen::ops::PostRenderOptions {
+                paged_center_output: None,
+                tag_center_output: None,
+                base_url: descriptor.base_url.as_ref(),
+                previous_post: previous_post,
+                next_post: next_post,
+                related_posts: related,
+                toc: metadata.toc,
+                highlight: descriptor.highlight.as_ref(),
+                math: descriptor.math,
+                markdown: metadata.markdown.with_fallback(&descriptor.markdown),
+                hooks: &descriptor.hooks,
+            };
 
-            let mut index_machine_json = vec![];
-            for (kind, subpath) in &descriptor.machine_data {
-                let mut f_out = p.create_machine_output(&opts.output_dir, subpath, kind)?;
+            let normalised_name = p.normalised_name();
+            let source_hash = bloguen::util::content_hash(&(p.source_hash(&metadata, &independent_tags)?, previous_post, next_post, related));
+            let previous = previous_manifest.posts
+                .get(&normalised_name)
+                .filter(|pm| !rebuild_all && pm.output == p.path && bloguen::util::concat_path(&opts.output_dir.1, &p.path).exists());
+
+            let (post_manifest, changed) = if let Some(previous) = previous.filter(|pm| pm.source == source_hash) {
+                let mut assets = BTreeMap::new();
+                for (link, &mtime) in &previous.assets {
+                    if let Some(mtime) = copy_changed_asset(p, &opts.output_dir, descriptor.asset_dir_override.as_ref().map(|s| &s[..]), link, Some(mtime))? {
+                        assets.insert(link.clone(), mtime);
+                    }
+                }
 
-                if *kind == bloguen::ops::MachineDataKind::Json && !index_machine_json.is_empty() && descriptor.index.is_some() {
-                    p.generate_machine(&mut bloguen::util::PolyWrite(f_out, &mut index_machine_json),
-                                          kind,
-                                          &descriptor.name,
-                                          &language,
-                                          author,
-                                          &metadata.tags,
-                                          &independent_tags,
-                                          &metadata.data,
-                                          &descriptor.data,
-                                          &metadata.styles,
-                                          &descriptor.styles,
-                                          &metadata.scripts,
-                                          &descriptor.scripts)?;
-                } else {
-                    p.generate_machine(&mut f_out,
-                                          kind,
+                (bloguen::ops::PostManifest { assets: assets, ..previous.clone() }, false)
+            } else {
+                let mut index_machine_json = vec![];
+                for (kind, subpath) in &descriptor.machine_data {
+                    let mut f_out = p.create_machine_output(&opts.output_dir, subpath, kind)?;
+
+                    if *kind == bloguen::ops::MachineDataKind::Json && !index_machine_json.is_empty() && descriptor.index.is_some() {
+                        p.generate_machine(&mut bloguen::util::PolyWrite(f_out, &mut index_machine_json),
+                                              kind,
+                                              &render,
+                                              &descriptor.name,
+                                              &language,
+                                              author,
+                                              &metadata.tags,
+                                              &independent_tags,
+                                              &metadata.data,
+                                              &descriptor.data,
+                                              &metadata.styles,
+                                              &descriptor.styles,
+                                              &metadata.scripts,
+                                              &descriptor.scripts)?;
+                    } else {
+                        p.generate_machine(&mut f_out,
+                                              kind,
+                                              &render,
+                                              &descriptor.name,
+                                              &language,
+                                              author,
+                                              &metadata.tags,
+                                              &independent_tags,
+                                              &metadata.data,
+                                              &descriptor.data,
+                                              &metadata.styles,
+                                              &descriptor.styles,
+                                              &metadata.scripts,
+                                              &descriptor.scripts)?;
+                    }
+                }
+
+                if descriptor.index.is_some() && index_machine_json.is_empty() {
+                    p.generate_machine(&mut index_machine_json,
+                                          &bloguen::ops::MachineDataKind::Json,
+                                          &render,
                                           &descriptor.name,
                                           &language,
                                           author,
@@ -179,195 +400,511 @@ fn result_main() -> Result<(), bloguen::Error> {
                                           &metadata.scripts,
                                           &descriptor.scripts)?;
                 }
-            }
-
-            if descriptor.index.is_some() && index_machine_json.is_empty() {
-                p.generate_machine(&mut index_machine_json,
-                                      &bloguen::ops::MachineDataKind::Json,
-                                      &descriptor.name,
-                                      &language,
-                                      author,
-                                      &metadata.tags,
-                                      &independent_tags,
-                                      &metadata.data,
-                                      &descriptor.data,
-                                      &metadata.styles,
-                                      &descriptor.styles,
-                                      &metadata.scripts,
-                                      &descriptor.scripts)?;
-            }
 
-            let mut feed_items: BTreeMap<_, _> = feed_files.iter().map(|(tp, (_, fname))| (*tp, (vec![], fname.to_string()))).collect();
-            for (tp, (fbuf, fname)) in &mut feed_items {
-                p.generate_feed_head(fbuf, tp, fname, &language, &author)?;
-            }
+                let tags: BTreeSet<_> = metadata.tags.iter().chain(independent_tags.iter()).map(|t| t.to_string()).collect();
+                let mut feed_items: BTreeMap<_, _> = descriptor.post_feeds(tags.iter().map(|t| &t[..]), author, &language.to_string())?
+                    .into_iter()
+                    .map(|(fname, (tp, _))| (fname, (tp, vec![])))
+                    .collect();
+                for (fname, (tp, fbuf)) in &mut feed_items {
+                    p.generate_feed_head(fbuf, tp, fname, descriptor.base_url.as_ref(), &language, &author, descriptor.feed_summaries)?;
+                }
 
-            let mut center_buffer = vec![];
-            for link in p.generate(&opts.output_dir,
-                          if !feed_items.is_empty() {
-                                  let mut itr = feed_items.iter_mut().map(|(tp, (fbuf, _))| bloguen::ops::feed_type_post_body(tp)(fbuf));
-                                  let first_out = itr.next().unwrap();
-                                  Some(itr.fold(first_out, |cur, out| Box::new(bloguen::util::PolyWrite(cur, out))))
-                              } else {
-                                  None
-                              }
-                              .as_mut()
-                              .map(|out| out as &mut dyn Write),
-                          index_center.as_ref().map(|ic| (&ic[..], &mut center_buffer as &mut dyn Write)),
-                          descriptor.asset_dir_override.as_ref().map(|s| &s[..]),
-                          &post_header,
-                          &post_footer,
-                          &descriptor.name,
-                          &language,
-                          author,
-                          &metadata.tags,
-                          &independent_tags,
-                          &metadata.data,
-                          &descriptor.data,
-                          &metadata.styles,
-                          &descriptor.styles,
-                          &metadata.scripts,
-                          &descriptor.scripts)?
-                .into_iter()
-                .filter(|l| bloguen::util::is_asset_link(l)) {
-                if let Ok(link) = percent_decode(link.as_bytes()).decode_utf8() {
-                    if !p.copy_asset(&opts.output_dir, descriptor.asset_dir_override.as_ref().map(|s| &s[..]), &link)? {
-                        eprintln!("Couldn't find \"{}\" for \"{}\" post.", link, p.normalised_name());
+                let mut center_buffer = vec![];
+                let mut paged_center_buffer = vec![];
+                let mut tag_center_buffer = vec![];
+                render.paged_center_output = descriptor.index
+                    .as_ref()
+                    .and_then(|idx| idx.posts_per_page)
+                    .map(|_| &mut paged_center_buffer as &mut dyn Write);
+                render.tag_center_output = tag_index_center.as_ref().map(|tc| (tc, &mut tag_center_buffer as &mut dyn Write));
+                let mut assets = BTreeMap::new();
+                for link in p.generate(&opts.output_dir,
+                              if !feed_items.is_empty() {
+                                      let mut itr = feed_items.iter_mut().map(|(_, (tp, fbuf))| bloguen::ops::feed_type_post_body(tp)(fbuf));
+                                      let first_out = itr.next().unwrap();
+                                      Some(itr.fold(first_out, |cur, out| Box::new(bloguen::util::PolyWrite(cur, out))))
+                                  } else {
+                                      None
+                                  }
+                                  .as_mut()
+                                  .map(|out| (out as &mut dyn Write, descriptor.feed_summaries)),
+                              index_center.as_ref().map(|ic| (ic, &mut center_buffer as &mut dyn Write)),
+                              descriptor.asset_dir_override.as_ref().map(|s| &s[..]),
+                              render,
+                              &post_header,
+                              &post_footer,
+                              &descriptor.name,
+                              &language,
+                              author,
+                              &metadata.tags,
+                              &independent_tags,
+                              &metadata.data,
+                              &descriptor.data,
+                              &metadata.styles,
+                              &descriptor.styles,
+                              &metadata.scripts,
+                              &descriptor.scripts)?
+                    .into_iter()
+                    .filter(|l| bloguen::util::is_asset_link(l)) {
+                    if let Ok(link) = percent_decode(link.as_bytes()).decode_utf8() {
+                        if let Some(mtime) = copy_changed_asset(p,
+                                                                &opts.output_dir,
+                                                                descriptor.asset_dir_override.as_ref().map(|s| &s[..]),
+                                                                &link,
+                                                                previous.and_then(|pm| pm.assets.get(&link[..]).cloned()))? {
+                            assets.insert(link.into_owned(), mtime);
+                        }
+                    } else {
+                        eprintln!("Invalid percent-encoded \"{}\" link.", link);
                     }
-                } else {
-                    eprintln!("Invalid percent-encoded \"{}\" link.", link);
                 }
-            }
 
-            for (tp, (fbuf, _)) in &mut feed_items {
-                p.generate_feed_foot(fbuf, tp)?;
-            }
+                for (tp, fbuf) in feed_items.values_mut() {
+                    p.generate_feed_foot(fbuf, tp, descriptor.feed_summaries)?;
+                }
+
+                (bloguen::ops::PostManifest {
+                     source: source_hash,
+                     assets: assets,
+                     feeds: feed_items.into_iter().map(|(fname, (_, fbuf))| (fname, String::from_utf8_lossy(&fbuf).into_owned())).collect(),
+                     index_machine_data: String::from_utf8_lossy(&index_machine_json).into_owned(),
+                     index_center: String::from_utf8_lossy(&center_buffer).into_owned(),
+                     paged_index_center: String::from_utf8_lossy(&paged_center_buffer).into_owned(),
+                     tags: tags,
+                     author: author.to_string(),
+                     language: language.to_string(),
+                     tag_index_center: String::from_utf8_lossy(&tag_center_buffer).into_owned(),
+                     output: p.path.clone(),
+                     machine_outputs: descriptor.machine_data
+                         .iter()
+                         .map(|(kind, subpath)| format!("{}/{}.{}", subpath, p.slug, kind.extension()))
+                         .collect(),
+                 },
+                 true)
+            };
 
-            feed_sender.send((p.number.clone(), feed_items))
+            manifest_sender.send((p.number.clone(), normalised_name, post_manifest, changed))
                 .map_err(|e| {
                     bloguen::Error::Io {
-                        desc: format!("post {} feed data", p.number.1).into(),
+                        desc: format!("post {} build manifest", p.number.1).into(),
                         op: "save",
                         more: e.to_string().into(),
                     }
                 })?;
 
-            if descriptor.index.is_some() {
-                idx_sender.send((p.number.clone(), index_machine_json, center_buffer))
-                    .map_err(|e| {
-                        bloguen::Error::Io {
-                            desc: format!("post {} JSON metadata", p.number.1).into(),
-                            op: "save",
-                            more: e.to_string().into(),
-                        }
-                    })?;
-            }
-
             Ok(())
         })?;
 
-    for (post_num, feeds) in feed_receiver {
-        for (tp, (fbuf, _)) in feeds {
-            feed_files.get_mut(&tp)
-                .unwrap()
-                .0
-                .write_all(&fbuf)
-                .map_err(|e| {
+    let mut posts_data: Vec<_> = manifest_receiver.into_iter().collect();
+    posts_data.sort_unstable_by_key(|&((num, _), ..)| num);
+
+    let mut feeds = BTreeMap::new();
+    for (_, _, post_manifest, _) in &posts_data {
+        let post_feeds = descriptor.post_feeds(post_manifest.tags.iter().map(|t| &t[..]), &post_manifest.author, &post_manifest.language)?;
+        bloguen::ops::BlogueDescriptor::merge_feeds(&mut feeds, post_feeds)?;
+    }
+    feeds.extend(descriptor.feeds.iter().map(|(tp, fname)| (fname.clone(), (*tp, bloguen::ops::FeedFilter::All))));
+
+    let outputs_changed = rebuild_all || posts_data.iter().any(|&(.., changed)| changed) ||
+                          previous_manifest.posts.keys().any(|name| !posts_data.iter().any(|(_, n, ..)| n == name)) ||
+                          feeds.keys().any(|fname| !bloguen::util::concat_path(&opts.output_dir.1, fname).exists()) ||
+                          descriptor.sitemap.as_ref().map(|sitemap| !bloguen::util::concat_path(&opts.output_dir.1, sitemap).exists()).unwrap_or(false) ||
+                          (descriptor.index.is_some() && !opts.output_dir.1.join("index.html").exists()) ||
+                          (descriptor.tag_index.is_some() && !opts.output_dir.1.join("tags").join("index.html").exists());
+    if !outputs_changed {
+        println!("No posts changed, feeds and indices up to date.");
+    }
+
+    if outputs_changed {
+        for (fname, (tp, filter)) in &feeds {
+            let mut ff = descriptor.create_feed_output(&opts.output_dir, fname, tp)?;
+            let language = match filter {
+                bloguen::ops::FeedFilter::Language(language) => language.parse()?,
+                _ => global_language.clone(),
+            };
+            let author = match filter {
+                bloguen::ops::FeedFilter::Author(author) => author,
+                _ => &global_author[..],
+            };
+            descriptor.generate_feed_head(&mut ff, tp, fname, &language, author)?;
+
+            for ((_, post_num), _, post_manifest, _) in &posts_data {
+                if let Some(item) = post_manifest.feeds.get(fname) {
+                    ff.write_all(item.as_bytes())
+                        .map_err(|e| {
+                            bloguen::Error::Io {
+                                desc: format!("post {} feed data", post_num).into(),
+                                op: "write",
+                                more: e.to_string().into(),
+                            }
+                        })?;
+                }
+            }
+
+            descriptor.generate_feed_foot(&mut ff, tp)?;
+        }
+    }
+
+    if let Some(idx) = descriptor.index.as_ref().filter(|_| outputs_changed) {
+        let index_script = [bloguen::ops::ScriptElement::from_literal(format!("const BLOGUEN_POSTS = [{}];",
+                                                                               posts_data.iter()
+                                                                                   .map(|(_, _, pm, _)| &pm.index_machine_data[..])
+                                                                                   .collect::<Vec<_>>()
+                                                                                   .join(",\n")))];
+
+        let mut centers: Vec<_> = posts_data.iter().map(|(_, _, pm, _)| pm).collect();
+        if let bloguen::ops::CenterOrder::Backward = idx.center_order {
+            centers.reverse();
+        }
+        let pages: Vec<_> = match idx.posts_per_page {
+            Some(posts_per_page) if !centers.is_empty() => centers.chunks(posts_per_page).collect(),
+            _ => vec![&centers[..]],
+        };
+
+        let page_dir = opts.output_dir.1.join("page");
+        if page_dir.exists() {
+            fs::remove_dir_all(&page_dir).map_err(|e| {
                     bloguen::Error::Io {
-                        desc: format!("post {} feed data", post_num.1).into(),
-                        op: "write",
+                        desc: "stale index page directory".into(),
+                        op: "remove",
+                        more: e.to_string().into(),
+                    }
+                })?;
+        }
+        if pages.len() > 1 {
+            fs::create_dir_all(&page_dir).map_err(|e| {
+                    bloguen::Error::Io {
+                        desc: "index page directory".into(),
+                        op: "create",
                         more: e.to_string().into(),
                     }
                 })?;
         }
-    }
 
-    for (tp, (ff, _)) in &mut feed_files {
-        descriptor.generate_feed_foot(ff, tp)?;
-    }
+        let index_date = Utc::now();
+        for (page_idx, page) in pages.iter().enumerate() {
+            let page_number = page_idx + 1;
+            let page_link = |to: usize| match descriptor.base_url.as_ref() {
+                Some(u) => bloguen::util::resolve_url(u, &index_page_path(to)),
+                None => {
+                    match (page_number, to) {
+                        (1, _) => index_page_path(to),
+                        (_, 1) => "../index.html".to_string(),
+                        (_, to) => format!("{}.html", to),
+                    }
+                }
+            };
 
-    if let Some(idx) = descriptor.index.as_ref() {
-        let mut posts_data: Vec<_> = idx_receiver.into_iter().collect();
-        posts_data.sort_unstable_by_key(|&((num, _), ..)| num);
+            let mut index_file = File::create(bloguen::util::concat_path(&opts.output_dir.1, &index_page_path(page_number))).map_err(|e| {
+                    bloguen::Error::Io {
+                        desc: "output index file".into(),
+                        op: "create",
+                        more: e.to_string().into(),
+                    }
+                })?;
+            let root = if page_number == 1 { "" } else { "../" };
+            let mut index_variables = BTreeMap::new();
+            index_variables.insert("base_url", descriptor.base_url.as_ref().map(|u| u.to_string()).unwrap_or_default());
+            index_variables.insert("permalink", page_link(page_number));
+            if descriptor.tag_index.is_some() {
+                index_variables.insert("tag_index",
+                                       descriptor.base_url
+                                           .as_ref()
+                                           .map(|u| bloguen::util::resolve_url(u, "tags/"))
+                                           .unwrap_or_else(|| format!("{}tags/", root)));
+            }
+            index_variables.insert("page_number", page_number.to_string());
+            index_variables.insert("page_count", pages.len().to_string());
+            index_variables.insert("prev_page_link", if page_number > 1 { page_link(page_number - 1) } else { String::new() });
+            index_variables.insert("next_page_link", if page_number < pages.len() { page_link(page_number + 1) } else { String::new() });
+            bloguen::ops::format_output(index_header.as_ref().unwrap(),
+                                        &descriptor.name,
+                                        &global_language,
+                                        &[&descriptor.data, &idx.data],
+                                        &index_variables,
+                                        "index",
+                                        "index",
+                                        0,
+                                        "index",
+                                        &global_author,
+                                        &index_date,
+                                        &[],
+                                        &[],
+                                        &[&descriptor.styles, &idx.styles],
+                                        &[&descriptor.scripts, &idx.scripts, &index_script],
+                                        &mut index_file,
+                                        "index")?;
+
+            for pm in page.iter() {
+                index_file.write_all(if page_number == 1 {
+                            pm.index_center.as_bytes()
+                        } else {
+                            pm.paged_index_center.as_bytes()
+                        })
+                    .map_err(|e| {
+                        bloguen::Error::Io {
+                            desc: "output index file center".into(),
+                            op: "write",
+                            more: e.to_string().into(),
+                        }
+                    })?;
+            }
 
-        let index_script = [bloguen::ops::ScriptElement::from_literal(String::from_utf8(posts_data.iter_mut()
-                                    .fold("const BLOGUEN_POSTS = [".as_bytes().to_vec(), |mut acc, ((_, ref ns), ref mut metadata, ..)| {
-                    let mut md = vec![];
-                    swap(&mut md, metadata);
-                    acc.extend(md);
+            bloguen::ops::format_output(index_footer.as_ref().unwrap(),
+                                        &descriptor.name,
+                                        &global_language,
+                                        &[&descriptor.data, &idx.data],
+                                        &index_variables,
+                                        "index",
+                                        "index",
+                                        0,
+                                        "index",
+                                        &global_author,
+                                        &index_date,
+                                        &[],
+                                        &[],
+                                        &[&descriptor.styles, &idx.styles],
+                                        &[&descriptor.scripts, &idx.scripts, &index_script],
+                                        &mut index_file,
+                                        "index")?;
+        }
+    }
 
-                    if ns != &posts[posts.len() - 1].number.1 {
-                        acc.extend(",\n".as_bytes());
-                    } else {
-                        acc.extend("];".as_bytes());
-                    }
+    if let Some(tidx) = descriptor.tag_index.as_ref().filter(|_| outputs_changed) {
+        let mut tags: BTreeMap<bloguen::ops::TagName, Vec<&bloguen::ops::PostManifest>> = BTreeMap::new();
+        for (_, _, pm, _) in &posts_data {
+            for tag in &pm.tags {
+                tags.entry(tag.parse()?).or_insert_with(Vec::new).push(pm);
+            }
+        }
+        bloguen::ops::TagName::check_unique_file_names(tags.keys())?;
+        let all_tags: Vec<_> = tags.keys().cloned().collect();
+        let max_tag_post_count = tags.values().map(Vec::len).max().unwrap_or(0);
 
-                    acc
-                })).map_err(|e| {
-                bloguen::Error::Parse {
-                    tp: "UTF-8 string",
-                    wher: "index file post metadata".into(),
+        let tags_dir = opts.output_dir.1.join("tags");
+        fs::create_dir_all(&tags_dir).map_err(|e| {
+                bloguen::Error::Io {
+                    desc: "output tag index directory".into(),
+                    op: "create",
                     more: e.to_string().into(),
                 }
-            })?)];
+            })?;
 
-        let mut index_file = File::create(opts.output_dir.1.join("index.html")).map_err(|e| {
+        let tag_index_date = Utc::now();
+        let mut tag_index_variables = BTreeMap::new();
+        tag_index_variables.insert("base_url", descriptor.base_url.as_ref().map(|u| u.to_string()).unwrap_or_default());
+        tag_index_variables.insert("tag_index",
+                                   descriptor.base_url.as_ref().map(|u| bloguen::util::resolve_url(u, "tags/")).unwrap_or_default());
+        tag_index_variables.insert("tag_count", tags.len().to_string());
+        let format_tag_template = |template: &bloguen::ops::FormatTemplate, variables: &BTreeMap<&'static str, String>, title: &str,
+                                   tags: &[bloguen::ops::TagName], into: &mut File| {
+            bloguen::ops::format_output(template,
+                                        &descriptor.name,
+                                        &global_language,
+                                        &[&descriptor.data, &tidx.data],
+                                        variables,
+                                        "tag_index",
+                                        "tag_index",
+                                        0,
+                                        title,
+                                        &global_author,
+                                        &tag_index_date,
+                                        &[tags],
+                                        &[],
+                                        &[&descriptor.styles, &tidx.styles],
+                                        &[&descriptor.scripts, &tidx.scripts],
+                                        into,
+                                        format!("tag index {}", title))
+        };
+        let create_tag_file = |fname: &str| {
+            File::create(tags_dir.join(fname)).map_err(|e| {
                 bloguen::Error::Io {
-                    desc: "output index file".into(),
+                    desc: format!("output tag index file {}", fname).into(),
                     op: "create",
                     more: e.to_string().into(),
                 }
-            })?;
-        let index_date = Utc::now();
-        bloguen::ops::format_output(index_header.as_ref().unwrap(),
-                                    &descriptor.name,
-                                    &global_language,
-                                    &[&descriptor.data, &idx.data],
-                                    "index",
-                                    "index",
-                                    0,
-                                    "index",
-                                    &global_author,
-                                    &index_date,
-                                    &[],
-                                    &[&descriptor.styles, &idx.styles],
-                                    &[&descriptor.scripts, &idx.scripts, &index_script],
-                                    &mut index_file,
-                                    "index")?;
-
-        {
-            let write_center = |&(_, _, ref center): &(_, _, Vec<u8>)| {
-                index_file.write_all(&center)
-                    .map_err(|e| {
-                        bloguen::Error::Io {
-                            desc: "output index file center".into(),
-                            op: "write",
-                            more: e.to_string().into(),
-                        }
-                    })
+            })
+        };
+
+        for (tag, tag_posts) in &tags {
+            let fname = format!("{}.html", tag.file_name());
+            let mut tag_file = create_tag_file(&fname)?;
+
+            let mut variables = tag_index_variables.clone();
+            variables.insert("permalink", tag.permalink(descriptor.base_url.as_ref()));
+            variables.insert("tag", tag.to_string());
+            variables.insert("tag_post_count", tag_posts.len().to_string());
+
+            format_tag_template(tag_index_header.as_ref().unwrap(), &variables, tag, slice::from_ref(tag), &mut tag_file)?;
+            {
+                let mut write_center = |pm: &&bloguen::ops::PostManifest| {
+                    tag_file.write_all(pm.tag_index_center.as_bytes())
+                        .map_err(|e| {
+                            bloguen::Error::Io {
+                                desc: "output tag index file center".into(),
+                                op: "write",
+                                more: e.to_string().into(),
+                            }
+                        })
+                };
+                match tidx.center_order {
+                    bloguen::ops::CenterOrder::Forward => Result::from_iter(tag_posts.iter().map(&mut write_center))?,
+                    bloguen::ops::CenterOrder::Backward => Result::from_iter(tag_posts.iter().rev().map(&mut write_center))?,
+                }
+            }
+            format_tag_template(tag_index_footer.as_ref().unwrap(), &variables, tag, slice::from_ref(tag), &mut tag_file)?;
+        }
+
+        let mut cloud_file = create_tag_file("index.html")?;
+        let mut variables = tag_index_variables.clone();
+        variables.insert("permalink", bloguen::ops::TagName::cloud_permalink(descriptor.base_url.as_ref()));
+        variables.insert("tag", String::new());
+        variables.insert("tag_post_count", String::new());
+
+        format_tag_template(tag_index_header.as_ref().unwrap(), &variables, "tags", &all_tags, &mut cloud_file)?;
+        for (tag, tag_posts) in &tags {
+            let mut variables = variables.clone();
+            variables.insert("tag", tag.to_string());
+            variables.insert("tag_post_count", tag_posts.len().to_string());
+            variables.insert("tag_weight", tag_weight(tag_posts.len(), max_tag_post_count).to_string());
+
+            format_tag_template(tag_cloud_center.as_ref().unwrap(), &variables, tag, slice::from_ref(tag), &mut cloud_file)?;
+        }
+        format_tag_template(tag_index_footer.as_ref().unwrap(), &variables, "tags", &all_tags, &mut cloud_file)?;
+    }
+
+    if let Some(sitemap) = descriptor.sitemap.as_ref().filter(|_| outputs_changed) {
+        let base_url = descriptor.base_url.as_ref().unwrap();
+        let published: BTreeMap<_, _> = posts.iter().map(|p| (p.normalised_name(), p)).collect();
+        let last_modified = |name: &str| {
+            let post = published[name];
+            let datetime = post.datetime.with_timezone(&Utc);
+            post.source_modification_time()
+                .and_then(|mt| Utc.timestamp_opt((mt / 1_000_000_000) as i64, (mt % 1_000_000_000) as u32).single())
+                .filter(|mt| *mt > datetime)
+                .unwrap_or(datetime)
+        };
+        let latest = posts_data.iter().map(|(_, name, ..)| last_modified(name)).max().unwrap_or_else(Utc::now);
+
+        let mut urls = vec![];
+        if let Some(idx) = descriptor.index.as_ref() {
+            let page_count = match idx.posts_per_page {
+                Some(posts_per_page) if !posts_data.is_empty() => posts_data.len().div_ceil(posts_per_page),
+                _ => 1,
             };
-            match idx.center_order {
-                bloguen::ops::CenterOrder::Forward => Result::from_iter(posts_data.iter().map(write_center))?,
-                bloguen::ops::CenterOrder::Backward => Result::from_iter(posts_data.iter().rev().map(write_center))?,
+            urls.extend((1..page_count + 1).map(|page_number| (index_page_path(page_number), latest)));
+        }
+        urls.extend(posts_data.iter().map(|(_, name, ..)| (published[&name[..]].permalink(None), last_modified(name))));
+        if descriptor.tag_index.is_some() {
+            let mut tags: BTreeMap<bloguen::ops::TagName, _> = BTreeMap::new();
+            for (_, name, pm, _) in &posts_data {
+                for tag in &pm.tags {
+                    let tag_last_modified = tags.entry(tag.parse()?).or_insert_with(|| last_modified(name));
+                    *tag_last_modified = cmp::max(*tag_last_modified, last_modified(name));
+                }
             }
+
+            urls.push(("tags/index.html".to_string(), latest));
+            urls.extend(tags.into_iter().map(|(tag, tag_last_modified)| (format!("tags/{}.html", tag.file_name()), tag_last_modified)));
+        }
+
+        let sitemap_path = bloguen::util::concat_path(&opts.output_dir.1, sitemap);
+        if let Some(sitemap_parent) = sitemap_path.parent() {
+            fs::create_dir_all(sitemap_parent).map_err(|e| {
+                    bloguen::Error::Io {
+                        desc: format!("{} parent directory", sitemap).into(),
+                        op: "create",
+                        more: e.to_string().into(),
+                    }
+                })?;
+        }
+        let mut sitemap_file = File::create(sitemap_path).map_err(|e| {
+                bloguen::Error::Io {
+                    desc: "output sitemap file".into(),
+                    op: "create",
+                    more: e.to_string().into(),
+                }
+            })?;
+        bloguen::ops::sitemap_header(&mut sitemap_file, "sitemap")?;
+        for (link, url_last_modified) in &urls {
+            bloguen::ops::sitemap_url(&bloguen::util::resolve_url(base_url, link), url_last_modified, &mut sitemap_file, "sitemap")?;
         }
+        bloguen::ops::sitemap_footer(&mut sitemap_file, "sitemap")?;
 
-        bloguen::ops::format_output(index_footer.as_ref().unwrap(),
-                                    &descriptor.name,
-                                    &global_language,
-                                    &[&descriptor.data, &idx.data],
-                                    "index",
-                                    "index",
-                                    0,
-                                    "index",
-                                    &global_author,
-                                    &index_date,
-                                    &[],
-                                    &[&descriptor.styles, &idx.styles],
-                                    &[&descriptor.scripts, &idx.scripts, &index_script],
-                                    &mut index_file,
-                                    "index")?;
+        if descriptor.robots {
+            let mut robots_file = File::create(opts.output_dir.1.join("robots.txt")).map_err(|e| {
+                    bloguen::Error::Io {
+                        desc: "output robots.txt file".into(),
+                        op: "create",
+                        more: e.to_string().into(),
+                    }
+                })?;
+            bloguen::ops::robots_txt(&bloguen::util::resolve_url(base_url, sitemap), &mut robots_file, "robots.txt")?;
+        }
+    }
+
+    manifest.posts.extend(posts_data.into_iter().map(|(_, name, pm, _)| (name, pm)));
+    manifest.remove_stale_outputs(&previous_manifest, &opts.output_dir)?;
+    manifest.write(&opts.output_dir)?;
+
+    Ok(())
+}
+
+/// Remove the post HTML and machine data previously generated for the specified draft or scheduled post, so it doesn't stay published.
+fn remove_unpublished_outputs(post: &bloguen::ops::BloguePost, descriptor: &bloguen::ops::BlogueDescriptor, output_dir: &(String, PathBuf))
+                              -> Result<(), bloguen::Error> {
+    let outputs = iter::once(bloguen::util::concat_path(&output_dir.1, &post.path))
+        .chain(descriptor.machine_data
+            .iter()
+            .map(|(kind, subpath)| bloguen::util::concat_path(&output_dir.1, subpath).join(format!("{}.{}", post.slug, kind.extension()))));
+    for output in outputs {
+        match fs::remove_file(&output) {
+            Ok(()) => {}
+            Err(ref e) if e.kind() == IoErrorKind::NotFound => {}
+            Err(e) => {
+                return Err(bloguen::Error::Io {
+                    desc: format!("unpublished post {} output", post.number.1).into(),
+                    op: "remove",
+                    more: e.to_string().into(),
+                })
+            }
+        }
     }
 
     Ok(())
 }
+
+/// Get the path of the specified index page, relative to the output directory.
+fn index_page_path(page_number: usize) -> String {
+    if page_number == 1 {
+        "index.html".to_string()
+    } else {
+        format!("page/{}.html", page_number)
+    }
+}
+
+/// Scale the amount of posts with a tag to a tag cloud weight between 1 and 5.
+fn tag_weight(tag_post_count: usize, max_tag_post_count: usize) -> usize {
+    if max_tag_post_count > 1 {
+        1 + (tag_post_count - 1) * 4 / (max_tag_post_count - 1)
+    } else {
+        1
+    }
+}
+
+/// Copy the asset if its modification time differs from the previous one, returning the current one, if any.
+fn copy_changed_asset(p: &bloguen::ops::BloguePost, output_dir: &(String, PathBuf), asset_override: Option<&str>, link: &str, previous_mtime: Option<u64>)
+                      -> Result<Option<u64>, bloguen::Error> {
+    let mtime = p.asset_modification_time(link);
+    if mtime.is_some() && mtime == previous_mtime {
+        return Ok(mtime);
+    }
+
+    if p.copy_asset(output_dir, asset_override, link)? {
+        Ok(mtime)
+    } else {
+        eprintln!("Couldn't find \"{}\" for \"{}\" post.", link, p.normalised_name());
+        Ok(None)
+    }
+}
@@ -1,11 +1,12 @@
-use self::super::{MachineDataKind, ScriptElement, StyleElement, CenterOrder, LanguageTag, FeedType, feed_type_footer, feed_type_header};
-use self::super::super::util::{concat_path, path_depth, mul_str};
+use self::super::{MachineDataKind, MarkdownOptions, ScriptElement, StyleElement, CenterOrder, LanguageTag, FeedType, feed_type_footer, feed_type_header};
+use self::super::super::util::{THEME_SET, sanitise_file_name, highlight_stylesheet, resolve_url, concat_path, path_depth, mul_str};
 use std::collections::{BTreeMap, BTreeSet};
 use toml::de::from_str as from_toml_str;
 use self::super::super::Error;
 use std::io::{Write, Read};
 use std::fs::{self, File};
 use std::path::PathBuf;
+use url::Url;
 
 
 /// Generic blogue metadata.
@@ -19,6 +20,12 @@ pub struct BlogueDescriptor {
     ///
     /// If not present, defaults to the current system user's name, which, if not detected, errors out.
     pub author: Option<String>,
+    /// The blogue's public URL, against which links in feeds, machine data, and permalinks are resolved.
+    ///
+    /// The value's path is ended off with a slash, if not already specified.
+    ///
+    /// If not present, links are relative to the output directory.
+    pub base_url: Option<Url>,
     /// Data to put before post HTML, templated.
     ///
     /// Default: `"$ROOT/header.html"`, then `"$ROOT/header.htm"`.
@@ -34,10 +41,40 @@ pub struct BlogueDescriptor {
     ///
     /// No override is applied if not present – assets are copied alongside the posts' HTML.
     pub asset_dir_override: Option<String>,
+    /// Pattern of the posts' output paths, relative to the output root,
+    /// see [`BloguePost::apply_permalink_pattern()`](struct.BloguePost.html#method.apply_permalink_pattern).
+    ///
+    /// The value is stripped of leading slashes. All backslashes are normalised to forward ones.
+    ///
+    /// If not present, defaults to `"posts/{slug}.html"`.
+    pub permalink: String,
     /// Metadata specifying how to generate the blogue index file.
     ///
     /// If not present, index not generated.
     pub index: Option<BlogueDescriptorIndex>,
+    /// Metadata specifying how to generate the per-tag index files and the tag overview.
+    ///
+    /// If not present, tag indices not generated and tags not linked.
+    pub tag_index: Option<BlogueDescriptorTagIndex>,
+    /// Metadata specifying how to highlight the posts' fenced code blocks.
+    ///
+    /// If not present, code blocks not highlighted.
+    pub highlight: Option<BlogueDescriptorHighlight>,
+    /// Whether to render `$…$` and `$$…$$` TeX math in the posts to MathML,
+    /// see [`render_math()`](../util/fn.render_math.html).
+    ///
+    /// If not present, defaults to false.
+    pub math: bool,
+    /// Markdown extensions and options to render the posts with.
+    ///
+    /// Overriden by post metadata, if present.
+    ///
+    /// If not present, defaults to [`MARKDOWN_OPTIONS`](../util/struct.MARKDOWN_OPTIONS.html).
+    pub markdown: MarkdownOptions,
+    /// External commands to pipe the posts through.
+    ///
+    /// If not present, defaults to none.
+    pub hooks: BlogueDescriptorHooks,
     /// Where and which machine datasets to put.
     ///
     /// Each value here is a prefix appended to the output directory under which to put the machine data.
@@ -48,6 +85,43 @@ pub struct BlogueDescriptor {
     ///
     /// Each value here is a file path appended to the output directory into which to put the machine data.
     pub feeds: BTreeMap<FeedType, String>,
+    /// Where and which feeds of posts with a given tag to put.
+    ///
+    /// Each value here is a file path appended to the output directory into which to put the feed,
+    /// with `{tag}` replaced by the tag's [`file_name()`](struct.TagName.html#method.file_name).
+    pub tag_feeds: BTreeMap<FeedType, String>,
+    /// Where and which feeds of posts by a given author to put.
+    ///
+    /// Each value here is a file path appended to the output directory into which to put the feed,
+    /// with `{author}` replaced by the author, characters not valid in filenames replaced with underscores.
+    pub author_feeds: BTreeMap<FeedType, String>,
+    /// Where and which feeds of posts in a given language to put.
+    ///
+    /// Each value here is a file path appended to the output directory into which to put the feed,
+    /// with `{language}` replaced by the language in BCP47 format.
+    pub language_feeds: BTreeMap<FeedType, String>,
+    /// Whether to put the posts' excerpts into feeds instead of their full contents,
+    /// see [`write_excerpt()`](../util/fn.write_excerpt.html).
+    ///
+    /// If not present, defaults to false.
+    pub feed_summaries: bool,
+    /// Where to put the sitemap listing the index, posts, and tag indices, relative to the output directory.
+    ///
+    /// Requires a base URL.
+    ///
+    /// If not present, sitemap not generated.
+    pub sitemap: Option<String>,
+    /// Whether to put a `robots.txt` pointing at the sitemap in the output directory.
+    ///
+    /// Requires a sitemap.
+    ///
+    /// If not present, defaults to false.
+    pub robots: bool,
+    /// Whether to HTML-escape plain-text template substitutions without filters,
+    /// see [`FormatTemplate::escape_html_by_default()`](struct.FormatTemplate.html#method.escape_html_by_default).
+    ///
+    /// If not present, defaults to false.
+    pub escape_html: bool,
     /// Default post language.
     ///
     /// Overriden by post metadata, if present.
@@ -90,6 +164,51 @@ pub struct BlogueDescriptorIndex {
     ///
     /// If not present, defaults to forward.
     pub center_order: CenterOrder,
+    /// How many posts to put on each index page, the first one being `index.html`, and the following `page/<n>.html`.
+    ///
+    /// Can't be zero. If not present, all posts are put in `index.html`.
+    pub posts_per_page: Option<usize>,
+    /// A set of style descriptors.
+    ///
+    /// If not present, defaults to empty.
+    pub styles: Vec<StyleElement>,
+    /// A set of style descriptors.
+    ///
+    /// If not present, defaults to empty.
+    pub scripts: Vec<ScriptElement>,
+    /// Additional static data to substitute in header and footer.
+    ///
+    /// If not present, defaults to empty.
+    pub data: BTreeMap<String, String>,
+}
+
+/// Metadata pertaining specifically to generating the per-tag index files, under `tags/<tag>.html`,
+/// and the tag overview, under `tags/index.html`.
+#[derive(Debug, Clone, Hash, PartialEq, Eq, PartialOrd, Ord)]
+pub struct BlogueDescriptorTagIndex {
+    /// Data to start tag index and overview HTML with, templated.
+    ///
+    /// Default: `"$ROOT/tag_index_header.html"`, then `"$ROOT/tag_index_header.htm"`,
+    ///     then `"$ROOT/tag_idx_header.html"`, then `"$ROOT/tag_idx_header.htm"`.
+    pub header_file: (String, PathBuf),
+    /// Data to put in tag index HTML for each post with the tag, templated.
+    ///
+    /// Default: `"$ROOT/tag_index_center.html"`, then `"$ROOT/tag_index_center.htm"`,
+    ///     then `"$ROOT/tag_idx_center.html"`, then `"$ROOT/tag_idx_center.htm"`.
+    pub center_file: (String, PathBuf),
+    /// Data to put in the tag overview HTML for each tag, templated.
+    ///
+    /// Default: `"$ROOT/tag_cloud_center.html"`, then `"$ROOT/tag_cloud_center.htm"`.
+    pub cloud_center_file: (String, PathBuf),
+    /// Data to end tag index and overview HTML with, templated.
+    ///
+    /// Default: `"$ROOT/tag_index_footer.html"`, then `"$ROOT/tag_index_footer.htm"`,
+    ///     then `"$ROOT/tag_idx_footer.html"`, then `"$ROOT/tag_idx_footer.htm"`.
+    pub footer_file: (String, PathBuf),
+    /// The order to put center templates in.
+    ///
+    /// If not present, defaults to forward.
+    pub center_order: CenterOrder,
     /// A set of style descriptors.
     ///
     /// If not present, defaults to empty.
@@ -104,17 +223,77 @@ pub struct BlogueDescriptorIndex {
     pub data: BTreeMap<String, String>,
 }
 
+/// Metadata pertaining specifically to highlighting the posts' fenced code blocks,
+/// see [`highlight_code_blocks()`](../util/fn.highlight_code_blocks.html).
+#[derive(Debug, Clone, Hash, PartialEq, Eq, PartialOrd, Ord)]
+pub struct BlogueDescriptorHighlight {
+    /// Name of the theme to highlight with, one of [`THEME_SET`](../util/struct.THEME_SET.html)'s.
+    ///
+    /// If not present, defaults to `"InspiredGitHub"`.
+    pub theme: String,
+    /// Whether to mark the code up with CSS classes and add a [`stylesheet()`](#method.stylesheet),
+    /// instead of styling it inline.
+    ///
+    /// If not present, defaults to false.
+    pub classes: bool,
+}
+
+/// Metadata pertaining specifically to the external commands to pipe the posts through,
+/// see [`run_hook()`](../util/fn.run_hook.html).
+///
+/// Each hook gets the post's variables as `BLOGUEN_`-prefixed environment variables,
+/// see [`BloguePost::generate()`](struct.BloguePost.html#method.generate).
+#[derive(Debug, Default, Clone, Hash, PartialEq, Eq, PartialOrd, Ord)]
+pub struct BlogueDescriptorHooks {
+    /// Commands to pipe the post sources through, in order, before parsing them.
+    ///
+    /// If not present, defaults to empty.
+    pub pre: Vec<String>,
+    /// Commands to pipe the post HTML through, in order, after filling out the header and footer.
+    ///
+    /// If not present, defaults to empty.
+    pub post: Vec<String>,
+}
+
+/// Which posts a feed contains.
+#[derive(Debug, Copy, Clone, Hash, PartialEq, Eq, PartialOrd, Ord)]
+pub enum FeedFilter<'a> {
+    /// All of them, as specified in [`BlogueDescriptor::feeds`](struct.BlogueDescriptor.html#structfield.feeds).
+    All,
+    /// Ones with the specified tag, as specified in [`BlogueDescriptor::tag_feeds`](struct.BlogueDescriptor.html#structfield.tag_feeds).
+    Tag(&'a str),
+    /// Ones by the specified author, as specified in [`BlogueDescriptor::author_feeds`](struct.BlogueDescriptor.html#structfield.author_feeds).
+    Author(&'a str),
+    /// Ones in the specified language, as specified in
+    /// [`BlogueDescriptor::language_feeds`](struct.BlogueDescriptor.html#structfield.language_feeds).
+    Language(&'a str),
+}
+
 
 #[derive(Deserialize)]
 struct BlogueDescriptorSerialised {
     pub name: String,
     pub author: Option<String>,
+    pub base_url: Option<String>,
     pub header: Option<String>,
     pub footer: Option<String>,
     pub asset_dir: Option<String>,
+    pub permalink: Option<String>,
     pub index: Option<BlogueDescriptorIndexSerialised>,
+    pub tag_index: Option<BlogueDescriptorTagIndexSerialised>,
+    pub highlight: Option<BlogueDescriptorHighlightSerialised>,
+    pub math: Option<bool>,
+    pub markdown: Option<MarkdownOptions>,
+    pub hooks: Option<BlogueDescriptorHooksSerialised>,
     pub machine_data: Option<BTreeMap<MachineDataKind, String>>,
     pub feeds: Option<BTreeMap<FeedType, String>>,
+    pub tag_feeds: Option<BTreeMap<FeedType, String>>,
+    pub author_feeds: Option<BTreeMap<FeedType, String>>,
+    pub language_feeds: Option<BTreeMap<FeedType, String>>,
+    pub feed_summaries: Option<bool>,
+    pub sitemap: Option<String>,
+    pub robots: Option<bool>,
+    pub escape_html: Option<bool>,
     pub language: Option<LanguageTag>,
     pub styles: Option<Vec<StyleElement>>,
     pub scripts: Option<Vec<ScriptElement>>,
@@ -128,11 +307,37 @@ struct BlogueDescriptorIndexSerialised {
     pub center: Option<String>,
     pub footer: Option<String>,
     pub order: Option<CenterOrder>,
+    pub posts_per_page: Option<usize>,
     pub styles: Option<Vec<StyleElement>>,
     pub scripts: Option<Vec<ScriptElement>>,
     pub data: Option<BTreeMap<String, String>>,
 }
 
+#[derive(Deserialize)]
+struct BlogueDescriptorTagIndexSerialised {
+    pub generate: Option<bool>,
+    pub header: Option<String>,
+    pub center: Option<String>,
+    pub cloud_center: Option<String>,
+    pub footer: Option<String>,
+    pub order: Option<CenterOrder>,
+    pub styles: Option<Vec<StyleElement>>,
+    pub scripts: Option<Vec<ScriptElement>>,
+    pub data: Option<BTreeMap<String, String>>,
+}
+
+#[derive(Deserialize)]
+struct BlogueDescriptorHighlightSerialised {
+    pub theme: Option<String>,
+    pub classes: Option<bool>,
+}
+
+#[derive(Deserialize)]
+struct BlogueDescriptorHooksSerialised {
+    pub pre: Option<Vec<String>>,
+    pub post: Option<Vec<String>>,
+}
+
 
 impl BlogueDescriptor {
     /// Read the blogue descriptor from the specified root firectory.
@@ -155,14 +360,17 @@ impl BlogueDescriptor {
     ///
     /// ```toml
     /// name = "Блогг"
+    /// base_url = "https://nabijaczleweli.xyz/blogue"
     /// header = "head.html"
     /// language = "pl"
     /// asset_dir = "assets"
+    /// sitemap = "sitemap.xml"
     ///
     /// [index]
     /// header = "idx_head.html"
     /// center = "центр.html"
     /// order = "backward"
+    /// posts_per_page = 10
     /// styles = ["literal:.indented { text-indent: 1em; }"]
     ///
     /// [[scripts]]
@@ -180,6 +388,9 @@ impl BlogueDescriptor {
     /// RSS = "feed.rss"
     /// ATOM = "feed.atom"
     ///
+    /// [tag_feeds]
+    /// RSS = "feeds/tags/{tag}.rss"
+    ///
     /// [data]
     /// preferred_system = "capitalism"
     /// ```
@@ -196,14 +407,17 @@ impl BlogueDescriptor {
     /// # fs::create_dir_all(&root).unwrap();
     /// # File::create(root.join("blogue.toml")).unwrap().write_all("\
     /// #     name = \"Блогг\"\n\
+    /// #     base_url = \"https://nabijaczleweli.xyz/blogue\"\n\
     /// #     header = \"head.html\"\n\
     /// #     language = \"pl\"\n\
     /// #     asset_dir = \"assets\"\n\
+    /// #     sitemap = \"sitemap.xml\"\n\
     /// #     \n\
     /// #     [index]\n\
     /// #     header = \"idx_head.html\"\n\
     /// #     center = \"центр.html\"\n\
     /// #     order = \"backward\"\n\
+    /// #     posts_per_page = 10\n\
     /// #     styles = [\"literal:.indented { text-indent: 1em; }\"]\n\
     /// #     \n\
     /// #     [[scripts]]\n\
@@ -221,6 +435,9 @@ impl BlogueDescriptor {
     /// #     RSS = \"feed.rss\"\n\
     /// #     Atom = \"feed.atom\"\n\
     /// #     \n\
+    /// #     [tag_feeds]\n\
+    /// #     RSS = \"feeds/tags/{tag}.rss\"\n\
+    /// #     \n\
     /// #     [data]\n\
     /// #     preferred_system = \"capitalism\"\n\
     /// # ".as_bytes()).unwrap();
@@ -238,12 +455,21 @@ impl BlogueDescriptor {
     ///     BlogueDescriptor {
     ///         name: "Блогг".to_string(),
     ///         author: None,
+    ///         base_url: Some("https://nabijaczleweli.xyz/blogue/".parse().unwrap()),
     ///         header_file: ("$ROOT/head.html".to_string(), root.join("head.html")),
     ///         footer_file: ("$ROOT/footer.htm".to_string(), root.join("footer.htm")),
     ///         asset_dir_override: Some("assets/".to_string()),
+    ///         permalink: "posts/{slug}.html".to_string(),
     ///         machine_data: vec![(MachineDataKind::Json, "metadata/json/".to_string())].into_iter().collect(),
     ///         feeds: vec![(FeedType::Rss, "feed.rss".to_string()),
     ///                     (FeedType::Atom, "feed.atom".to_string())].into_iter().collect(),
+    ///         tag_feeds: vec![(FeedType::Rss, "feeds/tags/{tag}.rss".to_string())].into_iter().collect(),
+    ///         author_feeds: vec![].into_iter().collect(),
+    ///         language_feeds: vec![].into_iter().collect(),
+    ///         feed_summaries: false,
+    ///         sitemap: Some("sitemap.xml".to_string()),
+    ///         robots: false,
+    ///         escape_html: false,
     ///         language: Some("pl".parse().unwrap()),
     ///         styles: vec![],
     ///         scripts: vec![ScriptElement::from_link("/content/assets/syllable.js"),
@@ -253,10 +479,16 @@ impl BlogueDescriptor {
     ///             center_file: ("$ROOT/центр.html".to_string(), root.join("центр.html")),
     ///             footer_file: ("$ROOT/index_footer.htm".to_string(), root.join("index_footer.htm")),
     ///             center_order: CenterOrder::Backward,
+    ///             posts_per_page: Some(10),
     ///             styles: vec![StyleElement::from_literal(".indented { text-indent: 1em; }")],
     ///             scripts: vec![],
     ///             data: vec![].into_iter().collect(),
     ///         }),
+    ///         tag_index: None,
+    ///         highlight: None,
+    ///         math: false,
+    ///         markdown: Default::default(),
+    ///         hooks: Default::default(),
     ///         data: vec![("preferred_system".to_string(),
     ///                     "capitalism".to_string())].into_iter().collect(),
     ///     });
@@ -285,6 +517,33 @@ impl BlogueDescriptor {
                 }
             })?;
 
+        let base_url = match serialised.base_url {
+            Some(bu) => {
+                let mut bu = Url::parse(&bu).map_err(|e| {
+                        Error::Parse {
+                            tp: "URL",
+                            wher: "blogue descriptor".into(),
+                            more: format!("base URL {:?} invalid: {}", bu, e).into(),
+                        }
+                    })?;
+                if bu.cannot_be_a_base() {
+                    return Err(Error::Parse {
+                        tp: "URL",
+                        wher: "blogue descriptor".into(),
+                        more: format!("base URL {:?} cannot be a base", bu.as_str()).into(),
+                    });
+                }
+
+                if !bu.path().ends_with('/') {
+                    let path = format!("{}/", bu.path());
+                    bu.set_path(&path);
+                }
+
+                Some(bu)
+            }
+            None => None,
+        };
+
         let asset_dir_override = serialised.asset_dir.map(|mut ad| {
             if let Some(i) = ad.find(|c| !['/', '\\'].contains(&c)) {
                 ad.replace_range(..i, "");
@@ -303,6 +562,39 @@ impl BlogueDescriptor {
             ad
         });
 
+        let permalink = match serialised.permalink {
+            Some(mut pl) => {
+                if let Some(i) = pl.find(|c| !['/', '\\'].contains(&c)) {
+                    pl.replace_range(..i, "");
+                }
+                let pl = pl.replace('\\', "/");
+
+                let unknown_placeholder = ["{slug}", "{number}", "{year}", "{month}", "{day}"]
+                    .iter()
+                    .fold(pl.clone(), |pl, ph| pl.replace(ph, ""))
+                    .contains(|c| ['{', '}'].contains(&c));
+                let more = if pl.is_empty() {
+                    Some("permalink pattern empty".to_string())
+                } else if pl.ends_with('/') {
+                    Some(format!("permalink pattern {:?} ends with path separator", pl))
+                } else if unknown_placeholder {
+                    Some(format!("permalink pattern {:?} contains unknown placeholder", pl))
+                } else {
+                    None
+                };
+                if let Some(more) = more {
+                    return Err(Error::Parse {
+                        tp: "path chunk",
+                        wher: "blogue descriptor".into(),
+                        more: more.into(),
+                    });
+                }
+
+                pl
+            }
+            None => "posts/{slug}.html".to_string(),
+        };
+
         let machine_data = serialised.machine_data.unwrap_or_default();
         for (ref k, ref v) in &machine_data {
             if v.find(|c| !['/', '\\'].contains(&c)).is_none() {
@@ -315,26 +607,16 @@ impl BlogueDescriptor {
         }
 
         let feeds = serialised.feeds.unwrap_or_default();
-        for (ref k, ref v) in &feeds {
-            let more = if v.is_empty() {
-                Some(format!("{} filename empty", k))
-            } else if v.ends_with(|c| ['/', '\\'].contains(&c)) {
-                Some(format!("{} filename {:?} ends with path separator", k, v))
-            } else {
-                None
-            };
-
-            if let Some(more) = more {
-                return Err(Error::Parse {
-                    tp: "path chunk",
-                    wher: "blogue descriptor".into(),
-                    more: more.into(),
-                });
-            }
-        }
+        let tag_feeds = serialised.tag_feeds.unwrap_or_default();
+        let author_feeds = serialised.author_feeds.unwrap_or_default();
+        let language_feeds = serialised.language_feeds.unwrap_or_default();
+        check_feed_filenames(&feeds, None)?;
+        check_feed_filenames(&tag_feeds, Some("{tag}"))?;
+        check_feed_filenames(&author_feeds, Some("{author}"))?;
+        check_feed_filenames(&language_feeds, Some("{language}"))?;
         {
             let mut feeds_fnames = BTreeSet::new();
-            for v in feeds.values() {
+            for v in feeds.values().chain(tag_feeds.values()).chain(author_feeds.values()).chain(language_feeds.values()) {
                 if !feeds_fnames.insert(v) {
                     return Err(Error::Parse {
                         tp: "path chunk",
@@ -345,12 +627,91 @@ impl BlogueDescriptor {
             }
         }
 
+        let robots = serialised.robots.unwrap_or(false);
+        match serialised.sitemap.as_ref() {
+            Some(sitemap) => {
+                let more = if sitemap.is_empty() {
+                    Some("sitemap filename empty".to_string())
+                } else if sitemap.ends_with(|c| ['/', '\\'].contains(&c)) {
+                    Some(format!("sitemap filename {:?} ends with path separator", sitemap))
+                } else {
+                    None
+                };
+                if let Some(more) = more {
+                    return Err(Error::Parse {
+                        tp: "path chunk",
+                        wher: "blogue descriptor".into(),
+                        more: more.into(),
+                    });
+                }
+                if base_url.is_none() {
+                    return Err(Error::Parse {
+                        tp: "URL",
+                        wher: "blogue descriptor".into(),
+                        more: "sitemap requires base URL".into(),
+                    });
+                }
+            }
+            None if robots => {
+                return Err(Error::Parse {
+                    tp: "boolean",
+                    wher: "blogue descriptor".into(),
+                    more: "robots requires sitemap".into(),
+                })
+            }
+            None => {}
+        }
+
+        if serialised.index.as_ref().and_then(|si| si.posts_per_page) == Some(0) {
+            return Err(Error::Parse {
+                tp: "positive integer",
+                wher: "blogue descriptor".into(),
+                more: "index posts_per_page zero".into(),
+            });
+        }
+
+        let highlight = serialised.highlight.map(|sh| {
+            BlogueDescriptorHighlight {
+                theme: sh.theme.unwrap_or_else(|| "InspiredGitHub".to_string()),
+                classes: sh.classes.unwrap_or(false),
+            }
+        });
+        if let Some(highlight) = highlight.as_ref() {
+            if !THEME_SET.themes.contains_key(&highlight.theme) {
+                return Err(Error::Parse {
+                    tp: "highlight theme",
+                    wher: "blogue descriptor".into(),
+                    more: format!("theme {:?} unknown", highlight.theme).into(),
+                });
+            }
+        }
+
+        let hooks = serialised.hooks
+            .map(|sh| {
+                BlogueDescriptorHooks {
+                    pre: sh.pre.unwrap_or_default(),
+                    post: sh.post.unwrap_or_default(),
+                }
+            })
+            .unwrap_or_default();
+        for (kind, commands) in &[("pre", &hooks.pre), ("post", &hooks.post)] {
+            if commands.iter().any(|c| c.trim().is_empty()) {
+                return Err(Error::Parse {
+                    tp: "command",
+                    wher: "blogue descriptor".into(),
+                    more: format!("{} hook empty", kind).into(),
+                });
+            }
+        }
+
         Ok(BlogueDescriptor {
             name: serialised.name,
             author: serialised.author,
+            base_url: base_url,
             header_file: additional_file(serialised.header, root, "header", "post header")?,
             footer_file: additional_file(serialised.footer, root, "footer", "post footer")?,
             asset_dir_override: asset_dir_override,
+            permalink: permalink,
             index: match serialised.index {
                 Some(mut si) => {
                     match si.generate {
@@ -363,6 +724,7 @@ impl BlogueDescriptor {
                                     footer_file: additional_file(si.footer.clone(), root, "index_footer", "index footer")
                                                      .or_else(|_| additional_file(si.footer.take(), root, "idx_footer", "index footer"))?,
                                     center_order: si.order.unwrap_or_default(),
+                                    posts_per_page: si.posts_per_page,
                                     styles: si.styles.unwrap_or_default(),
                                     scripts: si.scripts.unwrap_or_default(),
                                     data: si.data.unwrap_or_default(),
@@ -373,8 +735,42 @@ impl BlogueDescriptor {
                 }
                 None => None,
             },
+            tag_index: match serialised.tag_index {
+                Some(mut sti) => {
+                    match sti.generate {
+                        None | Some(true) => {
+                                Some(BlogueDescriptorTagIndex {
+                                    header_file: additional_file(sti.header.clone(), root, "tag_index_header", "tag index header")
+                                                     .or_else(|_| additional_file(sti.header.take(), root, "tag_idx_header", "tag index header"))?,
+                                    center_file: additional_file(sti.center.clone(), root, "tag_index_center", "tag index center")
+                                                     .or_else(|_| additional_file(sti.center.take(), root, "tag_idx_center", "tag index center"))?,
+                                    cloud_center_file: additional_file(sti.cloud_center.take(), root, "tag_cloud_center", "tag cloud center")?,
+                                    footer_file: additional_file(sti.footer.clone(), root, "tag_index_footer", "tag index footer")
+                                                     .or_else(|_| additional_file(sti.footer.take(), root, "tag_idx_footer", "tag index footer"))?,
+                                    center_order: sti.order.unwrap_or_default(),
+                                    styles: sti.styles.unwrap_or_default(),
+                                    scripts: sti.scripts.unwrap_or_default(),
+                                    data: sti.data.unwrap_or_default(),
+                                })
+                            }
+                        Some(false) => None,
+                    }
+                }
+                None => None,
+            },
+            highlight: highlight,
+            math: serialised.math.unwrap_or(false),
+            markdown: serialised.markdown.unwrap_or_default(),
+            hooks: hooks,
             machine_data: machine_data,
             feeds: feeds,
+            tag_feeds: tag_feeds,
+            author_feeds: author_feeds,
+            language_feeds: language_feeds,
+            feed_summaries: serialised.feed_summaries.unwrap_or(false),
+            sitemap: serialised.sitemap,
+            robots: robots,
+            escape_html: serialised.escape_html.unwrap_or(false),
             language: serialised.language,
             styles: serialised.styles.unwrap_or_default(),
             scripts: serialised.scripts.unwrap_or_default(),
@@ -430,6 +826,8 @@ impl BlogueDescriptor {
 
     /// Generate header for the specified type of feed for this descriptor.
     ///
+    /// If the descriptor has a base URL, the index link is resolved against it and a self link to the feed is added.
+    ///
     /// # Examples
     ///
     /// Given `$ROOT/blogue.toml` containing:
@@ -471,7 +869,7 @@ impl BlogueDescriptor {
     /// let gendate_local_rfc2822 = /* extracted from output's lastBuildDate tag */;
     /// # */
     /// assert_eq!(out, format!(r###"<?xml version="1.0" encoding="UTF-8"?>
-    /// <rss version="2.0">
+    /// <rss version="2.0" xmlns:atom="http://www.w3.org/2005/Atom">
     ///   <channel>
     ///     <docs>https://validator.w3.org/feed/docs/rss2.html</docs>
     ///     <title>Блогг</title>
@@ -498,11 +896,39 @@ impl BlogueDescriptor {
     /// let gendate_local_rfc2822 = /* extracted from output's lastBuildDate tag */;
     /// # */
     /// assert_eq!(out, format!(r###"<?xml version="1.0" encoding="UTF-8"?>
-    /// <rss version="2.0">
+    /// <rss version="2.0" xmlns:atom="http://www.w3.org/2005/Atom">
+    ///   <channel>
+    ///     <docs>https://validator.w3.org/feed/docs/rss2.html</docs>
+    ///     <title>Блогг</title>
+    ///     <author>nabijaczleweli</author>
+    ///     <description>Блогг</description>
+    ///     <language>en-GB</language>
+    ///     <generator>bloguen 0.1.1</generator>
+    ///     <pubDate>{0}</pubDate>
+    ///     <lastBuildDate>{0}</lastBuildDate>
+    /// "###, gendate_local_rfc2822));
+    ///
+    /// // And
+    ///
+    /// let mut out = vec![];
+    /// descriptor.base_url = Some("https://nabijaczleweli.xyz/blogue/".parse().unwrap());
+    /// descriptor.generate_feed_head(&mut out, &FeedType::Rss, "feeds/rss.xml",
+    ///                                         &LANGUAGE_EN_GB, "nabijaczleweli").unwrap();
+    ///
+    /// let out = String::from_utf8(out).unwrap();
+    /// # let mut gendate_local_rfc2822 = out.lines().find(|l| l.contains("lastBuildDate")).unwrap();
+    /// # gendate_local_rfc2822 = &gendate_local_rfc2822[4 + 1 + 13 + 1..gendate_local_rfc2822.len() - (1 + 13 + 1 + 1)];
+    /// # /*
+    /// let gendate_local_rfc2822 = /* extracted from output's lastBuildDate tag */;
+    /// # */
+    /// assert_eq!(out, format!(r###"<?xml version="1.0" encoding="UTF-8"?>
+    /// <rss version="2.0" xmlns:atom="http://www.w3.org/2005/Atom">
     ///   <channel>
     ///     <docs>https://validator.w3.org/feed/docs/rss2.html</docs>
     ///     <title>Блогг</title>
     ///     <author>nabijaczleweli</author>
+    ///     <link>https://nabijaczleweli.xyz/blogue/</link>
+    ///     <atom:link href="https://nabijaczleweli.xyz/blogue/feeds/rss.xml" rel="self" type="application/rss+xml" />
     ///     <description>Блогг</description>
     ///     <language>en-GB</language>
     ///     <generator>bloguen 0.1.1</generator>
@@ -514,14 +940,20 @@ impl BlogueDescriptor {
         feed_type_header(tp)(&self.name,
                              language,
                              author,
-                             self.index.as_ref().map(|_| {
-            let depth = path_depth(fname);
-            if depth - 1 > 0 {
-                (mul_str("../", depth as usize - 1) + "index.html").into()
-            } else {
-                "index.html".into()
-            }
-        }),
+                             match self.base_url.as_ref() {
+                                 Some(base_url) => Some(resolve_url(base_url, if self.index.is_some() { "index.html" } else { "" }).into()),
+                                 None => {
+                                     self.index.as_ref().map(|_| {
+                                         let depth = path_depth(fname);
+                                         if depth - 1 > 0 {
+                                             (mul_str("../", depth as usize - 1) + "index.html").into()
+                                         } else {
+                                             "index.html".into()
+                                         }
+                                     })
+                                 }
+                             },
+                             self.base_url.as_ref().map(|base_url| resolve_url(base_url, fname).into()),
                              into,
                              format!("{} feed output", tp.name()))?;
 
@@ -560,6 +992,169 @@ impl BlogueDescriptor {
 
         Ok(())
     }
+
+    /// Get all feeds a post with the specified tags, author, and language belongs in, mapped from their filenames.
+    ///
+    /// Returns an error if two different feeds would output to the same file,
+    /// e.g. because their tags only differ in characters replaced when sanitising the filename.
+    ///
+    /// # Examples
+    ///
+    /// Given `$ROOT/blogue.toml` containing:
+    ///
+    /// ```toml
+    /// name = "Блогг"
+    ///
+    /// [feeds]
+    /// RSS = "feeds/rss.xml"
+    ///
+    /// [tag_feeds]
+    /// RSS = "feeds/tags/{tag}.xml"
+    ///
+    /// [author_feeds]
+    /// Atom = "feeds/authors/{author}.atom"
+    /// ```
+    ///
+    /// The following holds:
+    ///
+    /// ```
+    /// # use bloguen::ops::{BlogueDescriptor, FeedFilter, FeedType};
+    /// # use std::fs::{self, File};
+    /// # use std::env::temp_dir;
+    /// # use std::io::Write;
+    /// # let root = temp_dir().join("bloguen-doctest").join("ops-descriptor-post_feeds");
+    /// # fs::create_dir_all(&root).unwrap();
+    /// # File::create(root.join("blogue.toml")).unwrap().write_all("\
+    /// #     name = \"Блогг\"\n\
+    /// #     \n\
+    /// #     [feeds]\n\
+    /// #     RSS = \"feeds/rss.xml\"\n\
+    /// #     \n\
+    /// #     [tag_feeds]\n\
+    /// #     RSS = \"feeds/tags/{tag}.xml\"\n\
+    /// #     \n\
+    /// #     [author_feeds]\n\
+    /// #     Atom = \"feeds/authors/{author}.atom\"\n\
+    /// # ".as_bytes()).unwrap();
+    /// # File::create(root.join("header.html")).unwrap();
+    /// # File::create(root.join("footer.html")).unwrap();
+    /// # /*
+    /// let root: PathBuf = /* obtained elsewhere */;
+    /// # */
+    /// let descriptor = BlogueDescriptor::read(&("$ROOT/".to_string(), root.clone())).unwrap();
+    /// assert_eq!(descriptor.post_feeds(vec!["C/C++", "vodka"], "nabijaczleweli", "pl"),
+    ///            Ok(vec![("feeds/rss.xml".to_string(), (FeedType::Rss, FeedFilter::All)),
+    ///                    ("feeds/tags/C_C++.xml".to_string(), (FeedType::Rss, FeedFilter::Tag("C/C++"))),
+    ///                    ("feeds/tags/vodka.xml".to_string(), (FeedType::Rss, FeedFilter::Tag("vodka"))),
+    ///                    ("feeds/authors/nabijaczleweli.atom".to_string(), (FeedType::Atom, FeedFilter::Author("nabijaczleweli")))]
+    ///                   .into_iter().collect()));
+    /// assert!(descriptor.post_feeds(vec!["C/C++", "C_C++"], "nabijaczleweli", "pl").is_err());
+    /// ```
+    pub fn post_feeds<'a, T: IntoIterator<Item = &'a str>>(&'a self, tags: T, author: &'a str, language: &'a str)
+                                                          -> Result<BTreeMap<String, (FeedType, FeedFilter<'a>)>, Error> {
+        let mut ret = BTreeMap::new();
+        for (tp, fname) in &self.feeds {
+            insert_feed(&mut ret, fname.clone(), *tp, FeedFilter::All)?;
+        }
+        for tag in tags {
+            for (tp, fname) in &self.tag_feeds {
+                insert_feed(&mut ret, fname.replace("{tag}", &sanitise_file_name(tag)), *tp, FeedFilter::Tag(tag))?;
+            }
+        }
+        for (tp, fname) in &self.author_feeds {
+            insert_feed(&mut ret, fname.replace("{author}", &sanitise_file_name(author)), *tp, FeedFilter::Author(author))?;
+        }
+        for (tp, fname) in &self.language_feeds {
+            insert_feed(&mut ret, fname.replace("{language}", &sanitise_file_name(language)), *tp, FeedFilter::Language(language))?;
+        }
+        Ok(ret)
+    }
+
+    /// Add the specified feeds, as returned by [`post_feeds()`](#method.post_feeds), to the ones collected from other posts.
+    ///
+    /// Returns an error if a different feed already outputs to the same file as one of the new ones.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use bloguen::ops::{BlogueDescriptor, FeedFilter, FeedType};
+    /// # use bloguen::Error;
+    /// # use std::collections::BTreeMap;
+    /// let mut feeds = BTreeMap::new();
+    /// BlogueDescriptor::merge_feeds(&mut feeds,
+    ///                               vec![("feeds/tags/C_C++.xml".to_string(), (FeedType::Rss, FeedFilter::Tag("C/C++")))]
+    ///                                   .into_iter().collect()).unwrap();
+    /// BlogueDescriptor::merge_feeds(&mut feeds,
+    ///                               vec![("feeds/tags/C_C++.xml".to_string(), (FeedType::Rss, FeedFilter::Tag("C/C++")))]
+    ///                                   .into_iter().collect()).unwrap();
+    /// assert_eq!(BlogueDescriptor::merge_feeds(&mut feeds,
+    ///                                          vec![("feeds/tags/C_C++.xml".to_string(), (FeedType::Rss, FeedFilter::Tag("C_C++")))]
+    ///                                              .into_iter().collect()),
+    ///            Err(Error::Parse {
+    ///                tp: "path chunk",
+    ///                wher: "blogue feeds".into(),
+    ///                more: "tag \"C/C++\" RSS and tag \"C_C++\" RSS feeds both output to \"feeds/tags/C_C++.xml\"".into(),
+    ///            }));
+    /// ```
+    pub fn merge_feeds<'a>(into: &mut BTreeMap<String, (FeedType, FeedFilter<'a>)>, feeds: BTreeMap<String, (FeedType, FeedFilter<'a>)>)
+                           -> Result<(), Error> {
+        for (fname, (tp, filter)) in feeds {
+            insert_feed(into, fname, tp, filter)?;
+        }
+        Ok(())
+    }
+}
+
+impl BlogueDescriptorHighlight {
+    /// Get the stylesheet to style the code with, if [`classes`](#structfield.classes) are used,
+    /// see [`highlight_stylesheet()`](../util/fn.highlight_stylesheet.html).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use bloguen::ops::{BlogueDescriptorHighlight, StyleElement};
+    /// # use bloguen::util::highlight_stylesheet;
+    /// let mut highlight = BlogueDescriptorHighlight {
+    ///     theme: "InspiredGitHub".to_string(),
+    ///     classes: false,
+    /// };
+    /// assert_eq!(highlight.stylesheet(), Ok(None));
+    ///
+    /// highlight.classes = true;
+    /// assert_eq!(highlight.stylesheet(),
+    ///            Ok(Some(StyleElement::from_literal(highlight_stylesheet("InspiredGitHub").unwrap()))));
+    /// ```
+    pub fn stylesheet(&self) -> Result<Option<StyleElement>, Error> {
+        if self.classes {
+            Ok(Some(StyleElement::from_literal(highlight_stylesheet(&self.theme)?)))
+        } else {
+            Ok(None)
+        }
+    }
+}
+
+fn check_feed_filenames(feeds: &BTreeMap<FeedType, String>, placeholder: Option<&str>) -> Result<(), Error> {
+    for (ref k, ref v) in feeds {
+        let more = if v.is_empty() {
+            Some(format!("{} filename empty", k))
+        } else if v.ends_with(|c| ['/', '\\'].contains(&c)) {
+            Some(format!("{} filename {:?} ends with path separator", k, v))
+        } else if placeholder.map(|p| !v.contains(p)).unwrap_or(false) {
+            Some(format!("{} filename {:?} doesn't contain {}", k, v, placeholder.unwrap()))
+        } else {
+            None
+        };
+
+        if let Some(more) = more {
+            return Err(Error::Parse {
+                tp: "path chunk",
+                wher: "blogue descriptor".into(),
+                more: more.into(),
+            });
+        }
+    }
+
+    Ok(())
 }
 
 fn additional_file(file_opt: Option<String>, root: &(String, PathBuf), tp: &str, error_n: &'static str) -> Result<(String, PathBuf), Error> {
@@ -600,3 +1195,34 @@ fn check_additional_file(root: &(String, PathBuf), fname: &str) -> Option<(Strin
         None
     }
 }
+
+
+fn insert_feed<'a>(feeds: &mut BTreeMap<String, (FeedType, FeedFilter<'a>)>, fname: String, tp: FeedType, filter: FeedFilter<'a>) -> Result<(), Error> {
+    if let Some(&(other_tp, other_filter)) = feeds.get(&fname) {
+        if (other_tp, other_filter) != (tp, filter) {
+            return Err(Error::Parse {
+                tp: "path chunk",
+                wher: "blogue feeds".into(),
+                more: format!("{} {} and {} {} feeds both output to \"{}\"",
+                              feed_filter_name(&other_filter),
+                              other_tp.name(),
+                              feed_filter_name(&filter),
+                              tp.name(),
+                              fname)
+                    .into(),
+            });
+        }
+    } else {
+        feeds.insert(fname, (tp, filter));
+    }
+    Ok(())
+}
+
+fn feed_filter_name(filter: &FeedFilter) -> String {
+    match filter {
+        FeedFilter::All => "main".to_string(),
+        FeedFilter::Tag(tag) => format!("tag {:?}", tag),
+        FeedFilter::Author(author) => format!("author {:?}", author),
+        FeedFilter::Language(language) => format!("language {:?}", language),
+    }
+}
@@ -0,0 +1,361 @@
+use self::super::super::util::{BLOGUEN_VERSION, concat_path};
+use toml::de::from_str as from_toml_str;
+use toml::ser::to_string as to_toml_string;
+use std::io::{ErrorKind as IoErrorKind, Write, Read};
+use std::collections::{BTreeMap, BTreeSet};
+use self::super::super::Error;
+use std::default::Default;
+use std::num::ParseIntError;
+use std::path::PathBuf;
+use std::fs::{self, File};
+use std::iter;
+
+
+/// Name of the manifest file in the output directory.
+pub static MANIFEST_FILE_NAME: &str = ".bloguen-manifest.toml";
+
+
+/// Record of a build's inputs and intermediate outputs, persisted in the output directory.
+///
+/// Used to only regenerate the posts whose inputs changed since the last build.
+#[derive(Debug, Clone, Hash, PartialEq, Eq, PartialOrd, Ord)]
+pub struct BuildManifest {
+    /// Version of `bloguen` the build was made with.
+    pub version: String,
+    /// Hash of the blogue descriptor, including the global language, author, styles, and scripts.
+    pub descriptor: u64,
+    /// Hashes of the post and index templates, keyed by their purpose.
+    pub templates: BTreeMap<String, u64>,
+    /// Per-post records, keyed by the normalised post name.
+    pub posts: BTreeMap<String, PostManifest>,
+}
+
+/// Record of a single post's inputs and the outputs shared with the feeds and index.
+#[derive(Debug, Clone, Hash, PartialEq, Eq, PartialOrd, Ord)]
+pub struct PostManifest {
+    /// Hash of the post's source, metadata, and additional tags.
+    pub source: u64,
+    /// Modification times of the post's assets in nanoseconds since the Unix epoch, keyed by link.
+    pub assets: BTreeMap<String, u64>,
+    /// The post's items in the feeds, keyed by feed filename.
+    pub feeds: BTreeMap<String, String>,
+    /// The post's JSON machine data, as embedded in the index.
+    pub index_machine_data: String,
+    /// The post's formatted index center.
+    pub index_center: String,
+    /// The post's formatted index center for index pages past the first one.
+    pub paged_index_center: String,
+    /// All of the post's tags.
+    pub tags: BTreeSet<String>,
+    /// The post's author.
+    pub author: String,
+    /// The post's language, in BCP47 format.
+    pub language: String,
+    /// The post's formatted tag index center.
+    pub tag_index_center: String,
+    /// Path of the post's HTML relative to the output directory.
+    pub output: String,
+    /// Paths of the post's machine data relative to the output directory.
+    pub machine_outputs: BTreeSet<String>,
+}
+
+#[derive(Serialize, Deserialize)]
+struct BuildManifestSerialised {
+    pub version: String,
+    pub descriptor: String,
+    pub templates: BTreeMap<String, String>,
+    pub posts: BTreeMap<String, PostManifestSerialised>,
+}
+
+#[derive(Serialize, Deserialize)]
+struct PostManifestSerialised {
+    pub source: String,
+    pub index_machine_data: String,
+    pub index_center: String,
+    pub paged_index_center: String,
+    pub tags: BTreeSet<String>,
+    pub author: String,
+    pub language: String,
+    pub tag_index_center: String,
+    pub output: String,
+    pub machine_outputs: BTreeSet<String>,
+    pub assets: BTreeMap<String, u64>,
+    pub feeds: BTreeMap<String, String>,
+}
+
+
+impl BuildManifest {
+    /// Create an empty manifest for the current version with the specified global hashes.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use bloguen::ops::BuildManifest;
+    /// # use bloguen::util::BLOGUEN_VERSION;
+    /// let manifest = BuildManifest::new(0xB10C, vec![("post_header".to_string(), 0x4EAD)].into_iter().collect());
+    /// assert_eq!(manifest.version, BLOGUEN_VERSION);
+    /// assert!(manifest.posts.is_empty());
+    /// ```
+    pub fn new(descriptor: u64, templates: BTreeMap<String, u64>) -> BuildManifest {
+        BuildManifest {
+            version: BLOGUEN_VERSION.to_string(),
+            descriptor: descriptor,
+            templates: templates,
+            posts: BTreeMap::new(),
+        }
+    }
+
+    /// Read the manifest from the specified output directory.
+    ///
+    /// If the manifest file doesn't exist, `Ok(Default::default())` is returned.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use bloguen::ops::{BuildManifest, PostManifest};
+    /// # use std::fs;
+    /// # use std::env::temp_dir;
+    /// # let root = temp_dir().join("bloguen-doctest").join("ops-manifest-read_or_default");
+    /// # let _ = fs::remove_dir_all(&root);
+    /// # fs::create_dir_all(&root).unwrap();
+    /// # /*
+    /// let root: PathBuf = /* obtained elsewhere */;
+    /// # */
+    /// let out_pair = ("$ROOT/out/".to_string(), root.join("out"));
+    /// assert_eq!(BuildManifest::read_or_default(&out_pair), Ok(Default::default()));
+    ///
+    /// let mut manifest = BuildManifest::new(0xB10C, Default::default());
+    /// manifest.posts.insert("01. 2018-01-08 16-52-00 The venture into crocheting".to_string(), PostManifest {
+    ///     source: 0xF00D,
+    ///     assets: vec![("assets/img.png".to_string(), 1535042400000000000)].into_iter().collect(),
+    ///     feeds: vec![("feeds/rss.xml".to_string(), "<item>…</item>".to_string())].into_iter().collect(),
+    ///     index_machine_data: "{}".to_string(),
+    ///     index_center: "<p>Blogg</p>".to_string(),
+    ///     paged_index_center: "<p>Blogg</p>".to_string(),
+    ///     tags: vec!["vodka".to_string(), "коммунизм".to_string()].into_iter().collect(),
+    ///     author: "nabijaczleweli".to_string(),
+    ///     language: "pl".to_string(),
+    ///     tag_index_center: "<p>Blogg</p>".to_string(),
+    ///     output: "posts/01. 2018-01-08 16-52-00 The venture into crocheting.html".to_string(),
+    ///     machine_outputs: vec!["machine/01. 2018-01-08 16-52-00 The venture into crocheting.json".to_string()].into_iter().collect(),
+    /// });
+    /// manifest.write(&out_pair).unwrap();
+    /// assert_eq!(BuildManifest::read_or_default(&out_pair), Ok(manifest));
+    /// ```
+    pub fn read_or_default(output_dir: &(String, PathBuf)) -> Result<BuildManifest, Error> {
+        let mut buf = String::new();
+        match File::open(output_dir.1.join(MANIFEST_FILE_NAME)) {
+                Ok(f) => f,
+                Err(ref e) if e.kind() == IoErrorKind::NotFound => return Ok(Default::default()),
+                Err(e) => {
+                    return Err(Error::Io {
+                        desc: "build manifest".into(),
+                        op: "open",
+                        more: e.to_string().into(),
+                    })
+                }
+            }.read_to_string(&mut buf)
+            .map_err(|_| {
+                Error::Io {
+                    desc: "build manifest".into(),
+                    op: "read",
+                    more: "not UTF-8".into(),
+                }
+            })?;
+
+        let serialised: BuildManifestSerialised = from_toml_str(&buf).map_err(move |err| {
+                Error::FileParsingFailed {
+                    desc: "build manifest".into(),
+                    errors: err.to_string().into(),
+                }
+            })?;
+
+        Ok(BuildManifest {
+            version: serialised.version,
+            descriptor: parse_hash(&serialised.descriptor, "descriptor hash")?,
+            templates: serialised.templates
+                .into_iter()
+                .map(|(k, v)| parse_hash(&v, "template hash").map(|v| (k, v)))
+                .collect::<Result<_, _>>()?,
+            posts: serialised.posts
+                .into_iter()
+                .map(|(k, v)| {
+                    Ok((k,
+                        PostManifest {
+                            source: parse_hash(&v.source, "post source hash")?,
+                            assets: v.assets,
+                            feeds: v.feeds,
+                            index_machine_data: v.index_machine_data,
+                            index_center: v.index_center,
+                            paged_index_center: v.paged_index_center,
+                            tags: v.tags,
+                            author: v.author,
+                            language: v.language,
+                            tag_index_center: v.tag_index_center,
+                            output: v.output,
+                            machine_outputs: v.machine_outputs,
+                        }))
+                })
+                .collect::<Result<_, _>>()?,
+        })
+    }
+
+    /// Write the manifest into the specified output directory, creating it if needed.
+    ///
+    /// See [`read_or_default()`](#method.read_or_default) for examples.
+    pub fn write(&self, output_dir: &(String, PathBuf)) -> Result<(), Error> {
+        let serialised = BuildManifestSerialised {
+            version: self.version.clone(),
+            descriptor: format_hash(self.descriptor),
+            templates: self.templates.iter().map(|(k, &v)| (k.clone(), format_hash(v))).collect(),
+            posts: self.posts
+                .iter()
+                .map(|(k, v)| {
+                    (k.clone(),
+                     PostManifestSerialised {
+                         source: format_hash(v.source),
+                         index_machine_data: v.index_machine_data.clone(),
+                         index_center: v.index_center.clone(),
+                         paged_index_center: v.paged_index_center.clone(),
+                         tags: v.tags.clone(),
+                         author: v.author.clone(),
+                         language: v.language.clone(),
+                         tag_index_center: v.tag_index_center.clone(),
+                         output: v.output.clone(),
+                         machine_outputs: v.machine_outputs.clone(),
+                         assets: v.assets.clone(),
+                         feeds: v.feeds.clone(),
+                     })
+                })
+                .collect(),
+        };
+
+        let data = to_toml_string(&serialised).map_err(|e| {
+                Error::Parse {
+                    tp: "TOML",
+                    wher: "build manifest".into(),
+                    more: e.to_string().into(),
+                }
+            })?;
+        fs::create_dir_all(&output_dir.1)
+            .and_then(|_| File::create(output_dir.1.join(MANIFEST_FILE_NAME)))
+            .and_then(|mut f| f.write_all(data.as_bytes()))
+            .map_err(|e| {
+                Error::Io {
+                    desc: "build manifest".into(),
+                    op: "write",
+                    more: e.to_string().into(),
+                }
+            })
+    }
+
+    /// Check whether the version, descriptor, and templates are the same in both manifests.
+    ///
+    /// If they aren't, all posts need regenerating.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use bloguen::ops::BuildManifest;
+    /// let templates = vec![("post_header".to_string(), 0x4EAD)].into_iter().collect();
+    /// let manifest = BuildManifest::new(0xB10C, templates);
+    ///
+    /// assert!(manifest.globals_match(&manifest.clone()));
+    /// assert!(!manifest.globals_match(&BuildManifest::new(0xB10C, Default::default())));
+    /// assert!(!manifest.globals_match(&Default::default()));
+    /// ```
+    pub fn globals_match(&self, other: &BuildManifest) -> bool {
+        self.version == other.version && self.descriptor == other.descriptor && self.templates == other.templates
+    }
+
+    /// Remove the outputs of the posts in the previous manifest which are no longer output by any post in this one,
+    /// i.e. those of posts since deleted or unpublished, and those left behind by a changed slug or permalink.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use bloguen::ops::{BuildManifest, PostManifest};
+    /// # use std::fs::{self, File};
+    /// # use std::env::temp_dir;
+    /// # let root = temp_dir().join("bloguen-doctest").join("ops-manifest-remove_stale_outputs");
+    /// # let _ = fs::remove_dir_all(&root);
+    /// # fs::create_dir_all(root.join("posts")).unwrap();
+    /// # /*
+    /// let root: PathBuf = /* obtained elsewhere */;
+    /// # */
+    /// let post = PostManifest {
+    ///     source: 0xF00D,
+    ///     assets: Default::default(),
+    ///     feeds: Default::default(),
+    ///     index_machine_data: String::new(),
+    ///     index_center: String::new(),
+    ///     paged_index_center: String::new(),
+    ///     tags: Default::default(),
+    ///     author: "nabijaczleweli".to_string(),
+    ///     language: "pl".to_string(),
+    ///     tag_index_center: String::new(),
+    ///     output: "posts/crocheting.html".to_string(),
+    ///     machine_outputs: Default::default(),
+    /// };
+    /// File::create(root.join("posts").join("crocheting.html")).unwrap();
+    /// File::create(root.join("posts").join("crochet.html")).unwrap();
+    ///
+    /// let mut previous = BuildManifest::new(0xB10C, Default::default());
+    /// previous.posts.insert("01. 2018-01-08 16-52-00 The venture into crocheting".to_string(), post.clone());
+    ///
+    /// let mut manifest = BuildManifest::new(0xB10C, Default::default());
+    /// manifest.posts.insert("01. 2018-01-08 16-52-00 The venture into crocheting".to_string(), PostManifest {
+    ///     output: "posts/crochet.html".to_string(),
+    ///     ..post
+    /// });
+    ///
+    /// manifest.remove_stale_outputs(&previous, &("$ROOT/".to_string(), root.clone())).unwrap();
+    /// assert!(!root.join("posts").join("crocheting.html").exists());
+    /// assert!(root.join("posts").join("crochet.html").exists());
+    /// ```
+    pub fn remove_stale_outputs(&self, previous: &BuildManifest, output_dir: &(String, PathBuf)) -> Result<(), Error> {
+        let current: BTreeSet<_> = self.posts.values().flat_map(|pm| iter::once(&pm.output).chain(&pm.machine_outputs)).collect();
+        for (name, pm) in &previous.posts {
+            for output in iter::once(&pm.output).chain(&pm.machine_outputs).filter(|o| !current.contains(o)) {
+                match fs::remove_file(concat_path(&output_dir.1, output)) {
+                    Ok(()) => {}
+                    Err(ref e) if e.kind() == IoErrorKind::NotFound => {}
+                    Err(e) => {
+                        return Err(Error::Io {
+                            desc: format!("stale post {} output", name).into(),
+                            op: "remove",
+                            more: e.to_string().into(),
+                        })
+                    }
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+impl Default for BuildManifest {
+    fn default() -> BuildManifest {
+        BuildManifest {
+            version: String::new(),
+            descriptor: 0,
+            templates: BTreeMap::new(),
+            posts: BTreeMap::new(),
+        }
+    }
+}
+
+
+fn format_hash(hash: u64) -> String {
+    format!("{:016x}", hash)
+}
+
+fn parse_hash(hash: &str, what: &'static str) -> Result<u64, Error> {
+    u64::from_str_radix(hash, 16).map_err(|e: ParseIntError| {
+        Error::Parse {
+            tp: "hexadecimal unsigned integer",
+            wher: "build manifest".into(),
+            more: format!("{} {:?}: {}", what, hash, e).into(),
+        }
+    })
+}
@@ -0,0 +1,135 @@
+use self::super::super::util::MARKDOWN_OPTIONS;
+use comrak::ComrakOptions;
+
+
+/// Markdown extensions and options to render posts with.
+///
+/// Each option, if not present, falls back to the [`MARKDOWN_OPTIONS`](../util/struct.MARKDOWN_OPTIONS.html) default.
+#[derive(Deserialize, Debug, Default, Copy, Clone, Hash, PartialEq, Eq, PartialOrd, Ord)]
+pub struct MarkdownOptions {
+    /// Whether to turn soft line breaks into `<br />`s.
+    ///
+    /// If not present, defaults to true.
+    pub hardbreaks: Option<bool>,
+    /// Whether to turn straight quotes, `--`, `---`, and `...` into their typographic counterparts.
+    ///
+    /// If not present, defaults to false.
+    pub smart: Option<bool>,
+    /// Whether to parse `~~strikethrough~~`.
+    ///
+    /// If not present, defaults to true.
+    pub strikethrough: Option<bool>,
+    /// Whether to escape the HTML tags GitHub filters out, like `<script>` and `<iframe>`.
+    ///
+    /// If not present, defaults to false.
+    pub tagfilter: Option<bool>,
+    /// Whether to parse GitHub-style tables.
+    ///
+    /// If not present, defaults to true.
+    pub table: Option<bool>,
+    /// Whether to turn bare URLs and e-mail addresses into links.
+    ///
+    /// If not present, defaults to true.
+    pub autolink: Option<bool>,
+    /// Whether to parse `- [ ]` and `- [x]` task list items.
+    ///
+    /// If not present, defaults to true.
+    pub tasklist: Option<bool>,
+    /// Whether to parse `^superscript^`.
+    ///
+    /// If not present, defaults to false.
+    pub superscript: Option<bool>,
+    /// Whether to give the headings IDs to link to, required for the `toc` variable.
+    ///
+    /// If not present, defaults to true.
+    pub header_ids: Option<bool>,
+    /// Whether to parse `[^footnote]`s.
+    ///
+    /// If not present, defaults to false.
+    pub footnotes: Option<bool>,
+    /// Whether to parse description lists, each term followed by a line starting with `: `.
+    ///
+    /// If not present, defaults to false.
+    pub description_lists: Option<bool>,
+}
+
+impl MarkdownOptions {
+    /// Get these options with those not present here taken from the specified ones.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use bloguen::ops::MarkdownOptions;
+    /// let post = MarkdownOptions { footnotes: Some(true), ..Default::default() };
+    /// let blogue = MarkdownOptions { footnotes: Some(false), smart: Some(true), ..Default::default() };
+    /// assert_eq!(post.with_fallback(&blogue),
+    ///            MarkdownOptions { footnotes: Some(true), smart: Some(true), ..Default::default() });
+    /// ```
+    pub fn with_fallback(&self, fallback: &MarkdownOptions) -> MarkdownOptions {
+        MarkdownOptions {
+            hardbreaks: self.hardbreaks.or(fallback.hardbreaks),
+            smart: self.smart.or(fallback.smart),
+            strikethrough: self.strikethrough.or(fallback.strikethrough),
+            tagfilter: self.tagfilter.or(fallback.tagfilter),
+            table: self.table.or(fallback.table),
+            autolink: self.autolink.or(fallback.autolink),
+            tasklist: self.tasklist.or(fallback.tasklist),
+            superscript: self.superscript.or(fallback.superscript),
+            header_ids: self.header_ids.or(fallback.header_ids),
+            footnotes: self.footnotes.or(fallback.footnotes),
+            description_lists: self.description_lists.or(fallback.description_lists),
+        }
+    }
+
+    /// Build the comrak options to parse and format the posts with.
+    ///
+    /// Raw HTML is always passed through.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use bloguen::ops::MarkdownOptions;
+    /// let options = MarkdownOptions { hardbreaks: Some(false), footnotes: Some(true), ..Default::default() }.comrak_options();
+    /// assert!(!options.hardbreaks);
+    /// assert!(options.ext_footnotes);
+    /// assert!(options.ext_table);
+    /// assert!(!options.ext_superscript);
+    /// ```
+    pub fn comrak_options(&self) -> ComrakOptions {
+        let mut ret = MARKDOWN_OPTIONS.clone();
+        if let Some(hardbreaks) = self.hardbreaks {
+            ret.hardbreaks = hardbreaks;
+        }
+        if let Some(smart) = self.smart {
+            ret.smart = smart;
+        }
+        if let Some(strikethrough) = self.strikethrough {
+            ret.ext_strikethrough = strikethrough;
+        }
+        if let Some(tagfilter) = self.tagfilter {
+            ret.ext_tagfilter = tagfilter;
+        }
+        if let Some(table) = self.table {
+            ret.ext_table = table;
+        }
+        if let Some(autolink) = self.autolink {
+            ret.ext_autolink = autolink;
+        }
+        if let Some(tasklist) = self.tasklist {
+            ret.ext_tasklist = tasklist;
+        }
+        if let Some(superscript) = self.superscript {
+            ret.ext_superscript = superscript;
+        }
+        if let Some(header_ids) = self.header_ids {
+            ret.ext_header_ids = if header_ids { Some("".to_string()) } else { None };
+        }
+        if let Some(footnotes) = self.footnotes {
+            ret.ext_footnotes = footnotes;
+        }
+        if let Some(description_lists) = self.description_lists {
+            ret.ext_description_lists = description_lists;
+        }
+        ret
+    }
+}
@@ -1,4 +1,4 @@
-use self::super::{ScriptElement, StyleElement, LanguageTag, TagName};
+use self::super::{MarkdownOptions, ScriptElement, StyleElement, LanguageTag, TagName};
 use toml::de::from_str as from_toml_str;
 use std::collections::BTreeMap;
 use self::super::super::Error;
@@ -19,6 +19,16 @@ pub struct PostMetadata {
     ///
     /// If not present, default post author is used.
     pub author: Option<String>,
+    /// Post title override.
+    ///
+    /// If not present, the title from the post's directory name is used.
+    pub title: Option<String>,
+    /// Post slug override, see [`BloguePost::slug`](struct.BloguePost.html#structfield.slug).
+    ///
+    /// Can't be empty, contain path separators, or be `.` or `..`.
+    ///
+    /// If not present, the post's normalised name is used.
+    pub slug: Option<String>,
     /// A set of tags.
     ///
     /// If not present, defaults to empty.
@@ -35,16 +45,33 @@ pub struct PostMetadata {
     ///
     /// If not present, defaults to empty.
     pub data: BTreeMap<String, String>,
+    /// Whether the post is a draft, to only be generated if requested.
+    ///
+    /// If not present, defaults to false.
+    pub draft: bool,
+    /// Whether to fill out the `toc` variable with a table of contents of the post's headings.
+    ///
+    /// If not present, defaults to true.
+    pub toc: bool,
+    /// Markdown extensions and options to render the post with, overriding the blogue's.
+    ///
+    /// If not present, defaults to empty.
+    pub markdown: MarkdownOptions,
 }
 
 #[derive(Deserialize)]
 struct PostMetadataSerialised {
     pub language: Option<LanguageTag>,
     pub author: Option<String>,
+    pub title: Option<String>,
+    pub slug: Option<String>,
     pub tags: Option<Vec<TagName>>,
     pub styles: Option<Vec<StyleElement>>,
     pub scripts: Option<Vec<ScriptElement>>,
     pub data: Option<BTreeMap<String, String>>,
+    pub draft: Option<bool>,
+    pub toc: Option<bool>,
+    pub markdown: Option<MarkdownOptions>,
 }
 
 impl PostMetadata {
@@ -105,6 +132,8 @@ impl PostMetadata {
     ///            PostMetadata {
     ///                language: Some("pl".parse().unwrap()),
     ///                author: None,
+    ///                title: None,
+    ///                slug: None,
     ///                tags: vec!["vodka".parse().unwrap(), "depression".parse().unwrap()],
     ///                styles: vec![],
     ///                scripts: vec![ScriptElement::from_link("/content/assets/syllable.js"),
@@ -112,6 +141,9 @@ impl PostMetadata {
     ///                data: vec![("desc".to_string(),
     ///                            "Każdy koniec to nowy początek [PL]".to_string())]
     ///                          .into_iter().collect(),
+    ///                draft: false,
+    ///                toc: true,
+    ///                markdown: Default::default(),
     ///            });
     /// ```
     pub fn read_or_default(post_root: &(String, PathBuf)) -> Result<PostMetadata, Error> {
@@ -136,13 +168,37 @@ impl PostMetadata {
                 }
             })?;
 
+        if let Some(slug) = serialised.slug.as_ref() {
+            let more = if slug.is_empty() {
+                Some("slug empty".to_string())
+            } else if slug.contains(|c| ['/', '\\'].contains(&c)) {
+                Some(format!("slug {:?} contains path separator", slug))
+            } else if slug == "." || slug == ".." {
+                Some(format!("slug {:?} is a relative path component", slug))
+            } else {
+                None
+            };
+            if let Some(more) = more {
+                return Err(Error::Parse {
+                    tp: "path chunk",
+                    wher: "post metadata".into(),
+                    more: more.into(),
+                });
+            }
+        }
+
         Ok(PostMetadata {
             language: serialised.language,
             author: serialised.author,
+            title: serialised.title,
+            slug: serialised.slug,
             tags: serialised.tags.unwrap_or_default(),
             styles: serialised.styles.unwrap_or_default(),
             scripts: serialised.scripts.unwrap_or_default(),
             data: serialised.data.unwrap_or_default(),
+            draft: serialised.draft.unwrap_or(false),
+            toc: serialised.toc.unwrap_or(true),
+            markdown: serialised.markdown.unwrap_or_default(),
         })
     }
 }
@@ -152,10 +208,15 @@ impl Default for PostMetadata {
         PostMetadata {
             language: None,
             author: None,
+            title: None,
+            slug: None,
             tags: vec![],
             styles: vec![],
             scripts: vec![],
             data: BTreeMap::new(),
+            draft: false,
+            toc: true,
+            markdown: Default::default(),
         }
     }
 }
@@ -5,35 +5,84 @@
 //!   1. [`BlogueDescriptor::read()`](struct.BlogueDescriptor.html#method.read) to read a blogue descriptor from the filesystem
 //!   2. [`BloguePost::list()`](struct.BloguePost.html#method.list) and pipe the results into
 //!      [`BloguePost::new()`](struct.BloguePost.html#method.new) to discover and load posts from the filesystem
-//!   3. Read in the post header and footer, and, if applicable, index header, footer, and center
-//!   4. [`{Script,Style}Element::load()`](struct.StyleElement.html#method.load) the blogue and index descriptors
-//!   5. [`BlogueDescriptor::create_feed_output()`](struct.BlogueDescriptor.html#method.create_feed_output), yielding feed files
-//!   6. [`BlogueDescriptor::generate_feed_head()`](struct.BlogueDescriptor.html#method.generate_feed_head)s
-//!   7. For each discovered post:
+//!   3. Read in the post header and footer, and, if applicable, index header, footer, and center,
+//!      and [`FormatTemplate::parse_with_includes()`](struct.FormatTemplate.html#method.parse_with_includes) them
+//!   4. [`{Script,Style}Element::load()`](struct.StyleElement.html#method.load) the blogue and index descriptors,
+//!      and add the [`BlogueDescriptorHighlight::stylesheet()`](struct.BlogueDescriptorHighlight.html#method.stylesheet), if any,
+//!      to the blogue's styles
+//!   5. Hash the descriptor and templates into a [`BuildManifest::new()`](struct.BuildManifest.html#method.new), and,
+//!      if building incrementally, [`BuildManifest::read_or_default()`](struct.BuildManifest.html#method.read_or_default)
+//!      the previous one from the output directory
+//!   6. For each discovered post:
 //!     1. [`PostMetadata::read_or_default()`](struct.PostMetadata.html#method.read_or_default) to discover and load any
-//!        metadata that might be present from the filesystem
+//!        metadata that might be present from the filesystem, applying its title and slug overrides to the post,
+//!        then [`BloguePost::apply_permalink_pattern()`](struct.BloguePost.html#method.apply_permalink_pattern) with
+//!        [`BlogueDescriptor::permalink`](struct.BlogueDescriptor.html#structfield.permalink),
+//!        then, once every post's permalink is applied, [`BloguePost::check_unique_paths()`](struct.BloguePost.html#method.check_unique_paths)
 //!     2. [`{Script,Style}Element::load()`](struct.ScriptElement.html#method.load)s
 //!     3. [`TagName::load_additional_post_tags()`](struct.TagName.html#method.load_additional_post_tags)s to discover and load
 //!        any additional tags that might be present from the filesystem
-//!     4. For each pair in [`BlogueDescriptor::machine_data`](struct.BlogueDescriptor.html#structfield.machine_data):
-//!        1. [`BloguePost::create_machine_output()`](struct.BloguePost.html#method.create_machine_output),
-//!           yielding the machine data file
-//!        2. [`BloguePost::generate_machine()`](struct.BloguePost.html#method.generate_machine) into the file from above
-//!     5. If index file requested, [`BloguePost::generate_machine(MachineDataKind::Json)`](struct.BloguePost.html#method.generate_machine) into the script file
-//!     6. [`BloguePost::generate_feed_head()`](struct.BloguePost.html#method.generate_feed_head) into the feed files
-//!     7. [`BloguePost::generate()`](struct.BloguePost.html#method.generate) to create the post HTML, with
-//!        [`feed_type_post_body()`](fn.feed_type_post_body.html)s connected to the alt stream and, if requested, the index center buffer,
-//!        and get the asset list
-//!     8. [`BloguePost::copy_asset()`](struct.BloguePost.html#method.copy_asset) the returned percent-decoded links
-//!        if they're assets
-//!     9. [`BloguePost::generate_feed_foot()`](struct.BloguePost.html#method.generate_feed_foot) into the feed files
-//!   7. [`BlogueDescriptor::generate_feed_foot()`](struct.BlogueDescriptor.html#method.generate_feed_foot)s
-//!   8. If index requested:
+//!     4. Once every post's tags are loaded, [`BloguePost::related_posts()`](struct.BloguePost.html#method.related_posts)
+//!        to rank the posts sharing them
+//!     5. If the post isn't [`BloguePost::is_published()`](struct.BloguePost.html#method.is_published), with its
+//!        [`draft`](struct.BloguePost.html#structfield.draft) flag updated from [`PostMetadata::draft`](struct.PostMetadata.html#structfield.draft),
+//!        remove its previous outputs and skip it
+//!     6. If the globals, [`BloguePost::source_hash()`](struct.BloguePost.html#method.source_hash), and output path match the previous manifest,
+//!        reuse its [`PostManifest`](struct.PostManifest.html), copying only assets whose
+//!        [`BloguePost::asset_modification_time()`](struct.BloguePost.html#method.asset_modification_time) changed, otherwise:
+//!        1. For each pair in [`BlogueDescriptor::machine_data`](struct.BlogueDescriptor.html#structfield.machine_data):
+//!           1. [`BloguePost::create_machine_output()`](struct.BloguePost.html#method.create_machine_output),
+//!              yielding the machine data file
+//!           2. [`BloguePost::generate_machine()`](struct.BloguePost.html#method.generate_machine) into the file from above
+//!        2. If index file requested, [`BloguePost::generate_machine(MachineDataKind::Json)`](struct.BloguePost.html#method.generate_machine)
+//!           into the script buffer
+//!        3. [`BloguePost::generate_feed_head()`](struct.BloguePost.html#method.generate_feed_head) into the buffers for the
+//!           [`BlogueDescriptor::post_feeds()`](struct.BlogueDescriptor.html#method.post_feeds) the post belongs in
+//!        4. [`BloguePost::generate()`](struct.BloguePost.html#method.generate) to create the post HTML from its
+//!           [`BloguePost::source()`](struct.BloguePost.html#method.source), highlighted per
+//!           [`BlogueDescriptor::highlight`](struct.BlogueDescriptor.html#structfield.highlight) and with math rendered per
+//!           [`BlogueDescriptor::math`](struct.BlogueDescriptor.html#structfield.math), rendered with
+//!           [`PostMetadata::markdown`](struct.PostMetadata.html#structfield.markdown)
+//!           [`MarkdownOptions::with_fallback()`](struct.MarkdownOptions.html#method.with_fallback)
+//!           [`BlogueDescriptor::markdown`](struct.BlogueDescriptor.html#structfield.markdown), piped through the
+//!           [`BlogueDescriptor::hooks`](struct.BlogueDescriptor.html#structfield.hooks), with
+//!           [`feed_type_post_body()`](fn.feed_type_post_body.html)s connected to the alt stream, excerpted if
+//!           [`BlogueDescriptor::feed_summaries`](struct.BlogueDescriptor.html#structfield.feed_summaries) is set, and, if requested,
+//!           the index, paged index, and tag index center buffers,
+//!           and get the asset list
+//!        5. [`BloguePost::copy_asset()`](struct.BloguePost.html#method.copy_asset) the returned percent-decoded links
+//!           if they're assets
+//!        6. [`BloguePost::generate_feed_foot()`](struct.BloguePost.html#method.generate_feed_foot) into the feed buffers
+//!        7. Save the buffers, the post's tags, author, and language, and its output paths into a [`PostManifest`](struct.PostManifest.html)
+//!   7. If any post changed, for each feed, including [`BlogueDescriptor::post_feeds()`](struct.BlogueDescriptor.html#method.post_feeds)
+//!      for each post's saved tags, author, and language:
+//!     1. [`BlogueDescriptor::create_feed_output()`](struct.BlogueDescriptor.html#method.create_feed_output), yielding the feed file
+//!     2. [`BlogueDescriptor::generate_feed_head()`](struct.BlogueDescriptor.html#method.generate_feed_head)
+//!     3. Write out the posts' saved feed items
+//!     4. [`BlogueDescriptor::generate_feed_foot()`](struct.BlogueDescriptor.html#method.generate_feed_foot)
+//!   8. If index requested and any post changed:
 //!     1. Concatenate the JSON machine data into an additional script
-//!     2. Create an `index.html` file
-//!     3. [`format_output()`](fn.format_output.html) the index header with the above script
-//!     4. Write out the previously saved centers
-//!     5. [`format_output()`](fn.format_output.html) the index header with the above script
+//!     2. Split the previously saved centers into pages of
+//!        [`BlogueDescriptorIndex::posts_per_page`](struct.BlogueDescriptorIndex.html#structfield.posts_per_page), if specified
+//!     3. For each page, create an `index.html` file for the first one, or a `page/<n>.html` one for the following ones
+//!     4. [`format_output()`](fn.format_output.html) the index header with the above script
+//!     5. Write out the previously saved centers, or the paged centers past the first page
+//!     6. [`format_output()`](fn.format_output.html) the index header with the above script
+//!   9. If tag index requested and any post changed:
+//!     1. Group the posts' saved tags, and [`TagName::check_unique_file_names()`](struct.TagName.html#method.check_unique_file_names) of them
+//!     2. For each tag, create a `tags/<tag>.html` file, named by [`TagName::file_name()`](struct.TagName.html#method.file_name)
+//!     3. [`format_output()`](fn.format_output.html) the tag index header, write out the saved tag centers of posts with the tag,
+//!        and [`format_output()`](fn.format_output.html) the tag index footer
+//!     4. Likewise, create a `tags/index.html` file with the tag cloud center formatted for each tag
+//!   10. If sitemap requested and any post changed:
+//!     1. Collect the index pages, posts, and tag indices, with the later of each post's
+//!        [`datetime`](struct.BloguePost.html#structfield.datetime) and
+//!        [`BloguePost::source_modification_time()`](struct.BloguePost.html#method.source_modification_time)
+//!     2. [`sitemap_header()`](fn.sitemap_header.html), [`sitemap_url()`](fn.sitemap_url.html) for each of the above,
+//!        and [`sitemap_footer()`](fn.sitemap_footer.html) into the sitemap file
+//!     3. If requested, [`robots_txt()`](fn.robots_txt.html) into `robots.txt`
+//!   11. [`BuildManifest::remove_stale_outputs()`](struct.BuildManifest.html#method.remove_stale_outputs) left over from the previous manifest
+//!       and [`BuildManifest::write()`](struct.BuildManifest.html#method.write) the manifest with all posts' records
 //!
 //! Variables available in [`format_output()`](fn.format_output.html):
 //!
@@ -47,37 +96,84 @@
 //! | `normalised_post_name`    | passed-in normalised post name, unformatted                                           | 004. 2018-03-30 06-00-51 Stir plate                           |
 //! | `blog_name`               | passed-in blog name, unformatted                                                      | Блогг                                                         |
 //! | `bloguen-version`         | current version of `bloguen`                                                          | v0.1.1                                                        |
+//! | `base_url`                | the blogue's base URL, or empty if not specified                                      | https://nabijaczleweli.xyz/blogue/                            |
+//! | `permalink`               | link to the post (or index), absolute if base URL specified                           | https://nabijaczleweli.xyz/blogue/posts/004.html              |
 //! | `tags`                    | ↓                                                                                     | `<span class="post-tag">maths</span>`…                        |
 //! | `tags()`                  | all passed-in tags with the default class (`post-tag`)                                | `<span class="post-tag">maths</span>`…                        |
 //! | `tags(class)`             | all passed-in tags with the specified class, headers and footers                      | `<span class="пост-таг">maths</span>`…                        |
+//! | `tag_index`               | link to the tag index directory, if passed in; tags link to their indices therein     | ../tags/                                                      |
+//! | `related_posts(n)`        | links to up to `n` passed-in related posts, most related first                        | `<a class="related-post" href="../posts/002.html">Amps</a>`…  |
+//! | `draft`                   | `draft` if the post is a draft, empty otherwise                                       | draft                                                         |
+//! | `toc`                     | nested lists of links to the post's headings, if any, requested, and with header IDs  | `<ul><li><a href="#introduction">Introduction</a>`…           |
+//! | `excerpt`                 | post up to a `<!-- more -->` line, or its first paragraph, see [`write_excerpt()`]    | `<p>Intro</p>`…                                               |
+//! | `word_count`              | amount of words in the post, not counting code or raw HTML                            | 1312                                                          |
+//! | `reading_time`            | ↓                                                                                     | 7                                                             |
+//! | `reading_time(wpm)`       | minutes it takes to read the post at `wpm` words per minute, 200 by default, rounded up | 7                                                             |
+//! | `page_number`             | the index page's number, starting at 1, in index headers and footers                  | 2                                                             |
+//! | `page_count`              | amount of index pages, in index headers and footers                                   | 5                                                             |
+//! | `prev_page_link`          | link to the previous index page, in index headers and footers; empty on the first one | ../index.html                                                 |
+//! | `next_page_link`          | link to the next index page, in index headers and footers; empty on the last one      | 3.html                                                        |
+//! | `prev_post_title`         | the previous post's title, in post headers and footers and index centers              | Big speakers                                                  |
+//! | `prev_post_name`          | the previous post's normalised name, empty if there is none                           | 003. 2018-03-27 13-07-18 Big speakers                         |
+//! | `prev_post_link`          | link to the previous post, absolute if base URL specified, empty if there is none     | ../posts/003.html                                             |
+//! | `next_post_title`         | the next post's title, in post headers and footers and index centers                  | Small speakers                                                |
+//! | `next_post_name`          | the next post's normalised name, empty if there is none                               | 005. 2018-04-01 09-00-00 Small speakers                       |
+//! | `next_post_link`          | link to the next post, absolute if base URL specified, empty if there is none         | ../posts/005.html                                             |
+//! | `tag`                     | the tag, in tag indices; empty in the tag overview                                    | maths                                                         |
+//! | `tag_post_count`          | amount of posts with the tag, in tag indices; empty in the tag overview               | 3                                                             |
+//! | `tag_count`               | amount of tags, in tag indices and the tag overview                                   | 12                                                            |
+//! | `tag_weight`              | the tag's popularity from 1 to 5, in tag overview centers                             | 2                                                             |
 //! | `styles`                  | all the passed-in styles with their headers and footers                               | `<style type="text/css">* {color: magenta;}</style>`…         |
 //! | `scripts`                 | all the passed-in scripts with their headers and footers                              | `<script type="text/javascript">alert("hewwo")</script>`…     |
 //! | `data-name`               | passed-in data under the `name` key, unformatted                                      | hewwo                                                         |
+//! | `data(name, fallback)`    | passed-in data under the `name` key, or `fallback` if not specified                   | No description                                                |
+//! | `data(name)`              | passed-in data under the `name` key, or nothing if not specified                      | hewwo                                                         |
+//! | `has_data(name)`          | `true` if data under the `name` key is specified, even if empty, nothing otherwise    | true                                                          |
 //! | `date(post, format)`      | post date formatted with [`parse_date_format_specifier(format)`]                      | Thu,  6 Sep 2018 18:32:22 +0200                               |
 //! | `date(now_utc, format)`   | current date in UTC formatted with [`parse_date_format_specifier(format)`]            | Thu,  6 Sep 2018 18:32:22 +0200                               |
 //! | `date(now_local, format)` | current date in local timezone formatted with [`parse_date_format_specifier(format)`] | Thu,  6 Sep 2018 18:32:22 +0200                               |
+//! | `date(prev_post, format)` | previous post's date formatted with [`parse_date_format_specifier(format)`]           | Tue, 27 Mar 2018 13:07:18 +0200                               |
+//! | `date(next_post, format)` | next post's date formatted with [`parse_date_format_specifier(format)`]               | Sun,  1 Apr 2018 09:00:00 +0200                               |
 //! | `machine_data(kind)`      | machine data of the specified kind                                                    | `{"number": 3, "language": "en-GB", …}`…                      |
 //! | `pass_paragraphs(n, var)` | parse `var` and write its contents formatted through [`ParagraphPasser`]              | `<p>Paragraph 1</p> <p>Paragraph 2</p>`…                      |
 //!
+//! Variables can be escaped with filters like `{title|html}`, tested with `{if var}…{else}…{end}`,
+//! the tags iterated over with `{for tag in tags}…{end}`, and other templates included with `{include(path)}`,
+//! see [`FormatTemplate`](struct.FormatTemplate.html).
+//!
 //! [`parse_date_format_specifier(format)`]: fn.parse_date_format_specifier.html
 //! [`ParagraphPasser`]: struct.ParagraphPasser.html
+//! [`write_excerpt()`]: ../util/fn.write_excerpt.html
 
 mod language_tag;
 mod descriptor;
+mod manifest;
+mod markdown_options;
 mod metadata;
 mod tag_name;
+mod source_snapshot;
+mod source_format;
+mod render_options;
+mod preview_server;
 mod output;
 mod post;
 
 pub use self::output::{WrappedElementImpl, ParagraphPasser, WrappedElement, ScriptElement, StyleElement, feed_type_post_footer, feed_atom_post_footer,
                        feed_rss_post_footer, feed_type_post_header, feed_atom_post_header, feed_rss_post_header, feed_type_post_body, feed_atom_post_body,
                        feed_rss_post_body, machine_output_json, machine_output_kind, feed_type_header, feed_type_footer, feed_atom_header, feed_rss_header,
-                       feed_atom_footer, feed_rss_footer, format_output};
-pub use self::descriptor::{BlogueDescriptorIndex, BlogueDescriptor};
+                       feed_atom_footer, feed_rss_footer, sitemap_header, sitemap_footer, sitemap_url, robots_txt, format_output,
+                       FormatTemplate};
+pub use self::descriptor::{BlogueDescriptorHighlight, BlogueDescriptorTagIndex, BlogueDescriptorHooks, BlogueDescriptorIndex, BlogueDescriptor, FeedFilter};
+pub use self::manifest::{MANIFEST_FILE_NAME, BuildManifest, PostManifest};
+pub use self::markdown_options::MarkdownOptions;
 pub use self::machine_data::MachineDataKind;
 pub use self::center_order::CenterOrder;
 pub use self::language_tag::LanguageTag;
 pub use self::metadata::PostMetadata;
+pub use self::source_snapshot::SourceSnapshot;
+pub use self::source_format::{SOURCE_FORMATS, MarkdownSource, SourceFormat, HtmlSource, source_format};
+pub use self::render_options::PostRenderOptions;
+pub use self::preview_server::{RequestTarget, PreviewServer, resolve_request, mime_type};
 pub use self::feed_type::FeedType;
 pub use self::tag_name::TagName;
 pub use self::post::BloguePost;
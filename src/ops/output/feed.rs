@@ -25,6 +25,7 @@ pub fn feed_type_header<W, E>(kind: &FeedType)
                                      language: &LanguageTag,
                                      author: &str,
                                      link: Option<Cow<'static, str>>,
+                                     self_link: Option<Cow<'static, str>>,
                                      into: &mut W,
                                      out_name_err: E)
                                      -> Result<Cow<'static, str>, Error>)
@@ -63,6 +64,7 @@ pub fn feed_type_post_header<W, E, Tz>(kind: &FeedType)
                                               base: &str,
                                               link: &str,
                                               post_date: &DateTime<Tz>,
+                                              summary: bool,
                                               into: &mut W,
                                               out_name_err: E)
                                               -> Result<Cow<'static, str>, Error>)
@@ -93,7 +95,7 @@ pub fn feed_type_post_body<W>(kind: &FeedType) -> (for<'w> fn(into: &'w mut W) -
 ///
 /// Returns [`feed_rss_post_footer()`](fn.feed_rss_post_footer.html) for `FeedType::Rss`,
 ///     and [`feed_atom_post_footer()`](fn.feed_atom_post_footer.html) for `FeedType::Atom`.
-pub fn feed_type_post_footer<W, E>(kind: &FeedType) -> (fn(into: &mut W, out_name_err: E) -> Result<Cow<'static, str>, Error>)
+pub fn feed_type_post_footer<W, E>(kind: &FeedType) -> (fn(summary: bool, into: &mut W, out_name_err: E) -> Result<Cow<'static, str>, Error>)
     where W: Write,
           E: Into<Cow<'static, str>>
 {
@@ -109,6 +111,8 @@ pub fn feed_type_post_footer<W, E>(kind: &FeedType) -> (fn(into: &mut W, out_nam
 /// The `link` argument corresponds to the link tag, linking to the index page,
 /// and, if not present, will not be output.
 ///
+/// The `self_link` argument is the absolute URL of the feed itself, output as an `atom:link`, if present.
+///
 /// # Examples
 ///
 /// ```
@@ -117,7 +121,7 @@ pub fn feed_type_post_footer<W, E>(kind: &FeedType) -> (fn(into: &mut W, out_nam
 /// # use std::str;
 /// let mut out = vec![];
 /// let res = feed_rss_header(
-///     "Блогг", &LANGUAGE_EN_GB, "nabijaczleweli", Some("../index.html".into()),
+///     "Блогг", &LANGUAGE_EN_GB, "nabijaczleweli", Some("../index.html".into()), None,
 ///     &mut out, "test blog");
 /// assert_eq!(res, Ok("test blog".into()));
 ///
@@ -128,7 +132,7 @@ pub fn feed_type_post_footer<W, E>(kind: &FeedType) -> (fn(into: &mut W, out_nam
 /// let gendate_local_rfc2822 = /* extracted from output's lastBuildDate tag */;
 /// # */
 /// assert_eq!(out, format!(r###"<?xml version="1.0" encoding="UTF-8"?>
-/// <rss version="2.0">
+/// <rss version="2.0" xmlns:atom="http://www.w3.org/2005/Atom">
 ///   <channel>
 ///     <docs>https://validator.w3.org/feed/docs/rss2.html</docs>
 ///     <title>Блогг</title>
@@ -141,12 +145,13 @@ pub fn feed_type_post_footer<W, E>(kind: &FeedType) -> (fn(into: &mut W, out_nam
 ///     <lastBuildDate>{0}</lastBuildDate>
 /// "###, gendate_local_rfc2822));
 /// ```
-pub fn feed_rss_header<W, E>(blog_name: &str, language: &LanguageTag, author: &str, link: Option<Cow<'static, str>>, into: &mut W, out_name_err: E)
+pub fn feed_rss_header<W, E>(blog_name: &str, language: &LanguageTag, author: &str, link: Option<Cow<'static, str>>, self_link: Option<Cow<'static, str>>,
+                             into: &mut W, out_name_err: E)
                              -> Result<Cow<'static, str>, Error>
     where W: Write,
           E: Into<Cow<'static, str>>
 {
-    feed_rss_header_impl(blog_name, language, author, link, into, out_name_err.into())
+    feed_rss_header_impl(blog_name, language, author, link, self_link, into, out_name_err.into())
 }
 
 /// Output the footer for an RSS feed,
@@ -177,7 +182,8 @@ pub fn feed_rss_footer<W, E>(into: &mut W, out_name_err: E) -> Result<Cow<'stati
 ///
 /// The `post_id_name` argment is used as the entry GUID,
 /// the `base` argument is unused,
-/// the `link` argument points to the corresponding post HTML.
+/// the `link` argument points to the corresponding post HTML,
+/// the `summary` argument is unused, as the description holds either the post contents or its summary.
 ///
 /// # Examples
 ///
@@ -194,7 +200,7 @@ pub fn feed_rss_footer<W, E>(into: &mut W, out_name_err: E) -> Result<Cow<'stati
 ///     "003. 2018-02-05 release-front - a generic release front-end, like Patchwork's",
 ///     &LANGUAGE_EN_GB, "nabijaczleweli", "../posts/",
 ///     "../posts/003. 2018-02-05 release-front - a generic release front-end, like Patchwork's.html",
-///     &DateTime::parse_from_rfc3339("2018-09-06T18:32:22+02:00").unwrap(),
+///     &DateTime::parse_from_rfc3339("2018-09-06T18:32:22+02:00").unwrap(), false,
 ///     &mut out, "test post");
 /// assert_eq!(res, Ok("test post".into()));
 ///
@@ -210,7 +216,7 @@ pub fn feed_rss_footer<W, E>(into: &mut W, out_name_err: E) -> Result<Cow<'stati
 /// "###);
 /// ```
 pub fn feed_rss_post_header<W, E, Tz>(post_name: &str, post_id_name: &str, language: &LanguageTag, author: &str, base: &str, link: &str,
-                                      post_date: &DateTime<Tz>, into: &mut W, out_name_err: E)
+                                      post_date: &DateTime<Tz>, summary: bool, into: &mut W, out_name_err: E)
                                       -> Result<Cow<'static, str>, Error>
     where Tz: TimeZone,
           W: Write,
@@ -223,19 +229,22 @@ pub fn feed_rss_post_header<W, E, Tz>(post_name: &str, post_id_name: &str, langu
                               base,
                               link,
                               normalise_datetime(post_date),
+                              summary,
                               into,
                               out_name_err.into())
 }
 
 /// Output the post footer for an RSS feed,
 ///
+/// The `summary` argument is unused.
+///
 /// # Examples
 ///
 /// ```
 /// # use bloguen::ops::feed_rss_post_footer;
 /// # use std::str;
 /// let mut out = vec![];
-/// let res = feed_rss_post_footer(&mut out, "test post");
+/// let res = feed_rss_post_footer(false, &mut out, "test post");
 /// assert_eq!(res, Ok("test post".into()));
 ///
 /// let out = str::from_utf8(&out).unwrap();
@@ -243,15 +252,15 @@ pub fn feed_rss_post_header<W, E, Tz>(post_name: &str, post_id_name: &str, langu
 ///     </item>
 /// "###);
 /// ```
-pub fn feed_rss_post_footer<W, E>(into: &mut W, out_name_err: E) -> Result<Cow<'static, str>, Error>
+pub fn feed_rss_post_footer<W, E>(summary: bool, into: &mut W, out_name_err: E) -> Result<Cow<'static, str>, Error>
     where W: Write,
           E: Into<Cow<'static, str>>
 {
-    feed_rss_post_footer_impl(into, out_name_err.into())
+    feed_rss_post_footer_impl(summary, into, out_name_err.into())
 }
 
-fn feed_rss_header_impl<W>(blog_name: &str, language: &LanguageTag, author: &str, link: Option<Cow<'static, str>>, into: &mut W,
-                           out_name_err: Cow<'static, str>)
+fn feed_rss_header_impl<W>(blog_name: &str, language: &LanguageTag, author: &str, link: Option<Cow<'static, str>>, self_link: Option<Cow<'static, str>>,
+                           into: &mut W, out_name_err: Cow<'static, str>)
                            -> Result<Cow<'static, str>, Error>
     where W: Write
 {
@@ -265,6 +274,11 @@ fn feed_rss_header_impl<W>(blog_name: &str, language: &LanguageTag, author: &str
             if let Some(link) = link {
                 write_tag("link", link, into)?;
             }
+            if let Some(self_link) = self_link {
+                into.write_all(b"    <atom:link href=\"").map_err(|e| (e, "self link tag header".into()))?;
+                XmlEscapeWrite(&mut *into).write_all(self_link.as_bytes()).map_err(|e| (e, "self link tag".into()))?;
+                into.write_all(b"\" rel=\"self\" type=\"application/rss+xml\" />\n").map_err(|e| (e, "self link tag footer".into()))?;
+            }
             write_tag("description", blog_name, into)?;
             write_tag("language", language, into)?;
             write_tag("generator", format!("bloguen {}", BLOGUEN_VERSION), into)?;
@@ -294,7 +308,7 @@ fn feed_rss_footer_impl<W>(into: &mut W, out_name_err: Cow<'static, str>) -> Res
 }
 
 fn feed_rss_post_header_impl<W>(post_name: &str, post_id_name: &str, _: &LanguageTag, author: &str, _: &str, link: &str, post_date: DateTime<FixedOffset>,
-                                into: &mut W, out_name_err: Cow<'static, str>)
+                                _: bool, into: &mut W, out_name_err: Cow<'static, str>)
                                 -> Result<Cow<'static, str>, Error>
     where W: Write
 {
@@ -327,7 +341,7 @@ pub fn feed_rss_post_body<'w, W>(into: &'w mut W) -> Box<dyn Write + 'w>
     Box::new(XmlEscapeWrite(into))
 }
 
-fn feed_rss_post_footer_impl<W>(into: &mut W, out_name_err: Cow<'static, str>) -> Result<Cow<'static, str>, Error>
+fn feed_rss_post_footer_impl<W>(_: bool, into: &mut W, out_name_err: Cow<'static, str>) -> Result<Cow<'static, str>, Error>
     where W: Write
 {
     let mut out_name_err = Some(out_name_err);
@@ -348,6 +362,9 @@ fn feed_rss_post_footer_impl<W>(into: &mut W, out_name_err: Cow<'static, str>) -
 /// The `link` argument corresponds to the link tag, linking to the index page,
 /// and, if not present, will not be output.
 ///
+/// The `self_link` argument is the absolute URL of the feed itself, output as a `self` link and used as the feed ID,
+/// if present.
+///
 /// # Examples
 ///
 /// ```
@@ -356,7 +373,7 @@ fn feed_rss_post_footer_impl<W>(into: &mut W, out_name_err: Cow<'static, str>) -
 /// # use std::str;
 /// let mut out = vec![];
 /// let res = feed_atom_header(
-///     "Блогг", &LANGUAGE_EN_GB, "nabijaczleweli", Some("../index.html".into()),
+///     "Блогг", &LANGUAGE_EN_GB, "nabijaczleweli", Some("../index.html".into()), None,
 ///     &mut out, "test blog");
 /// assert_eq!(res, Ok("test blog".into()));
 ///
@@ -378,12 +395,13 @@ fn feed_rss_post_footer_impl<W>(into: &mut W, out_name_err: Cow<'static, str>) -
 ///   <updated>{}</updated>
 /// "###, gendate_local_rfc3339));
 /// ```
-pub fn feed_atom_header<W, E>(blog_name: &str, language: &LanguageTag, author: &str, link: Option<Cow<'static, str>>, into: &mut W, out_name_err: E)
+pub fn feed_atom_header<W, E>(blog_name: &str, language: &LanguageTag, author: &str, link: Option<Cow<'static, str>>, self_link: Option<Cow<'static, str>>,
+                              into: &mut W, out_name_err: E)
                               -> Result<Cow<'static, str>, Error>
     where W: Write,
           E: Into<Cow<'static, str>>
 {
-    feed_atom_header_impl(blog_name, language, author, link, into, out_name_err.into())
+    feed_atom_header_impl(blog_name, language, author, link, self_link, into, out_name_err.into())
 }
 
 /// Output the footer for an Atom feed,
@@ -414,7 +432,8 @@ pub fn feed_atom_footer<W, E>(into: &mut W, out_name_err: E) -> Result<Cow<'stat
 /// The `post_id_name` argment is used as the entry GUID,
 /// the `base` argument is the `xml:base` attribute, and should point to thr posts output directory to properly handle image
 /// links,
-/// the `link` argument points to the corresponding post HTML.
+/// the `link` argument points to the corresponding post HTML,
+/// the `summary` argument opens a `summary` element instead of a `content` one.
 ///
 /// # Examples
 ///
@@ -431,7 +450,7 @@ pub fn feed_atom_footer<W, E>(into: &mut W, out_name_err: E) -> Result<Cow<'stat
 ///     "003. 2018-02-05 release-front - a generic release front-end, like Patchwork's",
 ///     &LANGUAGE_EN_GB, "nabijaczleweli", "../posts/",
 ///     "../posts/003. 2018-02-05 release-front - a generic release front-end, like Patchwork's.html",
-///     &DateTime::parse_from_rfc3339("2018-09-06T18:32:22+02:00").unwrap(),
+///     &DateTime::parse_from_rfc3339("2018-09-06T18:32:22+02:00").unwrap(), false,
 ///     &mut out, "test post");
 /// assert_eq!(res, Ok("test post".into()));
 ///
@@ -450,7 +469,7 @@ pub fn feed_atom_footer<W, E>(into: &mut W, out_name_err: E) -> Result<Cow<'stat
 /// "###);
 /// ```
 pub fn feed_atom_post_header<W, E, Tz>(post_name: &str, post_id_name: &str, language: &LanguageTag, author: &str, base: &str, link: &str,
-                                       post_date: &DateTime<Tz>, into: &mut W, out_name_err: E)
+                                       post_date: &DateTime<Tz>, summary: bool, into: &mut W, out_name_err: E)
                                        -> Result<Cow<'static, str>, Error>
     where Tz: TimeZone,
           W: Write,
@@ -463,34 +482,43 @@ pub fn feed_atom_post_header<W, E, Tz>(post_name: &str, post_id_name: &str, lang
                                base,
                                link,
                                normalise_datetime(post_date),
+                               summary,
                                into,
                                out_name_err.into())
 }
 
 /// Output the post footer for an Atom feed,
 ///
+/// The `summary` argument closes a `summary` element instead of a `content` one,
+/// and should match the one passed to [`feed_atom_post_header()`](fn.feed_atom_post_header.html).
+///
 /// # Examples
 ///
 /// ```
 /// # use bloguen::ops::feed_atom_post_footer;
 /// # use std::str;
 /// let mut out = vec![];
-/// let res = feed_atom_post_footer(&mut out, "test post");
+/// let res = feed_atom_post_footer(false, &mut out, "test post");
 /// assert_eq!(res, Ok("test post".into()));
 ///
 /// let out = str::from_utf8(&out).unwrap();
 /// assert_eq!(out, r###"    </content>
 ///   </entry>
 /// "###);
+///
+/// let mut out = vec![];
+/// feed_atom_post_footer(true, &mut out, "test post").unwrap();
+/// assert_eq!(str::from_utf8(&out).unwrap(), "    </summary>\n  </entry>\n");
 /// ```
-pub fn feed_atom_post_footer<W, E>(into: &mut W, out_name_err: E) -> Result<Cow<'static, str>, Error>
+pub fn feed_atom_post_footer<W, E>(summary: bool, into: &mut W, out_name_err: E) -> Result<Cow<'static, str>, Error>
     where W: Write,
           E: Into<Cow<'static, str>>
 {
-    feed_atom_post_footer_impl(into, out_name_err.into())
+    feed_atom_post_footer_impl(summary, into, out_name_err.into())
 }
 
-fn feed_atom_header_impl<W>(blog_name: &str, _: &LanguageTag, author: &str, link: Option<Cow<'static, str>>, into: &mut W, out_name_err: Cow<'static, str>)
+fn feed_atom_header_impl<W>(blog_name: &str, _: &LanguageTag, author: &str, link: Option<Cow<'static, str>>, self_link: Option<Cow<'static, str>>,
+                            into: &mut W, out_name_err: Cow<'static, str>)
                             -> Result<Cow<'static, str>, Error>
     where W: Write
 {
@@ -510,7 +538,15 @@ fn feed_atom_header_impl<W>(blog_name: &str, _: &LanguageTag, author: &str, link
                 into.write_all(link.as_bytes()).map_err(|e| (e, "link tag".into()))?;
                 into.write_all(b"\" />\n").map_err(|e| (e, "link tag footer".into()))?;
             }
-            write_tag_atom("id", Uuid::new_v5(&Uuid::NAMESPACE_URL, blog_name.as_bytes()).to_urn_ref(), into)?;
+            if let Some(self_link) = self_link {
+                into.write_all(b"  <link rel=\"self\" href=\"").map_err(|e| (e, "self link tag header".into()))?;
+                XmlEscapeWrite(&mut *into).write_all(self_link.as_bytes()).map_err(|e| (e, "self link tag".into()))?;
+                into.write_all(b"\" />\n").map_err(|e| (e, "self link tag footer".into()))?;
+
+                write_tag_atom("id", self_link, into)?;
+            } else {
+                write_tag_atom("id", Uuid::new_v5(&Uuid::NAMESPACE_URL, blog_name.as_bytes()).to_urn_ref(), into)?;
+            }
 
             // TODO: language?
 
@@ -542,7 +578,7 @@ fn feed_atom_footer_impl<W>(into: &mut W, out_name_err: Cow<'static, str>) -> Re
 }
 
 fn feed_atom_post_header_impl<W>(post_name: &str, post_id_name: &str, language: &LanguageTag, author: &str, base: &str, link: &str,
-                                 post_date: DateTime<FixedOffset>, into: &mut W, out_name_err: Cow<'static, str>)
+                                 post_date: DateTime<FixedOffset>, summary: bool, into: &mut W, out_name_err: Cow<'static, str>)
                                  -> Result<Cow<'static, str>, Error>
     where W: Write
 {
@@ -566,7 +602,12 @@ fn feed_atom_post_header_impl<W>(post_name: &str, post_id_name: &str, language:
             write_date("published", &post_date, FixedTimeFormatItem::RFC3339, into)?;
             write_tag("guid", Uuid::new_v5(&Uuid::NAMESPACE_URL, post_id_name.as_bytes()).to_urn_ref(), into)?;
 
-            into.write_all(b"    <content type=\"html\" xml:lang=\"").map_err(|e| (e, "header content tag header".into()))?;
+            into.write_all(if summary {
+                        &b"    <summary type=\"html\" xml:lang=\""[..]
+                    } else {
+                        &b"    <content type=\"html\" xml:lang=\""[..]
+                    })
+                .map_err(|e| (e, "header content tag header".into()))?;
             into.write_fmt(format_args!("{}", language)).map_err(|e| (e, "header content tag language".into()))?;
             into.write_all(b"\" xml:base=\"").map_err(|e| (e, "header content tag middle".into()))?;
             into.write_all(base.as_bytes()).map_err(|e| (e, "header content tag base".into()))?;
@@ -588,13 +629,13 @@ pub fn feed_atom_post_body<'w, W>(into: &'w mut W) -> Box<dyn Write + 'w>
     Box::new(XmlEscapeWrite(into))
 }
 
-fn feed_atom_post_footer_impl<W>(into: &mut W, out_name_err: Cow<'static, str>) -> Result<Cow<'static, str>, Error>
+fn feed_atom_post_footer_impl<W>(summary: bool, into: &mut W, out_name_err: Cow<'static, str>) -> Result<Cow<'static, str>, Error>
     where W: Write
 {
     let mut out_name_err = Some(out_name_err);
 
     (|| {
-            into.write_all(b"    </content>\n").map_err(|e| (e, "footer content tag".into()))?;
+            into.write_all(if summary { b"    </summary>\n" } else { b"    </content>\n" }).map_err(|e| (e, "footer content tag".into()))?;
             into.write_all(b"  </entry>\n").map_err(|e| (e, "footer entry tag".into()))?;
 
             Ok(())
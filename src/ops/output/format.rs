@@ -1,4 +1,6 @@
-use self::super::super::super::util::{BLOGUEN_VERSION, parse_date_format_specifier, parse_function_notation, normalise_datetime};
+use self::super::super::super::util::{BLOGUEN_VERSION, DEFAULT_READING_SPEED, XmlEscapeWrite, parse_date_format_specifier, parse_function_notation,
+                                      normalise_datetime, concat_path, reading_time, read_file};
+use percent_encoding::{AsciiSet, NON_ALPHANUMERIC, utf8_percent_encode};
 use self::super::super::{MachineDataKind, ParagraphPasser, WrappedElement, LanguageTag, TagName};
 use chrono::{FixedOffset, DateTime, TimeZone, Local, Utc};
 use self::super::{machine_output_kind, err_io};
@@ -6,7 +8,15 @@ use std::io::{Error as IoError, Write};
 use self::super::super::super::Error;
 use std::collections::BTreeMap;
 use std::iter::FromIterator;
+use std::path::{PathBuf, Path};
 use std::borrow::Cow;
+use std::str::FromStr;
+use std::cmp;
+use std::mem;
+
+
+/// Characters escaped by the `url` filter: all but the RFC 3986 unreserved ones.
+static URL_FILTER_ESCAPED: &AsciiSet = &NON_ALPHANUMERIC.remove(b'-').remove(b'_').remove(b'.').remove(b'~');
 
 
 lazy_static! {
@@ -14,6 +24,12 @@ lazy_static! {
     static ref TAG_CNTR: &'static str = include_str!("../../../assets/element_wrappers/tag/cntr.htm").trim();
     static ref TAG_FOOT: &'static str = include_str!("../../../assets/element_wrappers/tag/foot.htm").trim();
     static ref TAG_DEFAULT_CLASS: &'static str = include_str!("../../../assets/element_wrappers/tag/default.class").trim();
+    static ref TAG_LINK_HEAD: &'static str = include_str!("../../../assets/element_wrappers/tag/link_head.htm").trim();
+    static ref TAG_LINK_CNTR: &'static str = include_str!("../../../assets/element_wrappers/tag/link_cntr.htm").trim();
+    static ref TAG_LINK_FOOT: &'static str = include_str!("../../../assets/element_wrappers/tag/link_foot.htm").trim();
+    static ref RELATED_POST_HEAD: &'static str = include_str!("../../../assets/element_wrappers/related_post/head.htm").trim();
+    static ref RELATED_POST_CNTR: &'static str = include_str!("../../../assets/element_wrappers/related_post/cntr.htm").trim();
+    static ref RELATED_POST_FOOT: &'static str = include_str!("../../../assets/element_wrappers/related_post/foot.htm").trim();
 }
 
 
@@ -21,6 +37,15 @@ lazy_static! {
 ///
 /// All fields must be addressed even if formatted to be empty.
 ///
+/// `{if}` and `{for}` blocks are handled as described in [`FormatTemplate`](struct.FormatTemplate.html).
+///
+/// The `variables` are substituted under their own names, without a prefix.
+///
+/// If the `tag_index` variable is specified, the tags link to their `{tag_index}<tag>.html` indices.
+///
+/// The `related_posts` are `(title, link)` pairs, most related first, as ranked by
+/// [`BloguePost::related_posts()`](struct.BloguePost.html#method.related_posts).
+///
 /// # Examples
 ///
 /// ```
@@ -51,6 +76,7 @@ lazy_static! {
 ///     <meta name="author" content="{author}">
 ///     <meta name="description" content="{data-desc}">
 ///     <title>{title}</title>
+///     <link rel="canonical" href="{permalink}">
 ///
 ///     {styles}
 ///     {scripts}
@@ -70,15 +96,18 @@ lazy_static! {
 ///           "Każdy koniec to nowy początek [PL]".to_string()),
 ///          ("insertable".to_string(),
 ///           "<p>Hi!</p>\n<p>My name is…</p>\n<p>What?</p>\n".to_string())].into_iter().collect();
+/// let variables =
+///     vec![("permalink", "https://nabijaczleweli.xyz/blogue/posts/003.html".to_string())].into_iter().collect();
 /// let mut out = vec![];
 /// let res = format_output(
-///     head, "Блогг", &LANGUAGE_EN_GB, &[&global_data, &local_data],
+///     &head.parse().unwrap(), "Блогг", &LANGUAGE_EN_GB, &[&global_data, &local_data], &variables,
 ///     "003. 2018-02-05 release-front - a generic release front-end, like Patchwork's",
 ///     "003. 2018-02-05 12-33-05 release-front - a generic release front-end, like Patchwork's",
 ///     3, "release-front - a generic release front-end, like Patchwork's", "nabijaczleweli",
 ///     &DateTime::parse_from_rfc3339("2018-09-06T18:32:22+02:00").unwrap(),
 ///     &[&["vodka".parse().unwrap(), "depression".parse().unwrap()][..],
 ///       &["коммунизм".parse().unwrap()][..]],
+///     &[],
 ///     &[&[StyleElement::from_link("//nabijaczleweli.xyz/kaschism/assets/column.css")],
 ///       &[StyleElement::from_literal(".indented { text-indent: 1em; }")]],
 ///     &[&[ScriptElement::from_link("/content/assets/syllable.js")],
@@ -108,6 +137,7 @@ lazy_static! {
 ///     <meta name="author" content="nabijaczleweli">
 ///     <meta name="description" content="Każdy koniec to nowy początek [PL]">
 ///     <title>release-front - a generic release front-end, like Patchwork's</title>
+///     <link rel="canonical" href="https://nabijaczleweli.xyz/blogue/posts/003.html">
 ///
 ///     <link href="//nabijaczleweli.xyz/kaschism/assets/column.css" rel="stylesheet" />
 /// <style type="text/css">
@@ -135,9 +165,10 @@ lazy_static! {
 ///
 /// "###);
 /// ```
-pub fn format_output<W, E, Tz, St, Sc>(to_format: &str, blog_name: &str, language: &LanguageTag, additional_data_sets: &[&BTreeMap<String, String>],
-                                       raw_post_name: &str, normalised_post_name: &str, number: usize, title: &str, author: &str, post_date: &DateTime<Tz>,
-                                       tags: &[&[TagName]], styles: &[&[St]], scripts: &[&[Sc]], into: &mut W, out_name_err: E)
+pub fn format_output<W, E, Tz, St, Sc>(to_format: &FormatTemplate, blog_name: &str, language: &LanguageTag, additional_data_sets: &[&BTreeMap<String, String>],
+                                       variables: &BTreeMap<&'static str, String>, raw_post_name: &str, normalised_post_name: &str, number: usize,
+                                       title: &str, author: &str, post_date: &DateTime<Tz>, tags: &[&[TagName]], related_posts: &[(String, String)],
+                                       styles: &[&[St]], scripts: &[&[Sc]], into: &mut W, out_name_err: E)
                                        -> Result<Cow<'static, str>, Error>
     where W: Write,
           E: Into<Cow<'static, str>>,
@@ -145,292 +176,965 @@ pub fn format_output<W, E, Tz, St, Sc>(to_format: &str, blog_name: &str, languag
           St: WrappedElement,
           Sc: WrappedElement
 {
-    format_output_impl(to_format,
-                       blog_name,
-                       language,
-                       additional_data_sets,
-                       raw_post_name,
-                       normalised_post_name,
-                       number,
-                       title,
-                       author,
-                       normalise_datetime(post_date),
-                       tags,
-                       styles,
-                       scripts,
-                       into,
-                       out_name_err.into())
+    let context = FormatContext {
+        blog_name: blog_name,
+        language: language,
+        additional_data_sets: additional_data_sets,
+        variables: variables,
+        raw_post_name: raw_post_name,
+        normalised_post_name: normalised_post_name,
+        number: number,
+        title: title,
+        author: author,
+        post_date: normalise_datetime(post_date),
+        tags: tags,
+        related_posts: related_posts,
+        styles: styles,
+        scripts: scripts,
+    };
+
+    let mut out_name_err = Some(out_name_err.into());
+    format_nodes(&to_format.0, &context, &mut vec![], into, &mut out_name_err)?;
+
+    Ok(out_name_err.unwrap())
 }
 
-fn format_output_impl<W, St, Sc>(mut to_format: &str, blog_name: &str, language: &LanguageTag, additional_data_sets: &[&BTreeMap<String, String>],
-                                 raw_post_name: &str, normalised_post_name: &str, number: usize, title: &str, author: &str, post_date: DateTime<FixedOffset>,
-                                 tags: &[&[TagName]], styles: &[&[St]], scripts: &[&[Sc]], into: &mut W, out_name_err: Cow<'static, str>)
-                                 -> Result<Cow<'static, str>, Error>
-    where W: Write,
-          St: WrappedElement,
-          Sc: WrappedElement
-{
-    let mut out_name_err = Some(out_name_err);
-
-    let mut byte_pos = 0usize;
-    while let Some(idx) = to_format.find(|ref c| ['{', '}'].contains(c)) {
-        let (before, mut after) = to_format.split_at(idx);
-
-        into.write_all(before.as_bytes()).map_err(|e| err_io("write", format!("{} when writing unformatted output", e), out_name_err.take().unwrap()))?;
-        byte_pos += before.len();
-
-        if after.starts_with("{{") {
-            into.write_all(&['{' as u8]).map_err(|e| err_io("write", format!("{} when writing escaped opening curly brace", e), out_name_err.take().unwrap()))?;
-            byte_pos += 2;
-            after = &after[2..];
-        } else if after.starts_with("}}") {
-            into.write_all(&['}' as u8]).map_err(|e| err_io("write", format!("{} when writing escaped closing curly brace", e), out_name_err.take().unwrap()))?;
-            byte_pos += 2;
-            after = &after[2..];
-        } else if after.starts_with("}") {
-            return Err(err_parse(format!("stray closing brace at position {}", byte_pos), out_name_err.take().unwrap()));
-        } else {
-            // Must start with { – begin format sequence
-
-            if let Some(idx) = after.find('}') {
-                let (mut format_str, post) = after.split_at(idx);
-                after = &post[1..]; // drop closing paren
-                byte_pos += format_str.len() + 1; // plus closing paren
-
-                format_str = &format_str[1..].trim(); // drop open paren
-
-                var_parse(format_str,
-                          byte_pos,
-                          blog_name,
-                          language,
-                          additional_data_sets,
-                          raw_post_name,
-                          normalised_post_name,
-                          number,
-                          title,
-                          author,
-                          post_date,
-                          tags,
-                          styles,
-                          scripts,
-                          into,
-                          &mut out_name_err)?;
+
+/// A template parsed for [`format_output()`](fn.format_output.html).
+///
+/// Parsing a template once and formatting it many times avoids re-scanning it for each post.
+///
+/// Besides `{var}` substitutions and `{{`/`}}` escapes, the template can contain:
+///
+///   * `{if var}…{end}` and `{if var}…{else}…{end}` blocks, the first branch of which is used if `var`
+///     would be formatted to a non-empty string; unknown variables and missing data are considered empty,
+///     and `{if has_data(name)}` tests whether `name` is specified in any data set, even if empty,
+///   * `{for tag in tags}…{end}` loops, formatting their contents for each tag,
+///     with `{tag}` substituted for the tag, and `{tag-file_name}` for its [`file_name()`](struct.TagName.html#method.file_name),
+///   * filters applied to a substitution in order, like `{title|html}`, `{data-desc|attr}`, or `{tag|url|attr}`:
+///     * `html` – escape `<`, `>`, and `&`,
+///     * `attr` – also escape `"` and `'` as numeric references, for use in attributes,
+///     * `xml` – also escape `"` and `'` as `&quot;` and `&apos;`,
+///     * `url` – percent-encode all but unreserved characters, for use as a URL component,
+///     * `json` – write as a quoted JSON string, with `<`, `>`, and `&` escaped as well, for use in `<script>`s,
+///     * `raw` – do nothing, to opt out of [`escape_html_by_default()`](#method.escape_html_by_default),
+///   * `{include(path)}`s of other templates, resolved relative to the blog root when parsed with
+///     [`parse_with_includes()`](#method.parse_with_includes), and formatted in place with the same variables.
+///
+/// # Examples
+///
+/// ```
+/// # use bloguen::ops::FormatTemplate;
+/// # use bloguen::Error;
+/// assert!(FormatTemplate::parse("{if data-desc}{data-desc}{else}{title}{end}", "test template").is_ok());
+/// assert!(FormatTemplate::parse("{for tag in tags}<li>{tag}</li>{end}", "test template").is_ok());
+///
+/// assert_eq!(FormatTemplate::parse("<ul>{for tag in tags}<li>{tag}</li>", "test template"),
+///            Err(Error::Parse {
+///                tp: "unformatted input",
+///                wher: "test template".into(),
+///                more: "unterminated {for} at position 4".into(),
+///            }));
+/// ```
+#[derive(Debug, Clone, Hash, PartialEq, Eq)]
+pub struct FormatTemplate(Vec<FormatNode>);
+
+#[derive(Debug, Clone, Hash, PartialEq, Eq)]
+enum FormatNode {
+    Literal(String),
+    Expression(FormatExpression, Vec<FormatFilter>, usize),
+    If(FormatExpression, usize, Vec<FormatNode>, Vec<FormatNode>),
+    For(String, FormatCollection, Vec<FormatNode>),
+    Include(Cow<'static, str>, PathBuf, Vec<FormatNode>),
+}
+
+#[derive(Debug, Clone, Hash, PartialEq, Eq)]
+enum FormatExpression {
+    Name(String),
+    Data(String, Option<String>),
+    HasData(String),
+    Date(String, String),
+    Tags(Option<String>),
+    RelatedPosts(usize),
+    ReadingTime(usize),
+    MachineData(MachineDataKind),
+    PassParagraphs(usize, Box<FormatExpression>),
+}
+
+#[derive(Debug, Copy, Clone, Hash, PartialEq, Eq)]
+enum FormatFilter {
+    Html,
+    Attr,
+    Xml,
+    Url,
+    Json,
+    Raw,
+}
+
+#[derive(Debug, Copy, Clone, Hash, PartialEq, Eq)]
+enum FormatCollection {
+    Tags,
+}
+
+enum OpenBlock {
+    If(FormatExpression, usize, Option<Vec<FormatNode>>),
+    For(String, FormatCollection, usize),
+}
+
+struct FormatContext<'a, St: 'a, Sc: 'a> {
+    blog_name: &'a str,
+    language: &'a LanguageTag,
+    additional_data_sets: &'a [&'a BTreeMap<String, String>],
+    variables: &'a BTreeMap<&'static str, String>,
+    raw_post_name: &'a str,
+    normalised_post_name: &'a str,
+    number: usize,
+    title: &'a str,
+    author: &'a str,
+    post_date: DateTime<FixedOffset>,
+    tags: &'a [&'a [TagName]],
+    related_posts: &'a [(String, String)],
+    styles: &'a [&'a [St]],
+    scripts: &'a [&'a [Sc]],
+}
+
+static BUILTIN_NAMES: &[&str] = &["language", "number", "title", "author", "raw_post_name", "normalised_post_name", "blog_name", "bloguen-version", "styles",
+                                  "scripts"];
+
+impl FormatTemplate {
+    /// Parse the specified template, reporting errors as coming from the specified output.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use bloguen::ops::FormatTemplate;
+    /// # use bloguen::Error;
+    /// assert_eq!(FormatTemplate::parse("{title}{else}", "post header"),
+    ///            Err(Error::Parse {
+    ///                tp: "unformatted input",
+    ///                wher: "post header".into(),
+    ///                more: "{else} without matching {if} at position 7".into(),
+    ///            }));
+    /// ```
+    pub fn parse<E: Into<Cow<'static, str>>>(to_format: &str, out_name_err: E) -> Result<FormatTemplate, Error> {
+        FormatTemplate::parse_impl(to_format, None, &mut vec![], out_name_err.into())
+    }
+
+    /// Parse the specified template, resolving `{include(path)}`s relative to the specified blog root.
+    ///
+    /// Errors in included templates are reported as coming from the included file.
+    ///
+    /// # Examples
+    ///
+    /// Given:
+    ///
+    /// ```plaintext
+    /// nav.html
+    /// cycle.html
+    /// ```
+    ///
+    /// Where `nav.html` contains `<a href="{base_url}">{blog_name}</a>`
+    /// and `cycle.html` contains `{include(cycle.html)}`, the following holds:
+    ///
+    /// ```
+    /// # use bloguen::ops::FormatTemplate;
+    /// # use std::io::Write;
+    /// # use std::fs::{self, File};
+    /// # use std::env::temp_dir;
+    /// # use bloguen::Error;
+    /// # let root = temp_dir().join("bloguen-doctest").join("ops-output-format-template-parse_with_includes");
+    /// # fs::create_dir_all(&root).unwrap();
+    /// # File::create(root.join("nav.html")).unwrap().write_all(b"<a href=\"{base_url}\">{blog_name}</a>").unwrap();
+    /// # File::create(root.join("cycle.html")).unwrap().write_all(b"{include(cycle.html)}").unwrap();
+    /// # /*
+    /// let root: PathBuf = /* obtained elsewhere */;
+    /// # */
+    /// let root = ("$ROOT/".to_string(), root);
+    /// assert!(FormatTemplate::parse_with_includes("<nav>{include(nav.html)}</nav>", &root, "post header").is_ok());
+    ///
+    /// assert_eq!(FormatTemplate::parse_with_includes("{include(cycle.html)}", &root, "post header"),
+    ///            Err(Error::Parse {
+    ///                tp: "unformatted input",
+    ///                wher: "$ROOT/cycle.html".into(),
+    ///                more: "include cycle $ROOT/cycle.html -> $ROOT/cycle.html around position 21".into(),
+    ///            }));
+    /// ```
+    pub fn parse_with_includes<E: Into<Cow<'static, str>>>(to_format: &str, root: &(String, PathBuf), out_name_err: E) -> Result<FormatTemplate, Error> {
+        FormatTemplate::parse_impl(to_format, Some(root), &mut vec![], out_name_err.into())
+    }
+
+    /// HTML-escape all plain-text substitutions without filters, as if they had the `html` filter applied.
+    ///
+    /// `styles`, `scripts`, `toc`, `excerpt`, `tags`, `related_posts()`, `machine_data()`, `pass_paragraphs()`, and `data-post_content`,
+    /// which are HTML, as well as substitutions with any filters, like `raw`, are left as-is.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use bloguen::ops::FormatTemplate;
+    /// let template = FormatTemplate::parse("<title>{title}</title>{data-post_content}", "test template").unwrap();
+    /// let mut escaped = template.clone();
+    /// escaped.escape_html_by_default();
+    /// assert!(escaped != template);
+    ///
+    /// let template = FormatTemplate::parse("<title>{title|raw}</title>{data-post_content}", "test template").unwrap();
+    /// let mut escaped = template.clone();
+    /// escaped.escape_html_by_default();
+    /// assert_eq!(escaped, template);
+    /// ```
+    pub fn escape_html_by_default(&mut self) {
+        escape_html_by_default(&mut self.0)
+    }
+
+    /// Get the paths of all templates `{include(path)}`d by this one, including transitively, in order of appearance.
+    ///
+    /// # Examples
+    ///
+    /// Given:
+    ///
+    /// ```plaintext
+    /// nav.html
+    /// logo.html
+    /// ```
+    ///
+    /// Where `nav.html` contains `{include(logo.html)}<a href="{base_url}">{blog_name}</a>`, the following holds:
+    ///
+    /// ```
+    /// # use bloguen::ops::FormatTemplate;
+    /// # use std::io::Write;
+    /// # use std::fs::{self, File};
+    /// # use std::env::temp_dir;
+    /// # let root = temp_dir().join("bloguen-doctest").join("ops-output-format-template-includes");
+    /// # fs::create_dir_all(&root).unwrap();
+    /// # File::create(root.join("nav.html")).unwrap().write_all(b"{include(logo.html)}<a href=\"{base_url}\">{blog_name}</a>").unwrap();
+    /// # File::create(root.join("logo.html")).unwrap();
+    /// # /*
+    /// let root: PathBuf = /* obtained elsewhere */;
+    /// # */
+    /// let template = FormatTemplate::parse_with_includes("<nav>{include(nav.html)}</nav>", &("$ROOT/".to_string(), root.clone()), "post header").unwrap();
+    /// assert_eq!(template.includes(), vec![root.join("nav.html"), root.join("logo.html")]);
+    ///
+    /// assert!(FormatTemplate::parse("<nav>{title}</nav>", "post header").unwrap().includes().is_empty());
+    /// ```
+    pub fn includes(&self) -> Vec<&Path> {
+        let mut ret = vec![];
+        includes(&self.0, &mut ret);
+        ret
+    }
+
+    fn parse_impl(mut to_format: &str, root: Option<&(String, PathBuf)>, includes: &mut Vec<(String, PathBuf)>, out_name_err: Cow<'static, str>)
+                  -> Result<FormatTemplate, Error> {
+        let mut blocks: Vec<(OpenBlock, Vec<FormatNode>)> = vec![];
+        let mut nodes = vec![];
+
+        let mut byte_pos = 0usize;
+        while let Some(idx) = to_format.find(|ref c| ['{', '}'].contains(c)) {
+            let (before, mut after) = to_format.split_at(idx);
+
+            push_literal(&mut nodes, before);
+            byte_pos += before.len();
+
+            if after.starts_with("{{") {
+                push_literal(&mut nodes, "{");
+                byte_pos += 2;
+                after = &after[2..];
+            } else if after.starts_with("}}") {
+                push_literal(&mut nodes, "}");
+                byte_pos += 2;
+                after = &after[2..];
+            } else if after.starts_with("}") {
+                return Err(err_parse(format!("stray closing brace at position {}", byte_pos), out_name_err));
             } else {
-                return Err(err_parse(format!("unmatched open brace at position {}", byte_pos), out_name_err.take().unwrap()));
+                // Must start with { – begin format sequence
+
+                if let Some(idx) = after.find('}') {
+                    let (mut format_str, post) = after.split_at(idx);
+                    after = &post[1..]; // drop closing paren
+                    let open_pos = byte_pos;
+                    byte_pos += format_str.len() + 1; // plus closing paren
+
+                    format_str = &format_str[1..].trim(); // drop open paren
+
+                    let (keyword, rest) = match format_str.find(char::is_whitespace) {
+                        Some(idx) => (&format_str[..idx], format_str[idx..].trim()),
+                        None => (format_str, ""),
+                    };
+                    match keyword {
+                        "if" => {
+                            if rest.is_empty() {
+                                return Err(err_parse(format!("{{if}} without condition at position {}", open_pos), out_name_err));
+                            }
+
+                            let condition = parse_expression(rest, byte_pos, &out_name_err)?;
+                            blocks.push((OpenBlock::If(condition, open_pos, None), mem::take(&mut nodes)));
+                        }
+                        "for" => {
+                            let words: Vec<_> = rest.split_whitespace().collect();
+                            if words.len() != 3 || words[1] != "in" {
+                                return Err(err_parse(format!("{{{}}} is an invalid loop, expected {{for variable in collection}}, at position {}",
+                                                             format_str,
+                                                             open_pos),
+                                                     out_name_err));
+                            }
+
+                            let collection = match words[2] {
+                                "tags" => FormatCollection::Tags,
+                                collection => {
+                                    return Err(err_parse(format!("{} is an unrecognised loop collection (accepted: tags), at position {}",
+                                                                 collection,
+                                                                 open_pos),
+                                                         out_name_err))
+                                }
+                            };
+                            blocks.push((OpenBlock::For(words[0].to_string(), collection, open_pos), mem::take(&mut nodes)));
+                        }
+                        "else" if rest.is_empty() => {
+                            match blocks.last_mut() {
+                                Some(&mut (OpenBlock::If(_, _, ref mut then @ None), _)) => *then = Some(mem::take(&mut nodes)),
+                                _ => return Err(err_parse(format!("{{else}} without matching {{if}} at position {}", open_pos), out_name_err)),
+                            }
+                        }
+                        "end" if rest.is_empty() => {
+                            let (block, parent) = match blocks.pop() {
+                                Some(block) => block,
+                                None => return Err(err_parse(format!("{{end}} without matching {{if}} or {{for}} at position {}", open_pos), out_name_err)),
+                            };
+
+                            let body = mem::replace(&mut nodes, parent);
+                            nodes.push(match block {
+                                OpenBlock::If(condition, pos, Some(then)) => FormatNode::If(condition, pos, then, body),
+                                OpenBlock::If(condition, pos, None) => FormatNode::If(condition, pos, body, vec![]),
+                                OpenBlock::For(variable, collection, _) => FormatNode::For(variable, collection, body),
+                            });
+                        }
+                        _ => {
+                            let (format_str, filters) = parse_filters(format_str, byte_pos, &out_name_err)?;
+                            if let Some(("include", args)) = parse_function_notation(format_str) {
+                                if !filters.is_empty() {
+                                    return Err(err_parse(format!("filters applied to include() at position {}", byte_pos), out_name_err));
+                                }
+                                nodes.push(parse_include(&args, byte_pos, root, includes, &out_name_err)?);
+                            } else {
+                                nodes.push(FormatNode::Expression(parse_expression(format_str, byte_pos, &out_name_err)?, filters, byte_pos));
+                            }
+                        }
+                    }
+                } else {
+                    return Err(err_parse(format!("unmatched open brace at position {}", byte_pos), out_name_err));
+                }
             }
+
+            to_format = after;
         }
 
-        to_format = after;
+        push_literal(&mut nodes, to_format);
+
+        match blocks.pop() {
+            Some((OpenBlock::If(_, pos, _), _)) => Err(err_parse(format!("unterminated {{if}} at position {}", pos), out_name_err)),
+            Some((OpenBlock::For(_, _, pos), _)) => Err(err_parse(format!("unterminated {{for}} at position {}", pos), out_name_err)),
+            None => Ok(FormatTemplate(nodes)),
+        }
     }
+}
 
-    into.write_all(to_format.as_bytes()).map_err(|e| err_io("write", format!("{} when writing unformatted output", e), out_name_err.take().unwrap()))?;
+impl FromStr for FormatTemplate {
+    type Err = Error;
 
-    Ok(out_name_err.unwrap())
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        FormatTemplate::parse(s, "template")
+    }
 }
 
-fn var_parse<W, St, Sc>(format_str: &str, byte_pos: usize, blog_name: &str, language: &LanguageTag, additional_data_sets: &[&BTreeMap<String, String>],
-                        raw_post_name: &str, normalised_post_name: &str, number: usize, title: &str, author: &str, post_date: DateTime<FixedOffset>,
-                        tags: &[&[TagName]], styles: &[&[St]], scripts: &[&[Sc]], into: &mut W, out_name_err: &mut Option<Cow<'static, str>>)
-                        -> Result<(), Error>
-    where W: Write,
-          St: WrappedElement,
-          Sc: WrappedElement
-{
-    match format_str {
-            "language" => into.write_all(language.as_bytes()).map_err(|e| (e, "language tag".into())),
-            "number" => into.write_fmt(format_args!("{}", number)).map_err(|e| (e, "number tag".into())),
-            "title" => into.write_all(title.as_bytes()).map_err(|e| (e, "title tag".into())),
-            "author" => into.write_all(author.as_bytes()).map_err(|e| (e, "author tag".into())),
-            "raw_post_name" => into.write_all(raw_post_name.as_bytes()).map_err(|e| (e, "raw_post_name tag".into())),
-            "normalised_post_name" => into.write_all(normalised_post_name.as_bytes()).map_err(|e| (e, "normalised_post_name tag".into())),
-            "blog_name" => into.write_all(blog_name.as_bytes()).map_err(|e| (e, "blog_name tag".into())),
-
-            "bloguen-version" => into.write_all(BLOGUEN_VERSION.as_bytes()).map_err(|e| (e, "bloguen-version tag".into())),
-
-            "tags" => write_tags(&TAG_DEFAULT_CLASS, tags, into),
-
-            "styles" => {
-                Result::from_iter(styles.iter().map(|ss| {
-                    Result::from_iter(ss.iter().map(|s| {
-                        into.write_all(s.head_b()).map_err(|e| (e, "style tag header".into()))?;
-                        into.write_all(s.content_b()).map_err(|e| (e, "style tag content".into()))?;
-                        into.write_all(s.foot_b()).map_err(|e| (e, "style tag footer".into()))?;
-
-                        Ok(())
-                    }))
-                }))
-            }
+fn push_literal(nodes: &mut Vec<FormatNode>, literal: &str) {
+    if literal.is_empty() {
+        return;
+    }
 
-            "scripts" => {
-                Result::from_iter(scripts.iter().map(|ss| {
-                    Result::from_iter(ss.iter().map(|s| {
-                        into.write_all(s.head_b()).map_err(|e| (e, "script tag header".into()))?;
-                        into.write_all(s.content_b()).map_err(|e| (e, "script tag content".into()))?;
-                        into.write_all(s.foot_b()).map_err(|e| (e, "script tag footer".into()))?;
+    if let Some(&mut FormatNode::Literal(ref mut prev)) = nodes.last_mut() {
+        prev.push_str(literal);
+        return;
+    }
+    nodes.push(FormatNode::Literal(literal.to_string()));
+}
 
-                        Ok(())
-                    }))
-                }))
+fn parse_filters<'s>(format_str: &'s str, byte_pos: usize, out_name_err: &str) -> Result<(&'s str, Vec<FormatFilter>), Error> {
+    let mut expr = format_str;
+    let mut filters = vec![];
+    while let Some(idx) = expr.rfind('|') {
+        let filter = expr[idx + 1..].trim();
+        if filter.is_empty() || !filter.chars().all(|c| c.is_alphanumeric() || c == '_') {
+            break;
+        }
+
+        filters.push(match filter {
+            "html" => FormatFilter::Html,
+            "attr" => FormatFilter::Attr,
+            "xml" => FormatFilter::Xml,
+            "url" => FormatFilter::Url,
+            "json" => FormatFilter::Json,
+            "raw" => FormatFilter::Raw,
+            filter => {
+                return Err(err_parse(format!("{} is an unrecognised filter (accepted: html, attr, xml, url, json, raw), around position {}",
+                                             filter,
+                                             byte_pos),
+                                     out_name_err.to_string()))
             }
+        });
+        expr = expr[..idx].trim();
+    }
 
-            key if key.starts_with("data-") => {
-                let key = &key["data-".len()..];
-                match additional_data_sets.iter().rev().map(|dt| dt.get(key)).find(Option::is_some).into_iter().flatten().next() {
-                    Some(data) => into.write_all(data.as_bytes()).map_err(|e| (e, format!("data-{} tag with value {}", key, data).into())),
-                    None => return Err(err_parse(format!("missing value for data-{}", key), out_name_err.take().unwrap())),
+    filters.reverse();
+    Ok((expr, filters))
+}
+
+fn escape_html_by_default(nodes: &mut [FormatNode]) {
+    for node in nodes {
+        match node {
+            FormatNode::Literal(_) => {}
+            FormatNode::Expression(expr, filters, _) => {
+                let html = match expr {
+                    FormatExpression::Name(name) => name == "styles" || name == "scripts" || name == "toc" || name == "excerpt",
+                    FormatExpression::Data(key, _) => key == "post_content",
+                    FormatExpression::HasData(_) |
+                    FormatExpression::Date(..) |
+                    FormatExpression::ReadingTime(_) => false,
+                    FormatExpression::Tags(_) |
+                    FormatExpression::RelatedPosts(_) |
+                    FormatExpression::MachineData(_) |
+                    FormatExpression::PassParagraphs(..) => true,
+                };
+                if !html && filters.is_empty() {
+                    filters.push(FormatFilter::Html);
                 }
             }
+            FormatNode::If(_, _, then, otherwise) => {
+                escape_html_by_default(then);
+                escape_html_by_default(otherwise);
+            }
+            FormatNode::For(_, _, body) |
+            FormatNode::Include(_, _, body) => escape_html_by_default(body),
+        }
+    }
+}
+
+fn includes<'n>(nodes: &'n [FormatNode], into: &mut Vec<&'n Path>) {
+    for node in nodes {
+        match node {
+            FormatNode::Literal(_) |
+            FormatNode::Expression(..) => {}
+            FormatNode::If(_, _, then, otherwise) => {
+                includes(then, into);
+                includes(otherwise, into);
+            }
+            FormatNode::For(_, _, body) => includes(body, into),
+            FormatNode::Include(_, path, body) => {
+                into.push(path);
+                includes(body, into);
+            }
+        }
+    }
+}
+
+fn parse_include(args: &[&str], byte_pos: usize, root: Option<&(String, PathBuf)>, includes: &mut Vec<(String, PathBuf)>,
+                 out_name_err: &str)
+                 -> Result<FormatNode, Error> {
+    if args.len() != 1 {
+        return Err(err_parse(format!("{} is an invalid amount of arguments to one-argument `include(path)` function, around position {}",
+                                     args.len(),
+                                     byte_pos),
+                             out_name_err.to_string()));
+    }
+
+    let root = root.ok_or_else(|| err_parse(format!("include({}) without a blog root to resolve it against, around position {}", args[0], byte_pos),
+                                            out_name_err.to_string()))?;
+    let file = (format!("{}{}", root.0, args[0]), concat_path(root.1.clone(), args[0]));
+    let canonical = file.1.canonicalize().unwrap_or_else(|_| file.1.clone());
+    if includes.iter().any(|&(_, ref p)| *p == canonical) {
+        return Err(err_parse(format!("include cycle {} -> {} around position {}",
+                                     includes.iter().map(|&(ref n, _)| &n[..]).collect::<Vec<_>>().join(" -> "),
+                                     file.0,
+                                     byte_pos),
+                             out_name_err.to_string()));
+    }
+
+    let text = read_file(&file, "included template")?;
+    includes.push((file.0.clone(), canonical));
+    let included = FormatTemplate::parse_impl(&text, Some(root), includes, file.0.clone().into());
+    includes.pop();
 
-            _ => {
-                match parse_function_notation(format_str) {
-                    Some(("date", args)) => {
-                        if args.len() != 2 {
-                            return Err(err_parse(format!("{} is an invalid amount of arguments to two-argument `date(of_what, format)` function, around \
-                                                          position {}",
-                                                         args.len(),
-                                                         byte_pos),
-                                                 out_name_err.take().unwrap()));
+    Ok(FormatNode::Include(file.0.into(), file.1, included?.0))
+}
+
+fn parse_expression(format_str: &str, byte_pos: usize, out_name_err: &str) -> Result<FormatExpression, Error> {
+    match format_str {
+        "tags" => Ok(FormatExpression::Tags(None)),
+        "reading_time" => Ok(FormatExpression::ReadingTime(DEFAULT_READING_SPEED)),
+
+        key if key.starts_with("data-") => Ok(FormatExpression::Data(key["data-".len()..].to_string(), None)),
+
+        _ => {
+            match parse_function_notation(format_str) {
+                Some(("data", args)) => {
+                    match args.len() {
+                        1 => Ok(FormatExpression::Data(args[0].to_string(), Some(String::new()))),
+                        2 => {
+                            let fallback = if args[1].len() >= 2 && args[1].starts_with('"') && args[1].ends_with('"') {
+                                &args[1][1..args[1].len() - 1]
+                            } else {
+                                args[1]
+                            };
+                            Ok(FormatExpression::Data(args[0].to_string(), Some(fallback.to_string())))
+                        }
+                        _ => {
+                            Err(err_parse(format!("{} is an invalid amount of arguments to `data(name, [fallback])` function, around position {}",
+                                                  args.len(),
+                                                  byte_pos),
+                                          out_name_err.to_string()))
                         }
+                    }
+                }
 
-                        let date_format = parse_date_format_specifier(args[1]).ok_or_else(|| {
-                                err_parse(format!("invalid date format specifier {} around position {}", args[1], byte_pos),
-                                          out_name_err.take().unwrap())
-                            })?;
-                        let date = match args[0] {
-                            "post" => Cow::Borrowed(&post_date),
-
-                            "now_utc" => Cow::Owned(normalise_datetime(&Utc::now())),
-                            "now_local" => Cow::Owned(normalise_datetime(&Local::now())),
-
-                            of_what => {
-                                return Err(err_parse(format!("{} is an unrecognised date specifier (accepted: post, now_{{utc,local}}), around position {}",
-                                                             of_what,
-                                                             byte_pos),
-                                                     out_name_err.take().unwrap()))
-                            }
-                        };
+                Some(("has_data", args)) => {
+                    match args.len() {
+                        1 => Ok(FormatExpression::HasData(args[0].to_string())),
+                        _ => {
+                            Err(err_parse(format!("{} is an invalid amount of arguments to one-argument `has_data(name)` function, around position {}",
+                                                  args.len(),
+                                                  byte_pos),
+                                          out_name_err.to_string()))
+                        }
+                    }
+                }
 
-                        into.write_fmt(format_args!("{}", date.format_with_items(date_format.to_vec().into_iter())))
-                            .map_err(|e| (e, format!("{} date as {}", args[0], args[1]).into()))
+                Some(("date", args)) => {
+                    if args.len() != 2 {
+                        return Err(err_parse(format!("{} is an invalid amount of arguments to two-argument `date(of_what, format)` function, around \
+                                                      position {}",
+                                                     args.len(),
+                                                     byte_pos),
+                                             out_name_err.to_string()));
                     }
 
-                    Some(("tags", args)) => {
-                        match args.len() {
-                            0 => write_tags(&TAG_DEFAULT_CLASS, tags, into),
-                            1 => write_tags(args[0], tags, into),
-                            _ => {
-                                return Err(err_parse(format!("{} is an invalid amount of arguments to two-argument `tags([html-class])` function, around \
-                                                              position {}",
-                                                             args.len(),
-                                                             byte_pos),
-                                                     out_name_err.take().unwrap()));
-                            }
+                    if parse_date_format_specifier(args[1]).is_none() {
+                        return Err(err_parse(format!("invalid date format specifier {} around position {}", args[1], byte_pos), out_name_err.to_string()));
+                    }
+                    match args[0] {
+                        "post" | "now_utc" | "now_local" | "prev_post" | "next_post" => {
+                            Ok(FormatExpression::Date(args[0].to_string(), args[1].to_string()))
+                        }
+                        of_what => {
+                            Err(err_parse(format!("{} is an unrecognised date specifier (accepted: post, now_{{utc,local}}, {{prev,next}}_post), around \
+                                                   position {}",
+                                                  of_what,
+                                                  byte_pos),
+                                          out_name_err.to_string()))
                         }
                     }
+                }
 
-                    Some(("machine_data", args)) => {
-                        match args.len() {
-                            1 => {
-                                match MachineDataKind::from(args[0]) {
-                                    Some(kind) => {
-                                        *out_name_err = Some(machine_output_kind(&kind)(blog_name,
-                                                                                        language,
-                                                                                        additional_data_sets,
-                                                                                        raw_post_name,
-                                                                                        number,
-                                                                                        title,
-                                                                                        author,
-                                                                                        &post_date,
-                                                                                        tags,
-                                                                                        styles,
-                                                                                        scripts,
-                                                                                        into,
-                                                                                        out_name_err.take().unwrap())?);
-                                        Ok(())
-                                    }
-                                    None => {
-                                        return Err(err_parse(format!("{} is an invalid data format for `machine_data([format])` function, accepted formats: \
-                                                                      json, around position {}",
-                                                                     args[0],
-                                                                     byte_pos),
-                                                             out_name_err.take().unwrap()))
-                                    }
-                                }
-                            }
-                            _ => {
-                                return Err(err_parse(format!("{} is an invalid amount of arguments to two-argument `tags([html-class])` function, around \
-                                                              position {}",
-                                                             args.len(),
-                                                             byte_pos),
-                                                     out_name_err.take().unwrap()));
-                            }
+                Some(("tags", args)) => {
+                    match args.len() {
+                        0 => Ok(FormatExpression::Tags(None)),
+                        1 => Ok(FormatExpression::Tags(Some(args[0].to_string()))),
+                        _ => {
+                            Err(err_parse(format!("{} is an invalid amount of arguments to two-argument `tags([html-class])` function, around position {}",
+                                                  args.len(),
+                                                  byte_pos),
+                                          out_name_err.to_string()))
                         }
                     }
+                }
 
-                    Some(("pass_paragraphs", args)) => {
-                        match args.len() {
-                            2 => {
-                                let para_count: usize = args[0].parse()
-                                    .map_err(|e| {
-                                        err_parse(format!("{} is an invalid paragraph count for `pass_paragraphs([count], [expr])` function, required: \
+                Some(("related_posts", args)) => {
+                    match args.len() {
+                        1 => {
+                            let post_count: usize = args[0].parse()
+                                .map_err(|e| {
+                                    err_parse(format!("{} is an invalid post count for `related_posts(count)` function, required: unsigned integer, \
+                                                       reason: {}, around position {}",
+                                                      args[0],
+                                                      e,
+                                                      byte_pos),
+                                              out_name_err.to_string())
+                                })?;
+
+                            Ok(FormatExpression::RelatedPosts(post_count))
+                        }
+                        _ => {
+                            Err(err_parse(format!("{} is an invalid amount of arguments to one-argument `related_posts(count)` function, around position {}",
+                                                  args.len(),
+                                                  byte_pos),
+                                          out_name_err.to_string()))
+                        }
+                    }
+                }
+
+                Some(("reading_time", args)) => {
+                    match args.len() {
+                        0 => Ok(FormatExpression::ReadingTime(DEFAULT_READING_SPEED)),
+                        1 => {
+                            match args[0].parse() {
+                                Ok(0) => {
+                                    Err(err_parse(format!("0 is an invalid reading speed for `reading_time([words-per-minute])` function, around position {}",
+                                                          byte_pos),
+                                                  out_name_err.to_string()))
+                                }
+                                Ok(words_per_minute) => Ok(FormatExpression::ReadingTime(words_per_minute)),
+                                Err(e) => {
+                                    Err(err_parse(format!("{} is an invalid reading speed for `reading_time([words-per-minute])` function, required: \
                                                            unsigned integer, reason: {}, around position {}",
                                                           args[0],
                                                           e,
                                                           byte_pos),
-                                                  out_name_err.take().unwrap())
-                                    })?;
-
-                                var_parse(args[1],
-                                          byte_pos,
-                                          blog_name,
-                                          language,
-                                          additional_data_sets,
-                                          raw_post_name,
-                                          normalised_post_name,
-                                          number,
-                                          title,
-                                          author,
-                                          post_date,
-                                          tags,
-                                          styles,
-                                          scripts,
-                                          &mut ParagraphPasser::new(into, para_count),
-                                          out_name_err)?;
-
-                                Ok(())
+                                                  out_name_err.to_string()))
+                                }
                             }
-                            _ => {
-                                return Err(err_parse(format!("{} is an invalid amount of arguments to two-argument `pass_paragraphs([count], [expr])` \
-                                                              function, around position {}",
-                                                             args.len(),
-                                                             byte_pos),
-                                                     out_name_err.take().unwrap()));
+                        }
+                        _ => {
+                            Err(err_parse(format!("{} is an invalid amount of arguments to one-argument `reading_time([words-per-minute])` function, around \
+                                                   position {}",
+                                                  args.len(),
+                                                  byte_pos),
+                                          out_name_err.to_string()))
+                        }
+                    }
+                }
+
+                Some(("machine_data", args)) => {
+                    match args.len() {
+                        1 => {
+                            match MachineDataKind::from(args[0]) {
+                                Some(kind) => Ok(FormatExpression::MachineData(kind)),
+                                None => {
+                                    Err(err_parse(format!("{} is an invalid data format for `machine_data([format])` function, accepted formats: json, around \
+                                                           position {}",
+                                                          args[0],
+                                                          byte_pos),
+                                                  out_name_err.to_string()))
+                                }
                             }
                         }
+                        _ => {
+                            Err(err_parse(format!("{} is an invalid amount of arguments to two-argument `tags([html-class])` function, around position {}",
+                                                  args.len(),
+                                                  byte_pos),
+                                          out_name_err.to_string()))
+                        }
+                    }
+                }
+
+                Some(("pass_paragraphs", args)) => {
+                    match args.len() {
+                        2 => {
+                            let para_count: usize = args[0].parse()
+                                .map_err(|e| {
+                                    err_parse(format!("{} is an invalid paragraph count for `pass_paragraphs([count], [expr])` function, required: \
+                                                       unsigned integer, reason: {}, around position {}",
+                                                      args[0],
+                                                      e,
+                                                      byte_pos),
+                                              out_name_err.to_string())
+                                })?;
+
+                            Ok(FormatExpression::PassParagraphs(para_count, Box::new(parse_expression(args[1], byte_pos, out_name_err)?)))
+                        }
+                        _ => {
+                            Err(err_parse(format!("{} is an invalid amount of arguments to two-argument `pass_paragraphs([count], [expr])` function, around \
+                                                   position {}",
+                                                  args.len(),
+                                                  byte_pos),
+                                          out_name_err.to_string()))
+                        }
+                    }
+                }
+
+                Some((fname, args)) => {
+                    Err(err_parse(format!("unrecognised format function {} with arguments {:?} at position {}", fname, args, byte_pos),
+                                  out_name_err.to_string()))
+                }
+                None => Ok(FormatExpression::Name(format_str.to_string())),
+            }
+        }
+    }
+}
+
+
+fn format_nodes<St, Sc>(nodes: &[FormatNode], context: &FormatContext<St, Sc>, scopes: &mut Vec<BTreeMap<String, String>>, into: &mut dyn Write,
+                        out_name_err: &mut Option<Cow<'static, str>>)
+                        -> Result<(), Error>
+    where St: WrappedElement,
+          Sc: WrappedElement
+{
+    for node in nodes {
+        match node {
+            FormatNode::Literal(literal) => {
+                into.write_all(literal.as_bytes())
+                    .map_err(|e| err_io("write", format!("{} when writing unformatted output", e), out_name_err.take().unwrap()))?
+            }
+            FormatNode::Expression(expr, filters, byte_pos) => {
+                if filters.is_empty() {
+                    format_expression(expr, *byte_pos, context, scopes, into, out_name_err)?
+                } else {
+                    let mut value = vec![];
+                    format_expression(expr, *byte_pos, context, scopes, &mut value, out_name_err)?;
+
+                    let value = filters.iter().fold(String::from_utf8_lossy(&value).into_owned(), |value, filter| filter.apply(&value));
+                    into.write_all(value.as_bytes())
+                        .map_err(|e| err_io("write", format!("{} when writing filtered substitution", e), out_name_err.take().unwrap()))?
+                }
+            }
+            FormatNode::If(condition, byte_pos, then, otherwise) => {
+                if expression_truthy(condition, *byte_pos, context, scopes, out_name_err)? {
+                    format_nodes(then, context, scopes, into, out_name_err)?
+                } else {
+                    format_nodes(otherwise, context, scopes, into, out_name_err)?
+                }
+            }
+            FormatNode::Include(name, _, body) => {
+                let outer_name_err = out_name_err.replace(name.clone());
+                format_nodes(body, context, scopes, into, out_name_err)?;
+                *out_name_err = outer_name_err;
+            }
+            FormatNode::For(variable, FormatCollection::Tags, body) => {
+                for tag in context.tags.iter().flat_map(|tt| tt.iter()) {
+                    let mut bindings = BTreeMap::new();
+                    bindings.insert(variable.clone(), tag.to_string());
+                    bindings.insert(format!("{}-file_name", variable), tag.file_name());
+
+                    scopes.push(bindings);
+                    let result = format_nodes(body, context, scopes, into, out_name_err);
+                    scopes.pop();
+                    result?;
+                }
+            }
+        }
+    }
+
+    Ok(())
+}
+
+fn expression_truthy<St, Sc>(expr: &FormatExpression, byte_pos: usize, context: &FormatContext<St, Sc>, scopes: &mut Vec<BTreeMap<String, String>>,
+                             out_name_err: &mut Option<Cow<'static, str>>)
+                             -> Result<bool, Error>
+    where St: WrappedElement,
+          Sc: WrappedElement
+{
+    match expr {
+        FormatExpression::Data(key, fallback) => Ok(find_data(context, key).or(fallback.as_ref()).map(|data| !data.is_empty()).unwrap_or(false)),
+        FormatExpression::HasData(key) => Ok(find_data(context, key).is_some()),
+        FormatExpression::Name(name) if !(scopes.iter().any(|s| s.contains_key(name)) || BUILTIN_NAMES.contains(&&name[..]) ||
+                                          context.variables.contains_key(&name[..])) => Ok(false),
+        FormatExpression::Tags(_) => Ok(context.tags.iter().any(|tt| !tt.is_empty())),
+        FormatExpression::RelatedPosts(post_count) => Ok(*post_count != 0 && !context.related_posts.is_empty()),
+        expr => {
+            let mut buf = vec![];
+            format_expression(expr, byte_pos, context, scopes, &mut buf, out_name_err)?;
+            Ok(!buf.is_empty())
+        }
+    }
+}
+
+fn find_data<'a, St, Sc>(context: &FormatContext<'a, St, Sc>, key: &str) -> Option<&'a String> {
+    context.additional_data_sets.iter().rev().map(|dt| dt.get(key)).find(Option::is_some).into_iter().flatten().next()
+}
+
+fn format_expression<St, Sc>(expr: &FormatExpression, byte_pos: usize, context: &FormatContext<St, Sc>, scopes: &mut Vec<BTreeMap<String, String>>,
+                             mut into: &mut dyn Write, out_name_err: &mut Option<Cow<'static, str>>)
+                             -> Result<(), Error>
+    where St: WrappedElement,
+          Sc: WrappedElement
+{
+    match expr {
+            FormatExpression::Name(name) => {
+                if let Some(value) = scopes.iter().rev().filter_map(|s| s.get(name)).next() {
+                    into.write_all(value.as_bytes()).map_err(|e| (e, format!("{} tag", name).into()))
+                } else {
+                    match &name[..] {
+                        "language" => into.write_all(context.language.as_bytes()).map_err(|e| (e, "language tag".into())),
+                        "number" => into.write_fmt(format_args!("{}", context.number)).map_err(|e| (e, "number tag".into())),
+                        "title" => into.write_all(context.title.as_bytes()).map_err(|e| (e, "title tag".into())),
+                        "author" => into.write_all(context.author.as_bytes()).map_err(|e| (e, "author tag".into())),
+                        "raw_post_name" => into.write_all(context.raw_post_name.as_bytes()).map_err(|e| (e, "raw_post_name tag".into())),
+                        "normalised_post_name" => into.write_all(context.normalised_post_name.as_bytes()).map_err(|e| (e, "normalised_post_name tag".into())),
+                        "blog_name" => into.write_all(context.blog_name.as_bytes()).map_err(|e| (e, "blog_name tag".into())),
+
+                        "bloguen-version" => into.write_all(BLOGUEN_VERSION.as_bytes()).map_err(|e| (e, "bloguen-version tag".into())),
+
+                        "styles" => {
+                            Result::from_iter(context.styles.iter().map(|ss| {
+                                Result::from_iter(ss.iter().map(|s| {
+                                    into.write_all(s.head_b()).map_err(|e| (e, "style tag header".into()))?;
+                                    into.write_all(s.content_b()).map_err(|e| (e, "style tag content".into()))?;
+                                    into.write_all(s.foot_b()).map_err(|e| (e, "style tag footer".into()))?;
+
+                                    Ok(())
+                                }))
+                            }))
+                        }
+
+                        "scripts" => {
+                            Result::from_iter(context.scripts.iter().map(|ss| {
+                                Result::from_iter(ss.iter().map(|s| {
+                                    into.write_all(s.head_b()).map_err(|e| (e, "script tag header".into()))?;
+                                    into.write_all(s.content_b()).map_err(|e| (e, "script tag content".into()))?;
+                                    into.write_all(s.foot_b()).map_err(|e| (e, "script tag footer".into()))?;
+
+                                    Ok(())
+                                }))
+                            }))
+                        }
+
+                        var if context.variables.contains_key(var) => {
+                            into.write_all(context.variables[var].as_bytes()).map_err(|e| (e, format!("{} tag", var).into()))
+                        }
+
+                        _ => {
+                            return Err(err_parse(format!("unrecognised format specifier {} at position {}", name, byte_pos),
+                                                 out_name_err.take().unwrap()))
+                        }
+                    }
+                }
+            }
+
+            FormatExpression::Data(key, fallback) => {
+                match find_data(context, key).or(fallback.as_ref()) {
+                    Some(data) => into.write_all(data.as_bytes()).map_err(|e| (e, format!("data-{} tag with value {}", key, data).into())),
+                    None => return Err(err_parse(format!("missing value for data-{}", key), out_name_err.take().unwrap())),
+                }
+            }
+
+            FormatExpression::HasData(key) => {
+                if find_data(context, key).is_some() {
+                    into.write_all(b"true").map_err(|e| (e, format!("has_data({}) tag", key).into()))
+                } else {
+                    Ok(())
+                }
+            }
+
+            FormatExpression::Date(of_what, format) => {
+                let date_format = parse_date_format_specifier(format).unwrap();
+                let date = match &of_what[..] {
+                    "now_utc" => Some(Cow::Owned(normalise_datetime(&Utc::now()))),
+                    "now_local" => Some(Cow::Owned(normalise_datetime(&Local::now()))),
+                    "prev_post" | "next_post" => {
+                        context.variables
+                            .get(&format!("{}_date", of_what)[..])
+                            .and_then(|date| DateTime::parse_from_rfc3339(date).ok())
+                            .map(Cow::Owned)
                     }
+                    _ => Some(Cow::Borrowed(&context.post_date)),
+                };
 
-                    Some((fname, args)) => {
-                        return Err(err_parse(format!("unrecognised format function {} with arguments {:?} at position {}", fname, args, byte_pos),
-                                             out_name_err.take().unwrap()))
+                match date {
+                    Some(date) => {
+                        into.write_fmt(format_args!("{}", date.format_with_items(date_format.to_vec().into_iter())))
+                            .map_err(|e| (e, format!("{} date as {}", of_what, format).into()))
                     }
-                    _ => {
-                        return Err(err_parse(format!("unrecognised format specifier {} at position {}", format_str, byte_pos),
-                                             out_name_err.take().unwrap()))
+                    None => Ok(()),
+                }
+            }
+
+            FormatExpression::Tags(class) => {
+                write_tags(class.as_ref().map(|c| &c[..]).unwrap_or(&TAG_DEFAULT_CLASS),
+                           context.tags,
+                           context.variables.get("tag_index"),
+                           into)
+            }
+
+            FormatExpression::RelatedPosts(post_count) => {
+                write_related_posts(&context.related_posts[..cmp::min(*post_count, context.related_posts.len())], into)
+            }
+
+            FormatExpression::ReadingTime(words_per_minute) => {
+                match context.variables.get("word_count").and_then(|wc| wc.parse().ok()) {
+                    Some(word_count) => {
+                        into.write_fmt(format_args!("{}", reading_time(word_count, *words_per_minute)))
+                            .map_err(|e| (e, format!("reading_time({}) tag", words_per_minute).into()))
                     }
+                    None => Ok(()),
                 }
             }
+
+            FormatExpression::MachineData(kind) => {
+                *out_name_err = Some(machine_output_kind(kind)(context.blog_name,
+                                                               context.language,
+                                                               context.additional_data_sets,
+                                                               context.variables,
+                                                               context.raw_post_name,
+                                                               context.number,
+                                                               context.title,
+                                                               context.author,
+                                                               &context.post_date,
+                                                               context.tags,
+                                                               context.styles,
+                                                               context.scripts,
+                                                               &mut into,
+                                                               out_name_err.take().unwrap())?);
+                Ok(())
+            }
+
+            FormatExpression::PassParagraphs(para_count, expr) => {
+                format_expression(expr, byte_pos, context, scopes, &mut ParagraphPasser::new(into, *para_count), out_name_err)?;
+
+                Ok(())
+            }
         }
         .map_err(|(e, d): (_, Cow<'static, str>)| err_io("write", format!("{} when writing substituted {}", e, d), out_name_err.take().unwrap()))
 }
 
-fn write_tags<W: Write>(class: &str, tags: &[&[TagName]], into: &mut W) -> Result<(), (IoError, Cow<'static, str>)> {
+impl FormatFilter {
+    fn apply(&self, value: &str) -> String {
+        match *self {
+            FormatFilter::Html => xml_escape(value),
+            FormatFilter::Attr => xml_escape(value).replace('"', "&#34;").replace('\'', "&#39;"),
+            FormatFilter::Xml => xml_escape(value).replace('"', "&quot;").replace('\'', "&apos;"),
+            FormatFilter::Url => utf8_percent_encode(value, URL_FILTER_ESCAPED).to_string(),
+            FormatFilter::Json => {
+                let mut out = String::with_capacity(value.len() + 2);
+                out.push('"');
+                for c in value.chars() {
+                    match c {
+                        '"' => out.push_str("\\\""),
+                        '\\' => out.push_str("\\\\"),
+                        '\n' => out.push_str("\\n"),
+                        '\r' => out.push_str("\\r"),
+                        '\t' => out.push_str("\\t"),
+                        '<' | '>' | '&' | '\u{2028}' | '\u{2029}' => out.push_str(&format!("\\u{:04x}", c as u32)),
+                        c if c.is_control() => out.push_str(&format!("\\u{:04x}", c as u32)),
+                        c => out.push(c),
+                    }
+                }
+                out.push('"');
+                out
+            }
+            FormatFilter::Raw => value.to_string(),
+        }
+    }
+}
+
+fn xml_escape(value: &str) -> String {
+    let mut out = vec![];
+    XmlEscapeWrite(&mut out).write_all(value.as_bytes()).unwrap();
+    String::from_utf8(out).unwrap()
+}
+
+fn write_tags(class: &str, tags: &[&[TagName]], tag_index: Option<&String>, into: &mut dyn Write) -> Result<(), (IoError, Cow<'static, str>)> {
     Result::from_iter(tags.iter().enumerate().map(|(i, tt)| (i == tags.len() - 1, tt)).map(|(ee, tt)| {
         Result::from_iter(tt.iter().enumerate().map(|(i, t)| (i == tt.len() - 1, t)).map(|(e, t)| {
+            if let Some(tag_index) = tag_index {
+                into.write_all(TAG_LINK_HEAD.as_bytes()).map_err(|e| (e, "tag link header".into()))?;
+                into.write_all(FormatFilter::Attr.apply(tag_index).as_bytes()).map_err(|e| (e, "tag link index".into()))?;
+                into.write_all(FormatFilter::Attr.apply(&t.file_name()).as_bytes()).map_err(|e| (e, "tag link file name".into()))?;
+                into.write_all(TAG_LINK_CNTR.as_bytes()).map_err(|e| (e, "tag link center".into()))?;
+            }
             into.write_all(TAG_HEAD.as_bytes()).map_err(|e| (e, "tag header".into()))?;
             into.write_all(class.as_bytes()).map_err(|e| (e, "tag class".into()))?;
             into.write_all(TAG_CNTR.as_bytes()).map_err(|e| (e, "tag center".into()))?;
-            into.write_all(t.as_bytes()).map_err(|e| (e, "style tag footer".into()))?;
+            XmlEscapeWrite(&mut *into).write_all(t.as_bytes()).map_err(|e| (e, "tag name".into()))?;
             into.write_all(TAG_FOOT.as_bytes()).map_err(|e| (e, "tag footer".into()))?;
+            if tag_index.is_some() {
+                into.write_all(TAG_LINK_FOOT.as_bytes()).map_err(|e| (e, "tag link footer".into()))?;
+            }
             if !(e && ee) {
                 into.write_all(b" ").map_err(|e| (e, "tag spacer".into()))?;
             }
@@ -440,9 +1144,24 @@ fn write_tags<W: Write>(class: &str, tags: &[&[TagName]], into: &mut W) -> Resul
     }))
 }
 
+fn write_related_posts(related_posts: &[(String, String)], into: &mut dyn Write) -> Result<(), (IoError, Cow<'static, str>)> {
+    Result::from_iter(related_posts.iter().enumerate().map(|(i, &(ref title, ref link))| {
+        into.write_all(RELATED_POST_HEAD.as_bytes()).map_err(|e| (e, "related post header".into()))?;
+        into.write_all(FormatFilter::Attr.apply(link).as_bytes()).map_err(|e| (e, "related post link".into()))?;
+        into.write_all(RELATED_POST_CNTR.as_bytes()).map_err(|e| (e, "related post center".into()))?;
+        XmlEscapeWrite(&mut *into).write_all(title.as_bytes()).map_err(|e| (e, "related post title".into()))?;
+        into.write_all(RELATED_POST_FOOT.as_bytes()).map_err(|e| (e, "related post footer".into()))?;
+        if i != related_posts.len() - 1 {
+            into.write_all(b" ").map_err(|e| (e, "related post spacer".into()))?;
+        }
+
+        Ok(())
+    }))
+}
+
 
-fn err_parse<M: Into<Cow<'static, str>>>(more: M, out_name_err: Cow<'static, str>) -> Error {
-    err_parse_impl(more.into(), out_name_err)
+fn err_parse<M: Into<Cow<'static, str>>, W: Into<Cow<'static, str>>>(more: M, out_name_err: W) -> Error {
+    err_parse_impl(more.into(), out_name_err.into())
 }
 
 fn err_parse_impl(more: Cow<'static, str>, out_name_err: Cow<'static, str>) -> Error {
@@ -1,5 +1,5 @@
 use self::super::super::{MachineDataKind, WrappedElement, LanguageTag, TagName};
-use self::super::super::super::util::{BLOGUEN_VERSION, normalise_datetime};
+use self::super::super::super::util::{BLOGUEN_VERSION, DEFAULT_READING_SPEED, normalise_datetime, reading_time};
 use chrono::format::{Fixed as FixedTimeFormatItem, Item as TimeFormatItem};
 use chrono::{FixedOffset, DateTime, TimeZone, Local, Utc};
 use std::collections::{BTreeMap, BTreeSet};
@@ -17,6 +17,7 @@ pub fn machine_output_kind<W, E, Tz, St, Sc>(kind: &MachineDataKind)
                                              -> (fn(blog_name: &str,
                                                     language: &LanguageTag,
                                                     additional_data_sets: &[&BTreeMap<String, String>],
+                                                    variables: &BTreeMap<&'static str, String>,
                                                     raw_post_name: &str,
                                                     number: usize,
                                                     title: &str,
@@ -45,6 +46,10 @@ pub fn machine_output_kind<W, E, Tz, St, Sc>(kind: &MachineDataKind)
 /// Care should be taken to ensure the arguments to this funxion are as close as possible to the arguments to
 /// [`format_output()`](fn.format_output.html)
 ///
+/// The `variables` are output as top-level string fields after the blog name,
+/// except `word_count`, which is output as a number, followed by the `reading_time` in minutes
+/// at [`DEFAULT_READING_SPEED`](../util/constant.DEFAULT_READING_SPEED.html).
+///
 /// # Examples
 ///
 /// ```
@@ -59,8 +64,10 @@ pub fn machine_output_kind<W, E, Tz, St, Sc>(kind: &MachineDataKind)
 ///     vec![("desc".to_string(),
 ///           "Każdy koniec to nowy początek [PL]".to_string())].into_iter().collect();
 /// let mut out = vec![];
+/// let variables =
+///     vec![("permalink", "https://nabijaczleweli.xyz/blogue/posts/003.html".to_string())].into_iter().collect();
 /// let res = machine_output_json(
-///     "Блогг", &LANGUAGE_EN_GB, &[&global_data, &local_data],
+///     "Блогг", &LANGUAGE_EN_GB, &[&global_data, &local_data], &variables,
 ///     "003. 2018-02-05 release-front - a generic release front-end, like Patchwork's",
 ///     3, "release-front - a generic release front-end, like Patchwork's", "nabijaczleweli",
 ///     &DateTime::parse_from_rfc3339("2018-09-06T18:32:22+02:00").unwrap(),
@@ -100,6 +107,7 @@ pub fn machine_output_kind<W, E, Tz, St, Sc>(kind: &MachineDataKind)
 ///
 ///     "raw_post_name": "003. 2018-02-05 release-front - a generic release front-end, like Patchwork\'s",
 ///     "blog_name": "Блогг",
+///     "permalink": "https://nabijaczleweli.xyz/blogue/posts/003.html",
 ///
 ///     "post_date_rfc3339": "2018-09-06T18:32:22+02:00",
 ///     "post_date_rfc2822": "Thu,  6 Sep 2018 18:32:22 +0200",
@@ -131,8 +139,9 @@ pub fn machine_output_kind<W, E, Tz, St, Sc>(kind: &MachineDataKind)
 ///     gendate_utc_rfc3339, gendate_utc_rfc2822, gendate_local_rfc3339, gendate_local_rfc2822));
 /// ```
 pub fn machine_output_json<W, E, Tz, St, Sc>(blog_name: &str, language: &LanguageTag, additional_data_sets: &[&BTreeMap<String, String>],
-                                             raw_post_name: &str, number: usize, title: &str, author: &str, post_date: &DateTime<Tz>, tags: &[&[TagName]],
-                                             styles: &[&[St]], scripts: &[&[Sc]], into: &mut W, out_name_err: E)
+                                             variables: &BTreeMap<&'static str, String>, raw_post_name: &str, number: usize, title: &str, author: &str,
+                                             post_date: &DateTime<Tz>, tags: &[&[TagName]], styles: &[&[St]], scripts: &[&[Sc]], into: &mut W,
+                                             out_name_err: E)
                                              -> Result<Cow<'static, str>, Error>
     where W: Write,
           E: Into<Cow<'static, str>>,
@@ -143,6 +152,7 @@ pub fn machine_output_json<W, E, Tz, St, Sc>(blog_name: &str, language: &Languag
     machine_output_json_impl(blog_name,
                              language,
                              additional_data_sets,
+                             variables,
                              raw_post_name,
                              number,
                              title,
@@ -155,9 +165,10 @@ pub fn machine_output_json<W, E, Tz, St, Sc>(blog_name: &str, language: &Languag
                              out_name_err.into())
 }
 
-fn machine_output_json_impl<W, St, Sc>(blog_name: &str, language: &LanguageTag, additional_data_sets: &[&BTreeMap<String, String>], raw_post_name: &str,
-                                       number: usize, title: &str, author: &str, post_date: DateTime<FixedOffset>, tags: &[&[TagName]], styles: &[&[St]],
-                                       scripts: &[&[Sc]], into: &mut W, out_name_err: Cow<'static, str>)
+fn machine_output_json_impl<W, St, Sc>(blog_name: &str, language: &LanguageTag, additional_data_sets: &[&BTreeMap<String, String>],
+                                       variables: &BTreeMap<&'static str, String>, raw_post_name: &str, number: usize, title: &str, author: &str,
+                                       post_date: DateTime<FixedOffset>, tags: &[&[TagName]], styles: &[&[St]], scripts: &[&[Sc]], into: &mut W,
+                                       out_name_err: Cow<'static, str>)
                                        -> Result<Cow<'static, str>, Error>
     where W: Write,
           St: WrappedElement,
@@ -182,6 +193,16 @@ fn machine_output_json_impl<W, St, Sc>(blog_name: &str, language: &LanguageTag,
             write_string_variable("raw_post_name", raw_post_name, into)?;
             into.write_all(b",\n    ").map_err(|e| (e, "newline".into()))?;
             write_string_variable("blog_name", blog_name, into)?;
+            for (name, value) in variables.iter().filter(|&(name, _)| *name != "word_count") {
+                into.write_all(b",\n    ").map_err(|e| (e, "newline".into()))?;
+                write_string_variable(name, value, into)?;
+            }
+            if let Some(word_count) = variables.get("word_count").and_then(|wc| wc.parse().ok()) {
+                into.write_all(b",\n    \"word_count\": ").map_err(|e| (e, "word count pre".into()))?;
+                into.write_fmt(format_args!("{}", word_count)).map_err(|e| (e, "word count".into()))?;
+                into.write_all(b",\n    \"reading_time\": ").map_err(|e| (e, "reading time pre".into()))?;
+                into.write_fmt(format_args!("{}", reading_time(word_count, DEFAULT_READING_SPEED))).map_err(|e| (e, "reading time".into()))?;
+            }
             into.write_all(b",\n\n    ").map_err(|e| (e, "newline".into()))?;
 
             write_date("post_date_rfc3339", &post_date, FixedTimeFormatItem::RFC3339, into)?;
@@ -1,6 +1,7 @@
 mod machine_readable;
 mod paragraph_passer;
 mod wrapped_element;
+mod sitemap;
 mod format;
 mod feed;
 
@@ -12,8 +13,9 @@ pub use self::feed::{feed_type_post_footer, feed_atom_post_footer, feed_rss_post
                      feed_atom_header, feed_rss_header};
 pub use self::wrapped_element::{WrappedElementImpl, WrappedElement, ScriptElement, StyleElement};
 pub use self::machine_readable::{machine_output_json, machine_output_kind};
+pub use self::sitemap::{sitemap_header, sitemap_footer, sitemap_url, robots_txt};
 pub use self::paragraph_passer::ParagraphPasser;
-pub use self::format::format_output;
+pub use self::format::{FormatTemplate, format_output};
 
 
 fn err_io<M: Into<Cow<'static, str>>>(op: &'static str, more: M, out_name_err: Cow<'static, str>) -> Error {
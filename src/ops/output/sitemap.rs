@@ -0,0 +1,170 @@
+use self::super::super::super::util::{XmlEscapeWrite, normalise_datetime};
+use std::io::{Error as IoError, Write};
+use chrono::{FixedOffset, DateTime, TimeZone};
+use self::super::super::super::Error;
+use self::super::err_io;
+use std::fmt::Display;
+use std::borrow::Cow;
+
+
+static SITEMAP_HEAD: &'static str = include_str!("../../../assets/element_wrappers/sitemap/sitemap.head");
+static SITEMAP_FOOT: &'static str = include_str!("../../../assets/element_wrappers/sitemap/sitemap.foot");
+static ROBOTS_HEAD: &'static str = include_str!("../../../assets/element_wrappers/sitemap/robots.head");
+
+
+/// Output the header for an XML sitemap.
+///
+/// # Examples
+///
+/// ```
+/// # use bloguen::ops::sitemap_header;
+/// # use std::str;
+/// let mut out = vec![];
+/// let res = sitemap_header(&mut out, "test sitemap");
+/// assert_eq!(res, Ok("test sitemap".into()));
+///
+/// let out = str::from_utf8(&out).unwrap();
+/// assert_eq!(out, r###"<?xml version="1.0" encoding="UTF-8"?>
+/// <urlset xmlns="http://www.sitemaps.org/schemas/sitemap/0.9">
+/// "###);
+/// ```
+pub fn sitemap_header<W, E>(into: &mut W, out_name_err: E) -> Result<Cow<'static, str>, Error>
+    where W: Write,
+          E: Into<Cow<'static, str>>
+{
+    sitemap_wrapper_impl(SITEMAP_HEAD, "header", into, out_name_err.into())
+}
+
+/// Output a URL entry for an XML sitemap.
+///
+/// The `link` argument should be absolute, the `last_modified` date is written out with second precision.
+///
+/// # Examples
+///
+/// ```
+/// # extern crate bloguen;
+/// # extern crate chrono;
+/// # use bloguen::ops::sitemap_url;
+/// # use chrono::DateTime;
+/// # use std::str;
+/// let mut out = vec![];
+/// let res = sitemap_url("https://nabijaczleweli.xyz/blogue/posts/003.%202018-02-05%2012-33-05%20release-front.html",
+///                       &DateTime::parse_from_rfc3339("2018-09-06T18:32:22.5+02:00").unwrap(),
+///                       &mut out, "test sitemap");
+/// assert_eq!(res, Ok("test sitemap".into()));
+///
+/// let out = str::from_utf8(&out).unwrap();
+/// assert_eq!(out, r###"  <url>
+///     <loc>https://nabijaczleweli.xyz/blogue/posts/003.%202018-02-05%2012-33-05%20release-front.html</loc>
+///     <lastmod>2018-09-06T18:32:22+02:00</lastmod>
+///   </url>
+/// "###);
+/// ```
+pub fn sitemap_url<W, E, Tz>(link: &str, last_modified: &DateTime<Tz>, into: &mut W, out_name_err: E) -> Result<Cow<'static, str>, Error>
+    where Tz: TimeZone,
+          W: Write,
+          E: Into<Cow<'static, str>>
+{
+    sitemap_url_impl(link, normalise_datetime(last_modified), into, out_name_err.into())
+}
+
+/// Output the footer for an XML sitemap.
+///
+/// # Examples
+///
+/// ```
+/// # use bloguen::ops::sitemap_footer;
+/// # use std::str;
+/// let mut out = vec![];
+/// let res = sitemap_footer(&mut out, "test sitemap");
+/// assert_eq!(res, Ok("test sitemap".into()));
+///
+/// let out = str::from_utf8(&out).unwrap();
+/// assert_eq!(out, "</urlset>\n");
+/// ```
+pub fn sitemap_footer<W, E>(into: &mut W, out_name_err: E) -> Result<Cow<'static, str>, Error>
+    where W: Write,
+          E: Into<Cow<'static, str>>
+{
+    sitemap_wrapper_impl(SITEMAP_FOOT, "footer", into, out_name_err.into())
+}
+
+/// Output a `robots.txt` allowing everything and pointing at the specified sitemap.
+///
+/// # Examples
+///
+/// ```
+/// # use bloguen::ops::robots_txt;
+/// # use std::str;
+/// let mut out = vec![];
+/// let res = robots_txt("https://nabijaczleweli.xyz/sitemap.xml", &mut out, "test robots");
+/// assert_eq!(res, Ok("test robots".into()));
+///
+/// let out = str::from_utf8(&out).unwrap();
+/// assert_eq!(out, r###"User-agent: *
+/// Disallow:
+///
+/// Sitemap: https://nabijaczleweli.xyz/sitemap.xml
+/// "###);
+/// ```
+pub fn robots_txt<W, E>(sitemap_link: &str, into: &mut W, out_name_err: E) -> Result<Cow<'static, str>, Error>
+    where W: Write,
+          E: Into<Cow<'static, str>>
+{
+    robots_txt_impl(sitemap_link, into, out_name_err.into())
+}
+
+fn sitemap_wrapper_impl<W>(wrapper: &str, what: &'static str, into: &mut W, out_name_err: Cow<'static, str>) -> Result<Cow<'static, str>, Error>
+    where W: Write
+{
+    into.write_all(wrapper.as_bytes()).map_err(|e| err_io("write", format!("{} when writing sitemap output {}", e, what), out_name_err.clone()))?;
+
+    Ok(out_name_err)
+}
+
+fn sitemap_url_impl<W>(link: &str, last_modified: DateTime<FixedOffset>, into: &mut W, out_name_err: Cow<'static, str>) -> Result<Cow<'static, str>, Error>
+    where W: Write
+{
+    let mut out_name_err = Some(out_name_err);
+
+    (|| {
+            into.write_all(b"  <url>\n").map_err(|e| (e, "url tag header".into()))?;
+
+            write_tag("loc", link, into)?;
+            write_tag("lastmod", last_modified.format("%Y-%m-%dT%H:%M:%S%:z"), into)?;
+
+            into.write_all(b"  </url>\n").map_err(|e| (e, "url tag footer".into()))?;
+
+            Ok(())
+        })().map_err(|(e, d): (_, Cow<'static, str>)| err_io("write", format!("{} when writing sitemap output {}", e, d), out_name_err.take().unwrap()))?;
+
+    Ok(out_name_err.unwrap())
+}
+
+fn robots_txt_impl<W>(sitemap_link: &str, into: &mut W, out_name_err: Cow<'static, str>) -> Result<Cow<'static, str>, Error>
+    where W: Write
+{
+    let mut out_name_err = Some(out_name_err);
+
+    (|| {
+            into.write_all(ROBOTS_HEAD.as_bytes()).map_err(|e| (e, "header".into()))?;
+            into.write_all(sitemap_link.as_bytes()).map_err(|e| (e, "sitemap link".into()))?;
+            into.write_all(b"\n").map_err(|e| (e, "footer".into()))?;
+
+            Ok(())
+        })().map_err(|(e, d): (_, Cow<'static, str>)| err_io("write", format!("{} when writing robots.txt output {}", e, d), out_name_err.take().unwrap()))?;
+
+    Ok(out_name_err.unwrap())
+}
+
+fn write_tag<W: Write, V: Display>(name: &str, value: V, mut into: &mut W) -> Result<(), (IoError, Cow<'static, str>)> {
+    into.write_all(b"    <").map_err(|e| (e, "tag pre start".into()))?;
+    into.write_all(name.as_bytes()).map_err(|e| (e, format!("{} open tag name", name).into()))?;
+    into.write_all(b">").map_err(|e| (e, "tag pre end".into()))?;
+    XmlEscapeWrite(&mut into).write_fmt(format_args!("{}", value)).map_err(|e| (e, format!("{} tag content", name).into()))?;
+    into.write_all(b"</").map_err(|e| (e, "tag post start".into()))?;
+    into.write_all(name.as_bytes()).map_err(|e| (e, format!("{} closing tag name", name).into()))?;
+    into.write_all(b">\n").map_err(|e| (e, "tag post end".into()))?;
+
+    Ok(())
+}
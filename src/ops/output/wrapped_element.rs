@@ -732,6 +732,24 @@ impl<Dt: WrappedElementImplData> WrappedElementImpl<Dt> {
     pub fn load(&mut self, base: &(String, PathBuf)) -> Result<(), Error> {
         self.0.load(base, Dt::file_load_what_for())
     }
+
+    /// Get the path this element will be loaded from, if it's a not-yet-loaded file element.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use bloguen::ops::{StyleElement, ScriptElement};
+    /// assert_eq!(StyleElement::from_path("assets/effects.css").file_path(), Some("assets/effects.css"));
+    /// assert_eq!(StyleElement::from_literal(".ruby { color: #E0115F; }").file_path(), None);
+    /// assert_eq!(ScriptElement::from_link("/content/assets/syllable.js").file_path(), None);
+    /// ```
+    pub fn file_path(&self) -> Option<&str> {
+        if self.0.class == ElementClass::File {
+            Some(&self.0.data)
+        } else {
+            None
+        }
+    }
 }
 
 
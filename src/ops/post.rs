@@ -1,19 +1,26 @@
-use self::super::{MachineDataKind, ScriptElement, StyleElement, LanguageTag, FeedType, TagName, feed_type_post_footer, feed_type_post_header,
+use self::super::{PostRenderOptions, MachineDataKind, MarkdownSource, ScriptElement, SourceFormat,
+                  StyleElement, PostMetadata, LanguageTag, FeedType, TagName, SOURCE_FORMATS, feed_type_post_footer, feed_type_post_header, FormatTemplate,
                   machine_output_kind, format_output};
-use self::super::super::util::{PolyWrite, MARKDOWN_OPTIONS, extract_actual_assets, name_based_post_time, extract_links, concat_path, path_depth, read_file,
-                               mul_str};
+use self::super::super::util::{PolyWrite, BLOGUEN_VERSION, extract_actual_assets, name_based_post_time, extract_links, content_hash,
+                               concat_path, write_excerpt, highlight_code_blocks, path_depth, read_file, resolve_url, run_hook, mul_str};
 use walkdir::{Error as WalkDirError, DirEntry, WalkDir};
 use chrono::{NaiveTime, DateTime, TimeZone};
 use chrono::offset::Local as LocalOffset;
-use comrak::{self, Arena as ComrakArena};
+use comrak::nodes::AstNode as ComrakAstNode;
+use comrak::{self, ComrakOptions, Arena as ComrakArena};
 use std::io::{Error as IoError, Write};
-use std::collections::BTreeMap;
+use std::collections::{BTreeMap, BTreeSet};
+use std::cmp::{self, Reverse};
+use std::borrow::Cow;
 use self::super::super::Error;
 use std::num::ParseIntError;
 use std::iter::FromIterator;
 use std::fs::{self, File};
+use std::time::UNIX_EPOCH;
 use std::path::PathBuf;
+use percent_encoding::{AsciiSet, CONTROLS, utf8_percent_encode};
 use regex::Regex;
+use url::Url;
 use std::str;
 
 
@@ -21,12 +28,15 @@ lazy_static! {
     static ref POST_DIR_NAME: Regex = Regex::new(include_str!("../../assets/post_dir_name.regex").trim()).unwrap();
 }
 
+/// Characters percent-encoded in relative links to other posts, the same ones as in URL paths.
+static LINK_ESCAPED: &AsciiSet = &CONTROLS.add(b' ').add(b'"').add(b'#').add(b'<').add(b'>').add(b'?').add(b'`').add(b'{').add(b'}');
+
 
 /// Information about a blogue post.
 ///
 /// Use `list()` to find valid post directories, then use `new()` to get the post data.
 ///
-/// A correct post directory name is `#+. YYYY-MM-DD [HH-MM[-SS]] name`.
+/// A correct post directory name is `#+. YYYY-MM-DD [HH-MM[-SS]] name[_draft]`.
 #[derive(Debug, Clone, Hash, PartialEq, Eq, PartialOrd, Ord)]
 pub struct BloguePost {
     /// Directory containing the post data.
@@ -34,9 +44,25 @@ pub struct BloguePost {
     /// Post number.
     pub number: (usize, String),
     /// Post name.
+    ///
+    /// Overriden by [`PostMetadata::title`](struct.PostMetadata.html#structfield.title).
     pub name: String,
+    /// Post slug, substituted for `{slug}` in the output path.
+    ///
+    /// Defaults to the post's [`normalised_name()`](#method.normalised_name),
+    /// overriden by [`PostMetadata::slug`](struct.PostMetadata.html#structfield.slug).
+    pub slug: String,
+    /// Path of the post's HTML relative to the output directory, into whose directory its assets are also copied.
+    ///
+    /// Defaults to `posts/{slug}.html`, see [`apply_permalink_pattern()`](#method.apply_permalink_pattern).
+    pub path: String,
     /// Date & time of posting.
     pub datetime: DateTime<LocalOffset>,
+    /// Whether the post is a draft, set if its directory name ends with `_draft`,
+    /// or by [`PostMetadata::draft`](struct.PostMetadata.html#structfield.draft).
+    ///
+    /// Drafts are only generated if requested.
+    pub draft: bool,
 }
 
 impl BloguePost {
@@ -103,6 +129,8 @@ impl BloguePost {
 
     /// Read post data into a `BloguePost` instance.
     ///
+    /// A `_draft` suffix is stripped from the name, marking the post as a draft.
+    ///
     /// Examples:
     ///
     /// ```
@@ -132,7 +160,10 @@ impl BloguePost {
     ///                source_dir: dir,
     ///                number: (1, "01".to_string()),
     ///                name: "The venture into crocheting".to_string(),
+    ///                slug: "01. 2018-01-08 16-52-00 The venture into crocheting".to_string(),
+    ///                path: "posts/01. 2018-01-08 16-52-00 The venture into crocheting.html".to_string(),
     ///                datetime: LocalOffset.ymd(2018, 01, 08).and_hms(16, 52, 00),
+    ///                draft: false,
     ///            }));
     ///
     /// let dir = ("$ROOT/posts/003. 2018-02-05 release-front - release front-end".to_string(),
@@ -142,7 +173,10 @@ impl BloguePost {
     ///                source_dir: dir,
     ///                number: (3, "003".to_string()),
     ///                name: "release-front - release front-end".to_string(),
+    ///                slug: "003. 2018-02-05 23-24-43 release-front - release front-end".to_string(),
+    ///                path: "posts/003. 2018-02-05 23-24-43 release-front - release front-end.html".to_string(),
     ///                datetime: LocalOffset.ymd(2018, 02, 05).and_hms(23, 24, 43),
+    ///                draft: false,
     ///            }));
     ///
     /// let dir = ("$ROOT/posts/004. stir plate".to_string(),
@@ -173,13 +207,19 @@ impl BloguePost {
                         more: "not found".into(),
                     }
                 })?;
-            let name = mch.name("name").unwrap().as_str();
+            let mut name = mch.name("name").unwrap().as_str();
+            let draft = name.ends_with("_draft");
+            if draft {
+                name = name[..name.len() - "_draft".len()].trim_end();
+            }
             let number = mch.name("post_number").unwrap().as_str();
 
             BloguePost {
                 source_dir: (String::new(), PathBuf::from(String::new())),
                 number: (number.parse().map_err(|e| uint_err("post number", e))?, number.to_string()),
                 name: name.to_string(),
+                slug: String::new(),
+                path: String::new(),
                 datetime: LocalOffset.ymd(mch.name("date_year").unwrap().as_str().parse().map_err(|e| uint_err("post date year", e))?,
                          mch.name("date_month").unwrap().as_str().parse().map_err(|e| uint_err("post date month", e))?,
                          mch.name("date_day").unwrap().as_str().parse().map_err(|e| uint_err("post date day", e))?)
@@ -195,18 +235,54 @@ impl BloguePost {
                         name_based_post_time(name)
                     })
                     .unwrap(),
+                draft: draft,
             }
         };
         ret.source_dir = wher;
+        ret.slug = ret.normalised_name();
+        ret.path = format!("posts/{}.html", ret.slug);
         Ok(ret)
     }
 
-    /// Generate an HTML output from the post into the specified output directory.
+    /// Generate an HTML output from the post's [`source()`](#method.source) into its [`path`](#structfield.path)
+    /// in the specified output directory.
     ///
-    /// Alternate output is filled with the HTML-formatted post Markdown.
+    /// Alternate output is filled with the HTML-formatted post, or, if its flag is set, with just the post's excerpt.
     ///
     /// Center output is filled with the specified template filled-out with additional `post_content` data element
-    /// consisting of the HTML-formatted post Markdown.
+    /// consisting of the HTML-formatted post.
+    ///
+    /// The post's page and its source are rendered according to the specified [`PostRenderOptions`](struct.PostRenderOptions.html).
+    ///
+    /// Paged center output is filled likewise to center output, but with links relative to the `page/` subdirectory;
+    /// it's ignored if center output isn't specified.
+    ///
+    /// Tag center output is filled likewise, but with links relative to the `tags/` subdirectory;
+    /// if it's specified, the `tag_index` variable is set to the tag index directory and the tags are linked to their indices.
+    ///
+    /// The `base_url` and `permalink` variables are set according to the specified base URL,
+    /// see [`permalink()`](#method.permalink).
+    ///
+    /// The `prev_post_*` and `next_post_*` variables describe the specified previous and next posts, if any,
+    /// and are empty otherwise.
+    ///
+    /// The `related_posts(n)` function links to up to `n` of the specified related posts,
+    /// which should be ranked by [`related_posts()`](#method.related_posts).
+    ///
+    /// If `toc` is set, the `toc` variable is set to a table of contents of the post,
+    /// see [`SourceFormat::table_of_contents()`](trait.SourceFormat.html#tymethod.table_of_contents), and is empty otherwise.
+    ///
+    /// The `excerpt` variable is set to the post's excerpt, see [`write_excerpt()`](../util/fn.write_excerpt.html).
+    ///
+    /// If `highlight` is specified, the post's fenced code blocks are highlighted according to it in every output,
+    /// see [`highlight_code_blocks()`](../util/fn.highlight_code_blocks.html).
+    ///
+    /// If `math` is set, the post's TeX math is rendered to MathML in every output, see [`render_math()`](../util/fn.render_math.html).
+    ///
+    /// The post's source is piped through the `pre` hooks before being parsed, and its HTML, header and footer included,
+    /// through the `post` hooks before being written, see [`run_hook()`](../util/fn.run_hook.html).
+    /// The hooks get the post's variables, `BLOGUEN_`-prefixed and uppercased, with non-alphanumerics replaced with underscores,
+    /// as environment variables; the `pre` hooks don't get `word_count`, `toc`, or `excerpt`.
     ///
     /// Returns: set of links in the markdown source.
     ///
@@ -239,12 +315,12 @@ impl BloguePost {
     /// let post =
     ///     BloguePost::new(("$ROOT/src/01. 2018-01-08 16-52 The venture into crocheting".to_string(),
     ///         root.join("src").join("01. 2018-01-08 16-52 The venture into crocheting"))).unwrap();
-    /// assert!(post.generate(&("$ROOT/out/".to_string(), root.join("out")), None, None, None, "header", "footer",
-    ///                       "Блогг", &LANGUAGE_EN_GB, "autheur", &[], &[], &Default::default(), &Default::default(),
-    ///                       &[], &[], &[], &[]).is_ok());
-    /// # assert_eq!(post.generate(&("$ROOT/out/".to_string(), root.join("out")), None, None, None, "header", "footer",
-    /// #                          "Блогг", &LANGUAGE_EN_GB, "autheur", &[], &[], &Default::default(), &Default::default(),
-    /// #                          &[], &[], &[], &[]),
+    /// assert!(post.generate(&("$ROOT/out/".to_string(), root.join("out")), None, None, None, Default::default(),
+    ///                       &"header".parse().unwrap(), &"footer".parse().unwrap(), "Блогг", &LANGUAGE_EN_GB, "autheur", &[], &[],
+    ///                       &Default::default(), &Default::default(), &[], &[], &[], &[]).is_ok());
+    /// # assert_eq!(post.generate(&("$ROOT/out/".to_string(), root.join("out")), None, None, None, Default::default(),
+    /// #                          &"header".parse().unwrap(), &"footer".parse().unwrap(), "Блогг", &LANGUAGE_EN_GB, "autheur", &[],
+    /// #                          &[], &Default::default(), &Default::default(), &[], &[], &[], &[]),
     /// #            Ok(vec!["url.html".to_string()]));
     ///
     /// assert!(root.join("out").join("posts")
@@ -254,10 +330,11 @@ impl BloguePost {
     /// #                .unwrap().read_to_string(&mut read).unwrap();
     /// # assert_eq!(read, "header<p><a href=\"url.html\">Блогг</a></p>\nfooter");
     /// ```
-    pub fn generate(&self, into: &(String, PathBuf), mut alt_output: Option<&mut dyn Write>, center_output: Option<(&str, &mut dyn Write)>,
-                    asset_override: Option<&str>, post_header: &str, post_footer: &str, blog_name: &str, language: &LanguageTag, author: &str,
-                    spec_tags: &[TagName], free_tags: &[TagName], post_data: &BTreeMap<String, String>, global_data: &BTreeMap<String, String>,
-                    post_styles: &[StyleElement], global_styles: &[StyleElement], post_scripts: &[ScriptElement], global_scripts: &[ScriptElement])
+    pub fn generate(&self, into: &(String, PathBuf), alt_output: Option<(&mut dyn Write, bool)>, center_output: Option<(&FormatTemplate, &mut dyn Write)>,
+                    asset_override: Option<&str>, mut render: PostRenderOptions, post_header: &FormatTemplate, post_footer: &FormatTemplate, blog_name: &str,
+                    language: &LanguageTag, author: &str, spec_tags: &[TagName], free_tags: &[TagName],
+                    post_data: &BTreeMap<String, String>, global_data: &BTreeMap<String, String>, post_styles: &[StyleElement],
+                    global_styles: &[StyleElement], post_scripts: &[ScriptElement], global_scripts: &[ScriptElement])
                     -> Result<Vec<String>, Error> {
         fn write_err(err: IoError, desc: &'static str) -> Error {
             Error::Io {
@@ -267,14 +344,49 @@ impl BloguePost {
             }
         }
 
+        fn utf8_excerpt(excerpt: Vec<u8>) -> Result<String, Error> {
+            String::from_utf8(excerpt).map_err(|e| {
+                Error::Parse {
+                    tp: "UTF-8 string",
+                    wher: "post excerpt".into(),
+                    more: e.to_string().into(),
+                }
+            })
+        }
+
 
-        let post_text = read_file(&(format!("{}post.md", self.source_dir.0), self.source_dir.1.join("post.md")), "post text")?;
+        let (base_url, previous_post, next_post, related_posts) = (render.base_url, render.previous_post, render.next_post, render.related_posts);
+        let paged_center_output = render.paged_center_output.take();
+        let tag_center_output = render.tag_center_output.take();
+
+        let normalised_name = self.normalised_name();
+        let post_root = self.root();
+        let original_name = self.source_dir.1.file_name().unwrap().to_str().unwrap();
+        let tag_index_enabled = tag_center_output.is_some();
+        let tag_index = format!("{}tags/", post_root);
+        let mut variables = self.variables(base_url, &post_root, if tag_index_enabled { Some(&tag_index) } else { None }, None);
+        BloguePost::neighbour_variables(&mut variables, base_url, &post_root, previous_post, next_post);
 
+        let markdown = render.markdown.comrak_options();
         let arena = ComrakArena::new();
-        let root = comrak::parse_document(&arena, &post_text, &MARKDOWN_OPTIONS);
+        let (source_format, post_text, root) =
+            self.parse_source(&arena,
+                              &render,
+                              &markdown,
+                              || self.hook_environment(blog_name, language, author, &[spec_tags, free_tags], &[global_data, post_data], &variables))?;
         let out_links = extract_links(root)?;
+        let word_count = source_format.word_count(&post_text, root);
+        let toc = if render.toc {
+            source_format.table_of_contents(&post_text, root, &markdown)
+        } else {
+            String::new()
+        };
+        if let Some(highlight) = render.highlight {
+            highlight_code_blocks(root, &highlight.theme, highlight.classes)?;
+        }
 
-        fs::create_dir_all(into.1.join("posts")).map_err(|e| {
+        let post_html_path = concat_path(&into.1, &self.path);
+        fs::create_dir_all(post_html_path.parent().unwrap()).map_err(|e| {
                 Error::Io {
                     desc: "posts directory".into(),
                     op: "create",
@@ -282,8 +394,6 @@ impl BloguePost {
                 }
             })?;
 
-        let normalised_name = self.normalised_name();
-        let post_html_path = into.1.join("posts").join(format!("{}.html", normalised_name));
         let mut post_html_f = File::create(post_html_path).map_err(|e| {
                 Error::Io {
                     desc: "post HTML".into(),
@@ -292,57 +402,101 @@ impl BloguePost {
                 }
             })?;
 
-        let original_name = self.source_dir.1.file_name().unwrap().to_str().unwrap();
-        let normalised_name_err = format_output(post_header,
-                                                blog_name,
-                                                language,
-                                                &[global_data, post_data],
-                                                &original_name,
-                                                &normalised_name,
-                                                self.number.0,
-                                                &self.name,
-                                                author,
-                                                &self.datetime,
-                                                &[spec_tags, free_tags],
-                                                &[global_styles, post_styles],
-                                                &[global_scripts, post_scripts],
-                                                &mut post_html_f,
-                                                normalised_name.clone())?;
-
-        let mut center_output = center_output.map(|(f, o)| (f, o, vec![]));
+        let alt_output_excerpt = alt_output.as_ref().map(|&(_, excerpt)| excerpt).unwrap_or(false);
+        let mut alt_output = alt_output.map(|(o, _)| o);
+        let mut post_content = vec![];
+        let mut post_excerpt = vec![];
+        let mut paged_center_output = paged_center_output.filter(|_| center_output.is_some()).map(|o| (o, vec![], vec![]));
+        let mut center_output = center_output.map(|(f, o)| (f, o, vec![], vec![]));
+        let mut tag_center_output = tag_center_output.map(|(f, o)| (f, o, vec![], vec![]));
         if let Some(asset_override) = asset_override {
             let mut asset_set = extract_actual_assets(&self.source_dir.1, root)?;
 
             asset_set.iter_mut().for_each(|url| { url.splice(0..0, asset_override.as_bytes().iter().cloned()); });
-            if let Some((_, _, center_tmp)) = center_output.as_mut() {
-                comrak::format_html(root, &MARKDOWN_OPTIONS, center_tmp).map_err(|e| write_err(e, "post center HTML"))?;
+            if let Some((_, _, center_tmp, center_excerpt)) = center_output.as_mut() {
+                comrak::format_html(root, &markdown, center_tmp).map_err(|e| write_err(e, "post center HTML"))?;
+                write_excerpt(root, &markdown, center_excerpt).map_err(|e| write_err(e, "post center excerpt HTML"))?;
             }
 
             asset_set.iter_mut().for_each(|url| { url.splice(0..0, b"../".iter().cloned()); });
-            if let Some(ref mut alt_out) = alt_output.as_mut() {
-                    comrak::format_html(root, &MARKDOWN_OPTIONS, &mut PolyWrite(&mut post_html_f, alt_out))
-                } else {
-                    comrak::format_html(root, &MARKDOWN_OPTIONS, &mut post_html_f)
+            if let Some((_, paged_center_tmp, paged_center_excerpt)) = paged_center_output.as_mut() {
+                comrak::format_html(root, &markdown, paged_center_tmp).map_err(|e| write_err(e, "post paged center HTML"))?;
+                write_excerpt(root, &markdown, paged_center_excerpt).map_err(|e| write_err(e, "post paged center excerpt HTML"))?;
+            }
+            if let Some((_, _, tag_center_tmp, tag_center_excerpt)) = tag_center_output.as_mut() {
+                comrak::format_html(root, &markdown, tag_center_tmp).map_err(|e| write_err(e, "post tag center HTML"))?;
+                write_excerpt(root, &markdown, tag_center_excerpt).map_err(|e| write_err(e, "post tag center excerpt HTML"))?;
+            }
+
+            asset_set.iter_mut().for_each(|url| { url.splice(0..3, post_root.as_bytes().iter().cloned()); });
+            match alt_output.as_mut() {
+                    Some(alt_out) if !alt_output_excerpt => comrak::format_html(root, &markdown, &mut PolyWrite(&mut post_content, alt_out)),
+                    _ => comrak::format_html(root, &markdown, &mut post_content),
                 }.map_err(|e| write_err(e, "post HTML"))?;
+            write_excerpt(root, &markdown, &mut post_excerpt).map_err(|e| write_err(e, "post excerpt HTML"))?;
         } else {
-            if let Some(ref mut alt_out) = alt_output.as_mut() {
-                    comrak::format_html(root, &MARKDOWN_OPTIONS, &mut PolyWrite(&mut post_html_f, alt_out))
-                } else {
-                    comrak::format_html(root, &MARKDOWN_OPTIONS, &mut post_html_f)
+            match alt_output.as_mut() {
+                    Some(alt_out) if !alt_output_excerpt => comrak::format_html(root, &markdown, &mut PolyWrite(&mut post_content, alt_out)),
+                    _ => comrak::format_html(root, &markdown, &mut post_content),
                 }.map_err(|e| write_err(e, "post HTML"))?;
+            write_excerpt(root, &markdown, &mut post_excerpt).map_err(|e| write_err(e, "post excerpt HTML"))?;
 
-            if let Some((_, _, center_tmp)) = center_output.as_mut() {
+            if center_output.is_some() || tag_center_output.is_some() {
                 let mut asset_set = extract_actual_assets(&self.source_dir.1, root)?;
-                asset_set.iter_mut().for_each(|url| { url.splice(0..0, b"posts/".iter().cloned()); });
+                asset_set.iter_mut().for_each(|url| { url.splice(0..0, self.directory().as_bytes().iter().cloned()); });
+
+                if let Some((_, _, center_tmp, center_excerpt)) = center_output.as_mut() {
+                    comrak::format_html(root, &markdown, center_tmp).map_err(|e| write_err(e, "post center HTML"))?;
+                    write_excerpt(root, &markdown, center_excerpt).map_err(|e| write_err(e, "post center excerpt HTML"))?;
+                }
 
-                comrak::format_html(root, &MARKDOWN_OPTIONS, center_tmp).map_err(|e| write_err(e, "post center HTML"))?;
+                asset_set.iter_mut().for_each(|url| { url.splice(0..0, b"../".iter().cloned()); });
+                if let Some((_, paged_center_tmp, paged_center_excerpt)) = paged_center_output.as_mut() {
+                    comrak::format_html(root, &markdown, paged_center_tmp).map_err(|e| write_err(e, "post paged center HTML"))?;
+                    write_excerpt(root, &markdown, paged_center_excerpt).map_err(|e| write_err(e, "post paged center excerpt HTML"))?;
+                }
+                if let Some((_, _, tag_center_tmp, tag_center_excerpt)) = tag_center_output.as_mut() {
+                    comrak::format_html(root, &markdown, tag_center_tmp).map_err(|e| write_err(e, "post tag center HTML"))?;
+                    write_excerpt(root, &markdown, tag_center_excerpt).map_err(|e| write_err(e, "post tag center excerpt HTML"))?;
+                }
             }
         }
+        if alt_output_excerpt {
+            if let Some(alt_out) = alt_output.as_mut() {
+                alt_out.write_all(&post_excerpt).map_err(|e| write_err(e, "post excerpt HTML"))?;
+            }
+        }
+
+        variables.insert("word_count", word_count.to_string());
+        variables.insert("toc", toc.clone());
+        variables.insert("excerpt", utf8_excerpt(post_excerpt)?);
+        let related = BloguePost::related_links(base_url, &post_root, related_posts);
+        let mut post_html = vec![];
+        let normalised_name_err = format_output(post_header,
+                                                blog_name,
+                                                language,
+                                                &[global_data, post_data],
+                                                &variables,
+                                                &original_name,
+                                                &normalised_name,
+                                                self.number.0,
+                                                &self.name,
+                                                author,
+                                                &self.datetime,
+                                                &[spec_tags, free_tags],
+                                                &related,
+                                                &[global_styles, post_styles],
+                                                &[global_scripts, post_scripts],
+                                                &mut post_html,
+                                                normalised_name.clone())?;
+
+        post_html.extend_from_slice(&post_content);
 
         let normalised_name_err = format_output(post_footer,
                                                 blog_name,
                                                 language,
                                                 &[global_data, post_data],
+                                                &variables,
                                                 &original_name,
                                                 &normalised_name,
                                                 self.number.0,
@@ -350,12 +504,29 @@ impl BloguePost {
                                                 author,
                                                 &self.datetime,
                                                 &[spec_tags, free_tags],
+                                                &related,
                                                 &[global_styles, post_styles],
                                                 &[global_scripts, post_scripts],
-                                                &mut post_html_f,
+                                                &mut post_html,
                                                 normalised_name_err)?;
 
-        if let Some((center, mut center_out, center_temp)) = center_output {
+        if !render.hooks.post.is_empty() {
+            let environment = self.hook_environment(blog_name, language, author, &[spec_tags, free_tags], &[global_data, post_data], &variables);
+            let wher = format!("post HTML of {}", normalised_name);
+            for command in &render.hooks.post {
+                post_html = run_hook(command, post_html, &environment, &wher)?;
+            }
+        }
+        post_html_f.write_all(&post_html).map_err(|e| write_err(e, "post HTML"))?;
+
+        let format_center = |center: &FormatTemplate, mut center_out: &mut dyn Write, center_temp: Vec<u8>, center_excerpt: Vec<u8>, root: &str,
+                             tag_index: &str, normalised_name_err: Cow<'static, str>| {
+            let mut variables = self.variables(base_url, root, if tag_index_enabled { Some(tag_index) } else { None }, Some(word_count));
+            variables.insert("toc", toc.clone());
+            variables.insert("excerpt", utf8_excerpt(center_excerpt)?);
+            BloguePost::neighbour_variables(&mut variables, base_url, root, previous_post, next_post);
+            let related = BloguePost::related_links(base_url, root, related_posts);
+
             let mut temp_data = BTreeMap::new();
             temp_data.insert("post_content".to_string(),
                              String::from_utf8(center_temp).map_err(|e| {
@@ -370,6 +541,7 @@ impl BloguePost {
                           blog_name,
                           language,
                           &[global_data, post_data, &temp_data],
+                          &variables,
                           &original_name,
                           &normalised_name,
                           self.number.0,
@@ -377,10 +549,22 @@ impl BloguePost {
                           author,
                           &self.datetime,
                           &[spec_tags, free_tags],
+                          &related,
                           &[global_styles, post_styles],
                           &[global_scripts, post_scripts],
                           &mut center_out,
-                          normalised_name_err)?;
+                          normalised_name_err)
+        };
+
+        let mut normalised_name_err = normalised_name_err;
+        if let Some((center, center_out, center_temp, center_excerpt)) = center_output {
+            if let Some((paged_center_out, paged_center_temp, paged_center_excerpt)) = paged_center_output {
+                normalised_name_err = format_center(center, paged_center_out, paged_center_temp, paged_center_excerpt, "../", "../tags/", normalised_name_err)?;
+            }
+            normalised_name_err = format_center(center, center_out, center_temp, center_excerpt, "", "tags/", normalised_name_err)?;
+        }
+        if let Some((tag_center, tag_center_out, tag_center_temp, tag_center_excerpt)) = tag_center_output {
+            format_center(tag_center, tag_center_out, tag_center_temp, tag_center_excerpt, "../", "", normalised_name_err)?;
         }
 
         Ok(out_links)
@@ -389,6 +573,10 @@ impl BloguePost {
     /// Generate machine output of the specified kind from the post into the specified subpath in the specified output
     /// directory.
     ///
+    /// The post's source is parsed like in [`generate()`](#method.generate), according to the specified
+    /// [`PostRenderOptions`](struct.PostRenderOptions.html), so that its word count matches;
+    /// the `pre` hooks don't get the `tag_index` or `prev_post_*` and `next_post_*` variables.
+    ///
     /// # Examples
     ///
     /// Given the following:
@@ -427,7 +615,8 @@ impl BloguePost {
     /// ```
     pub fn create_machine_output(&self, into: &(String, PathBuf), subpath: &str, kind: &MachineDataKind) -> Result<File, Error> {
         let mut machine_root_path = concat_path(&into.1, subpath);
-        fs::create_dir_all(&machine_root_path).map_err(|e| {
+        machine_root_path.push(format!("{}.{}", self.slug, kind.extension()));
+        fs::create_dir_all(machine_root_path.parent().unwrap()).map_err(|e| {
                 Error::Io {
                     desc: format!("{} directory", subpath).into(),
                     op: "create",
@@ -435,7 +624,6 @@ impl BloguePost {
                 }
             })?;
 
-        machine_root_path.push(format!("{}.{}", self.normalised_name(), kind.extension()));
         let post_kind_f = File::create(machine_root_path).map_err(|e| {
                 Error::Io {
                     desc: format!("post {}", kind).into(),
@@ -450,6 +638,10 @@ impl BloguePost {
     /// Generate machine output of the specified kind from the post into the specified subpath in the specified output
     /// directory.
     ///
+    /// The post's source is parsed like in [`generate()`](#method.generate), according to the specified
+    /// [`PostRenderOptions`](struct.PostRenderOptions.html), so that its word count matches;
+    /// the `pre` hooks don't get the `tag_index` or `prev_post_*` and `next_post_*` variables.
+    ///
     /// # Examples
     ///
     /// Given the following:
@@ -482,7 +674,7 @@ impl BloguePost {
     ///         root.join("src").join("01. 2018-01-08 16-52 The venture into crocheting"))).unwrap();
     ///
     /// let mut out = vec![];
-    /// assert!(post.generate_machine(&mut out, &MachineDataKind::Json,
+    /// assert!(post.generate_machine(&mut out, &MachineDataKind::Json, &Default::default(),
     ///                               "Блогг", &LANGUAGE_EN_GB, "autheur", &[], &[], &Default::default(), &Default::default(),
     ///                               &[], &[], &[], &[]).is_ok());
     ///
@@ -495,7 +687,12 @@ impl BloguePost {
     /// #     "author": "autheur",
     /// #
     /// #     "raw_post_name": "01. 2018-01-08 16-52 The venture into crocheting",
-    /// #     "blog_name": "Блогг","##.as_bytes()));
+    /// #     "blog_name": "Блогг",
+    /// #     "base_url": "",
+    /// #     "draft": "",
+    /// #     "permalink": "posts/01. 2018-01-08 16-52-00 The venture into crocheting.html",
+    /// #     "word_count": 1,
+    /// #     "reading_time": 1,"##.as_bytes()));
     /// # assert!(out.ends_with(r##"
     /// #     "tags": [
     /// #     ],
@@ -510,15 +707,29 @@ impl BloguePost {
     /// #     "bloguen-version": "0.1.1"
     /// # }"##.as_bytes()));
     /// ```
-    pub fn generate_machine<T: Write>(&self, into: &mut T, kind: &MachineDataKind, blog_name: &str, language: &LanguageTag, author: &str,
-                                      spec_tags: &[TagName], free_tags: &[TagName], post_data: &BTreeMap<String, String>,
+    pub fn generate_machine<T: Write>(&self, into: &mut T, kind: &MachineDataKind, render: &PostRenderOptions, blog_name: &str,
+                                      language: &LanguageTag, author: &str, spec_tags: &[TagName], free_tags: &[TagName], post_data: &BTreeMap<String, String>,
                                       global_data: &BTreeMap<String, String>, post_styles: &[StyleElement], global_styles: &[StyleElement],
                                       post_scripts: &[ScriptElement], global_scripts: &[ScriptElement])
                                       -> Result<(), Error> {
+        let markdown = render.markdown.comrak_options();
+        let arena = ComrakArena::new();
+        let (source_format, post_text, root) =
+            self.parse_source(&arena, render, &markdown, || {
+                    self.hook_environment(blog_name,
+                                          language,
+                                          author,
+                                          &[spec_tags, free_tags],
+                                          &[global_data, post_data],
+                                          &self.variables(render.base_url, "", None, None))
+                })?;
+        let word_count = source_format.word_count(&post_text, root);
+
         let original_name = self.source_dir.1.file_name().unwrap().to_str().unwrap();
         machine_output_kind(kind)(blog_name,
                                   language,
                                   &[global_data, post_data],
+                                  &self.variables(render.base_url, "", None, Some(word_count)),
                                   &original_name,
                                   self.number.0,
                                   &self.name,
@@ -535,6 +746,12 @@ impl BloguePost {
 
     /// Generate header for this post of the specified feed type.
     ///
+    /// If a base URL is specified, the post is linked to and identified by its absolute permalink,
+    /// otherwise the link is relative to the feed file.
+    ///
+    /// If `summary` is set, the entry is to hold the post's excerpt, rather than its full contents,
+    /// see [`feed_type_post_header()`](fn.feed_type_post_header.html).
+    ///
     /// # Examples
     ///
     /// Given the following:
@@ -570,8 +787,8 @@ impl BloguePost {
     ///         root.join("src").join("01. 2018-01-08 16-52 The venture into crocheting"))).unwrap();
     ///
     /// let mut out = vec![];
-    /// assert!(post.generate_feed_head(&mut out, &FeedType::Rss, "feeds/rss.xml",
-    ///                                           &LANGUAGE_EN_GB, "nabijaczleweli").is_ok());
+    /// assert!(post.generate_feed_head(&mut out, &FeedType::Rss, "feeds/rss.xml", None,
+    ///                                           &LANGUAGE_EN_GB, "nabijaczleweli", false).is_ok());
     ///
     /// let out = String::from_utf8(out).unwrap();
     /// # let mut pubdate_local_rfc2822 = out.lines().find(|l| l.contains("pubDate")).unwrap();
@@ -589,24 +806,30 @@ impl BloguePost {
     ///       <description>
     /// "###, pubdate_local_rfc2822));
     /// ```
-    pub fn generate_feed_head<T: Write>(&self, into: &mut T, tp: &FeedType, fname: &str, language: &LanguageTag, author: &str) -> Result<(), Error> {
-        let norm_name = self.normalised_name();
-
-        let depth = path_depth(fname);
-        let link_pref = if depth - 1 > 0 {
-            mul_str("../", depth as usize - 1)
+    pub fn generate_feed_head<T: Write>(&self, into: &mut T, tp: &FeedType, fname: &str, base_url: Option<&Url>, language: &LanguageTag, author: &str,
+                                        summary: bool)
+                                        -> Result<(), Error> {
+        let (id, base, link) = if let Some(base_url) = base_url {
+            let link = self.permalink(Some(base_url));
+            (link.clone(), resolve_url(base_url, self.directory()), link)
         } else {
-            String::new()
+            let depth = path_depth(fname);
+            let link_pref = if depth - 1 > 0 {
+                mul_str("../", depth as usize - 1)
+            } else {
+                String::new()
+            };
+            (self.normalised_name(), format!("{}{}", link_pref, self.directory()), format!("{}{}", link_pref, self.path))
         };
-        let link = format!("{}posts/{}.html", link_pref, norm_name);
 
         feed_type_post_header(tp)(&self.name,
-                                  &norm_name,
+                                  &id,
                                   language,
                                   author,
-                                  &link[..link_pref.len() + 5 + 1],
+                                  &base,
                                   &link,
                                   &self.datetime,
+                                  summary,
                                   into,
                                   self.normalised_name())?;
 
@@ -615,6 +838,8 @@ impl BloguePost {
 
     /// Generate footer for this post of the specified feed type.
     ///
+    /// `summary` should match the one passed to [`generate_feed_head()`](#method.generate_feed_head).
+    ///
     /// # Examples
     ///
     /// Given the following:
@@ -647,14 +872,14 @@ impl BloguePost {
     ///         root.join("src").join("01. 2018-01-08 16-52 The venture into crocheting"))).unwrap();
     ///
     /// let mut out = vec![];
-    /// assert!(post.generate_feed_foot(&mut out, &FeedType::Rss).is_ok());
+    /// assert!(post.generate_feed_foot(&mut out, &FeedType::Rss, false).is_ok());
     ///
     /// assert_eq!(String::from_utf8(out).unwrap(), r###"      </description>
     ///     </item>
     /// "###);
     /// ```
-    pub fn generate_feed_foot<T: Write>(&self, into: &mut T, tp: &FeedType) -> Result<(), Error> {
-        feed_type_post_footer(tp)(into, self.normalised_name())?;
+    pub fn generate_feed_foot<T: Write>(&self, into: &mut T, tp: &FeedType, summary: bool) -> Result<(), Error> {
+        feed_type_post_footer(tp)(summary, into, self.normalised_name())?;
 
         Ok(())
     }
@@ -685,6 +910,446 @@ impl BloguePost {
         format!("{}. {} {}", self.number.1, self.datetime.format("%Y-%m-%d %H-%M-%S"), self.name)
     }
 
+    /// Get the link to this post's output HTML.
+    ///
+    /// The link is absolute if a base URL is specified, and relative to the output directory otherwise.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use bloguen::ops::BloguePost;
+    /// # use std::io::{Write, Read};
+    /// # use std::fs::{self, File};
+    /// # use std::env::temp_dir;
+    /// # let root = temp_dir().join("bloguen-doctest").join("ops-post-permalink");
+    /// # let _ = fs::remove_dir_all(&root);
+    /// # fs::create_dir_all(root.join("src").join("01. 2018-01-08 16-52 The venture into crocheting")).unwrap();
+    /// # File::create(root.join("src").join("01. 2018-01-08 16-52 The venture into crocheting")
+    /// #                  .join("post.md")).unwrap().write_all("Блогг".as_bytes()).unwrap();
+    /// # /*
+    /// let root: PathBuf = /* obtained elsewhere */;
+    /// # */
+    /// let post =
+    ///     BloguePost::new(("$ROOT/src/01. 2018-01-08 16-52 The venture into crocheting".to_string(),
+    ///         root.join("src").join("01. 2018-01-08 16-52 The venture into crocheting"))).unwrap();
+    /// assert_eq!(post.permalink(None), "posts/01. 2018-01-08 16-52-00 The venture into crocheting.html");
+    /// assert_eq!(post.permalink(Some(&"https://nabijaczleweli.xyz/blogue/".parse().unwrap())),
+    ///            "https://nabijaczleweli.xyz/blogue/posts/01.%202018-01-08%2016-52-00%20The%20venture%20into%20crocheting.html");
+    /// ```
+    pub fn permalink(&self, base_url: Option<&Url>) -> String {
+        match base_url {
+            Some(base_url) => resolve_url(base_url, &self.path),
+            None => self.path.clone(),
+        }
+    }
+
+    /// Set this post's output [`path`](#structfield.path) from the specified permalink pattern.
+    ///
+    /// `{slug}` is replaced with the post's [`slug`](#structfield.slug), `{number}` with its number as written,
+    /// and `{year}`, `{month}`, and `{day}` with the zero-padded date of posting.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use bloguen::ops::BloguePost;
+    /// # use std::fs;
+    /// # use std::env::temp_dir;
+    /// # let root = temp_dir().join("bloguen-doctest").join("ops-post-apply_permalink_pattern");
+    /// # let _ = fs::remove_dir_all(&root);
+    /// # fs::create_dir_all(root.join("src").join("01. 2018-01-08 16-52 The venture into crocheting")).unwrap();
+    /// # /*
+    /// let root: PathBuf = /* obtained elsewhere */;
+    /// # */
+    /// let mut post =
+    ///     BloguePost::new(("$ROOT/src/01. 2018-01-08 16-52 The venture into crocheting".to_string(),
+    ///         root.join("src").join("01. 2018-01-08 16-52 The venture into crocheting"))).unwrap();
+    /// post.apply_permalink_pattern("posts/{year}/{month}/{slug}/index.html");
+    /// assert_eq!(post.path, "posts/2018/01/01. 2018-01-08 16-52-00 The venture into crocheting/index.html");
+    ///
+    /// post.slug = "crocheting".to_string();
+    /// post.apply_permalink_pattern("{year}-{month}-{day}/{number}-{slug}.html");
+    /// assert_eq!(post.path, "2018-01-08/01-crocheting.html");
+    /// ```
+    pub fn apply_permalink_pattern(&mut self, pattern: &str) {
+        self.path = pattern.replace("{year}", &self.datetime.format("%Y").to_string())
+            .replace("{month}", &self.datetime.format("%m").to_string())
+            .replace("{day}", &self.datetime.format("%d").to_string())
+            .replace("{number}", &self.number.1)
+            .replace("{slug}", &self.slug);
+    }
+
+    /// Check whether this post should be published at the specified time.
+    ///
+    /// [Drafts](#structfield.draft) are only published if `drafts` is set, and posts scheduled after `now` only if `future` is.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # extern crate bloguen;
+    /// # extern crate chrono;
+    /// # use bloguen::ops::BloguePost;
+    /// # use chrono::DateTime;
+    /// # use std::fs;
+    /// # use std::env::temp_dir;
+    /// # fn main() {
+    /// # let root = temp_dir().join("bloguen-doctest").join("ops-post-is_published");
+    /// # let _ = fs::remove_dir_all(&root);
+    /// # fs::create_dir_all(root.join("01. 2018-01-08 16-52 The venture into crocheting_draft")).unwrap();
+    /// # /*
+    /// let root: PathBuf = /* obtained elsewhere */;
+    /// # */
+    /// let post = BloguePost::new(("$ROOT/01. 2018-01-08 16-52 The venture into crocheting_draft/".to_string(),
+    ///                             root.join("01. 2018-01-08 16-52 The venture into crocheting_draft"))).unwrap();
+    /// let now = DateTime::parse_from_rfc3339("2019-01-01T00:00:00+00:00").unwrap();
+    /// assert!(!post.is_published(&now, false, false));
+    /// assert!(post.is_published(&now, true, false));
+    /// # }
+    /// ```
+    pub fn is_published<Tz: TimeZone>(&self, now: &DateTime<Tz>, drafts: bool, future: bool) -> bool {
+        (!self.draft || drafts) && (self.datetime <= *now || future)
+    }
+
+    /// Check that no two of the specified posts have the same output [`path`](#structfield.path).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use bloguen::ops::BloguePost;
+    /// # use bloguen::Error;
+    /// # use std::fs;
+    /// # use std::env::temp_dir;
+    /// # let root = temp_dir().join("bloguen-doctest").join("ops-post-check_unique_paths");
+    /// # let _ = fs::remove_dir_all(&root);
+    /// # for d in &["01. 2018-01-08 16-52 The venture into crocheting", "02. 2018-01-09 10-00 Cotton"] {
+    /// #     fs::create_dir_all(root.join("src").join(d)).unwrap();
+    /// # }
+    /// # /*
+    /// let root: PathBuf = /* obtained elsewhere */;
+    /// # */
+    /// let post = |name: &str| BloguePost::new((format!("$ROOT/src/{}", name), root.join("src").join(name))).unwrap();
+    /// let mut posts = vec![post("01. 2018-01-08 16-52 The venture into crocheting"), post("02. 2018-01-09 10-00 Cotton")];
+    /// assert_eq!(BloguePost::check_unique_paths(&posts), Ok(()));
+    ///
+    /// posts[1].slug = posts[0].slug.clone();
+    /// posts[1].apply_permalink_pattern("posts/{slug}.html");
+    /// assert_eq!(BloguePost::check_unique_paths(&posts),
+    ///            Err(Error::Parse {
+    ///                tp: "path chunk",
+    ///                wher: "blogue posts".into(),
+    ///                more: "posts \"01. 2018-01-08 16-52-00 The venture into crocheting\" and \"02. 2018-01-09 10-00-00 Cotton\" \
+    ///                       both output to \"posts/01. 2018-01-08 16-52-00 The venture into crocheting.html\"".into(),
+    ///            }));
+    /// ```
+    pub fn check_unique_paths<'p, I: IntoIterator<Item = &'p BloguePost>>(posts: I) -> Result<(), Error> {
+        let mut paths = BTreeMap::new();
+        for post in posts {
+            if let Some(other) = paths.insert(&post.path[..], post) {
+                return Err(Error::Parse {
+                    tp: "path chunk",
+                    wher: "blogue posts".into(),
+                    more: format!("posts {:?} and {:?} both output to {:?}", other.normalised_name(), post.normalised_name(), post.path).into(),
+                });
+            }
+        }
+        Ok(())
+    }
+
+    /// Rank the specified posts by how related they are to this one, given the tags of each.
+    ///
+    /// Posts sharing more tags with this one come first, then ones closer to it in time.
+    /// This post and posts sharing no tags with it are left out.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use bloguen::ops::BloguePost;
+    /// # use std::fs;
+    /// # use std::env::temp_dir;
+    /// # let root = temp_dir().join("bloguen-doctest").join("ops-post-related_posts");
+    /// # let _ = fs::remove_dir_all(&root);
+    /// # for d in &["01. 2018-01-08 16-52 The venture into crocheting", "02. 2018-01-09 Cotton", "03. 2018-03-01 Acrylic",
+    /// #           "04. 2018-03-02 Vodka"] {
+    /// #     fs::create_dir_all(root.join("src").join(d)).unwrap();
+    /// # }
+    /// # /*
+    /// let root: PathBuf = /* obtained elsewhere */;
+    /// # */
+    /// let post = |name: &str| BloguePost::new((format!("$ROOT/src/{}", name), root.join("src").join(name))).unwrap();
+    /// let posts = vec![post("01. 2018-01-08 16-52 The venture into crocheting"),
+    ///                  post("02. 2018-01-09 Cotton"), post("03. 2018-03-01 Acrylic"), post("04. 2018-03-02 Vodka")];
+    /// let tags: Vec<Vec<_>> =
+    ///     vec![vec!["crochet".parse().unwrap(), "yarn".parse().unwrap()],
+    ///          vec!["yarn".parse().unwrap()],
+    ///          vec!["crochet".parse().unwrap(), "yarn".parse().unwrap()],
+    ///          vec!["vodka".parse().unwrap()]];
+    ///
+    /// let all: Vec<_> = posts.iter().zip(&tags).map(|(p, t)| (p, &t[..])).collect();
+    /// assert_eq!(posts[0].related_posts(&tags[0], &all), vec![&posts[2], &posts[1]]);
+    /// assert_eq!(posts[3].related_posts(&tags[3], &all), Vec::<&BloguePost>::new());
+    /// ```
+    pub fn related_posts<'p>(&self, tags: &[TagName], posts: &[(&'p BloguePost, &[TagName])]) -> Vec<&'p BloguePost> {
+        let tags: BTreeSet<_> = tags.iter().collect();
+
+        let mut related: Vec<_> = posts.iter()
+            .filter(|&&(p, _)| p != self)
+            .map(|&(p, ref p_tags)| (p, p_tags.iter().collect::<BTreeSet<_>>().intersection(&tags).count()))
+            .filter(|&(_, shared)| shared != 0)
+            .collect();
+        related.sort_by_key(|&(p, shared)| (Reverse(shared), p.datetime.signed_duration_since(self.datetime).num_seconds().abs()));
+        related.into_iter().map(|(p, _)| p).collect()
+    }
+
+    fn directory(&self) -> &str {
+        &self.path[..self.path.rfind('/').map(|i| i + 1).unwrap_or(0)]
+    }
+
+    fn root(&self) -> String {
+        mul_str("../", cmp::max(path_depth(&self.path) - 1, 0) as usize)
+    }
+
+    fn variables(&self, base_url: Option<&Url>, root: &str, tag_index: Option<&str>, word_count: Option<usize>) -> BTreeMap<&'static str, String> {
+        let mut variables = BTreeMap::new();
+        variables.insert("base_url", base_url.map(Url::to_string).unwrap_or_default());
+        variables.insert("draft", if self.draft { "draft" } else { "" }.to_string());
+        if let Some(word_count) = word_count {
+            variables.insert("word_count", word_count.to_string());
+        }
+        variables.insert("permalink",
+                         match base_url {
+                             Some(_) => self.permalink(base_url),
+                             None => format!("{}{}", root, self.permalink(None)),
+                         });
+        if let Some(tag_index) = tag_index {
+            variables.insert("tag_index",
+                             match base_url {
+                                 Some(base_url) => resolve_url(base_url, "tags/"),
+                                 None => tag_index.to_string(),
+                             });
+        }
+        variables
+    }
+
+    fn neighbour_variables(variables: &mut BTreeMap<&'static str, String>, base_url: Option<&Url>, root: &str, previous_post: Option<&BloguePost>,
+                           next_post: Option<&BloguePost>) {
+        for &(post, title, name, link, date) in &[(previous_post, "prev_post_title", "prev_post_name", "prev_post_link", "prev_post_date"),
+                                                  (next_post, "next_post_title", "next_post_name", "next_post_link", "next_post_date")] {
+            variables.insert(title, post.map(|p| p.name.clone()).unwrap_or_default());
+            variables.insert(name, post.map(BloguePost::normalised_name).unwrap_or_default());
+            variables.insert(link, post.map(|p| p.link_from(base_url, root)).unwrap_or_default());
+            variables.insert(date, post.map(|p| p.datetime.to_rfc3339()).unwrap_or_default());
+        }
+    }
+
+    /// Read this post's source, pipe it through the `pre` hooks, getting the specified environment, and parse it into the specified arena.
+    fn parse_source<'a, E: FnOnce() -> BTreeMap<String, String>>(&self, arena: &'a ComrakArena<ComrakAstNode<'a>>, render: &PostRenderOptions,
+                                                                 markdown: &ComrakOptions, hook_environment: E)
+                                                                 -> Result<(&'static dyn SourceFormat, String, &'a ComrakAstNode<'a>), Error> {
+        let (source_format, source) = self.source();
+        let mut post_text = read_file(&source, "post text")?;
+        if !render.hooks.pre.is_empty() {
+            let environment = hook_environment();
+            let wher = format!("post source of {}", self.normalised_name());
+            for command in &render.hooks.pre {
+                post_text = String::from_utf8(run_hook(command, post_text.into_bytes(), &environment, &wher)?).map_err(|e| {
+                        Error::HookFailed {
+                            command: command.clone().into(),
+                            wher: wher.clone().into(),
+                            more: format!("output not UTF-8: {}", e).into(),
+                        }
+                    })?;
+            }
+        }
+
+        let root = source_format.parse(&post_text, arena, markdown, render.math)?;
+        Ok((source_format, post_text, root))
+    }
+
+    fn hook_environment(&self, blog_name: &str, language: &LanguageTag, author: &str, tags: &[&[TagName]], data_sets: &[&BTreeMap<String, String>],
+                        variables: &BTreeMap<&'static str, String>)
+                        -> BTreeMap<String, String> {
+        fn env_name(name: &str) -> String {
+            format!("BLOGUEN_{}", name.chars().map(|c| if c.is_ascii_alphanumeric() { c.to_ascii_uppercase() } else { '_' }).collect::<String>())
+        }
+
+        let mut environment = BTreeMap::new();
+        for (name, value) in &[("language", language.to_string()),
+                               ("number", self.number.0.to_string()),
+                               ("title", self.name.clone()),
+                               ("author", author.to_string()),
+                               ("raw_post_name", self.source_dir.1.file_name().unwrap().to_str().unwrap().to_string()),
+                               ("normalised_post_name", self.normalised_name()),
+                               ("blog_name", blog_name.to_string()),
+                               ("bloguen-version", BLOGUEN_VERSION.to_string()),
+                               ("date", self.datetime.to_rfc3339()),
+                               ("tags", tags.iter().flat_map(|ts| ts.iter()).map(|t| format!("{}\n", t)).collect())] {
+            environment.insert(env_name(name), value.clone());
+        }
+        for (name, value) in variables {
+            environment.insert(env_name(name), value.clone());
+        }
+        for data in data_sets {
+            for (key, value) in *data {
+                environment.insert(env_name(&format!("data-{}", key)), value.clone());
+            }
+        }
+        environment
+    }
+
+    fn related_links(base_url: Option<&Url>, root: &str, related_posts: &[&BloguePost]) -> Vec<(String, String)> {
+        related_posts.iter().map(|p| (p.name.clone(), p.link_from(base_url, root))).collect()
+    }
+
+    /// Link to this post from another post, with the specified path from it to the output directory.
+    ///
+    /// Unlike [`permalink()`](#method.permalink), the relative link is percent-encoded like the absolute one.
+    fn link_from(&self, base_url: Option<&Url>, root: &str) -> String {
+        match base_url {
+            Some(_) => self.permalink(base_url),
+            None => format!("{}{}", root, utf8_percent_encode(&self.path, LINK_ESCAPED)),
+        }
+    }
+
+    /// Find the post's source file, `post.<extension>` for the first of [`SOURCE_FORMATS`](static.SOURCE_FORMATS.html) that exists,
+    /// and its format.
+    ///
+    /// If none exists, defaults to Markdown in `post.md`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use bloguen::ops::BloguePost;
+    /// # use std::fs::{self, File};
+    /// # use std::env::temp_dir;
+    /// # let root = temp_dir().join("bloguen-doctest").join("ops-post-source");
+    /// # let _ = fs::remove_dir_all(&root);
+    /// # fs::create_dir_all(root.join("src").join("01. 2018-01-08 16-52 The venture into crocheting")).unwrap();
+    /// # File::create(root.join("src").join("01. 2018-01-08 16-52 The venture into crocheting").join("post.html")).unwrap();
+    /// # /*
+    /// let root: PathBuf = /* obtained elsewhere */;
+    /// # */
+    /// let post =
+    ///     BloguePost::new(("$ROOT/src/01. 2018-01-08 16-52 The venture into crocheting/".to_string(),
+    ///         root.join("src").join("01. 2018-01-08 16-52 The venture into crocheting"))).unwrap();
+    /// let (format, file) = post.source();
+    /// assert_eq!(format.extension(), "html");
+    /// assert_eq!(file,
+    ///            ("$ROOT/src/01. 2018-01-08 16-52 The venture into crocheting/post.html".to_string(),
+    ///             root.join("src").join("01. 2018-01-08 16-52 The venture into crocheting").join("post.html")));
+    /// ```
+    pub fn source(&self) -> (&'static dyn SourceFormat, (String, PathBuf)) {
+        let file = |format: &dyn SourceFormat| {
+            let fname = format!("post.{}", format.extension());
+            (format!("{}{}", self.source_dir.0, fname), self.source_dir.1.join(fname))
+        };
+
+        SOURCE_FORMATS.iter()
+            .map(|&format| (format, file(format)))
+            .find(|(_, file)| file.1.is_file())
+            .unwrap_or_else(|| (&MarkdownSource, file(&MarkdownSource)))
+    }
+
+    /// Get a hash of this post's inputs: the post source, whether it's a draft, the loaded metadata, and the additional tags.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use bloguen::ops::{PostMetadata, BloguePost};
+    /// # use std::io::{Write, Read};
+    /// # use std::fs::{self, File};
+    /// # use std::env::temp_dir;
+    /// # let root = temp_dir().join("bloguen-doctest").join("ops-post-source_hash");
+    /// # let _ = fs::remove_dir_all(&root);
+    /// # fs::create_dir_all(root.join("src").join("01. 2018-01-08 16-52 The venture into crocheting")).unwrap();
+    /// # File::create(root.join("src").join("01. 2018-01-08 16-52 The venture into crocheting")
+    /// #                  .join("post.md")).unwrap().write_all("Блогг".as_bytes()).unwrap();
+    /// # /*
+    /// let root: PathBuf = /* obtained elsewhere */;
+    /// # */
+    /// let post =
+    ///     BloguePost::new(("$ROOT/src/01. 2018-01-08 16-52 The venture into crocheting".to_string(),
+    ///         root.join("src").join("01. 2018-01-08 16-52 The venture into crocheting"))).unwrap();
+    /// let hash = post.source_hash(&PostMetadata::default(), &[]).unwrap();
+    /// assert_eq!(post.source_hash(&PostMetadata::default(), &[]), Ok(hash));
+    /// assert_ne!(post.source_hash(&PostMetadata::default(), &["vodka".parse().unwrap()]), Ok(hash));
+    ///
+    /// let mut draft = post.clone();
+    /// draft.draft = true;
+    /// assert_ne!(draft.source_hash(&PostMetadata::default(), &[]), Ok(hash));
+    ///
+    /// # File::create(root.join("src").join("01. 2018-01-08 16-52 The venture into crocheting")
+    /// #                  .join("post.md")).unwrap().write_all("Блогг!".as_bytes()).unwrap();
+    /// // post.md changed
+    /// assert_ne!(post.source_hash(&PostMetadata::default(), &[]), Ok(hash));
+    /// ```
+    pub fn source_hash(&self, metadata: &PostMetadata, additional_tags: &[TagName]) -> Result<u64, Error> {
+        let (format, source) = self.source();
+        let post_text = read_file(&source, "post text")?;
+        Ok(content_hash(&(format.extension(), &post_text, self.draft, metadata, additional_tags)))
+    }
+
+    /// Get the modification time of the post's [`source()`](#method.source) in nanoseconds since the Unix epoch.
+    ///
+    /// Returns `None` if the post text doesn't exist or its modification time is unavailable.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use bloguen::ops::BloguePost;
+    /// # use std::io::{Write, Read};
+    /// # use std::fs::{self, File};
+    /// # use std::env::temp_dir;
+    /// # let root = temp_dir().join("bloguen-doctest").join("ops-post-source_modification_time");
+    /// # let _ = fs::remove_dir_all(&root);
+    /// # fs::create_dir_all(root.join("src").join("01. 2018-01-08 16-52 The venture into crocheting")).unwrap();
+    /// # File::create(root.join("src").join("01. 2018-01-08 16-52 The venture into crocheting")
+    /// #                  .join("post.md")).unwrap().write_all("Блогг".as_bytes()).unwrap();
+    /// # /*
+    /// let root: PathBuf = /* obtained elsewhere */;
+    /// # */
+    /// let post =
+    ///     BloguePost::new(("$ROOT/src/01. 2018-01-08 16-52 The venture into crocheting".to_string(),
+    ///         root.join("src").join("01. 2018-01-08 16-52 The venture into crocheting"))).unwrap();
+    /// assert!(post.source_modification_time().is_some());
+    /// ```
+    pub fn source_modification_time(&self) -> Option<u64> {
+        self.asset_modification_time(&format!("post.{}", self.source().0.extension()))
+    }
+
+    /// Get the modification time of a referenced asset in nanoseconds since the Unix epoch.
+    ///
+    /// Returns `None` if the asset doesn't exist or its modification time is unavailable.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use bloguen::ops::BloguePost;
+    /// # use std::io::{Write, Read};
+    /// # use std::fs::{self, File};
+    /// # use std::env::temp_dir;
+    /// # let root = temp_dir().join("bloguen-doctest").join("ops-post-asset_modification_time");
+    /// # let _ = fs::remove_dir_all(&root);
+    /// # fs::create_dir_all(root.join("src").join("01. 2018-01-08 16-52 The venture into crocheting").join("assets")).unwrap();
+    /// # File::create(root.join("src").join("01. 2018-01-08 16-52 The venture into crocheting")
+    /// #                  .join("post.md")).unwrap().write_all("![img](assets/img.png)".as_bytes()).unwrap();
+    /// # File::create(root.join("src").join("01. 2018-01-08 16-52 The venture into crocheting")
+    /// #                  .join("assets").join("img.png")).unwrap();
+    /// # /*
+    /// let root: PathBuf = /* obtained elsewhere */;
+    /// # */
+    /// let post =
+    ///     BloguePost::new(("$ROOT/src/01. 2018-01-08 16-52 The venture into crocheting".to_string(),
+    ///         root.join("src").join("01. 2018-01-08 16-52 The venture into crocheting"))).unwrap();
+    /// assert!(post.asset_modification_time("assets/img.png").is_some());
+    /// assert_eq!(post.asset_modification_time("assets/nonexistant.png"), None);
+    /// ```
+    pub fn asset_modification_time(&self, link: &str) -> Option<u64> {
+        fs::metadata(concat_path(self.source_dir.1.clone(), link))
+            .and_then(|m| m.modified())
+            .ok()
+            .and_then(|t| t.duration_since(UNIX_EPOCH).ok())
+            .map(|d| d.as_secs() * 1_000_000_000 + d.subsec_nanos() as u64)
+    }
+
     /// Copy a referenced asset to the output directory.
     ///
     /// Returns `Ok(b)`, where `b` is whether the asset existed and was copied, `Err(_)` for a copying error.
@@ -715,9 +1380,9 @@ impl BloguePost {
     /// let post =
     ///     BloguePost::new(("$ROOT/src/01. 2018-01-08 16-52 The venture into crocheting".to_string(),
     ///         root.join("src").join("01. 2018-01-08 16-52 The venture into crocheting"))).unwrap();
-    /// for link in post.generate(&out_pair, None, None, None, "header", "footer", "Блогг",
-    ///                           &LANGUAGE_EN_GB, "autheur", &[], &[],
-    ///                           &Default::default(), &Default::default(), &[], &[], &[], &[])
+    /// for link in post.generate(&out_pair, None, None, None, Default::default(),
+    ///                           &"header".parse().unwrap(), &"footer".parse().unwrap(), "Блогг", &LANGUAGE_EN_GB, "autheur",
+    ///                           &[], &[], &Default::default(), &Default::default(), &[], &[], &[], &[])
     ///             .unwrap().into_iter().filter(|l| util::is_asset_link(l)) {
     ///     let link = percent_decode(link.as_bytes()).decode_utf8().unwrap();
     ///     println!("Copying {}: {:?}", link, post.copy_asset(&out_pair, None, &link));
@@ -729,7 +1394,7 @@ impl BloguePost {
             let output = concat_path(if let Some(ass_dir) = asset_override {
                                          concat_path(&into.1, ass_dir)
                                      } else {
-                                         into.1.join("posts")
+                                         concat_path(&into.1, self.directory())
                                      },
                                      link);
 
@@ -0,0 +1,218 @@
+use tiny_http::{Response as HttpResponse, Server as HttpServer, StatusCode as HttpStatusCode, Header as HttpHeader, Method as HttpMethod};
+use std::path::{Component as PathComponent, PathBuf, Path};
+use percent_encoding::percent_decode;
+use std::net::{ToSocketAddrs, SocketAddr};
+use self::super::super::Error;
+use std::io::Read;
+use std::fs::File;
+
+
+/// What a request to the preview server resolves to.
+#[derive(Debug, Clone, Hash, PartialEq, Eq, PartialOrd, Ord)]
+pub enum RequestTarget {
+    /// Serve the specified file.
+    File(PathBuf),
+    /// Redirect to the specified URL path, used to append a slash to directory requests, so that relative links work.
+    Redirect(String),
+    /// No such file, or the request path is invalid.
+    NotFound,
+}
+
+
+/// Local HTTP server for previewing a generated blogue.
+pub struct PreviewServer {
+    server: HttpServer,
+    address: SocketAddr,
+}
+
+impl PreviewServer {
+    /// Start listening on the specified address.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use bloguen::ops::PreviewServer;
+    /// let server = PreviewServer::bind("127.0.0.1:0").unwrap();
+    /// assert!(server.address().ip().is_loopback());
+    /// assert!(server.address().port() != 0);
+    /// ```
+    pub fn bind<A: ToSocketAddrs>(address: A) -> Result<PreviewServer, Error> {
+        let address = address.to_socket_addrs()
+            .ok()
+            .and_then(|mut addrs| addrs.next())
+            .ok_or_else(|| {
+                Error::Parse {
+                    tp: "socket address",
+                    wher: "preview server".into(),
+                    more: "no address to bind to".into(),
+                }
+            })?;
+        let server = HttpServer::http(address).map_err(|e| {
+                Error::Io {
+                    desc: format!("preview server on {}", address).into(),
+                    op: "start",
+                    more: e.to_string().into(),
+                }
+            })?;
+        let address = server.server_addr();
+
+        Ok(PreviewServer {
+            server: server,
+            address: address,
+        })
+    }
+
+    /// Get the address the server is listening on.
+    pub fn address(&self) -> SocketAddr {
+        self.address
+    }
+
+    /// Serve the specified output directory, never returning.
+    ///
+    /// Only `GET` and `HEAD` requests are answered, with files resolved by
+    /// [`resolve_request()`](fn.resolve_request.html) and MIME types guessed from their extensions.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # use bloguen::ops::PreviewServer;
+    /// # use std::path::Path;
+    /// let server = PreviewServer::bind("127.0.0.1:8000").unwrap();
+    /// println!("Serving at http://{}/", server.address());
+    /// server.serve(Path::new("out"));
+    /// ```
+    pub fn serve(&self, output_dir: &Path) -> ! {
+        loop {
+            let request = match self.server.recv() {
+                Ok(request) => request,
+                Err(err) => {
+                    eprintln!("Failed to receive preview server request: {}", err);
+                    continue;
+                }
+            };
+
+            let response = if *request.method() != HttpMethod::Get && *request.method() != HttpMethod::Head {
+                text_response(405, "405 Method Not Allowed")
+            } else {
+                match resolve_request(output_dir, request.url()) {
+                    RequestTarget::File(path) => {
+                        match File::open(&path) {
+                            Ok(f) => {
+                                HttpResponse::from_file(f)
+                                    .with_header(HttpHeader::from_bytes(&b"Content-Type"[..], mime_type(&path).as_bytes()).unwrap())
+                                    .boxed()
+                            }
+                            Err(_) => text_response(404, "404 Not Found"),
+                        }
+                    }
+                    RequestTarget::Redirect(to) => {
+                        text_response(301, "301 Moved Permanently").with_header(HttpHeader::from_bytes(&b"Location"[..], to.as_bytes()).unwrap())
+                    }
+                    RequestTarget::NotFound => text_response(404, "404 Not Found"),
+                }
+            };
+
+            let _ = request.respond(response);
+        }
+    }
+}
+
+
+/// Resolve a request URL to what to serve from the specified output directory.
+///
+/// The URL's query and fragment are ignored and its path is percent-decoded.
+/// Directories resolve to their `index.html`, requests for them without a trailing slash are redirected to include one.
+/// Paths trying to escape the output directory are rejected.
+///
+/// # Examples
+///
+/// Given the following:
+///
+/// ```plaintext
+/// $ROOT
+///   index.html
+///   posts/
+///     01. 2018-01-08 16-52-00 The venture into crocheting.html
+/// ```
+///
+/// The following holds:
+///
+/// ```
+/// # use bloguen::ops::{RequestTarget, resolve_request};
+/// # use std::fs::{self, File};
+/// # use std::env::temp_dir;
+/// # let root = temp_dir().join("bloguen-doctest").join("ops-preview_server-resolve_request");
+/// # let _ = fs::remove_dir_all(&root);
+/// # fs::create_dir_all(root.join("posts")).unwrap();
+/// # File::create(root.join("index.html")).unwrap();
+/// # File::create(root.join("posts").join("01. 2018-01-08 16-52-00 The venture into crocheting.html")).unwrap();
+/// # /*
+/// let root: PathBuf = /* obtained elsewhere */;
+/// # */
+/// assert_eq!(resolve_request(&root, "/"), RequestTarget::File(root.join("index.html")));
+/// assert_eq!(resolve_request(&root, "/posts/01.%202018-01-08%2016-52-00%20The%20venture%20into%20crocheting.html?utm=1"),
+///            RequestTarget::File(root.join("posts").join("01. 2018-01-08 16-52-00 The venture into crocheting.html")));
+/// assert_eq!(resolve_request(&root, "/posts"), RequestTarget::Redirect("/posts/".to_string()));
+/// assert_eq!(resolve_request(&root, "/posts/"), RequestTarget::NotFound);
+/// assert_eq!(resolve_request(&root, "/../index.html"), RequestTarget::NotFound);
+/// ```
+pub fn resolve_request(output_dir: &Path, url: &str) -> RequestTarget {
+    let url_path = url.split(['?', '#']).next().unwrap_or("");
+    let decoded = match percent_decode(url_path.as_bytes()).decode_utf8() {
+        Ok(decoded) => decoded,
+        Err(_) => return RequestTarget::NotFound,
+    };
+
+    let relative = Path::new(decoded.trim_start_matches('/'));
+    if relative.components().any(|c| !matches!(c, PathComponent::Normal(_) | PathComponent::CurDir)) {
+        return RequestTarget::NotFound;
+    }
+
+    let path = output_dir.join(relative);
+    if path.is_dir() {
+        if !url_path.ends_with('/') {
+            return RequestTarget::Redirect(format!("{}/", url_path));
+        }
+
+        let index = path.join("index.html");
+        if index.is_file() {
+            RequestTarget::File(index)
+        } else {
+            RequestTarget::NotFound
+        }
+    } else if path.is_file() {
+        RequestTarget::File(path)
+    } else {
+        RequestTarget::NotFound
+    }
+}
+
+/// Get the MIME type to serve the specified file with, based on its extension.
+///
+/// Text types are marked as UTF-8, unknown types default to `application/octet-stream`.
+///
+/// # Examples
+///
+/// ```
+/// # use bloguen::ops::mime_type;
+/// assert_eq!(mime_type("index.html"), "text/html; charset=utf-8");
+/// assert_eq!(mime_type("feeds/rss.xml"), "text/xml; charset=utf-8");
+/// assert_eq!(mime_type("posts/assets/image.png"), "image/png");
+/// assert_eq!(mime_type("metadata/json/01. 2018-01-08 16-52-00 The venture into crocheting.json"), "application/json");
+/// assert_eq!(mime_type("posts/assets/data.unknown-extension"), "application/octet-stream");
+/// ```
+pub fn mime_type<P: AsRef<Path>>(path: P) -> String {
+    let mime = mime_guess::from_path(path).first_or_octet_stream();
+    if mime.type_() == mime_guess::mime::TEXT {
+        format!("{}; charset=utf-8", mime.essence_str())
+    } else {
+        mime.essence_str().to_string()
+    }
+}
+
+
+fn text_response(status: u16, text: &'static str) -> HttpResponse<Box<dyn Read + Send>> {
+    HttpResponse::from_string(text)
+        .with_status_code(HttpStatusCode(status))
+        .boxed()
+}
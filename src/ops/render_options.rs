@@ -0,0 +1,96 @@
+use self::super::{BlogueDescriptorHighlight, BlogueDescriptorHooks, FormatTemplate, MarkdownOptions, BloguePost};
+use std::io::Write;
+use url::Url;
+
+
+static NO_HOOKS: BlogueDescriptorHooks = BlogueDescriptorHooks {
+    pre: Vec::new(),
+    post: Vec::new(),
+};
+
+
+/// How to render a post's source, and the page-specific outputs and variables to render it with,
+/// see [`BloguePost::generate()`](struct.BloguePost.html#method.generate).
+///
+/// # Examples
+///
+/// ```
+/// # use bloguen::ops::PostRenderOptions;
+/// let options = PostRenderOptions::default();
+/// assert!(options.paged_center_output.is_none());
+/// assert!(options.tag_center_output.is_none());
+/// assert!(options.base_url.is_none());
+/// assert!(options.previous_post.is_none() && options.next_post.is_none());
+/// assert!(options.related_posts.is_empty());
+/// assert!(options.toc);
+/// assert!(options.highlight.is_none());
+/// assert!(!options.math);
+/// assert_eq!(options.markdown, Default::default());
+/// assert!(options.hooks.pre.is_empty() && options.hooks.post.is_empty());
+/// ```
+pub struct PostRenderOptions<'a> {
+    /// Where to write the post formatted with the center template, with links relative to the `page/` subdirectory.
+    ///
+    /// Defaults to nowhere.
+    pub paged_center_output: Option<&'a mut dyn Write>,
+    /// Template and output to format the post into, with links relative to the `tags/` subdirectory.
+    ///
+    /// If specified, the post's tags link to their indices in the tag index.
+    ///
+    /// Defaults to none.
+    pub tag_center_output: Option<(&'a FormatTemplate, &'a mut dyn Write)>,
+    /// URL to make the `permalink` and `base_url` variables absolute with.
+    ///
+    /// Defaults to none.
+    pub base_url: Option<&'a Url>,
+    /// The post preceding this one, if any.
+    ///
+    /// Defaults to none.
+    pub previous_post: Option<&'a BloguePost>,
+    /// The post following this one, if any.
+    ///
+    /// Defaults to none.
+    pub next_post: Option<&'a BloguePost>,
+    /// Posts to link to with `related_posts(n)`, see [`BloguePost::related_posts()`](struct.BloguePost.html#method.related_posts).
+    ///
+    /// Defaults to none.
+    pub related_posts: &'a [&'a BloguePost],
+    /// Whether to fill out the `toc` variable with a table of contents of the post's headings.
+    ///
+    /// Defaults to true.
+    pub toc: bool,
+    /// How to highlight the post's fenced code blocks, if at all.
+    ///
+    /// Defaults to not highlighting them.
+    pub highlight: Option<&'a BlogueDescriptorHighlight>,
+    /// Whether to render the post's TeX math to MathML.
+    ///
+    /// Defaults to false.
+    pub math: bool,
+    /// Markdown extensions and options to render the post with.
+    ///
+    /// Defaults to the [`MARKDOWN_OPTIONS`](../util/struct.MARKDOWN_OPTIONS.html) ones.
+    pub markdown: MarkdownOptions,
+    /// Commands to pipe the post's source and HTML through.
+    ///
+    /// Defaults to none.
+    pub hooks: &'a BlogueDescriptorHooks,
+}
+
+impl<'a> Default for PostRenderOptions<'a> {
+    fn default() -> PostRenderOptions<'a> {
+        PostRenderOptions {
+            paged_center_output: None,
+            tag_center_output: None,
+            base_url: None,
+            previous_post: None,
+            next_post: None,
+            related_posts: &[],
+            toc: true,
+            highlight: None,
+            math: false,
+            markdown: Default::default(),
+            hooks: &NO_HOOKS,
+        }
+    }
+}
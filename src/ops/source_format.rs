@@ -0,0 +1,105 @@
+use self::super::super::util::{html_table_of_contents, table_of_contents, count_html_words, render_math, count_words, parse_html};
+use comrak::nodes::AstNode as ComrakAstNode;
+use comrak::{self, ComrakOptions, Arena as ComrakArena};
+use self::super::super::Error;
+
+
+/// Post source formats, in order of precedence, see [`BloguePost::source()`](struct.BloguePost.html#method.source).
+pub static SOURCE_FORMATS: &[&dyn SourceFormat] = &[&MarkdownSource, &HtmlSource];
+
+
+/// A format post sources can be written in, keyed by the extension of their `post.<extension>` file.
+///
+/// Each format is parsed into a comrak AST, so that asset extraction, excerpts, feeds,
+/// and the index centers work uniformly across formats.
+pub trait SourceFormat: Sync {
+    /// Extension of the post source file in this format, without the dot.
+    fn extension(&self) -> &'static str;
+
+    /// Parse the specified post source into an AST allocated in the specified arena.
+    ///
+    /// If `math` is set, TeX math is rendered to MathML, if the format supports it.
+    fn parse<'a>(&self, source: &str, arena: &'a ComrakArena<ComrakAstNode<'a>>, options: &ComrakOptions, math: bool)
+                 -> Result<&'a ComrakAstNode<'a>, Error>;
+
+    /// Count the words in the specified post source, parsed into the specified AST.
+    fn word_count<'a>(&self, source: &str, ast: &'a ComrakAstNode<'a>) -> usize;
+
+    /// Render a table of contents of the headings in the specified post source, parsed into the specified AST with the specified options,
+    /// as nested HTML lists of links to their anchors.
+    ///
+    /// Returns an empty string if there are no headings with anchors.
+    fn table_of_contents<'a>(&self, source: &str, ast: &'a ComrakAstNode<'a>, options: &ComrakOptions) -> String;
+}
+
+/// Markdown post sources, in `post.md`, with TeX math support, see [`render_math()`](../util/fn.render_math.html).
+#[derive(Debug, Copy, Clone, Hash, PartialEq, Eq, PartialOrd, Ord)]
+pub struct MarkdownSource;
+
+/// HTML fragment post sources, in `post.html`, passed through as-is, see [`parse_html()`](../util/fn.parse_html.html).
+#[derive(Debug, Copy, Clone, Hash, PartialEq, Eq, PartialOrd, Ord)]
+pub struct HtmlSource;
+
+
+impl SourceFormat for MarkdownSource {
+    fn extension(&self) -> &'static str {
+        "md"
+    }
+
+    fn parse<'a>(&self, source: &str, arena: &'a ComrakArena<ComrakAstNode<'a>>, options: &ComrakOptions, math: bool)
+                 -> Result<&'a ComrakAstNode<'a>, Error> {
+        if math {
+            render_math(arena, source, options)
+        } else {
+            Ok(comrak::parse_document(arena, source, options))
+        }
+    }
+
+    /// See [`count_words()`](../util/fn.count_words.html).
+    fn word_count<'a>(&self, _: &str, ast: &'a ComrakAstNode<'a>) -> usize {
+        count_words(ast)
+    }
+
+    /// See [`table_of_contents()`](../util/fn.table_of_contents.html); headings only have anchors with a header ID prefix specified.
+    fn table_of_contents<'a>(&self, _: &str, ast: &'a ComrakAstNode<'a>, options: &ComrakOptions) -> String {
+        match options.ext_header_ids {
+            Some(ref header_id_prefix) => table_of_contents(ast, header_id_prefix),
+            None => String::new(),
+        }
+    }
+}
+
+impl SourceFormat for HtmlSource {
+    fn extension(&self) -> &'static str {
+        "html"
+    }
+
+    fn parse<'a>(&self, source: &str, arena: &'a ComrakArena<ComrakAstNode<'a>>, _: &ComrakOptions, _: bool) -> Result<&'a ComrakAstNode<'a>, Error> {
+        Ok(parse_html(arena, source))
+    }
+
+    /// See [`count_html_words()`](../util/fn.count_html_words.html).
+    fn word_count<'a>(&self, source: &str, _: &'a ComrakAstNode<'a>) -> usize {
+        count_html_words(source)
+    }
+
+    /// See [`html_table_of_contents()`](../util/fn.html_table_of_contents.html).
+    fn table_of_contents<'a>(&self, source: &str, _: &'a ComrakAstNode<'a>, _: &ComrakOptions) -> String {
+        html_table_of_contents(source)
+    }
+}
+
+
+/// Get the post source format with the specified extension from [`SOURCE_FORMATS`](static.SOURCE_FORMATS.html), if any.
+///
+/// # Examples
+///
+/// ```
+/// # use bloguen::ops::source_format;
+/// assert_eq!(source_format("md").map(|f| f.extension()), Some("md"));
+/// assert_eq!(source_format("html").map(|f| f.extension()), Some("html"));
+/// assert!(source_format("rst").is_none());
+/// ```
+pub fn source_format(extension: &str) -> Option<&'static dyn SourceFormat> {
+    SOURCE_FORMATS.iter().find(|f| f.extension() == extension).cloned()
+}
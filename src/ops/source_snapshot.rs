@@ -0,0 +1,175 @@
+use self::super::{BlogueDescriptor, FormatTemplate, PostMetadata, BloguePost};
+use std::path::{PathBuf, Path};
+use self::super::super::util::concat_path;
+use std::collections::BTreeMap;
+use self::super::super::Error;
+use std::time::SystemTime;
+use walkdir::WalkDir;
+use std::fs;
+
+
+/// State of all files a blogue is generated from at a point in time, used to detect changes to the source.
+///
+/// Covers the blogue descriptor, the templates (and templates they `{include(path)}`) and `file:` elements it references,
+/// as well as all files in post directories and `file:` elements referenced by their metadata.
+#[derive(Debug, Clone, Hash, PartialEq, Eq, PartialOrd, Ord)]
+pub struct SourceSnapshot {
+    /// The modification time and length of each watched path, `None` if it doesn't exist.
+    pub files: BTreeMap<PathBuf, Option<(SystemTime, u64)>>,
+}
+
+impl SourceSnapshot {
+    /// Record the current state of the blogue in the specified source directory.
+    ///
+    /// Errors in the descriptor or post metadata aren't reported, the files they'd reference are omitted instead,
+    /// so that the snapshot can be used to detect when they get fixed.
+    ///
+    /// # Examples
+    ///
+    /// Given the following:
+    ///
+    /// ```plaintext
+    /// $ROOT
+    ///   blogue.toml
+    ///   header.html
+    ///   nav.html
+    ///   footer.html
+    ///   01. 2018-01-08 16-52 The venture into crocheting/
+    ///     post.md
+    /// ```
+    ///
+    /// Where `header.html` contains `{include(nav.html)}`, the following holds:
+    ///
+    /// ```
+    /// # use bloguen::ops::SourceSnapshot;
+    /// # use std::io::Write;
+    /// # use std::fs::{self, File};
+    /// # use std::env::temp_dir;
+    /// # let root = temp_dir().join("bloguen-doctest").join("ops-source_snapshot-take");
+    /// # let _ = fs::remove_dir_all(&root);
+    /// # fs::create_dir_all(root.join("01. 2018-01-08 16-52 The venture into crocheting")).unwrap();
+    /// # File::create(root.join("blogue.toml")).unwrap().write_all("name = \"Блогг\"\n".as_bytes()).unwrap();
+    /// # File::create(root.join("header.html")).unwrap().write_all(b"{include(nav.html)}").unwrap();
+    /// # File::create(root.join("nav.html")).unwrap();
+    /// # File::create(root.join("footer.html")).unwrap();
+    /// # File::create(root.join("01. 2018-01-08 16-52 The venture into crocheting").join("post.md")).unwrap();
+    /// # /*
+    /// let root: PathBuf = /* obtained elsewhere */;
+    /// # */
+    /// let snapshot = SourceSnapshot::take(&("$ROOT/".to_string(), root.clone())).unwrap();
+    /// assert!(snapshot.files.contains_key(&root.join("blogue.toml")));
+    /// assert!(snapshot.files.contains_key(&root.join("header.html")));
+    /// assert!(snapshot.files.contains_key(&root.join("nav.html")));
+    /// assert!(snapshot.files.contains_key(&root.join("01. 2018-01-08 16-52 The venture into crocheting")
+    ///                                          .join("post.md")));
+    ///
+    /// // New post directory created
+    /// # fs::create_dir_all(root.join("02. 2018-01-09 Hooked")).unwrap();
+    /// assert_ne!(SourceSnapshot::take(&("$ROOT/".to_string(), root.clone())).unwrap(), snapshot);
+    /// ```
+    pub fn take(source_dir: &(String, PathBuf)) -> Result<SourceSnapshot, Error> {
+        let mut files = BTreeMap::new();
+
+        add_file(&mut files, source_dir.1.join("blogue.toml"));
+        if let Ok(descriptor) = BlogueDescriptor::read(source_dir) {
+            add_template(&mut files, source_dir, descriptor.header_file.1);
+            add_template(&mut files, source_dir, descriptor.footer_file.1);
+            for path in descriptor.styles.iter().flat_map(|s| s.file_path()).chain(descriptor.scripts.iter().flat_map(|s| s.file_path())) {
+                add_file(&mut files, concat_path(source_dir.1.clone(), path));
+            }
+
+            if let Some(index) = descriptor.index {
+                add_template(&mut files, source_dir, index.header_file.1);
+                add_template(&mut files, source_dir, index.center_file.1);
+                add_template(&mut files, source_dir, index.footer_file.1);
+                for path in index.styles.iter().flat_map(|s| s.file_path()).chain(index.scripts.iter().flat_map(|s| s.file_path())) {
+                    add_file(&mut files, concat_path(source_dir.1.clone(), path));
+                }
+            }
+
+            if let Some(tag_index) = descriptor.tag_index {
+                add_template(&mut files, source_dir, tag_index.header_file.1);
+                add_template(&mut files, source_dir, tag_index.center_file.1);
+                add_template(&mut files, source_dir, tag_index.cloud_center_file.1);
+                add_template(&mut files, source_dir, tag_index.footer_file.1);
+                for path in tag_index.styles.iter().flat_map(|s| s.file_path()).chain(tag_index.scripts.iter().flat_map(|s| s.file_path())) {
+                    add_file(&mut files, concat_path(source_dir.1.clone(), path));
+                }
+            }
+        }
+
+        for post_dir in BloguePost::list(source_dir)? {
+            add_file(&mut files, post_dir.1.clone());
+            for entry in WalkDir::new(&post_dir.1).min_depth(1).into_iter().flatten() {
+                add_file(&mut files, entry.path().to_path_buf());
+            }
+
+            if let Ok(metadata) = PostMetadata::read_or_default(&post_dir) {
+                for path in metadata.styles.iter().flat_map(|s| s.file_path()).chain(metadata.scripts.iter().flat_map(|s| s.file_path())) {
+                    add_file(&mut files, concat_path(post_dir.1.clone(), path));
+                }
+            }
+        }
+
+        Ok(SourceSnapshot { files: files })
+    }
+
+    /// Get the paths whose state differs between the two snapshots, including ones only present in one of them.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use bloguen::ops::SourceSnapshot;
+    /// # use std::time::SystemTime;
+    /// # use std::path::Path;
+    /// let now = SystemTime::now();
+    /// let old = SourceSnapshot {
+    ///     files: vec![(Path::new("blogue.toml").to_path_buf(), Some((now, 12))),
+    ///                 (Path::new("header.html").to_path_buf(), None),
+    ///                 (Path::new("footer.html").to_path_buf(), Some((now, 0)))].into_iter().collect(),
+    /// };
+    /// let new = SourceSnapshot {
+    ///     files: vec![(Path::new("blogue.toml").to_path_buf(), Some((now, 12))),
+    ///                 (Path::new("header.html").to_path_buf(), Some((now, 0))),
+    ///                 (Path::new("post.md").to_path_buf(), Some((now, 0)))].into_iter().collect(),
+    /// };
+    /// assert_eq!(old.differences(&new),
+    ///            vec![Path::new("footer.html"), Path::new("header.html"), Path::new("post.md")]);
+    /// ```
+    pub fn differences<'s>(&'s self, other: &'s SourceSnapshot) -> Vec<&'s Path> {
+        let mut ret: Vec<_> = self.files
+            .iter()
+            .filter(|(path, state)| other.files.get(*path) != Some(state))
+            .map(|(path, _)| path.as_path())
+            .chain(other.files.keys().filter(|path| !self.files.contains_key(*path)).map(|path| path.as_path()))
+            .collect();
+        ret.sort();
+        ret
+    }
+}
+
+
+fn add_file(files: &mut BTreeMap<PathBuf, Option<(SystemTime, u64)>>, path: PathBuf) {
+    let state = fs::metadata(&path).and_then(|m| m.modified().map(|t| (t, m.len()))).ok();
+    files.insert(path, state);
+}
+
+fn add_template(files: &mut BTreeMap<PathBuf, Option<(SystemTime, u64)>>, source_dir: &(String, PathBuf), path: PathBuf) {
+    if let Ok(template) = fs::read_to_string(&path) {
+        match FormatTemplate::parse_with_includes(&template, source_dir, "template") {
+            Ok(template) => {
+                for include in template.includes() {
+                    add_file(files, include.to_path_buf());
+                }
+            }
+            // Watch for the missing include to be created
+            Err(Error::FileNotFound { who: "included template", path: missing }) => {
+                if missing.starts_with(&source_dir.0[..]) {
+                    add_file(files, concat_path(source_dir.1.clone(), &missing[source_dir.0.len()..]));
+                }
+            }
+            Err(_) => {}
+        }
+    }
+    add_file(files, path);
+}
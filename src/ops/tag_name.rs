@@ -1,5 +1,7 @@
 use serde::de::{Deserializer, Deserialize, Error as SerdeError};
 use self::super::super::Error;
+use self::super::super::util::{sanitise_file_name, resolve_url};
+use std::collections::BTreeMap;
 use std::iter::FromIterator;
 use std::path::PathBuf;
 use std::str::FromStr;
@@ -7,6 +9,7 @@ use std::ops::Deref;
 use std::fs::File;
 use std::io::Read;
 use std::fmt;
+use url::Url;
 
 
 /// A verified-valid post tag.
@@ -67,6 +70,105 @@ impl TagName {
 
         Result::from_iter(buf.split(|c: char| c.is_whitespace()).filter(|s| !s.trim().is_empty()).map(TagName::from_str))
     }
+
+    /// Get the name of this tag's index file, sans the extension.
+    ///
+    /// Characters not valid in filenames or links are replaced with underscores.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use bloguen::ops::TagName;
+    /// # use std::str::FromStr;
+    /// assert_eq!(TagName::from_str("коммунизм").unwrap().file_name(), "коммунизм");
+    /// assert_eq!(TagName::from_str("C/C++").unwrap().file_name(), "C_C++");
+    /// assert_eq!(TagName::from_str("#100%?").unwrap().file_name(), "_100__");
+    /// ```
+    pub fn file_name(&self) -> String {
+        sanitise_file_name(&self.0)
+    }
+
+    /// Get the link to this tag's index file.
+    ///
+    /// The link is absolute if a base URL is specified, and relative to the `tags/` subdirectory otherwise.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use bloguen::ops::TagName;
+    /// # use std::str::FromStr;
+    /// let tag = TagName::from_str("C/C++").unwrap();
+    /// assert_eq!(tag.permalink(None), "C_C++.html");
+    /// assert_eq!(tag.permalink(Some(&"https://nabijaczleweli.xyz/blogue/".parse().unwrap())),
+    ///            "https://nabijaczleweli.xyz/blogue/tags/C_C++.html");
+    /// ```
+    pub fn permalink(&self, base_url: Option<&Url>) -> String {
+        let fname = format!("{}.html", self.file_name());
+        match base_url {
+            Some(base_url) => resolve_url(base_url, &format!("tags/{}", fname)),
+            None => fname,
+        }
+    }
+
+    /// Get the link to the tag cloud's index file.
+    ///
+    /// The link is absolute if a base URL is specified, and relative to the `tags/` subdirectory otherwise.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use bloguen::ops::TagName;
+    /// assert_eq!(TagName::cloud_permalink(None), "index.html");
+    /// assert_eq!(TagName::cloud_permalink(Some(&"https://nabijaczleweli.xyz/blogue/".parse().unwrap())),
+    ///            "https://nabijaczleweli.xyz/blogue/tags/index.html");
+    /// ```
+    pub fn cloud_permalink(base_url: Option<&Url>) -> String {
+        match base_url {
+            Some(base_url) => resolve_url(base_url, "tags/index.html"),
+            None => "index.html".to_string(),
+        }
+    }
+
+    /// Check that no two of the specified tags have the same [`file_name()`](#method.file_name),
+    /// and that none of them would overwrite the tag cloud's `index`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use bloguen::ops::TagName;
+    /// # use bloguen::Error;
+    /// let tags: Vec<TagName> = vec!["C++".parse().unwrap(), "C/C++".parse().unwrap()];
+    /// assert_eq!(TagName::check_unique_file_names(&tags), Ok(()));
+    ///
+    /// let tags: Vec<TagName> = vec!["C_C++".parse().unwrap(), "C/C++".parse().unwrap()];
+    /// assert_eq!(TagName::check_unique_file_names(&tags),
+    ///            Err(Error::Parse {
+    ///                tp: "path chunk",
+    ///                wher: "blogue tags".into(),
+    ///                more: "tags \"C_C++\" and \"C/C++\" both output to \"C_C++.html\"".into(),
+    ///            }));
+    /// ```
+    pub fn check_unique_file_names<'t, I: IntoIterator<Item = &'t TagName>>(tags: I) -> Result<(), Error> {
+        let mut file_names = BTreeMap::new();
+        for tag in tags {
+            let file_name = tag.file_name();
+            if file_name == "index" {
+                return Err(Error::Parse {
+                    tp: "path chunk",
+                    wher: "blogue tags".into(),
+                    more: format!("tag {:?} outputs to the tag cloud's \"index.html\"", tag.0).into(),
+                });
+            }
+            if let Some(other) = file_names.insert(file_name, tag) {
+                return Err(Error::Parse {
+                    tp: "path chunk",
+                    wher: "blogue tags".into(),
+                    more: format!("tags {:?} and {:?} both output to \"{}.html\"", other.0, tag.0, tag.file_name()).into(),
+                });
+            }
+        }
+        Ok(())
+    }
 }
 
 impl FromStr for TagName {
@@ -11,8 +11,10 @@
 //! ```
 
 
-use clap::{ErrorKind as ClapErrorKind, Error as ClapError, AppSettings, Arg};
+use chrono::{FixedOffset, DateTime};
+use clap::{ErrorKind as ClapErrorKind, Error as ClapError, AppSettings, SubCommand, Arg};
 use std::path::{PathBuf, Path};
+use std::str::FromStr;
 use std::fs;
 
 
@@ -21,8 +23,22 @@ use std::fs;
 pub struct Options {
     /// The directory containing the blogue source, must exist.
     pub source_dir: (String, PathBuf),
-    /// The directory to the blogue source, must not exist if `--force` not specified, parent directory must exist.
+    /// The directory to the blogue source, must not exist if neither `--force` nor `--incremental` specified, parent directory must exist.
     pub output_dir: (String, PathBuf),
+    /// Whether to only regenerate the posts whose inputs changed since the last build into the output directory.
+    pub incremental: bool,
+    /// Whether to keep running after the first build, regenerating the blogue when its source changes.
+    pub watch: bool,
+    /// Whether to generate draft posts alongside the published ones.
+    pub drafts: bool,
+    /// Whether to generate posts dated after the current time alongside the published ones.
+    pub future: bool,
+    /// The time to consider current when deciding which posts are published, if overriden.
+    ///
+    /// Defaults to the actual current time.
+    pub now: Option<DateTime<FixedOffset>>,
+    /// The port to serve the output directory on over HTTP on localhost, if the `serve` subcommand was used.
+    pub serve: Option<u16>,
 }
 
 impl Options {
@@ -30,10 +46,22 @@ impl Options {
     pub fn parse() -> Options {
         let matches = app_from_crate!("\n")
             .setting(AppSettings::ColoredHelp)
+            .setting(AppSettings::SubcommandsNegateReqs)
             .arg(Arg::from_usage("<IN_DIR> 'Directory to generate a blogue from'").validator(Options::source_directory_validator))
             .arg(Arg::from_usage("<OUT_DIR> 'Directory to generate the blogue into'").validator(Options::output_directory_validator))
-            .arg(Arg::from_usage("-f --force 'Allow the output directory to exist, overriding it'"))
+            .args(&Options::build_args())
+            .subcommand(SubCommand::with_name("serve")
+                .about("Generate a blogue and serve it over HTTP on localhost")
+                .setting(AppSettings::ColoredHelp)
+                .arg(Arg::from_usage("<IN_DIR> 'Directory to generate a blogue from'").validator(Options::source_directory_validator))
+                .arg(Arg::from_usage("<OUT_DIR> 'Directory to generate the blogue into'").validator(Options::output_directory_validator))
+                .args(&Options::build_args())
+                .arg(Arg::from_usage("-p --port [PORT] 'Port to serve on'").default_value("8000").validator(Options::port_validator)))
             .get_matches();
+        let (matches, serve) = match matches.subcommand_matches("serve") {
+            Some(serve_matches) => (serve_matches, Some(u16::from_str(serve_matches.value_of("port").unwrap()).unwrap())),
+            None => (&matches, None),
+        };
 
         Options {
             source_dir: matches.value_of("IN_DIR")
@@ -55,7 +83,7 @@ impl Options {
                         if !p.is_absolute() {
                             p = PathBuf::from(format!("./{}", o));
                         }
-                        if p.exists() {
+                        if p.exists() && !matches.is_present("incremental") {
                             if !matches.is_present("force") {
                                 ClapError {
                                         message: format!("Output directory \"{}\" already exists", p.display()),
@@ -86,9 +114,25 @@ impl Options {
                      })
                 })
                 .unwrap(),
+            incremental: matches.is_present("incremental"),
+            watch: matches.is_present("watch"),
+            drafts: matches.is_present("drafts"),
+            future: matches.is_present("future"),
+            now: matches.value_of("now").map(|n| DateTime::parse_from_rfc3339(n).unwrap()),
+            serve: serve,
         }
     }
 
+    fn build_args() -> Vec<Arg<'static, 'static>> {
+        vec![Arg::from_usage("-f --force 'Allow the output directory to exist, overriding it'"),
+             Arg::from_usage("-i --incremental 'Allow the output directory to exist, only regenerating what changed'").conflicts_with("force"),
+             Arg::from_usage("-w --watch 'Keep running, regenerating the blogue when its source changes'"),
+             Arg::from_usage("-d --drafts 'Generate draft posts, too'"),
+             Arg::from_usage("--future 'Generate posts dated in the future, too'"),
+             Arg::from_usage("--now [NOW] 'Time to consider current when publishing posts, in RFC3339 [default: current time]'")
+                 .validator(Options::now_validator)]
+    }
+
     fn source_directory_validator(s: String) -> Result<(), String> {
         fs::canonicalize(&s).map_err(|_| format!("Input directory \"{}\" not found", s)).and_then(|f| if f.is_file() {
             Err(format!("Input directory \"{}\" not actualy a directory", s))
@@ -97,6 +141,14 @@ impl Options {
         })
     }
 
+    fn port_validator(s: String) -> Result<(), String> {
+        u16::from_str(&s).map(|_| ()).map_err(|e| format!("Port \"{}\" invalid: {}", s, e))
+    }
+
+    fn now_validator(s: String) -> Result<(), String> {
+        DateTime::parse_from_rfc3339(&s).map(|_| ()).map_err(|e| format!("Current time \"{}\" invalid: {}", s, e))
+    }
+
     fn output_directory_validator(s: String) -> Result<(), String> {
         let mut p = PathBuf::from(&s);
         if !p.is_absolute() {
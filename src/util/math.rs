@@ -0,0 +1,892 @@
+use comrak::nodes::{NodeValue as ComrakNodeValue, AstNode as ComrakAstNode, Ast as ComrakAst};
+use comrak::{self, ComrakOptions, Arena as ComrakArena};
+use comrak::arena_tree::Node as ComrakNode;
+use self::super::super::Error;
+use std::cell::RefCell;
+use std::fmt::Write;
+use std::cmp;
+
+
+/// Put before backslash escapes in the Markdown source, which comrak doesn't otherwise keep track of.
+///
+/// A noncharacter, so that it doesn't clash with any actual text.
+const ESCAPE_MARK: char = '\u{FDD0}';
+
+
+/// Parse the specified Markdown, replacing the `$…$` inline and `$$…$$` display TeX math in its text with MathML,
+/// see [`tex_to_mathml()`](fn.tex_to_mathml.html).
+///
+/// Only text is considered, so code, link destinations, and raw HTML are left as-is, as are escaped dollar signs.
+/// Backslash escapes within formulas are passed to TeX verbatim.
+///
+/// A formula can span emphasis, which is assumed to have been written with asterisks, as in `$a*b*c$`.
+///
+/// An inline formula can't start with whitespace, end with whitespace, contain a backtick, or be followed by a digit,
+/// so prices and the like aren't mistaken for it.
+///
+/// # Examples
+///
+/// ```
+/// # extern crate bloguen;
+/// # extern crate comrak;
+/// # use bloguen::util::{MARKDOWN_OPTIONS, render_math};
+/// let arena = comrak::Arena::new();
+/// let ast = render_math(&arena, "Area: $\\pi r^2$, for $5 or `$x$`", &MARKDOWN_OPTIONS).unwrap();
+///
+/// let mut out = vec![];
+/// comrak::format_html(ast, &MARKDOWN_OPTIONS, &mut out).unwrap();
+/// assert_eq!(String::from_utf8(out).unwrap(),
+///            "<p>Area: <math xmlns=\"http://www.w3.org/1998/Math/MathML\"><semantics><mrow><mi>π</mi><msup><mi>r</mi><mn>2</mn></msup></mrow>\
+///             <annotation encoding=\"application/x-tex\">&#92;pi r&#94;2</annotation></semantics></math>, for $5 or <code>$x$</code></p>\n");
+/// ```
+pub fn render_math<'a>(arena: &'a ComrakArena<ComrakAstNode<'a>>, markdown: &str, options: &ComrakOptions) -> Result<&'a ComrakAstNode<'a>, Error> {
+    let mut marked = String::with_capacity(markdown.len());
+    let mut chars = markdown.chars().peekable();
+    while let Some(c) = chars.next() {
+        if c == '\\' && chars.peek().map(|n| n.is_ascii_punctuation()).unwrap_or(false) {
+            marked.push(ESCAPE_MARK);
+            marked.push(c);
+            marked.push(chars.next().unwrap());
+        } else {
+            marked.push(c);
+        }
+    }
+
+    let root = comrak::parse_document(arena, &marked, options);
+    render_math_children(arena, root)?;
+
+    for n in root.descendants() {
+        match n.data.borrow_mut().value {
+            ComrakNodeValue::Text(ref mut literal) |
+            ComrakNodeValue::Code(ref mut literal) |
+            ComrakNodeValue::HtmlInline(ref mut literal) |
+            ComrakNodeValue::FootnoteReference(ref mut literal) |
+            ComrakNodeValue::FootnoteDefinition(ref mut literal) => unmark_escapes(literal),
+            ComrakNodeValue::CodeBlock(ref mut block) => {
+                unmark_escapes(&mut block.info);
+                unmark_escapes(&mut block.literal);
+            }
+            ComrakNodeValue::HtmlBlock(ref mut block) => unmark_escapes(&mut block.literal),
+            ComrakNodeValue::Link(ref mut link) |
+            ComrakNodeValue::Image(ref mut link) => {
+                unmark_escapes(&mut link.url);
+                unmark_escapes(&mut link.title);
+            }
+            _ => {}
+        }
+    }
+
+    Ok(root)
+}
+
+fn unmark_escapes(literal: &mut Vec<u8>) {
+    let mut mark = [0; 4];
+    let mark = ESCAPE_MARK.encode_utf8(&mut mark).as_bytes();
+    while let Some(i) = literal.windows(mark.len()).position(|w| w == mark) {
+        literal.drain(i..i + mark.len());
+    }
+}
+
+/// Render the math in the runs of text among the children of the specified node, then in those of its children.
+fn render_math_children<'a>(arena: &'a ComrakArena<ComrakAstNode<'a>>, node: &'a ComrakAstNode<'a>) -> Result<(), Error> {
+    let mut run = vec![];
+    for child in node.children().collect::<Vec<_>>() {
+        match joined_text(child) {
+            Some(text) => run.push((child, text)),
+            None => {
+                render_math_run(arena, &run)?;
+                run.clear();
+            }
+        }
+    }
+    render_math_run(arena, &run)?;
+
+    for child in node.children().collect::<Vec<_>>() {
+        render_math_children(arena, child)?;
+    }
+    Ok(())
+}
+
+/// Get the text of the specified node as written, if it consists only of text, line breaks, and emphasis.
+fn joined_text<'a>(node: &'a ComrakAstNode<'a>) -> Option<String> {
+    let delimiter = match node.data.borrow().value {
+        ComrakNodeValue::Text(ref literal) => return Some(String::from_utf8_lossy(literal).into_owned()),
+        ComrakNodeValue::SoftBreak | ComrakNodeValue::LineBreak => return Some("\n".to_string()),
+        ComrakNodeValue::Emph => "*",
+        ComrakNodeValue::Strong => "**",
+        ComrakNodeValue::Strikethrough => "~~",
+        ComrakNodeValue::Superscript => "^",
+        _ => return None,
+    };
+
+    let mut ret = delimiter.to_string();
+    for child in node.children() {
+        ret.push_str(&joined_text(child)?);
+    }
+    ret.push_str(delimiter);
+    Some(ret)
+}
+
+/// Replace the math in the specified run of sibling nodes and their joined texts.
+///
+/// Formulas have to start and end in text nodes, and split them into text before, raw HTML MathML, and text after.
+fn render_math_run<'a>(arena: &'a ComrakArena<ComrakAstNode<'a>>, run: &[(&'a ComrakAstNode<'a>, String)]) -> Result<(), Error> {
+    let mut joined = String::new();
+    let mut bounds = vec![];
+    for &(node, ref text) in run {
+        let is_text = matches!(node.data.borrow().value, ComrakNodeValue::Text(_));
+        bounds.push((joined.len(), joined.len() + text.len(), is_text));
+        joined.push_str(text);
+    }
+    let in_text = |pos: usize| bounds.iter().any(|&(start, end, is_text)| is_text && start <= pos && pos < end);
+
+    let mut spans = vec![];
+    let bytes = joined.as_bytes();
+    let mut i = 0;
+    while i < bytes.len() {
+        if joined[i..].starts_with(ESCAPE_MARK) {
+            i += ESCAPE_MARK.len_utf8() + 1;
+            continue;
+        }
+        match bytes[i] {
+            b'$' => {
+                let display = bytes.get(i + 1) == Some(&b'$');
+                match find_math_end(&joined, i, display) {
+                    Some((content, end)) if in_text(i) && in_text(end - 1) => {
+                        spans.push((i, end, tex_to_mathml(&content.replace(ESCAPE_MARK, "\\"), display)?));
+                        i = end;
+                    }
+                    Some(_) => i += 1,
+                    None => i += if display { 2 } else { 1 },
+                }
+            }
+            _ => i += 1,
+        }
+    }
+
+    for (&(node, _), &(start, end, is_text)) in run.iter().zip(bounds.iter()) {
+        let mut overlapping = spans.iter().filter(|&&(span_start, span_end, _)| span_start < end && span_end > start).peekable();
+        if overlapping.peek().is_none() {
+            continue;
+        }
+
+        if is_text {
+            let mut pos = start;
+            for &(span_start, span_end, ref mathml) in overlapping {
+                if span_start >= start {
+                    if span_start > pos {
+                        node.insert_before(new_node(arena, ComrakNodeValue::Text(joined.as_bytes()[pos..span_start].to_vec())));
+                    }
+                    node.insert_before(new_node(arena, ComrakNodeValue::HtmlInline(mathml.as_bytes().to_vec())));
+                }
+                pos = cmp::max(pos, span_end);
+            }
+            if pos < end {
+                node.insert_before(new_node(arena, ComrakNodeValue::Text(joined.as_bytes()[pos..end].to_vec())));
+            }
+        }
+        node.detach();
+    }
+
+    Ok(())
+}
+
+fn new_node<'a>(arena: &'a ComrakArena<ComrakAstNode<'a>>, value: ComrakNodeValue) -> &'a ComrakAstNode<'a> {
+    arena.alloc(ComrakNode::new(RefCell::new(ComrakAst::new(value))))
+}
+
+fn find_math_end(prose: &str, start: usize, display: bool) -> Option<(&str, usize)> {
+    let bytes = prose.as_bytes();
+    let content_start = start + if display { 2 } else { 1 };
+    if !display && bytes.get(content_start).map(|b| b.is_ascii_whitespace()).unwrap_or(true) {
+        return None;
+    }
+
+    let mut i = content_start;
+    while i < bytes.len() {
+        if prose[i..].starts_with(ESCAPE_MARK) {
+            i += ESCAPE_MARK.len_utf8() + 1;
+            continue;
+        }
+        match bytes[i] {
+            b'\\' => i += 2,
+            b'`' => return None,
+            b'$' if display => {
+                if bytes.get(i + 1) == Some(&b'$') && !prose[content_start..i].trim().is_empty() {
+                    return Some((&prose[content_start..i], i + 2));
+                }
+                return None;
+            }
+            b'$' => {
+                if bytes[i - 1].is_ascii_whitespace() || bytes.get(i + 1).map(|b| b.is_ascii_digit()).unwrap_or(false) {
+                    return None;
+                }
+                return Some((&prose[content_start..i], i + 1));
+            }
+            _ => i += 1,
+        }
+    }
+    None
+}
+
+
+/// Convert the specified TeX formula to a MathML `<math>` element, annotated with the TeX source.
+///
+/// The formula is written on one line, with ASCII punctuation in the text written as character references,
+/// so that it can be embedded in Markdown as-is.
+///
+/// Supported are letters, numbers, operators, sub- and superscripts, `{}` groups, Greek letters, common symbols,
+/// big operators and function names, `\frac`, `\binom`, `\sqrt`, accents, `\text`, font commands, `\left`/`\right`,
+/// spacing commands, and the matrix, `cases`, and `aligned` environments.
+///
+/// # Examples
+///
+/// ```
+/// # use bloguen::util::tex_to_mathml;
+/// assert_eq!(tex_to_mathml("\\frac{a}{2}", false).unwrap(),
+///            "<math xmlns=\"http://www.w3.org/1998/Math/MathML\"><semantics><mfrac><mi>a</mi><mn>2</mn></mfrac>\
+///             <annotation encoding=\"application/x-tex\">&#92;frac&#123;a&#125;&#123;2&#125;</annotation></semantics></math>");
+/// assert_eq!(tex_to_mathml("x \\neq y", true).unwrap(),
+///            "<math xmlns=\"http://www.w3.org/1998/Math/MathML\" display=\"block\"><semantics><mrow><mi>x</mi><mo>≠</mo><mi>y</mi></mrow>\
+///             <annotation encoding=\"application/x-tex\">x &#92;neq y</annotation></semantics></math>");
+///
+/// assert!(tex_to_mathml("\\frac{a}", false).is_err());
+/// ```
+pub fn tex_to_mathml(tex: &str, display: bool) -> Result<String, Error> {
+    let mut parser = TexParser {
+        tex: tex,
+        pos: 0,
+        display: display,
+        in_optional_argument: false,
+    };
+    let body = parser.parse_row().and_then(|row| match parser.peek() {
+            None => Ok(row),
+            Some(c) => Err(format!("unexpected {:?} at position {}", c, parser.pos)),
+        })
+        .map_err(|e| {
+            Error::Parse {
+                tp: "TeX",
+                wher: "math".into(),
+                more: format!("{:?}: {}", tex.trim(), e).into(),
+            }
+        })?;
+
+    let mut ret = String::from("<math xmlns=\"http://www.w3.org/1998/Math/MathML\"");
+    if display {
+        ret.push_str(" display=\"block\"");
+    }
+    ret.push_str("><semantics>");
+    ret.push_str(&body);
+    ret.push_str("<annotation encoding=\"application/x-tex\">");
+    escape_text(tex.trim(), &mut ret);
+    ret.push_str("</annotation></semantics></math>");
+    Ok(ret)
+}
+
+
+struct TexParser<'s> {
+    tex: &'s str,
+    pos: usize,
+    display: bool,
+    in_optional_argument: bool,
+}
+
+impl<'s> TexParser<'s> {
+    fn peek(&mut self) -> Option<char> {
+        self.skip_whitespace();
+        self.tex[self.pos..].chars().next()
+    }
+
+    fn skip_whitespace(&mut self) {
+        loop {
+            let rest = &self.tex[self.pos..];
+            let trimmed = rest.trim_start();
+            self.pos += rest.len() - trimmed.len();
+            if trimmed.starts_with('%') {
+                self.pos += trimmed.find('\n').unwrap_or(trimmed.len());
+            } else {
+                break;
+            }
+        }
+    }
+
+    fn next_char(&mut self) -> Option<char> {
+        let c = self.tex[self.pos..].chars().next()?;
+        self.pos += c.len_utf8();
+        Some(c)
+    }
+
+    fn expect(&mut self, what: char) -> Result<(), String> {
+        match self.peek() {
+            Some(c) if c == what => {
+                self.pos += c.len_utf8();
+                Ok(())
+            }
+            Some(c) => Err(format!("expected {:?}, found {:?} at position {}", what, c, self.pos)),
+            None => Err(format!("expected {:?}, found end of formula", what)),
+        }
+    }
+
+    /// Peek at the command at the current position, if any, without consuming it.
+    fn peek_command(&mut self) -> Option<&'s str> {
+        self.skip_whitespace();
+        let rest = &self.tex[self.pos..];
+        if !rest.starts_with('\\') {
+            return None;
+        }
+
+        let name_len = rest[1..].find(|c: char| !c.is_ascii_alphabetic()).unwrap_or(rest.len() - 1);
+        if name_len != 0 {
+            Some(&rest[1..1 + name_len])
+        } else {
+            rest[1..].chars().next().map(|c| &rest[1..1 + c.len_utf8()])
+        }
+    }
+
+    /// Parse atoms until the end of the formula, the end of a group, or an environment separator.
+    ///
+    /// Yields an `<mrow>` unless there's exactly one atom.
+    fn parse_row(&mut self) -> Result<String, String> {
+        let mut atoms = vec![];
+        loop {
+            match self.peek() {
+                None | Some('}') | Some('&') => break,
+                Some(']') if self.in_optional_argument => break,
+                Some('\\') => {
+                    match self.peek_command() {
+                        Some("\\") | Some("end") | Some("right") => break,
+                        _ => {}
+                    }
+                }
+                _ => {}
+            }
+
+            let atom = self.parse_atom()?;
+            atoms.push(self.parse_scripts(atom)?);
+        }
+
+        Ok(if atoms.len() == 1 {
+            atoms.pop().unwrap().0
+        } else {
+            format!("<mrow>{}</mrow>", atoms.into_iter().map(|(a, _)| a).collect::<String>())
+        })
+    }
+
+    /// Parse a single argument: a group, a command, or a single character.
+    fn parse_argument(&mut self) -> Result<String, String> {
+        match self.peek() {
+            None => Err("expected argument, found end of formula".to_string()),
+            Some('}') => Err(format!("expected argument, found '}}' at position {}", self.pos)),
+            Some(c) if c.is_ascii_digit() => {
+                self.pos += 1;
+                Ok(element("mn", &c.to_string(), ""))
+            }
+            Some(_) => self.parse_atom().map(|(a, _)| a),
+        }
+    }
+
+    /// Parse a `{}`-delimited group as raw text.
+    fn parse_text_argument(&mut self) -> Result<&'s str, String> {
+        self.expect('{')?;
+        let start = self.pos;
+        let mut depth = 0;
+        loop {
+            match self.next_char() {
+                Some('{') => depth += 1,
+                Some('}') if depth == 0 => return Ok(&self.tex[start..self.pos - 1]),
+                Some('}') => depth -= 1,
+                Some('\\') => {
+                    self.next_char();
+                }
+                Some(_) => {}
+                None => return Err("unterminated group".to_string()),
+            }
+        }
+    }
+
+    /// Parse an atom, returning it and whether it takes limits under and over it in display mode.
+    fn parse_atom(&mut self) -> Result<(String, bool), String> {
+        let c = self.peek().ok_or_else(|| "unexpected end of formula".to_string())?;
+        match c {
+            '{' => {
+                self.pos += 1;
+                let in_optional_argument = self.in_optional_argument;
+                self.in_optional_argument = false;
+                let row = self.parse_row()?;
+                self.in_optional_argument = in_optional_argument;
+                self.expect('}')?;
+                Ok((row, false))
+            }
+            '^' | '_' => Ok(("<mrow></mrow>".to_string(), false)),
+            '\\' => self.parse_command(),
+            '0'..='9' | '.' => {
+                let len = self.tex[self.pos..].find(|c: char| !(c.is_ascii_digit() || c == '.')).unwrap_or(self.tex.len() - self.pos);
+                let number = &self.tex[self.pos..self.pos + len];
+                self.pos += len;
+                if number == "." {
+                    Ok((element("mo", ".", ""), false))
+                } else {
+                    Ok((element("mn", number, ""), false))
+                }
+            }
+            '&' | '#' => Err(format!("unexpected {:?} at position {}", c, self.pos)),
+            '~' => {
+                self.pos += 1;
+                Ok(("<mspace width=\"0.3333em\"></mspace>".to_string(), false))
+            }
+            '\'' => {
+                self.pos += 1;
+                Ok((element("mo", "′", ""), false))
+            }
+            '-' => {
+                self.pos += 1;
+                Ok((element("mo", "−", ""), false))
+            }
+            '*' => {
+                self.pos += 1;
+                Ok((element("mo", "∗", ""), false))
+            }
+            c if c.is_alphabetic() => {
+                self.pos += c.len_utf8();
+                Ok((element("mi", &c.to_string(), ""), false))
+            }
+            c => {
+                self.pos += c.len_utf8();
+                let attrs = if "()[]|".contains(c) { " stretchy=\"false\"" } else { "" };
+                Ok((element("mo", &c.to_string(), attrs), false))
+            }
+        }
+    }
+
+    /// Attach any sub- and superscripts following the specified atom.
+    fn parse_scripts(&mut self, (base, limits): (String, bool)) -> Result<(String, bool), String> {
+        let mut sub = None;
+        let mut sup = None;
+        loop {
+            match self.peek() {
+                Some('_') if sub.is_none() => {
+                    self.pos += 1;
+                    sub = Some(self.parse_argument()?);
+                }
+                Some('^') if sup.is_none() => {
+                    self.pos += 1;
+                    sup = Some(self.parse_argument()?);
+                }
+                Some(c @ '_') | Some(c @ '^') => return Err(format!("double {:?} at position {}", c, self.pos)),
+                _ => break,
+            }
+        }
+
+        let under_over = limits && self.display;
+        Ok((match (sub, sup) {
+                (None, None) => base,
+                (Some(sub), None) => format!("<{0}>{1}{2}</{0}>", if under_over { "munder" } else { "msub" }, base, sub),
+                (None, Some(sup)) => format!("<{0}>{1}{2}</{0}>", if under_over { "mover" } else { "msup" }, base, sup),
+                (Some(sub), Some(sup)) => format!("<{0}>{1}{2}{3}</{0}>", if under_over { "munderover" } else { "msubsup" }, base, sub, sup),
+            },
+            false))
+    }
+
+    fn parse_command(&mut self) -> Result<(String, bool), String> {
+        let name = self.peek_command().ok_or_else(|| "unexpected end of formula after \\".to_string())?;
+        let start = self.pos;
+        self.pos += 1 + name.len();
+
+        if let Some(letter) = greek_letter(name) {
+            let attrs = if letter.chars().all(char::is_uppercase) { " mathvariant=\"normal\"" } else { "" };
+            return Ok((element("mi", letter, attrs), false));
+        }
+        if let Some(identifier) = symbol_identifier(name) {
+            return Ok((element("mi", identifier, ""), false));
+        }
+        if let Some(operator) = symbol_operator(name) {
+            return Ok((element("mo", operator, ""), false));
+        }
+        if let Some((operator, limits)) = big_operator(name) {
+            return Ok((element("mo", operator, " largeop=\"true\" movablelimits=\"true\""), limits));
+        }
+        if let Some(limits) = function_name(name) {
+            return Ok((element("mi", name, ""), limits));
+        }
+        if let Some(width) = space_width(name) {
+            return Ok((format!("<mspace width=\"{}\"></mspace>", width), false));
+        }
+        if let Some(accent) = accent(name) {
+            let base = self.parse_argument()?;
+            return Ok((format!("<mover accent=\"true\">{}{}</mover>", base, element("mo", accent, " stretchy=\"true\"")), false));
+        }
+        if let Some(variant) = font_variant(name) {
+            let body = self.parse_argument()?;
+            let body = body.replace("<mi>", &format!("<mi mathvariant=\"{}\">", variant))
+                .replace("<mi mathvariant=\"normal\">", &format!("<mi mathvariant=\"{}\">", variant))
+                .replace("<mn>", &format!("<mn mathvariant=\"{}\">", variant));
+            return Ok((body, false));
+        }
+
+        match name {
+            "frac" | "dfrac" | "tfrac" => {
+                let numerator = self.parse_argument()?;
+                let denominator = self.parse_argument()?;
+                Ok((format!("<mfrac>{}{}</mfrac>", numerator, denominator), false))
+            }
+            "binom" => {
+                let top = self.parse_argument()?;
+                let bottom = self.parse_argument()?;
+                Ok((format!("<mrow><mo>(</mo><mfrac linethickness=\"0\">{}{}</mfrac><mo>)</mo></mrow>", top, bottom), false))
+            }
+            "sqrt" => {
+                if self.peek() == Some('[') {
+                    self.pos += 1;
+                    let in_optional_argument = self.in_optional_argument;
+                    self.in_optional_argument = true;
+                    let index = self.parse_row()?;
+                    self.in_optional_argument = in_optional_argument;
+                    self.expect(']')?;
+                    let radicand = self.parse_argument()?;
+                    Ok((format!("<mroot>{}{}</mroot>", radicand, index), false))
+                } else {
+                    Ok((format!("<msqrt>{}</msqrt>", self.parse_argument()?), false))
+                }
+            }
+            "underline" => Ok((format!("<munder accentunder=\"true\">{}<mo stretchy=\"true\">&#95;</mo></munder>", self.parse_argument()?), false)),
+            "text" | "textrm" | "mbox" => Ok((element("mtext", self.parse_text_argument()?, ""), false)),
+            "operatorname" => Ok((element("mi", self.parse_text_argument()?, ""), false)),
+            "left" => {
+                let open = self.parse_delimiter()?;
+                let body = self.parse_row()?;
+                if self.peek_command() != Some("right") {
+                    return Err(format!("\\left at position {} without matching \\right", start));
+                }
+                self.pos += "\\right".len();
+                let close = self.parse_delimiter()?;
+                Ok((format!("<mrow>{}{}{}</mrow>", fence(open), body, fence(close)), false))
+            }
+            "begin" => self.parse_environment(),
+            _ => Err(format!("unknown command \\{} at position {}", name, start)),
+        }
+    }
+
+    fn parse_delimiter(&mut self) -> Result<&'static str, String> {
+        match self.peek() {
+            Some('\\') => {
+                let name = self.peek_command().ok_or_else(|| "unexpected end of formula after \\".to_string())?;
+                self.pos += 1 + name.len();
+                match name {
+                    "{" | "lbrace" => Ok("{"),
+                    "}" | "rbrace" => Ok("}"),
+                    "|" | "Vert" => Ok("‖"),
+                    "vert" => Ok("|"),
+                    "langle" => Ok("⟨"),
+                    "rangle" => Ok("⟩"),
+                    "lfloor" => Ok("⌊"),
+                    "rfloor" => Ok("⌋"),
+                    "lceil" => Ok("⌈"),
+                    "rceil" => Ok("⌉"),
+                    _ => Err(format!("unknown delimiter \\{}", name)),
+                }
+            }
+            Some(c) => {
+                self.pos += c.len_utf8();
+                match c {
+                    '(' => Ok("("),
+                    ')' => Ok(")"),
+                    '[' => Ok("["),
+                    ']' => Ok("]"),
+                    '|' => Ok("|"),
+                    '/' => Ok("/"),
+                    '.' => Ok(""),
+                    c => Err(format!("unknown delimiter {:?}", c)),
+                }
+            }
+            None => Err("expected delimiter, found end of formula".to_string()),
+        }
+    }
+
+    fn parse_environment(&mut self) -> Result<(String, bool), String> {
+        let name = self.parse_text_argument()?;
+        let (open, close, columnalign) = match name {
+            "matrix" => ("", "", None),
+            "pmatrix" => ("(", ")", None),
+            "bmatrix" => ("[", "]", None),
+            "Bmatrix" => ("{", "}", None),
+            "vmatrix" => ("|", "|", None),
+            "Vmatrix" => ("‖", "‖", None),
+            "cases" => ("{", "", Some("left left")),
+            "aligned" | "align" | "align*" => ("", "", Some("right left")),
+            _ => return Err(format!("unknown environment {:?}", name)),
+        };
+
+        let mut rows = vec![];
+        let mut cells = vec![];
+        loop {
+            cells.push(format!("<mtd>{}</mtd>", self.parse_row()?));
+            match self.peek_command() {
+                Some("\\") => {
+                    self.pos += 2;
+                    rows.push(format!("<mtr>{}</mtr>", cells.drain(..).collect::<String>()));
+                }
+                Some("end") => {
+                    self.pos += "\\end".len();
+                    let end = self.parse_text_argument()?;
+                    if end != name {
+                        return Err(format!("\\begin{{{}}} ended by \\end{{{}}}", name, end));
+                    }
+                    break;
+                }
+                _ => {
+                    match self.peek() {
+                        Some('&') => self.pos += 1,
+                        Some(c) => return Err(format!("unexpected {:?} in {} at position {}", c, name, self.pos)),
+                        None => return Err(format!("\\begin{{{}}} without matching \\end", name)),
+                    }
+                }
+            }
+        }
+        if cells.len() > 1 || cells[0] != "<mtd><mrow></mrow></mtd>" {
+            rows.push(format!("<mtr>{}</mtr>", cells.concat()));
+        }
+
+        let table = match columnalign {
+            Some(columnalign) => format!("<mtable columnalign=\"{}\">{}</mtable>", columnalign, rows.concat()),
+            None => format!("<mtable>{}</mtable>", rows.concat()),
+        };
+        Ok((if open.is_empty() && close.is_empty() {
+                table
+            } else {
+                format!("<mrow>{}{}{}</mrow>", fence(open), table, fence(close))
+            },
+            false))
+    }
+}
+
+
+fn element(name: &str, text: &str, attrs: &str) -> String {
+    let mut ret = String::new();
+    write!(ret, "<{}{}>", name, attrs).unwrap();
+    escape_text(text, &mut ret);
+    write!(ret, "</{}>", name).unwrap();
+    ret
+}
+
+fn fence(delimiter: &str) -> String {
+    if delimiter.is_empty() {
+        String::new()
+    } else {
+        element("mo", delimiter, " fence=\"true\" stretchy=\"true\"")
+    }
+}
+
+/// Write the specified text with ASCII punctuation replaced with character references,
+/// so that it can be neither mistaken for HTML nor Markdown.
+fn escape_text(text: &str, into: &mut String) {
+    for c in text.chars() {
+        if c.is_ascii_punctuation() {
+            write!(into, "&#{};", c as u32).unwrap();
+        } else if c == '\n' || c == '\r' {
+            into.push(' ');
+        } else {
+            into.push(c);
+        }
+    }
+}
+
+fn greek_letter(name: &str) -> Option<&'static str> {
+    Some(match name {
+        "alpha" => "α",
+        "beta" => "β",
+        "gamma" => "γ",
+        "delta" => "δ",
+        "epsilon" => "ϵ",
+        "varepsilon" => "ε",
+        "zeta" => "ζ",
+        "eta" => "η",
+        "theta" => "θ",
+        "vartheta" => "ϑ",
+        "iota" => "ι",
+        "kappa" => "κ",
+        "lambda" => "λ",
+        "mu" => "μ",
+        "nu" => "ν",
+        "xi" => "ξ",
+        "omicron" => "ο",
+        "pi" => "π",
+        "varpi" => "ϖ",
+        "rho" => "ρ",
+        "varrho" => "ϱ",
+        "sigma" => "σ",
+        "varsigma" => "ς",
+        "tau" => "τ",
+        "upsilon" => "υ",
+        "phi" => "ϕ",
+        "varphi" => "φ",
+        "chi" => "χ",
+        "psi" => "ψ",
+        "omega" => "ω",
+        "Gamma" => "Γ",
+        "Delta" => "Δ",
+        "Theta" => "Θ",
+        "Lambda" => "Λ",
+        "Xi" => "Ξ",
+        "Pi" => "Π",
+        "Sigma" => "Σ",
+        "Upsilon" => "Υ",
+        "Phi" => "Φ",
+        "Psi" => "Ψ",
+        "Omega" => "Ω",
+        _ => return None,
+    })
+}
+
+fn symbol_identifier(name: &str) -> Option<&'static str> {
+    Some(match name {
+        "infty" => "∞",
+        "partial" => "∂",
+        "nabla" => "∇",
+        "emptyset" | "varnothing" => "∅",
+        "hbar" => "ℏ",
+        "ell" => "ℓ",
+        "aleph" => "ℵ",
+        "Re" => "ℜ",
+        "Im" => "ℑ",
+        "$" => "$",
+        "%" => "%",
+        "#" => "#",
+        "_" => "_",
+        _ => return None,
+    })
+}
+
+fn symbol_operator(name: &str) -> Option<&'static str> {
+    Some(match name {
+        "cdot" => "⋅",
+        "times" => "×",
+        "div" => "÷",
+        "pm" => "±",
+        "mp" => "∓",
+        "ast" => "∗",
+        "star" => "⋆",
+        "circ" => "∘",
+        "bullet" => "∙",
+        "oplus" => "⊕",
+        "otimes" => "⊗",
+        "leq" | "le" => "≤",
+        "geq" | "ge" => "≥",
+        "neq" | "ne" => "≠",
+        "ll" => "≪",
+        "gg" => "≫",
+        "approx" => "≈",
+        "equiv" => "≡",
+        "sim" => "∼",
+        "simeq" => "≃",
+        "cong" => "≅",
+        "propto" => "∝",
+        "to" | "rightarrow" => "→",
+        "leftarrow" | "gets" => "←",
+        "leftrightarrow" => "↔",
+        "Rightarrow" | "implies" => "⇒",
+        "Leftarrow" => "⇐",
+        "Leftrightarrow" | "iff" => "⇔",
+        "mapsto" => "↦",
+        "in" => "∈",
+        "notin" => "∉",
+        "ni" => "∋",
+        "subset" => "⊂",
+        "subseteq" => "⊆",
+        "supset" => "⊃",
+        "supseteq" => "⊇",
+        "cup" => "∪",
+        "cap" => "∩",
+        "setminus" => "∖",
+        "forall" => "∀",
+        "exists" => "∃",
+        "neg" | "lnot" => "¬",
+        "land" | "wedge" => "∧",
+        "lor" | "vee" => "∨",
+        "mid" => "∣",
+        "parallel" => "∥",
+        "perp" => "⊥",
+        "angle" => "∠",
+        "ldots" | "dots" => "…",
+        "cdots" => "⋯",
+        "vdots" => "⋮",
+        "ddots" => "⋱",
+        "prime" => "′",
+        "langle" => "⟨",
+        "rangle" => "⟩",
+        "lfloor" => "⌊",
+        "rfloor" => "⌋",
+        "lceil" => "⌈",
+        "rceil" => "⌉",
+        "{" | "lbrace" => "{",
+        "}" | "rbrace" => "}",
+        "|" | "Vert" => "‖",
+        "vert" => "|",
+        "&" => "&",
+        _ => return None,
+    })
+}
+
+fn big_operator(name: &str) -> Option<(&'static str, bool)> {
+    Some(match name {
+        "sum" => ("∑", true),
+        "prod" => ("∏", true),
+        "coprod" => ("∐", true),
+        "bigcup" => ("⋃", true),
+        "bigcap" => ("⋂", true),
+        "bigoplus" => ("⨁", true),
+        "bigotimes" => ("⨂", true),
+        "int" => ("∫", false),
+        "iint" => ("∬", false),
+        "iiint" => ("∭", false),
+        "oint" => ("∮", false),
+        _ => return None,
+    })
+}
+
+fn function_name(name: &str) -> Option<bool> {
+    match name {
+        "lim" | "max" | "min" | "sup" | "inf" | "det" | "gcd" | "Pr" => Some(true),
+        "sin" | "cos" | "tan" | "cot" | "sec" | "csc" | "arcsin" | "arccos" | "arctan" | "sinh" | "cosh" | "tanh" | "coth" | "log" | "ln" | "lg" |
+        "exp" | "dim" | "ker" | "deg" | "hom" | "arg" => Some(false),
+        _ => None,
+    }
+}
+
+fn space_width(name: &str) -> Option<&'static str> {
+    Some(match name {
+        "," | "thinspace" => "0.1667em",
+        ":" | ">" | "medspace" => "0.2222em",
+        ";" | "thickspace" => "0.2778em",
+        "!" | "negthinspace" => "-0.1667em",
+        " " => "0.3333em",
+        "quad" => "1em",
+        "qquad" => "2em",
+        _ => return None,
+    })
+}
+
+fn accent(name: &str) -> Option<&'static str> {
+    Some(match name {
+        "hat" | "widehat" => "^",
+        "bar" | "overline" => "¯",
+        "vec" | "overrightarrow" => "→",
+        "tilde" | "widetilde" => "~",
+        "dot" => "˙",
+        "ddot" => "¨",
+        _ => return None,
+    })
+}
+
+fn font_variant(name: &str) -> Option<&'static str> {
+    Some(match name {
+        "mathrm" => "normal",
+        "mathbf" => "bold",
+        "mathit" => "italic",
+        "mathbb" => "double-struck",
+        "mathcal" => "script",
+        "mathfrak" => "fraktur",
+        "mathsf" => "sans-serif",
+        "mathtt" => "monospace",
+        _ => return None,
+    })
+}
@@ -7,34 +7,46 @@ mod polywrite;
 #[cfg(not(target_os = "windows"))]
 mod non_windows;
 mod xml_escape_write;
+mod math;
 
 use chrono::format::{StrftimeItems as StrftimeFormatItems, Fixed as FixedTimeFormatItem, Item as TimeFormatItem};
-use comrak::nodes::{NodeValue as ComrakNodeValue, AstNode as ComrakAstNode};
+use comrak::nodes::{NodeValue as ComrakNodeValue, NodeHtmlBlock as ComrakNodeHtmlBlock, AstNode as ComrakAstNode, Ast as ComrakAst};
+use syntect::html::{ClassedHTMLGenerator, ClassStyle, css_for_theme_with_class_style, highlighted_html_for_string};
 use chrono::{FixedOffset, NaiveTime, DateTime, TimeZone, Offset};
 use safe_transmute::to_bytes::transmute_one_to_bytes;
-use std::io::{ErrorKind as IoErrorKind, Read};
+use std::io::{ErrorKind as IoErrorKind, Result as IoResult, Write, Read};
 use crc::crc32::checksum_ieee as crc32_ieee;
+use crc::crc64::{self, Digest as Crc64Digest};
 use percent_encoding::percent_decode;
 use std::path::{self, PathBuf, Path};
-use self::super::ops::LanguageTag;
+use std::process::Stdio;
+use std::collections::BTreeMap;
+use self::super::ops::{ParagraphPasser, LanguageTag};
 use rand_xorshift::XorShiftRng;
 use rand::{SeedableRng, Rng};
 use rand::seq::SliceRandom;
-use comrak::ComrakOptions;
-use std::{iter, cmp, str};
+use comrak::{self, ComrakOptions, Arena as ComrakArena, Anchorizer as ComrakAnchorizer};
+use comrak::arena_tree::Node as ComrakNode;
+use std::hash::{Hasher, Hash};
+use std::{thread, iter, cmp, str};
 use self::super::Error;
+use std::cell::RefCell;
 use std::borrow::Cow;
 use std::fs::File;
+use syntect::highlighting::ThemeSet;
+use syntect::parsing::SyntaxSet;
+use syntect::util::LinesWithEndings;
 use regex::Regex;
 use url::Url;
 
 #[cfg(target_os = "windows")]
-use self::windows::{current_username_impl, default_language_impl};
+use self::windows::{current_username_impl, default_language_impl, shell_command_impl};
 #[cfg(not(target_os = "windows"))]
-use self::non_windows::{current_username_impl, default_language_impl};
+use self::non_windows::{current_username_impl, default_language_impl, shell_command_impl};
 
 pub use self::polywrite::PolyWrite;
 pub use self::xml_escape_write::XmlEscapeWrite;
+pub use self::math::{tex_to_mathml, render_math};
 
 
 include!("../../ext/machine-usable-words/rust/words.rs");
@@ -60,11 +72,38 @@ lazy_static! {
 
     /// The default `en-GB` language tag.
     pub static ref LANGUAGE_EN_GB: LanguageTag = "en-GB".parse().unwrap();
+
+    /// Syntaxes to highlight fenced code blocks with, by info string.
+    pub static ref SYNTAX_SET: SyntaxSet = SyntaxSet::load_defaults_newlines();
+
+    /// Themes to highlight fenced code blocks with, by name.
+    pub static ref THEME_SET: ThemeSet = ThemeSet::load_defaults();
+
+    /// Regex to use for finding the quoted `href` and `src` attribute values in raw HTML.
+    static ref HTML_URL_ATTRIBUTE: Regex = Regex::new(r#"(?i)\s(?:href|src)\s*=\s*(?:"([^"]*)"|'([^']*)')"#).unwrap();
+
+    /// Regex to use for checking whether raw HTML ends with an opened `href` or `src` attribute value.
+    static ref HTML_URL_ATTRIBUTE_OPENED: Regex = Regex::new(r#"(?i)\s(?:href|src)\s*=\s*["']$"#).unwrap();
+
+    /// Regex to use for finding comments and tags, opening or closing, with their names, in raw HTML.
+    static ref HTML_TAG: Regex = Regex::new(r#"(?s)<!--.*?-->|<(/?)([a-zA-Z][a-zA-Z0-9-]*)[^>]*>"#).unwrap();
+
+    /// Regex to use for finding headings, with their levels, attributes, and contents, in raw HTML.
+    static ref HTML_HEADING: Regex = Regex::new(r#"(?is)<h([1-6])(\s[^>]*)?>(.*?)</h[1-6]\s*>"#).unwrap();
+
+    /// Regex to use for finding the `id` attribute value in raw HTML attributes.
+    static ref HTML_ID_ATTRIBUTE: Regex = Regex::new(r#"(?i)\sid\s*=\s*(?:"([^"]*)"|'([^']*)'|([^\s"'>]+))"#).unwrap();
 }
 
 /// Current version of `bloguen`.
 pub static BLOGUEN_VERSION: &str = env!("CARGO_PKG_VERSION");
 
+/// Reading speed, in words per minute, assumed where none is specified.
+pub const DEFAULT_READING_SPEED: usize = 200;
+
+/// Prefix of the CSS classes put on highlighted code, see [`highlight_code_blocks()`](fn.highlight_code_blocks.html).
+pub static HIGHLIGHT_CLASS_PREFIX: &str = "hl-";
+
 
 /// Uppercase the first character of the supplied string.
 ///
@@ -158,44 +197,414 @@ pub fn name_based_full_name(name: &str) -> String {
     }
 }
 
-/// Get list of all links in the specified AST.
+/// Get a hash of the specified value, stable across runs.
+///
+/// Works by ECMA-CRC64ing the value's [`Hash`](https://doc.rust-lang.org/std/hash/trait.Hash.html) representation.
+///
+/// # Examples
+///
+/// ```
+/// # use bloguen::util::content_hash;
+/// assert_eq!(content_hash("abolish"), content_hash("abolish"));
+/// assert_ne!(content_hash("abolish"), content_hash("capitalism"));
+/// assert_ne!(content_hash(&("abolish", "capitalism")), content_hash(&("abolishcapitalism", "")));
+/// ```
+pub fn content_hash<T: Hash + ?Sized>(what: &T) -> u64 {
+    let mut digest = Crc64Digest::new(crc64::ECMA);
+    what.hash(&mut digest);
+    digest.finish()
+}
+
+/// Get list of all links in the specified AST, including the URLs split out of raw HTML by [`parse_html()`](fn.parse_html.html).
 ///
 /// # Examples
 ///
 /// ```
 /// # extern crate bloguen;
 /// # extern crate comrak;
-/// # use bloguen::util::{MARKDOWN_OPTIONS, extract_links};
+/// # use bloguen::util::{MARKDOWN_OPTIONS, extract_links, parse_html};
 /// let doc_arena = comrak::Arena::new();
 /// let ast =
 ///     comrak::parse_document(&doc_arena, r#"[link](assets/link.html)
 ///                                           ![img](assets/image.png)"#, &MARKDOWN_OPTIONS);
 /// assert_eq!(extract_links(ast),
 ///            Ok(vec!["assets/link.html".to_string(), "assets/image.png".to_string()]));
+///
+/// let ast = parse_html(&doc_arena, r#"<a href="assets/link.html"><img src='assets/image.png' /></a>"#);
+/// assert_eq!(extract_links(ast),
+///            Ok(vec!["assets/link.html".to_string(), "assets/image.png".to_string()]));
 /// ```
 pub fn extract_links<'a>(ast: &'a ComrakAstNode<'a>) -> Result<Vec<String>, Error> {
     let mut out = vec![];
 
     for n in ast.descendants() {
-        match n.data.borrow().value {
+        let data = n.data.borrow();
+        let url = match data.value {
             ComrakNodeValue::Link(ref link) |
-            ComrakNodeValue::Image(ref link) => {
-                out.push(String::from_utf8(link.url.clone()).map_err(|e| {
-                        Error::Parse {
-                            tp: "UTF-8 string",
-                            wher: "URL list".into(),
-                            more: e.to_string().into(),
-                        }
-                    })?);
-            }
+            ComrakNodeValue::Image(ref link) => &link.url,
+            ComrakNodeValue::HtmlInline(ref url) if follows_html_url_attribute(n) => url,
+            _ => continue,
+        };
+
+        out.push(String::from_utf8(url.clone()).map_err(|e| {
+                Error::Parse {
+                    tp: "UTF-8 string",
+                    wher: "URL list".into(),
+                    more: e.to_string().into(),
+                }
+            })?);
+    }
+
+    Ok(out)
+}
+
+/// Count the words in the text of the specified AST, leaving out code and raw HTML.
+///
+/// # Examples
+///
+/// ```
+/// # extern crate bloguen;
+/// # extern crate comrak;
+/// # use bloguen::util::{MARKDOWN_OPTIONS, count_words};
+/// let doc_arena = comrak::Arena::new();
+/// let ast =
+///     comrak::parse_document(&doc_arena, "# Abolish *capitalism*\n\n\
+///                                         The `state` is [a tool](tool.html) of class rule.\n\n\
+///                                         ```\nfn main() {}\n```", &MARKDOWN_OPTIONS);
+/// assert_eq!(count_words(ast), 9);
+/// ```
+pub fn count_words<'a>(ast: &'a ComrakAstNode<'a>) -> usize {
+    // Text nodes split at inline markup, so join them up to the next block or break, as not to count "[link](link.html)." as two words
+    let mut text = vec![];
+    for n in ast.descendants() {
+        match n.data.borrow().value {
+            ComrakNodeValue::Text(ref t) => text.extend_from_slice(t),
+            ComrakNodeValue::SoftBreak |
+            ComrakNodeValue::LineBreak => text.push(b' '),
+            ref v if v.block() => text.push(b' '),
             _ => {}
         }
     }
 
-    Ok(out)
+    String::from_utf8_lossy(&text).split_whitespace().count()
+}
+
+/// Count the words in the text of the specified HTML fragment, leaving out code, scripts, styles, and comments.
+///
+/// Tags other than inline formatting ones, like `<em>` or `<a>`, separate words.
+///
+/// # Examples
+///
+/// ```
+/// # use bloguen::util::count_html_words;
+/// assert_eq!(count_html_words("<h1>Abolish <em>capital</em>ism</h1>\n\
+///                              <p>The <code>state</code> is <a href=\"tool.html\">a tool</a> of class rule.<!-- or is it --></p>\n\
+///                              <pre>fn main() {}</pre><p>Fin</p>"),
+///            10);
+/// ```
+pub fn count_html_words(html: &str) -> usize {
+    html_text(html, true).split_whitespace().count()
+}
+
+/// Render a table of contents of the headings in the specified AST as nested HTML lists of links to their anchors.
+///
+/// The anchors match the ones written by `comrak::format_html()` with the specified header ID prefix.
+///
+/// A heading deeper than the previous one opens a nested list, and one as shallow as an enclosing list's closes it.
+///
+/// Returns an empty string if there are no headings.
+///
+/// # Examples
+///
+/// ```
+/// # extern crate bloguen;
+/// # extern crate comrak;
+/// # use bloguen::util::{MARKDOWN_OPTIONS, table_of_contents};
+/// let doc_arena = comrak::Arena::new();
+/// let ast =
+///     comrak::parse_document(&doc_arena, "# Introduction\n## `Cotton` & wool\n## Acrylic\n# Introduction", &MARKDOWN_OPTIONS);
+/// assert_eq!(table_of_contents(ast, ""),
+///            "<ul>\n\
+///             <li><a href=\"#introduction\">Introduction</a>\n\
+///             <ul>\n\
+///             <li><a href=\"#cotton--wool\">Cotton &amp; wool</a></li>\n\
+///             <li><a href=\"#acrylic\">Acrylic</a></li>\n\
+///             </ul>\n\
+///             </li>\n\
+///             <li><a href=\"#introduction-1\">Introduction</a></li>\n\
+///             </ul>");
+/// ```
+pub fn table_of_contents<'a>(ast: &'a ComrakAstNode<'a>, header_id_prefix: &str) -> String {
+    fn collect_text<'a>(node: &'a ComrakAstNode<'a>, into: &mut Vec<u8>) {
+        match node.data.borrow().value {
+            ComrakNodeValue::Text(ref t) |
+            ComrakNodeValue::Code(ref t) => into.extend_from_slice(t),
+            ComrakNodeValue::LineBreak |
+            ComrakNodeValue::SoftBreak => into.push(b' '),
+            _ => node.children().for_each(|n| collect_text(n, into)),
+        }
+    }
+
+    let mut anchorizer = ComrakAnchorizer::new();
+    format_table_of_contents(ast.descendants().filter_map(|n| {
+        let level = match n.data.borrow().value {
+            ComrakNodeValue::Heading(ref heading) => heading.level,
+            _ => return None,
+        };
+
+        let mut text = vec![];
+        collect_text(n, &mut text);
+        let text = String::from_utf8_lossy(&text).into_owned();
+        let anchor = format!("{}{}", header_id_prefix, anchorizer.anchorize(text.clone()));
+
+        let mut escaped = vec![];
+        XmlEscapeWrite(&mut escaped).write_all(text.as_bytes()).unwrap();
+        Some((level, anchor, String::from_utf8(escaped).unwrap()))
+    }))
+}
+
+/// Render a table of contents of the headings with `id`s in the specified HTML fragment, like
+/// [`table_of_contents()`](fn.table_of_contents.html) does for Markdown.
+///
+/// Headings without an `id` can't be linked to, and are left out.
+///
+/// # Examples
+///
+/// ```
+/// # use bloguen::util::html_table_of_contents;
+/// assert_eq!(html_table_of_contents("<h1 id=\"introduction\">Introduction</h1>\n\
+///                                    <h2 class=\"fibre\" id='cotton'><code>Cotton</code> &amp; wool</h2>\n\
+///                                    <h2>Acrylic</h2>\n\
+///                                    <h1 id=conclusion>Conclusion</h1>"),
+///            "<ul>\n\
+///             <li><a href=\"#introduction\">Introduction</a>\n\
+///             <ul>\n\
+///             <li><a href=\"#cotton\">Cotton &amp; wool</a></li>\n\
+///             </ul>\n\
+///             </li>\n\
+///             <li><a href=\"#conclusion\">Conclusion</a></li>\n\
+///             </ul>");
+/// ```
+pub fn html_table_of_contents(html: &str) -> String {
+    format_table_of_contents(HTML_HEADING.captures_iter(html).filter_map(|c| {
+        let id = HTML_ID_ATTRIBUTE.captures(c.get(2).map(|a| a.as_str()).unwrap_or(""))
+            .and_then(|id| id.get(1).or_else(|| id.get(2)).or_else(|| id.get(3)))?;
+        let text = html_text(&c[3], false).split_whitespace().collect::<Vec<_>>().join(" ");
+        Some((c[1].parse().unwrap(), id.as_str().replace('"', "&quot;"), text))
+    }))
+}
+
+/// Format the specified headings, as levels, anchors, and already-escaped text, as nested HTML lists of links.
+fn format_table_of_contents<I: IntoIterator<Item = (u32, String, String)>>(headings: I) -> String {
+    let mut levels: Vec<u32> = vec![];
+    let mut out = vec![];
+    for (level, anchor, text) in headings {
+        match levels.last() {
+            None => {
+                out.extend_from_slice(b"<ul>\n");
+                levels.push(level);
+            }
+            Some(&last) if level > last => {
+                out.extend_from_slice(b"\n<ul>\n");
+                levels.push(level);
+            }
+            Some(_) => {
+                out.extend_from_slice(b"</li>\n");
+                while levels.len() > 1 && level <= levels[levels.len() - 2] {
+                    levels.pop();
+                    out.extend_from_slice(b"</ul>\n</li>\n");
+                }
+
+                let last = levels.last_mut().unwrap();
+                *last = cmp::min(*last, level);
+            }
+        }
+
+        out.extend_from_slice(b"<li><a href=\"#");
+        out.extend_from_slice(anchor.as_bytes());
+        out.extend_from_slice(b"\">");
+        out.extend_from_slice(text.as_bytes());
+        out.extend_from_slice(b"</a>");
+    }
+
+    if !levels.is_empty() {
+        out.extend_from_slice(b"</li>\n");
+        for _ in 1..levels.len() {
+            out.extend_from_slice(b"</ul>\n</li>\n");
+        }
+        out.extend_from_slice(b"</ul>");
+    }
+
+    String::from_utf8(out).unwrap()
+}
+
+/// Write the excerpt of the specified AST as HTML.
+///
+/// The excerpt is everything before the first `<!-- more -->` comment on its own line, if any,
+/// and the first paragraph (and whatever precedes it) otherwise.
+///
+/// # Examples
+///
+/// ```
+/// # extern crate bloguen;
+/// # extern crate comrak;
+/// # use bloguen::util::{MARKDOWN_OPTIONS, write_excerpt};
+/// let doc_arena = comrak::Arena::new();
+/// let ast = comrak::parse_document(&doc_arena, "Intro\n\nMore intro\n<!-- more -->\n\nBody", &MARKDOWN_OPTIONS);
+/// let mut out = vec![];
+/// write_excerpt(ast, &MARKDOWN_OPTIONS, &mut out).unwrap();
+/// assert_eq!(String::from_utf8(out).unwrap(), "<p>Intro</p>\n<p>More intro</p>\n");
+///
+/// let ast = comrak::parse_document(&doc_arena, "Intro\n\nBody", &MARKDOWN_OPTIONS);
+/// let mut out = vec![];
+/// write_excerpt(ast, &MARKDOWN_OPTIONS, &mut out).unwrap();
+/// assert_eq!(String::from_utf8(out).unwrap(), "<p>Intro</p>\n");
+/// ```
+pub fn write_excerpt<'a, W: Write>(ast: &'a ComrakAstNode<'a>, options: &ComrakOptions, into: &mut W) -> IoResult<()> {
+    fn is_separator<'a>(node: &'a ComrakAstNode<'a>) -> bool {
+        match node.data.borrow().value {
+            ComrakNodeValue::HtmlBlock(ref block) => is_more_comment(&String::from_utf8_lossy(&block.literal)),
+            _ => false,
+        }
+    }
+
+    match ast.children().position(is_separator) {
+        Some(end) => {
+            for n in ast.children().take(end) {
+                comrak::format_html(n, options, into)?;
+            }
+            Ok(())
+        }
+        None => comrak::format_html(ast, options, &mut ParagraphPasser::new(into, 1)),
+    }
+}
+
+/// Replace the fenced code blocks in the specified AST with HTML blocks highlighted with the specified theme from
+/// [`THEME_SET`](struct.THEME_SET.html).
+///
+/// The language is the first word of the code block's info string, looked up in [`SYNTAX_SET`](struct.SYNTAX_SET.html);
+/// code blocks without one, or in an unknown language, are left as-is.
+///
+/// If `classes` is set, the code is marked up with CSS classes prefixed with [`HIGHLIGHT_CLASS_PREFIX`](static.HIGHLIGHT_CLASS_PREFIX.html),
+/// to be styled by [`highlight_stylesheet()`](fn.highlight_stylesheet.html), otherwise it's styled inline.
+///
+/// # Examples
+///
+/// ```
+/// # extern crate bloguen;
+/// # extern crate comrak;
+/// # use bloguen::util::{MARKDOWN_OPTIONS, highlight_code_blocks};
+/// let doc_arena = comrak::Arena::new();
+/// let ast = comrak::parse_document(&doc_arena, "```rust\nfn main() {}\n```\n\n```\nplain\n```", &MARKDOWN_OPTIONS);
+/// highlight_code_blocks(ast, "InspiredGitHub", true).unwrap();
+///
+/// let mut out = vec![];
+/// comrak::format_html(ast, &MARKDOWN_OPTIONS, &mut out).unwrap();
+/// let out = String::from_utf8(out).unwrap();
+/// assert!(out.starts_with("<pre class=\"hl-code\"><code class=\"language-rust\"><span class=\"hl-source hl-rust\">"));
+/// assert!(out.ends_with("</code></pre>\n<pre><code>plain\n</code></pre>\n"));
+/// ```
+pub fn highlight_code_blocks<'a>(ast: &'a ComrakAstNode<'a>, theme: &str, classes: bool) -> Result<(), Error> {
+    let theme = THEME_SET.themes.get(theme).ok_or_else(|| {
+            Error::Parse {
+                tp: "highlight theme",
+                wher: "code block highlighting".into(),
+                more: format!("theme {:?} unknown", theme).into(),
+            }
+        })?;
+
+    for n in ast.descendants() {
+        let highlighted = match n.data.borrow().value {
+                ComrakNodeValue::CodeBlock(ref block) => {
+                    let info = String::from_utf8_lossy(&block.info);
+                    let (language, syntax) = match info.split_whitespace().next().and_then(|l| SYNTAX_SET.find_syntax_by_token(l).map(|s| (l, s))) {
+                        Some(ls) => ls,
+                        None => continue,
+                    };
+                    let code = String::from_utf8_lossy(&block.literal);
+
+                    if classes {
+                        let mut generator = ClassedHTMLGenerator::new_with_class_style(syntax,
+                                                                                       &SYNTAX_SET,
+                                                                                       ClassStyle::SpacedPrefixed { prefix: HIGHLIGHT_CLASS_PREFIX });
+                        LinesWithEndings::from(&code)
+                            .try_for_each(|l| generator.parse_html_for_line_which_includes_newline(l))
+                            .map(|_| {
+                                format!("<pre class=\"{}code\"><code class=\"language-{}\">{}</code></pre>\n",
+                                        HIGHLIGHT_CLASS_PREFIX,
+                                        language,
+                                        generator.finalize())
+                            })
+                    } else {
+                        highlighted_html_for_string(&code, &SYNTAX_SET, syntax, theme)
+                    }
+                }
+                _ => continue,
+            }
+            .map_err(|e| {
+                Error::Parse {
+                    tp: "code block",
+                    wher: "code block highlighting".into(),
+                    more: e.to_string().into(),
+                }
+            })?;
+
+        n.data.borrow_mut().value = ComrakNodeValue::HtmlBlock(ComrakNodeHtmlBlock {
+            block_type: 0,
+            literal: highlighted.into_bytes(),
+        });
+    }
+
+    Ok(())
+}
+
+/// Generate the stylesheet for code highlighted with CSS classes in the specified theme from
+/// [`THEME_SET`](struct.THEME_SET.html), see [`highlight_code_blocks()`](fn.highlight_code_blocks.html).
+///
+/// # Examples
+///
+/// ```
+/// # use bloguen::util::highlight_stylesheet;
+/// let stylesheet = highlight_stylesheet("InspiredGitHub").unwrap();
+/// assert!(stylesheet.contains(".hl-code {"));
+///
+/// assert!(highlight_stylesheet("Paint It Black").is_err());
+/// ```
+pub fn highlight_stylesheet(theme: &str) -> Result<String, Error> {
+    let theme = THEME_SET.themes.get(theme).ok_or_else(|| {
+            Error::Parse {
+                tp: "highlight theme",
+                wher: "stylesheet generation".into(),
+                more: format!("theme {:?} unknown", theme).into(),
+            }
+        })?;
+
+    css_for_theme_with_class_style(theme, ClassStyle::SpacedPrefixed { prefix: HIGHLIGHT_CLASS_PREFIX }).map_err(|e| {
+        Error::Parse {
+            tp: "highlight theme",
+            wher: "stylesheet generation".into(),
+            more: e.to_string().into(),
+        }
+    })
+}
+
+/// Get the amount of minutes it takes to read the specified amount of words at the specified speed, rounded up.
+///
+/// # Examples
+///
+/// ```
+/// # use bloguen::util::reading_time;
+/// assert_eq!(reading_time(0, 200), 0);
+/// assert_eq!(reading_time(1, 200), 1);
+/// assert_eq!(reading_time(400, 200), 2);
+/// assert_eq!(reading_time(401, 200), 3);
+/// ```
+pub fn reading_time(word_count: usize, words_per_minute: usize) -> usize {
+    word_count.div_ceil(words_per_minute)
 }
 
-/// Get a list of all actual (i.e. existant) assets in the specified AST.
+/// Get a list of all actual (i.e. existant) assets in the specified AST,
+/// including the URLs split out of raw HTML by [`parse_html()`](fn.parse_html.html).
 ///
 /// # Examples
 ///
@@ -239,43 +648,168 @@ fn extract_actual_assets_impl<'a>(post_source_dir: &Path, ast: &'a ComrakAstNode
     let mut out = vec![];
 
     for n in ast.descendants() {
-        match n.data.borrow_mut().value {
+        let mut data = n.data.borrow_mut();
+        let url = match data.value {
             ComrakNodeValue::Link(ref mut link) |
-            ComrakNodeValue::Image(ref mut link) => {
-                {
-                    let url = str::from_utf8(&link.url).map_err(|e| {
-                            Error::Parse {
-                                tp: "UTF-8 string",
-                                wher: "URL list".into(),
-                                more: e.to_string().into(),
-                            }
-                        })?;
-
-                    if !is_asset_link(url) {
-                        continue;
-                    }
-                }
+            ComrakNodeValue::Image(ref mut link) => &mut link.url,
+            ComrakNodeValue::HtmlInline(ref mut url) if follows_html_url_attribute(n) => url,
+            _ => continue,
+        };
 
-                if let Ok(subpath) = percent_decode(&link.url).decode_utf8() {
-                    let orig_path = concat_path(post_source_dir, &subpath);
-                    if !orig_path.exists() {
-                        continue;
+        {
+            let url = str::from_utf8(url).map_err(|e| {
+                    Error::Parse {
+                        tp: "UTF-8 string",
+                        wher: "URL list".into(),
+                        more: e.to_string().into(),
                     }
-                } else {
-                    continue;
-                }
+                })?;
 
-                // The references are valid as long as the allocation arena is (i.e. 'a),
-                // but there's only so much you can express :v
-                out.push(unsafe { &mut *(&mut link.url as *mut Vec<u8>) as &'a mut Vec<u8> });
+            if !is_asset_link(url) {
+                continue;
             }
-            _ => {}
         }
+
+        if let Ok(subpath) = percent_decode(url).decode_utf8() {
+            let orig_path = concat_path(post_source_dir, &subpath);
+            if !orig_path.exists() {
+                continue;
+            }
+        } else {
+            continue;
+        }
+
+        // The references are valid as long as the allocation arena is (i.e. 'a),
+        // but there's only so much you can express :v
+        out.push(unsafe { &mut *(url as *mut Vec<u8>) as &'a mut Vec<u8> });
     }
 
     Ok(out)
 }
 
+/// Parse the specified HTML fragment into an AST to be formatted back as-is.
+///
+/// The fragment is split into raw HTML nodes at `<!-- more -->` comments on their own lines, as recognised by
+/// [`write_excerpt()`](fn.write_excerpt.html), and around quoted `href` and `src` attribute values,
+/// so that [`extract_links()`](fn.extract_links.html) and [`extract_actual_assets()`](fn.extract_actual_assets.html) find them.
+///
+/// # Examples
+///
+/// ```
+/// # extern crate bloguen;
+/// # extern crate comrak;
+/// # use bloguen::util::{MARKDOWN_OPTIONS, parse_html};
+/// let doc_arena = comrak::Arena::new();
+/// let html = "<p>Intro <a href=\"link.html\">link</a></p>\n<!-- more -->\n<p><img src=\"image.png\" /></p>\n";
+/// let ast = parse_html(&doc_arena, html);
+/// assert_eq!(ast.children().count(), 7);
+///
+/// let mut out = vec![];
+/// comrak::format_html(ast, &MARKDOWN_OPTIONS, &mut out).unwrap();
+/// assert_eq!(String::from_utf8(out).unwrap(), html);
+/// ```
+pub fn parse_html<'a>(arena: &'a ComrakArena<ComrakAstNode<'a>>, html: &str) -> &'a ComrakAstNode<'a> {
+    fn node<'a>(arena: &'a ComrakArena<ComrakAstNode<'a>>, value: ComrakNodeValue) -> &'a ComrakAstNode<'a> {
+        arena.alloc(ComrakNode::new(RefCell::new(ComrakAst::new(value))))
+    }
+
+    fn append_split<'a>(arena: &'a ComrakArena<ComrakAstNode<'a>>, root: &'a ComrakAstNode<'a>, mut html: &str) {
+        while let Some(url) = HTML_URL_ATTRIBUTE.captures(html).and_then(|c| c.get(1).or_else(|| c.get(2))) {
+            root.append(node(arena, ComrakNodeValue::HtmlInline(html.as_bytes()[..url.start()].to_vec())));
+            root.append(node(arena, ComrakNodeValue::HtmlInline(url.as_str().as_bytes().to_vec())));
+            html = &html[url.end()..];
+        }
+        if !html.is_empty() {
+            root.append(node(arena, ComrakNodeValue::HtmlInline(html.as_bytes().to_vec())));
+        }
+    }
+
+    let root = node(arena, ComrakNodeValue::Document);
+    let mut start = 0;
+    for (line_start, line) in html.split_inclusive('\n').scan(0, |pos, line| {
+        let line_start = *pos;
+        *pos += line.len();
+        Some((line_start, line))
+    }) {
+        if is_more_comment(line) {
+            append_split(arena, root, &html[start..line_start]);
+            root.append(node(arena,
+                             ComrakNodeValue::HtmlBlock(ComrakNodeHtmlBlock {
+                                 block_type: 2,
+                                 literal: line.as_bytes().to_vec(),
+                             })));
+            start = line_start + line.len();
+        }
+    }
+    append_split(arena, root, &html[start..]);
+
+    root
+}
+
+/// Get the text of the specified HTML fragment, as escaped in it, leaving out scripts, styles, and comments,
+/// and, if `skip_code` is set, code.
+///
+/// Tags other than inline formatting ones are replaced with spaces.
+fn html_text(html: &str, skip_code: bool) -> String {
+    static INLINE: &[&str] = &["a", "abbr", "b", "bdi", "bdo", "cite", "code", "data", "del", "dfn", "em", "i", "ins", "kbd", "mark", "q", "s", "samp",
+                               "small", "span", "strong", "sub", "sup", "time", "u", "var"];
+
+    let mut out = String::with_capacity(html.len());
+    let mut skipping: Option<String> = None;
+    let mut last = 0;
+    for tag in HTML_TAG.captures_iter(html) {
+        let whole = tag.get(0).unwrap();
+        if skipping.is_none() {
+            out.push_str(&html[last..whole.start()]);
+        }
+        last = whole.end();
+
+        let name = match tag.get(2) {
+            Some(name) => name.as_str().to_lowercase(),
+            None => continue,
+        };
+        let closing = !tag[1].is_empty();
+        match skipping {
+            Some(ref skipped) if closing && *skipped == name => {}
+            Some(_) => continue,
+            None if !closing && (["script", "style", "template"].contains(&&name[..]) || (skip_code && ["code", "pre"].contains(&&name[..]))) => {
+                skipping = Some(name);
+                continue;
+            }
+            None => {
+                if !INLINE.contains(&&name[..]) {
+                    out.push(' ');
+                }
+                continue;
+            }
+        }
+        skipping = None;
+        out.push(' ');
+    }
+    if skipping.is_none() {
+        out.push_str(&html[last..]);
+    }
+
+    out
+}
+
+/// Check whether the specified node is a URL split out by `parse_html()`,
+/// i.e. it's raw HTML that follows raw HTML ending with an opened `href` or `src` attribute.
+fn follows_html_url_attribute<'a>(node: &'a ComrakAstNode<'a>) -> bool {
+    node.previous_sibling()
+        .map(|prev| match prev.data.borrow().value {
+            ComrakNodeValue::HtmlInline(ref literal) => str::from_utf8(literal).map(|l| HTML_URL_ATTRIBUTE_OPENED.is_match(l)).unwrap_or(false),
+            _ => false,
+        })
+        .unwrap_or(false)
+}
+
+/// Check whether the specified raw HTML is a lone `<!-- more -->` comment.
+fn is_more_comment(html: &str) -> bool {
+    let html = html.trim();
+    html.starts_with("<!--") && html.ends_with("-->") && html[4..html.len() - 3].trim() == "more"
+}
+
 /// Check if the link points to a local relative asset.
 ///
 /// # Examples
@@ -291,6 +825,28 @@ pub fn is_asset_link(link: &str) -> bool {
     Url::parse(link).is_err() && !link.starts_with('/')
 }
 
+/// Resolve the specified output-relative path against the specified base URL.
+///
+/// Falls back to simple concatenation if the path can't be resolved.
+///
+/// # Examples
+///
+/// ```
+/// # extern crate bloguen;
+/// # extern crate url;
+/// # use bloguen::util::resolve_url;
+/// # use url::Url;
+/// # fn main() {
+/// let base_url = Url::parse("https://nabijaczleweli.xyz/blogue/").unwrap();
+/// assert_eq!(resolve_url(&base_url, "posts/01. 2018-01-08 16-52-00 Crocheting.html"),
+///            "https://nabijaczleweli.xyz/blogue/posts/01.%202018-01-08%2016-52-00%20Crocheting.html");
+/// assert_eq!(resolve_url(&base_url, ""), "https://nabijaczleweli.xyz/blogue/");
+/// # }
+/// ```
+pub fn resolve_url(base: &Url, path: &str) -> String {
+    base.join(path).map(String::from).unwrap_or_else(|_| format!("{}{}", base, path))
+}
+
 
 /// Read the contents of the specified file into a `String`.
 ///
@@ -363,6 +919,69 @@ pub fn read_file(whom: &(String, PathBuf), what_for: &'static str) -> Result<Str
     Ok(buf)
 }
 
+/// Pipe the specified input through the specified shell command, returning its output.
+///
+/// The command is run with `sh -c` on non-Windows and `cmd /C` on Windows,
+/// with the specified environment variables added to the current ones, and its standard error inherited.
+///
+/// Failures to run the command, and it exiting unsuccessfully, are reported as coming from the specified hook site.
+///
+/// # Examples
+///
+/// ```
+/// # use bloguen::util::run_hook;
+/// # use bloguen::Error;
+/// # if cfg!(not(target_os = "windows")) {
+/// let environment = vec![("BLOGUEN_TITLE".to_string(), "Блогг".to_string())].into_iter().collect();
+/// assert_eq!(run_hook("tr a-z A-Z; echo \" $BLOGUEN_TITLE\"", b"henlo".to_vec(), &environment, "post source"),
+///            Ok("HENLO Блогг\n".as_bytes().to_vec()));
+/// assert_eq!(run_hook("cat; exit 3", b"henlo".to_vec(), &environment, "post HTML"),
+///            Err(Error::HookFailed {
+///                command: "cat; exit 3".into(),
+///                wher: "post HTML".into(),
+///                more: "exited with code 3".into(),
+///            }));
+/// # }
+/// ```
+pub fn run_hook(command: &str, input: Vec<u8>, environment: &BTreeMap<String, String>, wher: &str) -> Result<Vec<u8>, Error> {
+    let hook_err = |more: String| {
+        Error::HookFailed {
+            command: command.to_string().into(),
+            wher: wher.to_string().into(),
+            more: more.into(),
+        }
+    };
+
+    let mut child = shell_command_impl(command).envs(environment)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::inherit())
+        .spawn()
+        .map_err(|e| hook_err(e.to_string()))?;
+
+    // Written from a separate thread so that neither end blocks on a full pipe
+    let mut stdin = child.stdin.take().unwrap();
+    let writer = thread::spawn(move || stdin.write_all(&input));
+
+    let output = child.wait_with_output().map_err(|e| hook_err(e.to_string()))?;
+    match writer.join() {
+        Ok(Ok(())) => {}
+        // The hook is free to not read all of its input
+        Ok(Err(ref e)) if e.kind() == IoErrorKind::BrokenPipe => {}
+        Ok(Err(e)) => return Err(hook_err(format!("writing input failed: {}", e))),
+        Err(_) => return Err(hook_err("writing input panicked".to_string())),
+    }
+
+    if output.status.success() {
+        Ok(output.stdout)
+    } else {
+        Err(hook_err(match output.status.code() {
+            Some(code) => format!("exited with code {}", code),
+            None => "killed by a signal".to_string(),
+        }))
+    }
+}
+
 /// Insert enough newlines at the start and end of the string to reach the specified count.
 ///
 /// # Examples
@@ -521,6 +1140,20 @@ fn concat_path_impl(mut whom: PathBuf, with: &str) -> PathBuf {
     whom
 }
 
+/// Replace characters not valid in filenames or links with underscores.
+///
+/// # Examples
+///
+/// ```
+/// # use bloguen::util::sanitise_file_name;
+/// assert_eq!(sanitise_file_name("коммунизм"), "коммунизм");
+/// assert_eq!(sanitise_file_name("C/C++"), "C_C++");
+/// assert_eq!(sanitise_file_name("#100%?"), "_100__");
+/// ```
+pub fn sanitise_file_name(name: &str) -> String {
+    name.replace(|c| ['/', '\\', ':', '*', '?', '"', '<', '>', '|', '#', '%'].contains(&c), "_")
+}
+
 /// Get how many times this path descends minus how may times it goes up a level
 ///
 /// # Examples
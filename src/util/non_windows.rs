@@ -1,4 +1,5 @@
 use libc::{c_char, size_t, c_int};
+use std::process::Command;
 use std::ffi::CStr;
 use std::env;
 
@@ -53,3 +54,10 @@ pub fn current_username_impl() -> Option<String> {
 
     env::var("USER").ok()
 }
+
+#[inline]
+pub fn shell_command_impl(command: &str) -> Command {
+    let mut ret = Command::new("sh");
+    ret.arg("-c").arg(command);
+    ret
+}
@@ -3,6 +3,8 @@ use winapi::um::winnt::{LPCWSTR, WCHAR};
 use winapi::um::winbase::GetUserNameW;
 use winapi::shared::minwindef::DWORD;
 use winapi::shared::lmcons::UNLEN;
+use std::os::windows::process::CommandExt;
+use std::process::Command;
 use std::ptr;
 
 
@@ -33,3 +35,10 @@ pub fn current_username_impl() -> Option<String> {
         None
     }
 }
+
+#[inline]
+pub fn shell_command_impl(command: &str) -> Command {
+    let mut ret = Command::new("cmd");
+    ret.arg("/C").raw_arg(command);
+    ret
+}
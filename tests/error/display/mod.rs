@@ -43,3 +43,14 @@ fn file_parsing_failed() {
                    .to_string(),
                "Failed to parse blogue descriptor: unexpected eof encountered.");
 }
+
+#[test]
+fn hook_failed() {
+    assert_eq!(Error::HookFailed {
+                       command: "aspell list".into(),
+                       wher: "post source".into(),
+                       more: "exited with code 1".into(),
+                   }
+                   .to_string(),
+               "Hook \"aspell list\" for post source failed: exited with code 1.");
+}
@@ -52,3 +52,14 @@ fn file_parsing_failed() {
                    .exit_value(),
                5);
 }
+
+#[test]
+fn hook_failed() {
+    assert_eq!(Error::HookFailed {
+                       command: "".into(),
+                       wher: "".into(),
+                       more: "".into(),
+                   }
+                   .exit_value(),
+               6);
+}
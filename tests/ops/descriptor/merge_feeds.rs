@@ -0,0 +1,52 @@
+use bloguen::ops::{BlogueDescriptor, FeedFilter, FeedType};
+use std::collections::BTreeMap;
+use bloguen::Error;
+
+
+#[test]
+fn same_feeds() {
+    let mut feeds = BTreeMap::new();
+    for _ in 0..2 {
+        assert_eq!(BlogueDescriptor::merge_feeds(&mut feeds,
+                                                 vec![("feeds/rss.xml".to_string(), (FeedType::Rss, FeedFilter::All)),
+                                                      ("feeds/C_C++.xml".to_string(), (FeedType::Rss, FeedFilter::Tag("C/C++")))]
+                                                     .into_iter()
+                                                     .collect()),
+                   Ok(()));
+    }
+    assert_eq!(feeds,
+               vec![("feeds/rss.xml".to_string(), (FeedType::Rss, FeedFilter::All)),
+                    ("feeds/C_C++.xml".to_string(), (FeedType::Rss, FeedFilter::Tag("C/C++")))]
+                   .into_iter()
+                   .collect());
+}
+
+#[test]
+fn tag_collision() {
+    let mut feeds = BTreeMap::new();
+    assert_eq!(BlogueDescriptor::merge_feeds(&mut feeds,
+                                             vec![("feeds/C_C++.xml".to_string(), (FeedType::Rss, FeedFilter::Tag("C/C++")))].into_iter().collect()),
+               Ok(()));
+    assert_eq!(BlogueDescriptor::merge_feeds(&mut feeds,
+                                             vec![("feeds/C_C++.xml".to_string(), (FeedType::Rss, FeedFilter::Tag("C_C++")))].into_iter().collect()),
+               Err(Error::Parse {
+                   tp: "path chunk",
+                   wher: "blogue feeds".into(),
+                   more: "tag \"C/C++\" RSS and tag \"C_C++\" RSS feeds both output to \"feeds/C_C++.xml\"".into(),
+               }));
+    assert_eq!(feeds,
+               vec![("feeds/C_C++.xml".to_string(), (FeedType::Rss, FeedFilter::Tag("C/C++")))].into_iter().collect());
+}
+
+#[test]
+fn type_collision() {
+    let mut feeds = BTreeMap::new();
+    assert_eq!(BlogueDescriptor::merge_feeds(&mut feeds, vec![("feeds/vodka".to_string(), (FeedType::Rss, FeedFilter::Tag("vodka")))].into_iter().collect()),
+               Ok(()));
+    assert_eq!(BlogueDescriptor::merge_feeds(&mut feeds, vec![("feeds/vodka".to_string(), (FeedType::Atom, FeedFilter::Tag("vodka")))].into_iter().collect()),
+               Err(Error::Parse {
+                   tp: "path chunk",
+                   wher: "blogue feeds".into(),
+                   more: "tag \"vodka\" RSS and tag \"vodka\" Atom feeds both output to \"feeds/vodka\"".into(),
+               }));
+}
@@ -1 +1,3 @@
 mod read;
+mod post_feeds;
+mod merge_feeds;
@@ -0,0 +1,82 @@
+use bloguen::ops::{BlogueDescriptor, FeedFilter, FeedType};
+use std::fs::{self, File};
+use std::env::temp_dir;
+use std::io::Write;
+use bloguen::Error;
+
+
+fn descriptor(test: &str) -> BlogueDescriptor {
+    let root = temp_dir().join("bloguen-test").join(format!("ops-descriptor-post_feeds-{}", test));
+    let _ = fs::remove_dir_all(&root);
+    fs::create_dir_all(&root).unwrap();
+
+    File::create(root.join("blogue.toml"))
+        .unwrap()
+        .write_all("name = \"Блогг\"\n\
+                    \n\
+                    [feeds]\n\
+                    RSS = \"feeds/rss.xml\"\n\
+                    \n\
+                    [tag_feeds]\n\
+                    RSS = \"feeds/{tag}.xml\"\n\
+                    \n\
+                    [author_feeds]\n\
+                    Atom = \"feeds/{author}.atom\"\n\
+                    \n\
+                    [language_feeds]\n\
+                    Atom = \"feeds/{language}.atom\"\n"
+            .as_bytes())
+        .unwrap();
+    File::create(root.join("header.html")).unwrap();
+    File::create(root.join("footer.html")).unwrap();
+
+    BlogueDescriptor::read(&("$ROOT/".to_string(), root)).unwrap()
+}
+
+
+#[test]
+fn ok() {
+    let descriptor = descriptor("ok");
+    assert_eq!(descriptor.post_feeds(vec!["C/C++", "C++", "vodka", "vodka"], "nabijaczleweli", "pl"),
+               Ok(vec![("feeds/rss.xml".to_string(), (FeedType::Rss, FeedFilter::All)),
+                       ("feeds/C_C++.xml".to_string(), (FeedType::Rss, FeedFilter::Tag("C/C++"))),
+                       ("feeds/C++.xml".to_string(), (FeedType::Rss, FeedFilter::Tag("C++"))),
+                       ("feeds/vodka.xml".to_string(), (FeedType::Rss, FeedFilter::Tag("vodka"))),
+                       ("feeds/nabijaczleweli.atom".to_string(), (FeedType::Atom, FeedFilter::Author("nabijaczleweli"))),
+                       ("feeds/pl.atom".to_string(), (FeedType::Atom, FeedFilter::Language("pl")))]
+                   .into_iter()
+                   .collect()));
+}
+
+#[test]
+fn tag_collision() {
+    let descriptor = descriptor("tag_collision");
+    assert_eq!(descriptor.post_feeds(vec!["C/C++", "C_C++"], "nabijaczleweli", "pl"),
+               Err(Error::Parse {
+                   tp: "path chunk",
+                   wher: "blogue feeds".into(),
+                   more: "tag \"C/C++\" RSS and tag \"C_C++\" RSS feeds both output to \"feeds/C_C++.xml\"".into(),
+               }));
+}
+
+#[test]
+fn main_collision() {
+    let descriptor = descriptor("main_collision");
+    assert_eq!(descriptor.post_feeds(vec!["rss"], "nabijaczleweli", "pl"),
+               Err(Error::Parse {
+                   tp: "path chunk",
+                   wher: "blogue feeds".into(),
+                   more: "main RSS and tag \"rss\" RSS feeds both output to \"feeds/rss.xml\"".into(),
+               }));
+}
+
+#[test]
+fn author_language_collision() {
+    let descriptor = descriptor("author_language_collision");
+    assert_eq!(descriptor.post_feeds(vec![], "pl", "pl"),
+               Err(Error::Parse {
+                   tp: "path chunk",
+                   wher: "blogue feeds".into(),
+                   more: "author \"pl\" Atom and language \"pl\" Atom feeds both output to \"feeds/pl.atom\"".into(),
+               }));
+}
@@ -1,4 +1,5 @@
-use bloguen::ops::{BlogueDescriptorIndex, BlogueDescriptor, MachineDataKind, ScriptElement, StyleElement, CenterOrder, FeedType};
+use bloguen::ops::{BlogueDescriptorHighlight, BlogueDescriptorTagIndex, BlogueDescriptorHooks, BlogueDescriptorIndex, BlogueDescriptor, MachineDataKind,
+                   MarkdownOptions, ScriptElement, StyleElement, CenterOrder, FeedType};
 use std::fs::{self, File};
 use std::env::temp_dir;
 use std::io::Write;
@@ -26,10 +27,17 @@ fn ok_all_specified() {
         .unwrap()
         .write_all(format!("name = \"Блогг\"\n\
                             author = \"nabijaczleweli\"\n\
+                            base_url = \"https://nabijaczleweli.xyz/blogue\"\n\
                             header = \"templates/head\"\n\
                             footer = \"templates{0}foot\"\n\
                             asset_dir = \"{0}/as{0}set/dir\"\n\
+                            permalink = \"{0}posts{0}{{year}}{0}{{slug}}.html\"\n\
                             language = \"pl\"\n\
+                            sitemap = \"sitemap.xml\"\n\
+                            robots = true\n\
+                            escape_html = true\n\
+                            math = true\n\
+                            feed_summaries = true\n\
                             styles = [\"link://nabijaczleweli.xyz/kaschism/assets/column.css\",\n\
                                       \"literal:.indented {{ text-indent: 1em; }}\"]\n\
                             \n\
@@ -39,10 +47,32 @@ fn ok_all_specified() {
                             center = \"templates/idx_центр\"\n\
                             footer = \"templates{0}idx_foot\"\n\
                             order = \"backward\"\n\
+                            posts_per_page = 12\n\
                             styles = [\"file:common.css\"]\n\
                             scripts = [\"literal:console.log(\\\"adenosinetriphosphate\\\");\"]\n\
                             data = {{ preferred-system = \"capitalism\" }}\n\
                             \n\
+                            [tag_index]\n\
+                            header = \"templates/tag_head\"\n\
+                            center = \"templates/tag_центр\"\n\
+                            cloud_center = \"templates/tag_cloud\"\n\
+                            footer = \"templates{0}tag_foot\"\n\
+                            order = \"backward\"\n\
+                            styles = [\"literal:.post-tag {{ font-size: smaller; }}\"]\n\
+                            data = {{ preferred-system = \"feudalism\" }}\n\
+                            \n\
+                            [highlight]\n\
+                            theme = \"base16-ocean.dark\"\n\
+                            classes = true\n\
+                            \n\
+                            [markdown]\n\
+                            smart = true\n\
+                            description_lists = true\n\
+                            \n\
+                            [hooks]\n\
+                            pre = [\"aspell list\"]\n\
+                            post = [\"html-minifier\", \"tidy -q\"]\n\
+                            \n\
                             [[scripts]]\n\
                             class = \"link\"\n\
                             data = \"/content/assets/syllable.js\"\n\
@@ -58,6 +88,15 @@ fn ok_all_specified() {
                             RSS = \"feed.rss\"\n\
                             Atom = \"feed.atom\"\n\
                             \n\
+                            [tag_feeds]\n\
+                            RSS = \"feeds/tags/{{tag}}.rss\"\n\
+                            \n\
+                            [author_feeds]\n\
+                            Atom = \"feeds/authors/{{author}}.atom\"\n\
+                            \n\
+                            [language_feeds]\n\
+                            RSS = \"feeds{0}{{language}}.rss\"\n\
+                            \n\
                             [data]\n\
                             preferred-system = \"communism\"\n",
                            ALT_SLASH_ESC)
@@ -68,25 +107,62 @@ fn ok_all_specified() {
     File::create(root.join("templates").join("idx_head")).unwrap();
     File::create(root.join("templates").join("idx_центр")).unwrap();
     File::create(root.join("templates").join("idx_foot")).unwrap();
+    for f in &["tag_head", "tag_центр", "tag_cloud", "tag_foot"] {
+        File::create(root.join("templates").join(f)).unwrap();
+    }
 
     assert_eq!(BlogueDescriptor::read(&("$ROOT/".to_string(), root.clone())),
                Ok(BlogueDescriptor {
                    name: "Блогг".to_string(),
                    author: Some("nabijaczleweli".to_string()),
+                   base_url: Some("https://nabijaczleweli.xyz/blogue/".parse().unwrap()),
                    header_file: ("$ROOT/templates/head".to_string(), root.join("templates").join("head")),
                    footer_file: (format!("$ROOT/templates{}foot", ALT_SLASH), root.join("templates").join("foot")),
                    asset_dir_override: Some("as/set/dir/".to_string()),
+                   permalink: "posts/{year}/{slug}.html".to_string(),
                    index: Some(BlogueDescriptorIndex {
                        header_file: ("$ROOT/templates/idx_head".to_string(), root.join("templates").join("idx_head")),
                        center_file: ("$ROOT/templates/idx_центр".to_string(), root.join("templates").join("idx_центр")),
                        footer_file: (format!("$ROOT/templates{}idx_foot", ALT_SLASH), root.join("templates").join("idx_foot")),
                        center_order: CenterOrder::Backward,
+                       posts_per_page: Some(12),
                        styles: vec![StyleElement::from_path("common.css")],
                        scripts: vec![ScriptElement::from_literal("console.log(\"adenosinetriphosphate\");")],
                        data: vec![("preferred-system".to_string(), "capitalism".to_string())].into_iter().collect(),
                    }),
+                   tag_index: Some(BlogueDescriptorTagIndex {
+                       header_file: ("$ROOT/templates/tag_head".to_string(), root.join("templates").join("tag_head")),
+                       center_file: ("$ROOT/templates/tag_центр".to_string(), root.join("templates").join("tag_центр")),
+                       cloud_center_file: ("$ROOT/templates/tag_cloud".to_string(), root.join("templates").join("tag_cloud")),
+                       footer_file: (format!("$ROOT/templates{}tag_foot", ALT_SLASH), root.join("templates").join("tag_foot")),
+                       center_order: CenterOrder::Backward,
+                       styles: vec![StyleElement::from_literal(".post-tag { font-size: smaller; }")],
+                       scripts: vec![],
+                       data: vec![("preferred-system".to_string(), "feudalism".to_string())].into_iter().collect(),
+                   }),
+                   highlight: Some(BlogueDescriptorHighlight {
+                       theme: "base16-ocean.dark".to_string(),
+                       classes: true,
+                   }),
+                   math: true,
+                   markdown: MarkdownOptions {
+                       smart: Some(true),
+                       description_lists: Some(true),
+                       ..Default::default()
+                   },
+                   hooks: BlogueDescriptorHooks {
+                       pre: vec!["aspell list".to_string()],
+                       post: vec!["html-minifier".to_string(), "tidy -q".to_string()],
+                   },
                    machine_data: vec![(MachineDataKind::Json, "metadata/json/".to_string())].into_iter().collect(),
                    feeds: vec![(FeedType::Rss, "feed.rss".to_string()), (FeedType::Atom, "feed.atom".to_string())].into_iter().collect(),
+                   tag_feeds: vec![(FeedType::Rss, "feeds/tags/{tag}.rss".to_string())].into_iter().collect(),
+                   author_feeds: vec![(FeedType::Atom, "feeds/authors/{author}.atom".to_string())].into_iter().collect(),
+                   language_feeds: vec![(FeedType::Rss, format!("feeds{}{{language}}.rss", ALT_SLASH))].into_iter().collect(),
+                   feed_summaries: true,
+                   sitemap: Some("sitemap.xml".to_string()),
+                   robots: true,
+                   escape_html: true,
                    language: Some("pl".parse().unwrap()),
                    styles: vec![StyleElement::from_link("//nabijaczleweli.xyz/kaschism/assets/column.css"),
                                 StyleElement::from_literal(".indented { text-indent: 1em; }")],
@@ -109,15 +185,29 @@ fn ok_induced() {
                Ok(BlogueDescriptor {
                    name: "Блогг".to_string(),
                    author: None,
+                   base_url: None,
                    header_file: ("$ROOT/header.html".to_string(), root.join("header.html")),
                    footer_file: ("$ROOT/footer.htm".to_string(), root.join("footer.htm")),
                    asset_dir_override: None,
+                   permalink: "posts/{slug}.html".to_string(),
                    machine_data: vec![].into_iter().collect(),
                    feeds: vec![].into_iter().collect(),
+                   tag_feeds: vec![].into_iter().collect(),
+                   author_feeds: vec![].into_iter().collect(),
+                   language_feeds: vec![].into_iter().collect(),
+                   feed_summaries: false,
+                   sitemap: None,
+                   robots: false,
+                   escape_html: false,
                    language: None,
                    styles: vec![],
                    scripts: vec![],
                    index: None,
+                   tag_index: None,
+                   highlight: None,
+                   math: false,
+                   markdown: Default::default(),
+                   hooks: Default::default(),
                    data: vec![].into_iter().collect(),
                }));
 }
@@ -146,11 +236,20 @@ fn ok_induced_index() {
                Ok(BlogueDescriptor {
                    name: "Блогг".to_string(),
                    author: None,
+                   base_url: None,
                    header_file: ("$ROOT/header.html".to_string(), root.join("header.html")),
                    footer_file: ("$ROOT/footer.htm".to_string(), root.join("footer.htm")),
                    asset_dir_override: None,
+                   permalink: "posts/{slug}.html".to_string(),
                    machine_data: vec![].into_iter().collect(),
                    feeds: vec![].into_iter().collect(),
+                   tag_feeds: vec![].into_iter().collect(),
+                   author_feeds: vec![].into_iter().collect(),
+                   language_feeds: vec![].into_iter().collect(),
+                   feed_summaries: false,
+                   sitemap: None,
+                   robots: false,
+                   escape_html: false,
                    language: None,
                    styles: vec![],
                    scripts: vec![],
@@ -159,10 +258,16 @@ fn ok_induced_index() {
                        center_file: ("$ROOT/index_center.html".to_string(), root.join("index_center.html")),
                        footer_file: ("$ROOT/index_footer.htm".to_string(), root.join("index_footer.htm")),
                        center_order: CenterOrder::Forward,
+                       posts_per_page: None,
                        styles: vec![],
                        scripts: vec![],
                        data: vec![].into_iter().collect(),
                    }),
+                   tag_index: None,
+                   highlight: None,
+                   math: false,
+                   markdown: Default::default(),
+                   hooks: Default::default(),
                    data: vec![].into_iter().collect(),
                }));
 }
@@ -191,11 +296,20 @@ fn ok_induced_idx() {
                Ok(BlogueDescriptor {
                    name: "Блогг".to_string(),
                    author: None,
+                   base_url: None,
                    header_file: ("$ROOT/header.html".to_string(), root.join("header.html")),
                    footer_file: ("$ROOT/footer.htm".to_string(), root.join("footer.htm")),
                    asset_dir_override: None,
+                   permalink: "posts/{slug}.html".to_string(),
                    machine_data: vec![].into_iter().collect(),
                    feeds: vec![].into_iter().collect(),
+                   tag_feeds: vec![].into_iter().collect(),
+                   author_feeds: vec![].into_iter().collect(),
+                   language_feeds: vec![].into_iter().collect(),
+                   feed_summaries: false,
+                   sitemap: None,
+                   robots: false,
+                   escape_html: false,
                    language: None,
                    styles: vec![],
                    scripts: vec![],
@@ -204,14 +318,295 @@ fn ok_induced_idx() {
                        center_file: ("$ROOT/idx_center.htm".to_string(), root.join("idx_center.htm")),
                        footer_file: ("$ROOT/idx_footer.htm".to_string(), root.join("idx_footer.htm")),
                        center_order: CenterOrder::Forward,
+                       posts_per_page: None,
                        styles: vec![],
                        scripts: vec![],
                        data: vec![].into_iter().collect(),
                    }),
+                   tag_index: None,
+                   highlight: None,
+                   math: false,
+                   markdown: Default::default(),
+                   hooks: Default::default(),
                    data: vec![].into_iter().collect(),
                }));
 }
 
+#[test]
+fn ok_induced_tag_index() {
+    let root = temp_dir().join("bloguen-test").join("ops-descriptor-read-ok_induced_tag_index");
+    let _ = fs::remove_dir_all(&root);
+    fs::create_dir_all(&root).unwrap();
+
+    File::create(root.join("blogue.toml"))
+        .unwrap()
+        .write_all("name = \"Блогг\"\n\
+                    \n\
+                    [index]\n\
+                    generate = false\n\
+                    \n\
+                    [tag_index]\n"
+            .as_bytes())
+        .unwrap();
+    File::create(root.join("header.html")).unwrap();
+    File::create(root.join("footer.htm")).unwrap();
+    File::create(root.join("tag_index_header.html")).unwrap();
+    File::create(root.join("tag_idx_center.htm")).unwrap();
+    File::create(root.join("tag_cloud_center.html")).unwrap();
+    File::create(root.join("tag_index_footer.htm")).unwrap();
+
+    assert_eq!(BlogueDescriptor::read(&("$ROOT/".to_string(), root.clone())),
+               Ok(BlogueDescriptor {
+                   name: "Блогг".to_string(),
+                   author: None,
+                   base_url: None,
+                   header_file: ("$ROOT/header.html".to_string(), root.join("header.html")),
+                   footer_file: ("$ROOT/footer.htm".to_string(), root.join("footer.htm")),
+                   asset_dir_override: None,
+                   permalink: "posts/{slug}.html".to_string(),
+                   machine_data: vec![].into_iter().collect(),
+                   feeds: vec![].into_iter().collect(),
+                   tag_feeds: vec![].into_iter().collect(),
+                   author_feeds: vec![].into_iter().collect(),
+                   language_feeds: vec![].into_iter().collect(),
+                   feed_summaries: false,
+                   sitemap: None,
+                   robots: false,
+                   escape_html: false,
+                   language: None,
+                   styles: vec![],
+                   scripts: vec![],
+                   index: None,
+                   tag_index: Some(BlogueDescriptorTagIndex {
+                       header_file: ("$ROOT/tag_index_header.html".to_string(), root.join("tag_index_header.html")),
+                       center_file: ("$ROOT/tag_idx_center.htm".to_string(), root.join("tag_idx_center.htm")),
+                       cloud_center_file: ("$ROOT/tag_cloud_center.html".to_string(), root.join("tag_cloud_center.html")),
+                       footer_file: ("$ROOT/tag_index_footer.htm".to_string(), root.join("tag_index_footer.htm")),
+                       center_order: CenterOrder::Forward,
+                       styles: vec![],
+                       scripts: vec![],
+                       data: vec![].into_iter().collect(),
+                   }),
+                   highlight: None,
+                   math: false,
+                   markdown: Default::default(),
+                   hooks: Default::default(),
+                   data: vec![].into_iter().collect(),
+               }));
+}
+
+#[test]
+fn ok_tag_index_not_generated() {
+    let root = temp_dir().join("bloguen-test").join("ops-descriptor-read-ok_tag_index_not_generated");
+    let _ = fs::remove_dir_all(&root);
+    fs::create_dir_all(&root).unwrap();
+
+    File::create(root.join("blogue.toml"))
+        .unwrap()
+        .write_all("name = \"Блогг\"\n\
+                    \n\
+                    [tag_index]\n\
+                    generate = false\n\
+                    header = \"nonexistant.html\"\n"
+            .as_bytes())
+        .unwrap();
+    File::create(root.join("header.html")).unwrap();
+    File::create(root.join("footer.htm")).unwrap();
+
+    assert_eq!(BlogueDescriptor::read(&("$ROOT/".to_string(), root.clone())).map(|d| d.tag_index), Ok(None));
+}
+
+#[test]
+fn ok_induced_highlight() {
+    let root = temp_dir().join("bloguen-test").join("ops-descriptor-read-ok_induced_highlight");
+    let _ = fs::remove_dir_all(&root);
+    fs::create_dir_all(&root).unwrap();
+
+    File::create(root.join("blogue.toml"))
+        .unwrap()
+        .write_all("name = \"Блогг\"\n\
+                    \n\
+                    [highlight]\n"
+            .as_bytes())
+        .unwrap();
+    File::create(root.join("header.html")).unwrap();
+    File::create(root.join("footer.htm")).unwrap();
+
+    assert_eq!(BlogueDescriptor::read(&("$ROOT/".to_string(), root.clone())).map(|d| d.highlight),
+               Ok(Some(BlogueDescriptorHighlight {
+                   theme: "InspiredGitHub".to_string(),
+                   classes: false,
+               })));
+}
+
+#[test]
+fn ok_induced_hooks() {
+    let root = temp_dir().join("bloguen-test").join("ops-descriptor-read-ok_induced_hooks");
+    let _ = fs::remove_dir_all(&root);
+    fs::create_dir_all(&root).unwrap();
+
+    File::create(root.join("blogue.toml"))
+        .unwrap()
+        .write_all("name = \"Блогг\"\n\
+                    \n\
+                    [hooks]\n\
+                    post = [\"tidy -q\"]\n"
+            .as_bytes())
+        .unwrap();
+    File::create(root.join("header.html")).unwrap();
+    File::create(root.join("footer.htm")).unwrap();
+
+    assert_eq!(BlogueDescriptor::read(&("$ROOT/".to_string(), root.clone())).map(|d| d.hooks),
+               Ok(BlogueDescriptorHooks {
+                   pre: vec![],
+                   post: vec!["tidy -q".to_string()],
+               }));
+}
+
+#[test]
+fn tag_index_cloud_center_not_found() {
+    let root = temp_dir().join("bloguen-test").join("ops-descriptor-read-tag_index_cloud_center_not_found");
+    let _ = fs::remove_dir_all(&root);
+    fs::create_dir_all(&root).unwrap();
+
+    File::create(root.join("blogue.toml")).unwrap().write_all("name = \"Блогг\"\n[tag_index]\n".as_bytes()).unwrap();
+    File::create(root.join("header.html")).unwrap();
+    File::create(root.join("footer.htm")).unwrap();
+    File::create(root.join("tag_index_header.html")).unwrap();
+    File::create(root.join("tag_index_center.html")).unwrap();
+    File::create(root.join("tag_index_footer.html")).unwrap();
+
+    assert_eq!(BlogueDescriptor::read(&("$ROOT/".to_string(), root.clone())),
+               Err(Error::FileNotFound {
+                   who: "tag cloud center",
+                   path: "$ROOT/{tag_cloud_center.html/tag_cloud_center.htm}".into(),
+               }));
+}
+
+#[test]
+fn invalid_index_posts_per_page_zero() {
+    let root = temp_dir().join("bloguen-test").join("ops-descriptor-read-invalid_index_posts_per_page_zero");
+    let _ = fs::remove_dir_all(&root);
+    fs::create_dir_all(&root).unwrap();
+
+    File::create(root.join("blogue.toml"))
+        .unwrap()
+        .write_all("name = \"Блогг\"\n\
+                    \n\
+                    [index]\n\
+                    posts_per_page = 0\n"
+            .as_bytes())
+        .unwrap();
+    File::create(root.join("header.html")).unwrap();
+    File::create(root.join("footer.htm")).unwrap();
+    File::create(root.join("idx_header.html")).unwrap();
+    File::create(root.join("idx_center.html")).unwrap();
+    File::create(root.join("idx_footer.html")).unwrap();
+
+    assert_eq!(BlogueDescriptor::read(&("$ROOT/".to_string(), root.clone())),
+               Err(Error::Parse {
+                   tp: "positive integer",
+                   wher: "blogue descriptor".into(),
+                   more: "index posts_per_page zero".into(),
+               }));
+}
+
+#[test]
+fn invalid_permalink_slash_end_path() {
+    let root = temp_dir().join("bloguen-test").join("ops-descriptor-read-invalid_permalink_slash_end_path");
+    let _ = fs::remove_dir_all(&root);
+    fs::create_dir_all(root.join("templates")).unwrap();
+
+    File::create(root.join("blogue.toml"))
+        .unwrap()
+        .write_all("name = \"Блогг\"\n\
+                    permalink = \"posts/{year}/{slug}/\"\n"
+            .as_bytes())
+        .unwrap();
+    File::create(root.join("header.html")).unwrap();
+    File::create(root.join("footer.htm")).unwrap();
+
+    assert_eq!(BlogueDescriptor::read(&("$ROOT/".to_string(), root.clone())),
+               Err(Error::Parse {
+                   tp: "path chunk",
+                   wher: "blogue descriptor".into(),
+                   more: "permalink pattern \"posts/{year}/{slug}/\" ends with path separator".into(),
+               }));
+}
+
+#[test]
+fn invalid_permalink_unknown_placeholder() {
+    let root = temp_dir().join("bloguen-test").join("ops-descriptor-read-invalid_permalink_unknown_placeholder");
+    let _ = fs::remove_dir_all(&root);
+    fs::create_dir_all(root.join("templates")).unwrap();
+
+    File::create(root.join("blogue.toml"))
+        .unwrap()
+        .write_all("name = \"Блогг\"\n\
+                    permalink = \"/posts/{year}/{title}.html\"\n"
+            .as_bytes())
+        .unwrap();
+    File::create(root.join("header.html")).unwrap();
+    File::create(root.join("footer.htm")).unwrap();
+
+    assert_eq!(BlogueDescriptor::read(&("$ROOT/".to_string(), root.clone())),
+               Err(Error::Parse {
+                   tp: "path chunk",
+                   wher: "blogue descriptor".into(),
+                   more: "permalink pattern \"posts/{year}/{title}.html\" contains unknown placeholder".into(),
+               }));
+}
+
+#[test]
+fn invalid_highlight_theme() {
+    let root = temp_dir().join("bloguen-test").join("ops-descriptor-read-invalid_highlight_theme");
+    let _ = fs::remove_dir_all(&root);
+    fs::create_dir_all(&root).unwrap();
+
+    File::create(root.join("blogue.toml"))
+        .unwrap()
+        .write_all("name = \"Блогг\"\n\
+                    \n\
+                    [highlight]\n\
+                    theme = \"Paint It Black\"\n"
+            .as_bytes())
+        .unwrap();
+    File::create(root.join("header.html")).unwrap();
+    File::create(root.join("footer.htm")).unwrap();
+
+    assert_eq!(BlogueDescriptor::read(&("$ROOT/".to_string(), root.clone())),
+               Err(Error::Parse {
+                   tp: "highlight theme",
+                   wher: "blogue descriptor".into(),
+                   more: "theme \"Paint It Black\" unknown".into(),
+               }));
+}
+
+#[test]
+fn invalid_hook_empty() {
+    let root = temp_dir().join("bloguen-test").join("ops-descriptor-read-invalid_hook_empty");
+    let _ = fs::remove_dir_all(&root);
+    fs::create_dir_all(&root).unwrap();
+
+    File::create(root.join("blogue.toml"))
+        .unwrap()
+        .write_all("name = \"Блогг\"\n\
+                    \n\
+                    [hooks]\n\
+                    pre = [\"aspell list\", \" \"]\n"
+            .as_bytes())
+        .unwrap();
+    File::create(root.join("header.html")).unwrap();
+    File::create(root.join("footer.htm")).unwrap();
+
+    assert_eq!(BlogueDescriptor::read(&("$ROOT/".to_string(), root.clone())),
+               Err(Error::Parse {
+                   tp: "command",
+                   wher: "blogue descriptor".into(),
+                   more: "pre hook empty".into(),
+               }));
+}
+
 #[test]
 fn invalid_machine_data_empty_path() {
     let root = temp_dir().join("bloguen-test").join("ops-descriptor-read-invalid_machine_data_empty_path");
@@ -386,6 +781,201 @@ fn invalid_feeds_invalid_kind() {
                }));
 }
 
+#[test]
+fn invalid_tag_feeds_no_placeholder() {
+    let root = temp_dir().join("bloguen-test").join("ops-descriptor-read-invalid_tag_feeds_no_placeholder");
+    let _ = fs::remove_dir_all(&root);
+    fs::create_dir_all(root.join("templates")).unwrap();
+
+    File::create(root.join("blogue.toml"))
+        .unwrap()
+        .write_all("name = \"Блогг\"\n\
+                    \n\
+                    [tag_feeds]\n\
+                    RSS = \"feeds/tag.rss\"\n"
+            .as_bytes())
+        .unwrap();
+    File::create(root.join("header.html")).unwrap();
+    File::create(root.join("footer.htm")).unwrap();
+
+    assert_eq!(BlogueDescriptor::read(&("$ROOT/".to_string(), root.clone())),
+               Err(Error::Parse {
+                   tp: "path chunk",
+                   wher: "blogue descriptor".into(),
+                   more: "RSS filename \"feeds/tag.rss\" doesn't contain {tag}".into(),
+               }));
+}
+
+#[test]
+fn invalid_author_feeds_slash_end_path() {
+    let root = temp_dir().join("bloguen-test").join("ops-descriptor-read-invalid_author_feeds_slash_end_path");
+    let _ = fs::remove_dir_all(&root);
+    fs::create_dir_all(root.join("templates")).unwrap();
+
+    File::create(root.join("blogue.toml"))
+        .unwrap()
+        .write_all("name = \"Блогг\"\n\
+                    \n\
+                    [author_feeds]\n\
+                    Atom = \"feeds/{author}/\"\n"
+            .as_bytes())
+        .unwrap();
+    File::create(root.join("header.html")).unwrap();
+    File::create(root.join("footer.htm")).unwrap();
+
+    assert_eq!(BlogueDescriptor::read(&("$ROOT/".to_string(), root.clone())),
+               Err(Error::Parse {
+                   tp: "path chunk",
+                   wher: "blogue descriptor".into(),
+                   more: "Atom filename \"feeds/{author}/\" ends with path separator".into(),
+               }));
+}
+
+#[test]
+fn invalid_language_feeds_path_duplicate() {
+    let root = temp_dir().join("bloguen-test").join("ops-descriptor-read-invalid_language_feeds_path_duplicate");
+    let _ = fs::remove_dir_all(&root);
+    fs::create_dir_all(root.join("templates")).unwrap();
+
+    File::create(root.join("blogue.toml"))
+        .unwrap()
+        .write_all("name = \"Блогг\"\n\
+                    \n\
+                    [tag_feeds]\n\
+                    RSS = \"feeds/{tag}/{language}.rss\"\n\
+                    \n\
+                    [language_feeds]\n\
+                    Atom = \"feeds/{tag}/{language}.rss\"\n"
+            .as_bytes())
+        .unwrap();
+    File::create(root.join("header.html")).unwrap();
+    File::create(root.join("footer.htm")).unwrap();
+
+    assert_eq!(BlogueDescriptor::read(&("$ROOT/".to_string(), root.clone())),
+               Err(Error::Parse {
+                   tp: "path chunk",
+                   wher: "blogue descriptor".into(),
+                   more: "feed filename \"feeds/{tag}/{language}.rss\" duplicate".into(),
+               }));
+}
+
+#[test]
+fn invalid_sitemap_slash_end_path() {
+    let root = temp_dir().join("bloguen-test").join("ops-descriptor-read-invalid_sitemap_slash_end_path");
+    let _ = fs::remove_dir_all(&root);
+    fs::create_dir_all(root.join("templates")).unwrap();
+
+    File::create(root.join("blogue.toml"))
+        .unwrap()
+        .write_all("name = \"Блогг\"\n\
+                    base_url = \"https://nabijaczleweli.xyz/blogue\"\n\
+                    sitemap = \"sitemaps/\"\n"
+            .as_bytes())
+        .unwrap();
+    File::create(root.join("header.html")).unwrap();
+    File::create(root.join("footer.htm")).unwrap();
+
+    assert_eq!(BlogueDescriptor::read(&("$ROOT/".to_string(), root.clone())),
+               Err(Error::Parse {
+                   tp: "path chunk",
+                   wher: "blogue descriptor".into(),
+                   more: "sitemap filename \"sitemaps/\" ends with path separator".into(),
+               }));
+}
+
+#[test]
+fn invalid_sitemap_no_base_url() {
+    let root = temp_dir().join("bloguen-test").join("ops-descriptor-read-invalid_sitemap_no_base_url");
+    let _ = fs::remove_dir_all(&root);
+    fs::create_dir_all(root.join("templates")).unwrap();
+
+    File::create(root.join("blogue.toml"))
+        .unwrap()
+        .write_all("name = \"Блогг\"\n\
+                    sitemap = \"sitemap.xml\"\n"
+            .as_bytes())
+        .unwrap();
+    File::create(root.join("header.html")).unwrap();
+    File::create(root.join("footer.htm")).unwrap();
+
+    assert_eq!(BlogueDescriptor::read(&("$ROOT/".to_string(), root.clone())),
+               Err(Error::Parse {
+                   tp: "URL",
+                   wher: "blogue descriptor".into(),
+                   more: "sitemap requires base URL".into(),
+               }));
+}
+
+#[test]
+fn invalid_robots_no_sitemap() {
+    let root = temp_dir().join("bloguen-test").join("ops-descriptor-read-invalid_robots_no_sitemap");
+    let _ = fs::remove_dir_all(&root);
+    fs::create_dir_all(root.join("templates")).unwrap();
+
+    File::create(root.join("blogue.toml"))
+        .unwrap()
+        .write_all("name = \"Блогг\"\n\
+                    base_url = \"https://nabijaczleweli.xyz/blogue\"\n\
+                    robots = true\n"
+            .as_bytes())
+        .unwrap();
+    File::create(root.join("header.html")).unwrap();
+    File::create(root.join("footer.htm")).unwrap();
+
+    assert_eq!(BlogueDescriptor::read(&("$ROOT/".to_string(), root.clone())),
+               Err(Error::Parse {
+                   tp: "boolean",
+                   wher: "blogue descriptor".into(),
+                   more: "robots requires sitemap".into(),
+               }));
+}
+
+#[test]
+fn invalid_base_url_unparseable() {
+    let root = temp_dir().join("bloguen-test").join("ops-descriptor-read-invalid_base_url_unparseable");
+    let _ = fs::remove_dir_all(&root);
+    fs::create_dir_all(root.join("templates")).unwrap();
+
+    File::create(root.join("blogue.toml"))
+        .unwrap()
+        .write_all("name = \"Блогг\"\n\
+                    base_url = \"nabijaczleweli.xyz/blogue/\"\n"
+            .as_bytes())
+        .unwrap();
+    File::create(root.join("header.html")).unwrap();
+    File::create(root.join("footer.htm")).unwrap();
+
+    assert_eq!(BlogueDescriptor::read(&("$ROOT/".to_string(), root.clone())),
+               Err(Error::Parse {
+                   tp: "URL",
+                   wher: "blogue descriptor".into(),
+                   more: "base URL \"nabijaczleweli.xyz/blogue/\" invalid: relative URL without a base".into(),
+               }));
+}
+
+#[test]
+fn invalid_base_url_cannot_be_a_base() {
+    let root = temp_dir().join("bloguen-test").join("ops-descriptor-read-invalid_base_url_cannot_be_a_base");
+    let _ = fs::remove_dir_all(&root);
+    fs::create_dir_all(root.join("templates")).unwrap();
+
+    File::create(root.join("blogue.toml"))
+        .unwrap()
+        .write_all("name = \"Блогг\"\n\
+                    base_url = \"mailto:nabijaczleweli@nabijaczleweli.xyz\"\n"
+            .as_bytes())
+        .unwrap();
+    File::create(root.join("header.html")).unwrap();
+    File::create(root.join("footer.htm")).unwrap();
+
+    assert_eq!(BlogueDescriptor::read(&("$ROOT/".to_string(), root.clone())),
+               Err(Error::Parse {
+                   tp: "URL",
+                   wher: "blogue descriptor".into(),
+                   more: "base URL \"mailto:nabijaczleweli@nabijaczleweli.xyz\" cannot be a base".into(),
+               }));
+}
+
 #[test]
 fn invalid_language() {
     let root = temp_dir().join("bloguen-test").join("ops-descriptor-read-invalid_language");
@@ -0,0 +1,54 @@
+use bloguen::ops::{BuildManifest, PostManifest};
+use std::default::Default;
+
+
+#[test]
+fn same() {
+    let manifest = BuildManifest::new(0xB10C, vec![("post_header".to_string(), 0x4EAD)].into_iter().collect());
+    assert!(manifest.globals_match(&manifest.clone()));
+}
+
+#[test]
+fn different_posts() {
+    let manifest = BuildManifest::new(0xB10C, vec![("post_header".to_string(), 0x4EAD)].into_iter().collect());
+    let mut other = manifest.clone();
+    other.posts.insert("01. 2018-01-08 16-52-00 The venture into crocheting".to_string(),
+                       PostManifest {
+                           source: 0xF00D,
+                           assets: Default::default(),
+                           feeds: Default::default(),
+                           index_machine_data: String::new(),
+                           index_center: String::new(),
+                           paged_index_center: String::new(),
+                           tags: Default::default(),
+                           author: String::new(),
+                           language: String::new(),
+                           tag_index_center: String::new(),
+                           output: String::new(),
+                           machine_outputs: Default::default(),
+                       });
+
+    assert!(manifest.globals_match(&other));
+}
+
+#[test]
+fn different_version() {
+    let manifest = BuildManifest::new(0xB10C, vec![("post_header".to_string(), 0x4EAD)].into_iter().collect());
+    let mut other = manifest.clone();
+    other.version = "0.1.0".to_string();
+
+    assert!(!manifest.globals_match(&other));
+}
+
+#[test]
+fn different_descriptor() {
+    let manifest = BuildManifest::new(0xB10C, vec![("post_header".to_string(), 0x4EAD)].into_iter().collect());
+    assert!(!manifest.globals_match(&BuildManifest::new(0xB10D, vec![("post_header".to_string(), 0x4EAD)].into_iter().collect())));
+}
+
+#[test]
+fn different_templates() {
+    let manifest = BuildManifest::new(0xB10C, vec![("post_header".to_string(), 0x4EAD)].into_iter().collect());
+    assert!(!manifest.globals_match(&BuildManifest::new(0xB10C, vec![("post_header".to_string(), 0x4EAE)].into_iter().collect())));
+    assert!(!manifest.globals_match(&BuildManifest::new(0xB10C, vec![("index_header".to_string(), 0x4EAD)].into_iter().collect())));
+}
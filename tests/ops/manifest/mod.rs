@@ -0,0 +1,19 @@
+mod read_or_default;
+mod globals_match;
+mod remove_stale_outputs;
+
+use bloguen::ops::BuildManifest;
+use std::collections::BTreeMap;
+use std::default::Default;
+
+
+#[test]
+fn default() {
+    assert_eq!(BuildManifest::default(),
+               BuildManifest {
+                   version: String::new(),
+                   descriptor: 0,
+                   templates: BTreeMap::new(),
+                   posts: BTreeMap::new(),
+               });
+}
@@ -0,0 +1,174 @@
+use bloguen::ops::{MANIFEST_FILE_NAME, BuildManifest, PostManifest};
+use bloguen::util::BLOGUEN_VERSION;
+use std::fs::{self, File};
+use std::default::Default;
+use std::env::temp_dir;
+use std::io::Write;
+use bloguen::Error;
+
+
+#[test]
+fn ok_roundtrip() {
+    let root = temp_dir().join("bloguen-test").join("ops-manifest-read_or_default-ok_roundtrip");
+    let _ = fs::remove_dir_all(&root);
+
+    let mut manifest = BuildManifest::new(0xFFFFFFFFFFFFFFFF,
+                                          vec![("post_header".to_string(), 0x4EAD), ("post_footer".to_string(), 0)].into_iter().collect());
+    manifest.posts.insert("01. 2018-01-08 16-52-00 The venture into crocheting".to_string(),
+                          PostManifest {
+                              source: 0xF00D,
+                              assets: vec![("assets/img.png".to_string(), 1535042400000000000)].into_iter().collect(),
+                              feeds: vec![("feeds/rss.xml".to_string(), "    <item>\n    </item>\n".to_string()), ("feeds/atom.xml".to_string(), String::new())]
+                                  .into_iter()
+                                  .collect(),
+                              index_machine_data: "{\n    \"number\": 1\n}".to_string(),
+                              index_center: "<p>Блогг</p>\n".to_string(),
+                              paged_index_center: "<p>Блогг</p>\n".to_string(),
+                              tags: vec!["vodka".to_string(), "коммунизм".to_string()].into_iter().collect(),
+                              author: "nabijaczleweli".to_string(),
+                              language: "pl".to_string(),
+                              tag_index_center: "<p>Блогг</p>\n".to_string(),
+                              output: "posts/01. 2018-01-08 16-52-00 The venture into crocheting.html".to_string(),
+                              machine_outputs: vec!["machine/01. 2018-01-08 16-52-00 The venture into crocheting.json".to_string(), "json/crocheting.json".to_string()]
+                                  .into_iter()
+                                  .collect(),
+                          });
+    manifest.posts.insert("02. 2018-01-09 16-52-00 Hooked".to_string(),
+                          PostManifest {
+                              source: 0,
+                              assets: Default::default(),
+                              feeds: Default::default(),
+                              index_machine_data: String::new(),
+                              index_center: String::new(),
+                              paged_index_center: String::new(),
+                              tags: Default::default(),
+                              author: String::new(),
+                              language: String::new(),
+                              tag_index_center: String::new(),
+                              output: "posts/02. 2018-01-09 16-52-00 Hooked.html".to_string(),
+                              machine_outputs: Default::default(),
+                          });
+
+    manifest.write(&("$ROOT/".to_string(), root.clone())).unwrap();
+    assert!(root.join(MANIFEST_FILE_NAME).is_file());
+
+    assert_eq!(BuildManifest::read_or_default(&("$ROOT/".to_string(), root)), Ok(manifest));
+}
+
+#[test]
+fn ok_explicit() {
+    let root = temp_dir().join("bloguen-test").join("ops-manifest-read_or_default-ok_explicit");
+    let _ = fs::remove_dir_all(&root);
+    fs::create_dir_all(&root).unwrap();
+
+    File::create(root.join(MANIFEST_FILE_NAME))
+        .unwrap()
+        .write_all(format!("version = \"{}\"\n\
+                            descriptor = \"000000000000b10c\"\n\
+                            \n\
+                            [templates]\n\
+                            post_header = \"4ead\"\n\
+                            \n\
+                            [posts.\"01. 2018-01-08 16-52-00 The venture into crocheting\"]\n\
+                            source = \"F00D\"\n\
+                            index_machine_data = \"{{}}\"\n\
+                            index_center = \"\"\n\
+                            paged_index_center = \"\"\n\
+                            tags = [\"vodka\"]\n\
+                            author = \"nabijaczleweli\"\n\
+                            language = \"pl\"\n\
+                            tag_index_center = \"\"\n\
+                            output = \"posts/crocheting.html\"\n\
+                            machine_outputs = [\"machine/crocheting.json\"]\n\
+                            assets = {{ \"assets/img.png\" = 12 }}\n\
+                            feeds = {{}}\n",
+                           BLOGUEN_VERSION)
+            .as_bytes())
+        .unwrap();
+
+    let mut manifest = BuildManifest::new(0xB10C, vec![("post_header".to_string(), 0x4EAD)].into_iter().collect());
+    manifest.posts.insert("01. 2018-01-08 16-52-00 The venture into crocheting".to_string(),
+                          PostManifest {
+                              source: 0xF00D,
+                              assets: vec![("assets/img.png".to_string(), 12)].into_iter().collect(),
+                              feeds: Default::default(),
+                              index_machine_data: "{}".to_string(),
+                              index_center: String::new(),
+                              paged_index_center: String::new(),
+                              tags: vec!["vodka".to_string()].into_iter().collect(),
+                              author: "nabijaczleweli".to_string(),
+                              language: "pl".to_string(),
+                              tag_index_center: String::new(),
+                              output: "posts/crocheting.html".to_string(),
+                              machine_outputs: vec!["machine/crocheting.json".to_string()].into_iter().collect(),
+                          });
+    assert_eq!(BuildManifest::read_or_default(&("$ROOT/".to_string(), root)), Ok(manifest));
+}
+
+#[test]
+fn manifest_not_found() {
+    let root = temp_dir().join("bloguen-test").join("ops-manifest-read_or_default-manifest_not_found");
+
+    assert_eq!(BuildManifest::read_or_default(&("$ROOT/".to_string(), root)), Ok(Default::default()));
+}
+
+#[test]
+fn non_utf8() {
+    let root = temp_dir().join("bloguen-test").join("ops-manifest-read_or_default-non_utf8");
+    let _ = fs::remove_dir_all(&root);
+    fs::create_dir_all(&root).unwrap();
+
+    // https://stackoverflow.com/a/3886015/2851815
+    File::create(root.join(MANIFEST_FILE_NAME))
+        .unwrap()
+        .write_all(&[0xC3, 0x28, 0xA0, 0xA1, 0xE2, 0x28, 0xA1, 0xE2, 0x82, 0x28, 0xF0, 0x28, 0x8C, 0xBC, 0xF0, 0x90, 0x28, 0xBC, 0xF0, 0x28, 0x8C, 0x28])
+        .unwrap();
+
+    assert_eq!(BuildManifest::read_or_default(&("$ROOT/".to_string(), root)),
+               Err(Error::Io {
+                   desc: "build manifest".into(),
+                   op: "read",
+                   more: "not UTF-8".into(),
+               }));
+}
+
+#[test]
+fn invalid_toml() {
+    let root = temp_dir().join("bloguen-test").join("ops-manifest-read_or_default-invalid_toml");
+    let _ = fs::remove_dir_all(&root);
+    fs::create_dir_all(&root).unwrap();
+
+    File::create(root.join(MANIFEST_FILE_NAME))
+        .unwrap()
+        .write_all("[description\n".as_bytes())
+        .unwrap();
+
+    assert_eq!(BuildManifest::read_or_default(&("$ROOT/".to_string(), root)),
+               Err(Error::FileParsingFailed {
+                   desc: "build manifest".into(),
+                   errors: "expected a right bracket, found a newline at line 1 column 13".into(),
+               }));
+}
+
+#[test]
+fn invalid_hash() {
+    let root = temp_dir().join("bloguen-test").join("ops-manifest-read_or_default-invalid_hash");
+    let _ = fs::remove_dir_all(&root);
+    fs::create_dir_all(&root).unwrap();
+
+    File::create(root.join(MANIFEST_FILE_NAME))
+        .unwrap()
+        .write_all("version = \"0.1.1\"\n\
+                    descriptor = \"b10g\"\n\
+                    templates = {}\n\
+                    posts = {}\n"
+            .as_bytes())
+        .unwrap();
+
+    assert_eq!(BuildManifest::read_or_default(&("$ROOT/".to_string(), root)),
+               Err(Error::Parse {
+                   tp: "hexadecimal unsigned integer",
+                   wher: "build manifest".into(),
+                   more: "descriptor hash \"b10g\": invalid digit found in string".into(),
+               }));
+}
@@ -0,0 +1,129 @@
+use bloguen::ops::{BuildManifest, PostManifest};
+use std::fs::{self, File};
+use std::env::temp_dir;
+
+
+fn post_manifest(output: &str, machine_output: &str) -> PostManifest {
+    PostManifest {
+        source: 0xF00D,
+        assets: Default::default(),
+        feeds: Default::default(),
+        index_machine_data: String::new(),
+        index_center: String::new(),
+        paged_index_center: String::new(),
+        tags: Default::default(),
+        author: "nabijaczleweli".to_string(),
+        language: "pl".to_string(),
+        tag_index_center: String::new(),
+        output: output.to_string(),
+        machine_outputs: vec![machine_output.to_string()].into_iter().collect(),
+    }
+}
+
+
+#[test]
+fn unchanged() {
+    let root = temp_dir().join("bloguen-test").join("ops-manifest-remove_stale_outputs-unchanged");
+    let _ = fs::remove_dir_all(&root);
+    fs::create_dir_all(root.join("posts")).unwrap();
+    fs::create_dir_all(root.join("machine")).unwrap();
+    File::create(root.join("posts").join("crocheting.html")).unwrap();
+    File::create(root.join("machine").join("crocheting.json")).unwrap();
+
+    let mut manifest = BuildManifest::new(0xB10C, Default::default());
+    manifest.posts.insert("01. 2018-01-08 16-52-00 The venture into crocheting".to_string(),
+                          post_manifest("posts/crocheting.html", "machine/crocheting.json"));
+
+    assert_eq!(manifest.remove_stale_outputs(&manifest.clone(), &("$ROOT/".to_string(), root.clone())), Ok(()));
+    assert!(root.join("posts").join("crocheting.html").is_file());
+    assert!(root.join("machine").join("crocheting.json").is_file());
+}
+
+#[test]
+fn deleted_post() {
+    let root = temp_dir().join("bloguen-test").join("ops-manifest-remove_stale_outputs-deleted_post");
+    let _ = fs::remove_dir_all(&root);
+    fs::create_dir_all(root.join("posts")).unwrap();
+    fs::create_dir_all(root.join("machine")).unwrap();
+    for f in &["crocheting", "hooked"] {
+        File::create(root.join("posts").join(format!("{}.html", f))).unwrap();
+        File::create(root.join("machine").join(format!("{}.json", f))).unwrap();
+    }
+
+    let mut previous = BuildManifest::new(0xB10C, Default::default());
+    previous.posts.insert("01. 2018-01-08 16-52-00 The venture into crocheting".to_string(),
+                          post_manifest("posts/crocheting.html", "machine/crocheting.json"));
+    previous.posts.insert("02. 2018-01-09 16-52-00 Hooked".to_string(),
+                          post_manifest("posts/hooked.html", "machine/hooked.json"));
+
+    let mut manifest = previous.clone();
+    manifest.posts.remove("02. 2018-01-09 16-52-00 Hooked");
+
+    assert_eq!(manifest.remove_stale_outputs(&previous, &("$ROOT/".to_string(), root.clone())), Ok(()));
+    assert!(root.join("posts").join("crocheting.html").is_file());
+    assert!(root.join("machine").join("crocheting.json").is_file());
+    assert!(!root.join("posts").join("hooked.html").exists());
+    assert!(!root.join("machine").join("hooked.json").exists());
+}
+
+#[test]
+fn changed_slug() {
+    let root = temp_dir().join("bloguen-test").join("ops-manifest-remove_stale_outputs-changed_slug");
+    let _ = fs::remove_dir_all(&root);
+    fs::create_dir_all(root.join("posts")).unwrap();
+    fs::create_dir_all(root.join("machine")).unwrap();
+    for f in &["crocheting", "crochet"] {
+        File::create(root.join("posts").join(format!("{}.html", f))).unwrap();
+        File::create(root.join("machine").join(format!("{}.json", f))).unwrap();
+    }
+
+    let mut previous = BuildManifest::new(0xB10C, Default::default());
+    previous.posts.insert("01. 2018-01-08 16-52-00 The venture into crocheting".to_string(),
+                          post_manifest("posts/crocheting.html", "machine/crocheting.json"));
+
+    let mut manifest = BuildManifest::new(0xB10C, Default::default());
+    manifest.posts.insert("01. 2018-01-08 16-52-00 The venture into crocheting".to_string(),
+                          post_manifest("posts/crochet.html", "machine/crochet.json"));
+
+    assert_eq!(manifest.remove_stale_outputs(&previous, &("$ROOT/".to_string(), root.clone())), Ok(()));
+    assert!(!root.join("posts").join("crocheting.html").exists());
+    assert!(!root.join("machine").join("crocheting.json").exists());
+    assert!(root.join("posts").join("crochet.html").is_file());
+    assert!(root.join("machine").join("crochet.json").is_file());
+}
+
+#[test]
+fn taken_over() {
+    let root = temp_dir().join("bloguen-test").join("ops-manifest-remove_stale_outputs-taken_over");
+    let _ = fs::remove_dir_all(&root);
+    fs::create_dir_all(root.join("posts")).unwrap();
+    fs::create_dir_all(root.join("machine")).unwrap();
+    File::create(root.join("posts").join("crocheting.html")).unwrap();
+    File::create(root.join("machine").join("crocheting.json")).unwrap();
+
+    let mut previous = BuildManifest::new(0xB10C, Default::default());
+    previous.posts.insert("01. 2018-01-08 16-52-00 The venture into crocheting".to_string(),
+                          post_manifest("posts/crocheting.html", "machine/crocheting.json"));
+
+    let mut manifest = BuildManifest::new(0xB10C, Default::default());
+    manifest.posts.insert("02. 2018-01-09 16-52-00 Crocheting again".to_string(),
+                          post_manifest("posts/crocheting.html", "machine/crocheting.json"));
+
+    assert_eq!(manifest.remove_stale_outputs(&previous, &("$ROOT/".to_string(), root.clone())), Ok(()));
+    assert!(root.join("posts").join("crocheting.html").is_file());
+    assert!(root.join("machine").join("crocheting.json").is_file());
+}
+
+#[test]
+fn already_removed() {
+    let root = temp_dir().join("bloguen-test").join("ops-manifest-remove_stale_outputs-already_removed");
+    let _ = fs::remove_dir_all(&root);
+    fs::create_dir_all(&root).unwrap();
+
+    let mut previous = BuildManifest::new(0xB10C, Default::default());
+    previous.posts.insert("01. 2018-01-08 16-52-00 The venture into crocheting".to_string(),
+                          post_manifest("posts/crocheting.html", "machine/crocheting.json"));
+
+    assert_eq!(BuildManifest::new(0xB10C, Default::default()).remove_stale_outputs(&previous, &("$ROOT/".to_string(), root.clone())),
+               Ok(()));
+}
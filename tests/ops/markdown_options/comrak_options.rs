@@ -0,0 +1,65 @@
+use bloguen::util::MARKDOWN_OPTIONS;
+use bloguen::ops::MarkdownOptions;
+
+
+#[test]
+fn all_enabled() {
+    let options = MarkdownOptions {
+            hardbreaks: Some(true),
+            smart: Some(true),
+            strikethrough: Some(true),
+            tagfilter: Some(true),
+            table: Some(true),
+            autolink: Some(true),
+            tasklist: Some(true),
+            superscript: Some(true),
+            header_ids: Some(true),
+            footnotes: Some(true),
+            description_lists: Some(true),
+        }
+        .comrak_options();
+
+    assert!(options.hardbreaks);
+    assert!(options.smart);
+    assert!(options.unsafe_);
+    assert!(options.ext_strikethrough);
+    assert!(options.ext_tagfilter);
+    assert!(options.ext_table);
+    assert!(options.ext_autolink);
+    assert!(options.ext_tasklist);
+    assert!(options.ext_superscript);
+    assert_eq!(options.ext_header_ids, MARKDOWN_OPTIONS.ext_header_ids);
+    assert!(options.ext_footnotes);
+    assert!(options.ext_description_lists);
+}
+
+#[test]
+fn all_disabled() {
+    let options = MarkdownOptions {
+            hardbreaks: Some(false),
+            smart: Some(false),
+            strikethrough: Some(false),
+            tagfilter: Some(false),
+            table: Some(false),
+            autolink: Some(false),
+            tasklist: Some(false),
+            superscript: Some(false),
+            header_ids: Some(false),
+            footnotes: Some(false),
+            description_lists: Some(false),
+        }
+        .comrak_options();
+
+    assert!(!options.hardbreaks);
+    assert!(!options.smart);
+    assert!(options.unsafe_);
+    assert!(!options.ext_strikethrough);
+    assert!(!options.ext_tagfilter);
+    assert!(!options.ext_table);
+    assert!(!options.ext_autolink);
+    assert!(!options.ext_tasklist);
+    assert!(!options.ext_superscript);
+    assert_eq!(options.ext_header_ids, None);
+    assert!(!options.ext_footnotes);
+    assert!(!options.ext_description_lists);
+}
@@ -0,0 +1,26 @@
+use bloguen::util::MARKDOWN_OPTIONS;
+use bloguen::ops::MarkdownOptions;
+
+
+mod with_fallback;
+mod comrak_options;
+
+
+#[test]
+fn default() {
+    assert_eq!(MarkdownOptions::default(),
+               MarkdownOptions {
+                   hardbreaks: None,
+                   smart: None,
+                   strikethrough: None,
+                   tagfilter: None,
+                   table: None,
+                   autolink: None,
+                   tasklist: None,
+                   superscript: None,
+                   header_ids: None,
+                   footnotes: None,
+                   description_lists: None,
+               });
+    assert_eq!(format!("{:?}", MarkdownOptions::default().comrak_options()), format!("{:?}", *MARKDOWN_OPTIONS));
+}
@@ -0,0 +1,35 @@
+use bloguen::ops::MarkdownOptions;
+
+
+#[test]
+fn empty() {
+    let options = MarkdownOptions {
+        smart: Some(true),
+        footnotes: Some(false),
+        ..Default::default()
+    };
+    assert_eq!(options.with_fallback(&Default::default()), options);
+    assert_eq!(MarkdownOptions::default().with_fallback(&options), options);
+}
+
+#[test]
+fn overriden() {
+    let post = MarkdownOptions {
+        hardbreaks: Some(false),
+        footnotes: Some(true),
+        ..Default::default()
+    };
+    let blogue = MarkdownOptions {
+        hardbreaks: Some(true),
+        superscript: Some(true),
+        footnotes: Some(false),
+        ..Default::default()
+    };
+    assert_eq!(post.with_fallback(&blogue),
+               MarkdownOptions {
+                   hardbreaks: Some(false),
+                   superscript: Some(true),
+                   footnotes: Some(true),
+                   ..Default::default()
+               });
+}
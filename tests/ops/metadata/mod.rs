@@ -1,7 +1,7 @@
 mod read_or_default;
 
 use std::collections::BTreeMap;
-use bloguen::ops::PostMetadata;
+use bloguen::ops::{MarkdownOptions, PostMetadata};
 use std::default::Default;
 
 
@@ -11,9 +11,14 @@ fn default() {
                PostMetadata {
                    language: None,
                    author: None,
+                   title: None,
+                   slug: None,
                    tags: vec![],
                    styles: vec![],
                    scripts: vec![],
                    data: BTreeMap::new(),
+                   draft: false,
+                   toc: true,
+                   markdown: MarkdownOptions::default(),
                });
 }
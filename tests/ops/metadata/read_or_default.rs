@@ -1,4 +1,4 @@
-use bloguen::ops::{ScriptElement, StyleElement, PostMetadata};
+use bloguen::ops::{MarkdownOptions, ScriptElement, StyleElement, PostMetadata};
 use std::collections::BTreeMap;
 use std::fs::{self, File};
 use std::default::Default;
@@ -17,6 +17,10 @@ fn ok_all_specified() {
         .unwrap()
         .write_all("language = \"pl\"\n\
                     author = \"nabijaczleweli\"\n\
+                    title = \"Wódka / depresja?\"\n\
+                    slug = \"wodka-depresja\"\n\
+                    draft = true\n\
+                    toc = false\n\
                     styles = [\"link://nabijaczleweli.xyz/kaschism/assets/column.css\",\n\
                               \"literal:.indented { text-indent: 1em; }\"]\n\
                     \n\
@@ -30,7 +34,11 @@ fn ok_all_specified() {
                     \n\
                     [data]\n\
                     desc = \"Każdy koniec to nowy początek [PL]\"\n\
-                    communism = \"yass, queen\"\n"
+                    communism = \"yass, queen\"\n\
+                    \n\
+                    [markdown]\n\
+                    hardbreaks = false\n\
+                    footnotes = true\n"
             .as_bytes())
         .unwrap();
 
@@ -38,6 +46,8 @@ fn ok_all_specified() {
                Ok(PostMetadata {
                    language: Some("pl".parse().unwrap()),
                    author: Some("nabijaczleweli".to_string()),
+                   title: Some("Wódka / depresja?".to_string()),
+                   slug: Some("wodka-depresja".to_string()),
                    tags: vec![],
                    styles: vec![StyleElement::from_link("//nabijaczleweli.xyz/kaschism/assets/column.css"),
                                 StyleElement::from_literal(".indented { text-indent: 1em; }")],
@@ -45,6 +55,13 @@ fn ok_all_specified() {
                    data: vec![("desc".to_string(), "Każdy koniec to nowy początek [PL]".to_string()), ("communism".to_string(), "yass, queen".to_string())]
                        .into_iter()
                        .collect(),
+                   draft: true,
+                   toc: false,
+                   markdown: MarkdownOptions {
+                       hardbreaks: Some(false),
+                       footnotes: Some(true),
+                       ..Default::default()
+                   },
                }));
 }
 
@@ -65,10 +82,15 @@ fn ok_no_data() {
                Ok(PostMetadata {
                    language: Some("pl".parse().unwrap()),
                    author: Some("nabijaczleweli".to_string()),
+                   title: None,
+                   slug: None,
                    tags: vec![],
                    styles: vec![],
                    scripts: vec![],
                    data: BTreeMap::new(),
+                   draft: false,
+                   toc: true,
+                   markdown: Default::default(),
                }));
 }
 
@@ -101,6 +123,8 @@ fn ok_just_data() {
                Ok(PostMetadata {
                    language: None,
                    author: None,
+                   title: None,
+                   slug: None,
                    tags: vec![],
                    styles: vec![StyleElement::from_link("//nabijaczleweli.xyz/kaschism/assets/column.css"),
                                 StyleElement::from_literal(".indented { text-indent: 1em; }")],
@@ -108,6 +132,9 @@ fn ok_just_data() {
                    data: vec![("desc".to_string(), "Każdy koniec to nowy początek [PL]".to_string()), ("communism".to_string(), "yass, queen".to_string())]
                        .into_iter()
                        .collect(),
+                   draft: false,
+                   toc: true,
+                   markdown: Default::default(),
                }));
 }
 
@@ -197,3 +224,28 @@ fn invalid_toml() {
                    errors: "expected a right bracket, found a newline at line 1 column 13".into(),
                }));
 }
+
+#[test]
+fn invalid_slug() {
+    for &(i, slug, more) in &[(0, "", "slug empty"),
+                              (1, "a/b", "slug \"a/b\" contains path separator"),
+                              (2, "a\\\\b", "slug \"a\\\\b\" contains path separator"),
+                              (3, "..", "slug \"..\" is a relative path component"),
+                              (4, ".", "slug \".\" is a relative path component")] {
+        let post_root = temp_dir().join("bloguen-test").join(format!("ops-metadata-read_or_default-invalid_slug-{}", i));
+        let _ = fs::remove_dir_all(&post_root);
+        fs::create_dir_all(&post_root).unwrap();
+
+        File::create(post_root.join("metadata.toml"))
+            .unwrap()
+            .write_all(format!("slug = \"{}\"\n", slug).as_bytes())
+            .unwrap();
+
+        assert_eq!(PostMetadata::read_or_default(&("$POST_ROOT/".to_string(), post_root)),
+                   Err(Error::Parse {
+                       tp: "path chunk",
+                       wher: "post metadata".into(),
+                       more: more.into(),
+                   }));
+    }
+}
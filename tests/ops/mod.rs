@@ -2,6 +2,11 @@ mod center_order;
 mod machine_data;
 mod descriptor;
 mod feed_type;
+mod manifest;
+mod markdown_options;
 mod metadata;
+mod source_snapshot;
+mod tag_name;
+mod preview_server;
 mod output;
 mod post;
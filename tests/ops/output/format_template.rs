@@ -0,0 +1,327 @@
+use bloguen::ops::{FormatTemplate, ScriptElement, StyleElement, TagName, format_output};
+use bloguen::util::{LANGUAGE_EN_GB, content_hash};
+use std::collections::BTreeMap;
+use std::fs::{self, File};
+use std::env::temp_dir;
+use chrono::DateTime;
+use std::io::Write;
+use bloguen::Error;
+
+
+fn format(template: &str, data: &[(&str, &str)], tags: &[TagName]) -> String {
+    let data: BTreeMap<_, _> = data.iter().map(|&(k, v)| (k.to_string(), v.to_string())).collect();
+    String::from_utf8(format_parsed(&template.parse().unwrap(), &data, tags).unwrap()).unwrap()
+}
+
+fn format_parsed(template: &FormatTemplate, data: &BTreeMap<String, String>, tags: &[TagName]) -> Result<Vec<u8>, Error> {
+    let mut out = vec![];
+    format_output(template,
+                  "Блогг",
+                  &LANGUAGE_EN_GB,
+                  &[data],
+                  &BTreeMap::new(),
+                  "release-front - a generic release front-end, like Patchwork's",
+                  "release-front - a generic release front-end, like Patchwork's",
+                  3,
+                  "release-front - a generic release front-end, like Patchwork's",
+                  "nabijaczleweli",
+                  &DateTime::parse_from_rfc3339("2018-09-06T18:32:22+02:00").unwrap(),
+                  &[tags],
+                  &[],
+                  &[] as &[&[StyleElement]],
+                  &[] as &[&[ScriptElement]],
+                  &mut out,
+                  "test blog")
+        .map(|_| out)
+}
+
+fn parse_error(template: &str, more: &'static str) {
+    assert_eq!(FormatTemplate::parse(template, "test blog"),
+               Err(Error::Parse {
+                   tp: "unformatted input",
+                   wher: "test blog".into(),
+                   more: more.into(),
+               }));
+}
+
+
+#[test]
+fn if_data() {
+    assert_eq!(format("<p>{if data-desc}{data-desc}{end}</p>", &[("desc", "Описание")], &[]), "<p>Описание</p>");
+    assert_eq!(format("<p>{if data-desc}{data-desc}{end}</p>", &[("desc", "")], &[]), "<p></p>");
+    assert_eq!(format("<p>{if data-desc}{data-desc}{end}</p>", &[], &[]), "<p></p>");
+}
+
+#[test]
+fn data_fallback() {
+    assert_eq!(format("<p>{data(desc, No description)}</p>", &[("desc", "Описание")], &[]), "<p>Описание</p>");
+    assert_eq!(format("<p>{data(desc, No description)}</p>", &[("desc", "")], &[]), "<p></p>");
+    assert_eq!(format("<p>{data(desc, No description)}</p>", &[], &[]), "<p>No description</p>");
+    assert_eq!(format("<p>{data(desc, \" quoted \")}</p>", &[], &[]), "<p> quoted </p>");
+    assert_eq!(format("<p>{data(desc)}</p>", &[], &[]), "<p></p>");
+    assert_eq!(format("{if data(desc, fallback)}yes{else}no{end}", &[], &[]), "yes");
+    assert_eq!(format("{if data(desc)}yes{else}no{end}", &[], &[]), "no");
+}
+
+#[test]
+fn has_data() {
+    assert_eq!(format("{if has_data(desc)}yes{else}no{end}", &[("desc", "")], &[]), "yes");
+    assert_eq!(format("{if has_data(desc)}yes{else}no{end}", &[("desc", "Описание")], &[]), "yes");
+    assert_eq!(format("{if has_data(desc)}yes{else}no{end}", &[], &[]), "no");
+}
+
+#[test]
+fn data_order() {
+    let global: BTreeMap<_, _> = vec![("desc".to_string(), "global".to_string()), ("lang".to_string(), "global".to_string())].into_iter().collect();
+    let post: BTreeMap<_, _> = vec![("desc".to_string(), "post".to_string())].into_iter().collect();
+
+    let mut out = vec![];
+    format_output(&"{data(desc, none)} {data(lang, none)} {data(other, none)}".parse().unwrap(),
+                  "Блогг",
+                  &LANGUAGE_EN_GB,
+                  &[&global, &post],
+                  &BTreeMap::new(),
+                  "release-front",
+                  "release-front",
+                  3,
+                  "release-front",
+                  "nabijaczleweli",
+                  &DateTime::parse_from_rfc3339("2018-09-06T18:32:22+02:00").unwrap(),
+                  &[],
+                  &[],
+                  &[] as &[&[StyleElement]],
+                  &[] as &[&[ScriptElement]],
+                  &mut out,
+                  "test blog")
+        .unwrap();
+    assert_eq!(String::from_utf8(out).unwrap(), "post global none");
+}
+
+#[test]
+fn filters() {
+    let data = [("desc", "\"Fish\" & <chips>'s")];
+    assert_eq!(format("{data-desc|html}", &data, &[]), "\"Fish\" &amp; &lt;chips&gt;'s");
+    assert_eq!(format("{data-desc|attr}", &data, &[]), "&#34;Fish&#34; &amp; &lt;chips&gt;&#39;s");
+    assert_eq!(format("{data-desc|xml}", &data, &[]), "&quot;Fish&quot; &amp; &lt;chips&gt;&apos;s");
+    assert_eq!(format("{data-desc|url}", &data, &[]), "%22Fish%22%20%26%20%3Cchips%3E%27s");
+    assert_eq!(format("{data-desc|json}", &data, &[]), "\"\\\"Fish\\\" \\u0026 \\u003cchips\\u003e's\"");
+    assert_eq!(format("{data-desc|raw}", &data, &[]), "\"Fish\" & <chips>'s");
+    assert_eq!(format("{data-desc | url | html}", &data, &[]), "%22Fish%22%20%26%20%3Cchips%3E%27s");
+    assert_eq!(format("{data(desc, a&b)|html}", &[], &[]), "a&amp;b");
+    assert_eq!(format("{for tag in tags}{tag|url}{end}", &[], &["C/C++".parse().unwrap()]), "C%2FC%2B%2B");
+    assert_eq!(format("{date(post, \"%Y|%m\")}", &[], &[]), "2018|09");
+}
+
+#[test]
+fn escape_html_by_default() {
+    let data: BTreeMap<_, _> = vec![("desc".to_string(), "<b>Fish & chips</b>".to_string()), ("post_content".to_string(), "<p>Post</p>".to_string())]
+        .into_iter()
+        .collect();
+    let mut template: FormatTemplate = "{data-desc} {data-desc|raw} {data-desc|attr} {if data-desc}{data-desc}{end} {data-post_content} {tags}"
+        .parse()
+        .unwrap();
+    template.escape_html_by_default();
+
+    assert_eq!(String::from_utf8(format_parsed(&template, &data, &["vodka".parse().unwrap()]).unwrap()).unwrap(),
+               "&lt;b&gt;Fish &amp; chips&lt;/b&gt; <b>Fish & chips</b> &lt;b&gt;Fish &amp; chips&lt;/b&gt; &lt;b&gt;Fish &amp; chips&lt;/b&gt; <p>Post</p> \
+                <span class=\"post-tag\">vodka</span>");
+}
+
+#[test]
+fn if_else() {
+    assert_eq!(format("{if data-desc}{data-desc}{else}{title}{end}", &[], &[]),
+               "release-front - a generic release front-end, like Patchwork's");
+    assert_eq!(format("{if data-desc}{data-desc}{else}{title}{end}", &[("desc", "desc")], &[]), "desc");
+    assert_eq!(format("{if base_url}{base_url}{else}no URL{end}", &[], &[]), "no URL");
+}
+
+#[test]
+fn if_tags() {
+    assert_eq!(format("{if tags}has tags{else}no tags{end}", &[], &[]), "no tags");
+    assert_eq!(format("{if tags}has tags{else}no tags{end}", &[], &["vodka".parse().unwrap()]), "has tags");
+}
+
+#[test]
+fn for_tags() {
+    assert_eq!(format("<ul>{for tag in tags}<li><a href=\"{tag-file_name}.html\">{tag}</a></li>{end}</ul>",
+                      &[],
+                      &["vodka".parse().unwrap(), "C/C++".parse().unwrap()]),
+               "<ul><li><a href=\"vodka.html\">vodka</a></li><li><a href=\"C_C++.html\">C/C++</a></li></ul>");
+    assert_eq!(format("<ul>{for tag in tags}<li>{tag}</li>{end}</ul>", &[], &[]), "<ul></ul>");
+}
+
+#[test]
+fn tags_escaped() {
+    let variables = vec![("tag_index", "../\"tags\"&co/".to_string())].into_iter().collect();
+    let mut out = vec![];
+    format_output(&"{tags}".parse().unwrap(),
+                  "Блогг",
+                  &LANGUAGE_EN_GB,
+                  &[],
+                  &variables,
+                  "release-front - a generic release front-end, like Patchwork's",
+                  "release-front - a generic release front-end, like Patchwork's",
+                  3,
+                  "release-front - a generic release front-end, like Patchwork's",
+                  "nabijaczleweli",
+                  &DateTime::parse_from_rfc3339("2018-09-06T18:32:22+02:00").unwrap(),
+                  &[&["R&B'n'<b>".parse().unwrap()]],
+                  &[],
+                  &[] as &[&[StyleElement]],
+                  &[] as &[&[ScriptElement]],
+                  &mut out,
+                  "test blog")
+        .unwrap();
+    assert_eq!(String::from_utf8(out).unwrap(),
+               "<a href=\"../&#34;tags&#34;&amp;co/R&amp;B&#39;n&#39;_b_.html\"><span class=\"post-tag\">R&amp;B'n'&lt;b&gt;</span></a>");
+}
+
+#[test]
+fn nested() {
+    assert_eq!(format("{for t in tags}{if data-sep}{t}{data-sep}{else}{t};{end}{end}",
+                      &[("sep", ", ")],
+                      &["vodka".parse().unwrap(), "beer".parse().unwrap()]),
+               "vodka, beer, ");
+}
+
+#[test]
+fn escapes() {
+    assert_eq!(format("{{if data-desc}}{if data-desc}{{{data-desc}}}{end}", &[("desc", "d")], &[]), "{if data-desc}{d}");
+}
+
+#[test]
+fn else_without_if() {
+    parse_error("abc{else}", "{else} without matching {if} at position 3");
+    parse_error("{for t in tags}{else}{end}", "{else} without matching {if} at position 15");
+    parse_error("{if title}{else}{else}{end}", "{else} without matching {if} at position 16");
+}
+
+#[test]
+fn end_without_block() {
+    parse_error("abc{end}", "{end} without matching {if} or {for} at position 3");
+    parse_error("{if title}{end}{end}", "{end} without matching {if} or {for} at position 15");
+}
+
+#[test]
+fn unterminated() {
+    parse_error("abc{if title}", "unterminated {if} at position 3");
+    parse_error("abc{for t in tags}{if title}{end}", "unterminated {for} at position 3");
+}
+
+#[test]
+fn if_without_condition() {
+    parse_error("abc{if}{end}", "{if} without condition at position 3");
+}
+
+#[test]
+fn invalid_for() {
+    parse_error("{for t of tags}{end}", "{for t of tags} is an invalid loop, expected {for variable in collection}, at position 0");
+    parse_error("{for t in posts}{end}", "posts is an unrecognised loop collection (accepted: tags), at position 0");
+}
+
+#[test]
+fn invalid_function() {
+    parse_error("{title|bold}", "bold is an unrecognised filter (accepted: html, attr, xml, url, json, raw), around position 12");
+    parse_error("{include(nav.html)|html}", "filters applied to include() at position 24");
+    parse_error("{data()}", "0 is an invalid amount of arguments to `data(name, [fallback])` function, around position 8");
+    parse_error("{has_data(a, b)}", "2 is an invalid amount of arguments to one-argument `has_data(name)` function, around position 16");
+    parse_error("{if title}{date(post)}{end}", "1 is an invalid amount of arguments to two-argument `date(of_what, format)` function, around position 22");
+    parse_error("{reading_time(0)}", "0 is an invalid reading speed for `reading_time([words-per-minute])` function, around position 17");
+    parse_error("{reading_time(1, 2)}", "2 is an invalid amount of arguments to one-argument `reading_time([words-per-minute])` function, around position 20");
+    parse_error("{related_posts()}", "0 is an invalid amount of arguments to one-argument `related_posts(count)` function, around position 17");
+    parse_error("{related_posts(many)}",
+                "many is an invalid post count for `related_posts(count)` function, required: unsigned integer, reason: invalid digit found in string, \
+                 around position 21");
+}
+
+#[test]
+fn include() {
+    let root = temp_dir().join("bloguen-test").join("ops-output-format_template-include");
+    let _ = fs::remove_dir_all(&root);
+    fs::create_dir_all(root.join("partials")).unwrap();
+    File::create(root.join("partials").join("nav.html")).unwrap().write_all(b"<nav>{blog_name}{include(partials/../tags.html)}</nav>").unwrap();
+    File::create(root.join("tags.html")).unwrap().write_all(b"{for tag in tags}[{tag}]{end}{if data-desc}{data-desc}{end}").unwrap();
+
+    let template = FormatTemplate::parse_with_includes("<html>{include(partials/nav.html)}{title}", &("$ROOT/".to_string(), root), "test blog").unwrap();
+    assert_eq!(String::from_utf8(format_parsed(&template, &BTreeMap::new(), &["vodka".parse().unwrap()]).unwrap()).unwrap(),
+               "<html><nav>Блогг[vodka]</nav>release-front - a generic release front-end, like Patchwork's");
+}
+
+#[test]
+fn includes() {
+    let root = temp_dir().join("bloguen-test").join("ops-output-format_template-includes");
+    let _ = fs::remove_dir_all(&root);
+    fs::create_dir_all(root.join("partials")).unwrap();
+    File::create(root.join("partials").join("nav.html")).unwrap().write_all(b"<nav>{include(partials/logo.html)}</nav>").unwrap();
+    File::create(root.join("partials").join("logo.html")).unwrap().write_all(b"<img src=\"logo.png\">").unwrap();
+    File::create(root.join("tags.html")).unwrap().write_all(b"{for tag in tags}[{tag}]{end}").unwrap();
+    let root = ("$ROOT/".to_string(), root);
+
+    let template = FormatTemplate::parse_with_includes("{if title}{include(partials/nav.html)}{else}{include(tags.html)}{end}{include(tags.html)}",
+                                                       &root,
+                                                       "test blog")
+        .unwrap();
+    assert_eq!(template.includes(),
+               vec![root.1.join("partials").join("nav.html"), root.1.join("partials").join("logo.html"), root.1.join("tags.html"), root.1.join("tags.html")]);
+
+    let hash = content_hash(&template);
+    File::create(root.1.join("partials").join("logo.html")).unwrap().write_all(b"<img src=\"logo.svg\">").unwrap();
+    let changed = FormatTemplate::parse_with_includes("{if title}{include(partials/nav.html)}{else}{include(tags.html)}{end}{include(tags.html)}",
+                                                      &root,
+                                                      "test blog")
+        .unwrap();
+    assert_eq!(changed.includes(), template.includes());
+    assert_ne!(content_hash(&changed), hash);
+}
+
+#[test]
+fn include_error_name() {
+    let root = temp_dir().join("bloguen-test").join("ops-output-format_template-include_error_name");
+    let _ = fs::remove_dir_all(&root);
+    fs::create_dir_all(&root).unwrap();
+    File::create(root.join("nav.html")).unwrap().write_all(b"{data-missing}").unwrap();
+    File::create(root.join("broken.html")).unwrap().write_all(b"{if title}").unwrap();
+    let root = ("$ROOT/".to_string(), root);
+
+    let template = FormatTemplate::parse_with_includes("{include(nav.html)}", &root, "test blog").unwrap();
+    assert_eq!(format_parsed(&template, &BTreeMap::new(), &[]),
+               Err(Error::Parse {
+                   tp: "unformatted input",
+                   wher: "$ROOT/nav.html".into(),
+                   more: "missing value for data-missing".into(),
+               }));
+
+    assert_eq!(FormatTemplate::parse_with_includes("{include(broken.html)}", &root, "test blog"),
+               Err(Error::Parse {
+                   tp: "unformatted input",
+                   wher: "$ROOT/broken.html".into(),
+                   more: "unterminated {if} at position 0".into(),
+               }));
+    assert_eq!(FormatTemplate::parse_with_includes("{include(nonexistant.html)}", &root, "test blog"),
+               Err(Error::FileNotFound {
+                   who: "included template",
+                   path: "$ROOT/nonexistant.html".into(),
+               }));
+}
+
+#[test]
+fn include_cycle() {
+    let root = temp_dir().join("bloguen-test").join("ops-output-format_template-include_cycle");
+    let _ = fs::remove_dir_all(&root);
+    fs::create_dir_all(&root).unwrap();
+    File::create(root.join("a.html")).unwrap().write_all(b"{include(b.html)}").unwrap();
+    File::create(root.join("b.html")).unwrap().write_all(b"b{include(./a.html)}").unwrap();
+
+    assert_eq!(FormatTemplate::parse_with_includes("{include(a.html)}", &("$ROOT/".to_string(), root), "test blog"),
+               Err(Error::Parse {
+                   tp: "unformatted input",
+                   wher: "$ROOT/b.html".into(),
+                   more: "include cycle $ROOT/a.html -> $ROOT/b.html -> $ROOT/./a.html around position 20".into(),
+               }));
+}
+
+#[test]
+fn include_without_root() {
+    parse_error("{include(nav.html)}", "include(nav.html) without a blog root to resolve it against, around position 19");
+    parse_error("{include(nav.html, tags.html)}", "2 is an invalid amount of arguments to one-argument `include(path)` function, around position 30");
+}
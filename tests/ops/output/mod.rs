@@ -1,2 +1,3 @@
 mod paragraph_passer;
 mod wrapped_element;
+mod format_template;
@@ -0,0 +1,62 @@
+use bloguen::ops::BloguePost;
+use std::env::temp_dir;
+use bloguen::Error;
+use std::fs;
+
+
+fn posts(root: &str, names: &[&str]) -> Vec<BloguePost> {
+    let root = temp_dir().join("bloguen-test").join(root);
+    let _ = fs::remove_dir_all(&root);
+    names.iter()
+        .map(|name| {
+            fs::create_dir_all(root.join("posts").join(name)).unwrap();
+            BloguePost::new((format!("$ROOT/posts/{}/", name), root.join("posts").join(name))).unwrap()
+        })
+        .collect()
+}
+
+
+#[test]
+fn unique() {
+    let mut posts = posts("ops-post-check_unique_paths-unique", &["1. 2018-01-08 16-52 Speakers", "2. 2018-01-09 10-00 Amps", "3. 2018-06-01 Cables"]);
+    for p in &mut posts {
+        p.apply_permalink_pattern("{year}/{month}/{slug}.html");
+    }
+
+    assert_eq!(BloguePost::check_unique_paths(&posts), Ok(()));
+}
+
+#[test]
+fn same_slug() {
+    let mut posts = posts("ops-post-check_unique_paths-same_slug", &["1. 2018-01-08 16-52 Speakers", "2. 2018-01-09 10-00 Amps", "3. 2018-06-01 Cables"]);
+    for p in &mut posts {
+        p.slug = "audio".to_string();
+        p.apply_permalink_pattern("posts/{slug}.html");
+    }
+
+    assert_eq!(BloguePost::check_unique_paths(&posts),
+               Err(Error::Parse {
+                   tp: "path chunk",
+                   wher: "blogue posts".into(),
+                   more: "posts \"1. 2018-01-08 16-52-00 Speakers\" and \"2. 2018-01-09 10-00-00 Amps\" both output to \"posts/audio.html\"".into(),
+               }));
+}
+
+#[test]
+fn same_slug_different_month() {
+    let mut posts = posts("ops-post-check_unique_paths-same_slug_different_month", &["1. 2018-01-08 16-52 Speakers", "2. 2018-06-01 10-00 Speakers"]);
+    for p in &mut posts {
+        p.apply_permalink_pattern("{year}/{month}/speakers.html");
+    }
+    assert_eq!(BloguePost::check_unique_paths(&posts), Ok(()));
+
+    for p in &mut posts {
+        p.apply_permalink_pattern("{year}/speakers.html");
+    }
+    assert_eq!(BloguePost::check_unique_paths(&posts),
+               Err(Error::Parse {
+                   tp: "path chunk",
+                   wher: "blogue posts".into(),
+                   more: "posts \"1. 2018-01-08 16-52-00 Speakers\" and \"2. 2018-06-01 10-00-00 Speakers\" both output to \"2018/speakers.html\"".into(),
+               }));
+}
@@ -35,8 +35,9 @@ fn ok_copied() {
                   None,
                   None,
                   None,
-                  "header",
-                  "footer",
+                  Default::default(),
+                  &"header".parse().unwrap(),
+                  &"footer".parse().unwrap(),
                   "Блогг",
                   &LANGUAGE_EN_GB,
                   "autheur",
@@ -69,8 +70,9 @@ fn ok_copied() {
                   None,
                   None,
                   None,
-                  "header",
-                  "footer",
+                  Default::default(),
+                  &"header".parse().unwrap(),
+                  &"footer".parse().unwrap(),
                   "Блогг",
                   &LANGUAGE_EN_GB,
                   "autheur",
@@ -102,8 +104,9 @@ fn ok_copied() {
                   None,
                   None,
                   None,
-                  "header",
-                  "footer",
+                  Default::default(),
+                  &"header".parse().unwrap(),
+                  &"footer".parse().unwrap(),
                   "Блогг",
                   &LANGUAGE_EN_GB,
                   "autheur",
@@ -153,8 +156,9 @@ fn ok_not_copied() {
                   None,
                   None,
                   None,
-                  "header",
-                  "footer",
+                  Default::default(),
+                  &"header".parse().unwrap(),
+                  &"footer".parse().unwrap(),
                   "Блогг",
                   &LANGUAGE_EN_GB,
                   "autheur",
@@ -180,8 +184,9 @@ fn ok_not_copied() {
                   None,
                   None,
                   None,
-                  "header",
-                  "footer",
+                  Default::default(),
+                  &"header".parse().unwrap(),
+                  &"footer".parse().unwrap(),
                   "Блогг",
                   &LANGUAGE_EN_GB,
                   "autheur",
@@ -206,8 +211,9 @@ fn ok_not_copied() {
                   None,
                   None,
                   None,
-                  "header",
-                  "footer",
+                  Default::default(),
+                  &"header".parse().unwrap(),
+                  &"footer".parse().unwrap(),
                   "Блогг",
                   &LANGUAGE_EN_GB,
                   "autheur",
@@ -305,8 +311,9 @@ fn copy_failed() {
                   None,
                   None,
                   None,
-                  "header",
-                  "footer",
+                  Default::default(),
+                  &"header".parse().unwrap(),
+                  &"footer".parse().unwrap(),
                   "Блогг",
                   &LANGUAGE_EN_GB,
                   "autheur",
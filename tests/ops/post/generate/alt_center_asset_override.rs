@@ -1,5 +1,5 @@
 use bloguen::util::LANGUAGE_EN_GB;
-use bloguen::ops::BloguePost;
+use bloguen::ops::{PostRenderOptions, BloguePost};
 use std::io::{Write, Read};
 use std::fs::{self, File};
 use std::env::temp_dir;
@@ -25,11 +25,12 @@ fn ok() {
     let mut alt_buf = vec![];
     let mut center_buf = vec![];
     assert_eq!(post.generate(&("$ROOT/out/".to_string(), root.join("out")),
-                             Some(&mut alt_buf as &mut dyn Write),
-                             Some(("cen{data-post_content}ter", &mut center_buf as &mut dyn Write)),
+                             Some((&mut alt_buf as &mut dyn Write, false)),
+                             Some((&"cen{data-post_content}ter".parse().unwrap(), &mut center_buf as &mut dyn Write)),
                              Some("overriden-assets"),
-                             "header",
-                             "footer",
+                             Default::default(),
+                             &"header".parse().unwrap(),
+                             &"footer".parse().unwrap(),
                              "Блогг",
                              &LANGUAGE_EN_GB,
                              "autheur",
@@ -57,11 +58,12 @@ fn ok() {
     alt_buf.clear();
     center_buf.clear();
     assert_eq!(post.generate(&("$ROOT/out/".to_string(), root.join("out")),
-                             Some(&mut alt_buf as &mut dyn Write),
-                             Some(("cen{data-post_content}ter", &mut center_buf as &mut dyn Write)),
+                             Some((&mut alt_buf as &mut dyn Write, false)),
+                             Some((&"cen{data-post_content}ter".parse().unwrap(), &mut center_buf as &mut dyn Write)),
                              Some("overriden-assets"),
-                             "header",
-                             "footer",
+                             Default::default(),
+                             &"header".parse().unwrap(),
+                             &"footer".parse().unwrap(),
                              "Блогг",
                              &LANGUAGE_EN_GB,
                              "autheur",
@@ -89,11 +91,12 @@ fn ok() {
     alt_buf.clear();
     center_buf.clear();
     assert_eq!(post.generate(&("$ROOT/out/".to_string(), root.join("out")),
-                             Some(&mut alt_buf as &mut dyn Write),
-                             Some(("cen{data-post_content}ter", &mut center_buf as &mut dyn Write)),
+                             Some((&mut alt_buf as &mut dyn Write, false)),
+                             Some((&"cen{data-post_content}ter".parse().unwrap(), &mut center_buf as &mut dyn Write)),
                              Some("overriden-assets"),
-                             "header",
-                             "footer",
+                             Default::default(),
+                             &"header".parse().unwrap(),
+                             &"footer".parse().unwrap(),
                              "Блогг",
                              &LANGUAGE_EN_GB,
                              "autheur",
@@ -130,11 +133,12 @@ fn not_found() {
     let mut alt_buf = vec![];
     let mut center_buf = vec![];
     assert_eq!(post.generate(&("$ROOT/out/".to_string(), root.join("out")),
-                             Some(&mut alt_buf as &mut dyn Write),
-                             Some(("cen{data-post_content}ter", &mut center_buf as &mut dyn Write)),
+                             Some((&mut alt_buf as &mut dyn Write, false)),
+                             Some((&"cen{data-post_content}ter".parse().unwrap(), &mut center_buf as &mut dyn Write)),
                              Some("overriden-assets"),
-                             "header",
-                             "footer",
+                             Default::default(),
+                             &"header".parse().unwrap(),
+                             &"footer".parse().unwrap(),
                              "Блогг",
                              &LANGUAGE_EN_GB,
                              "autheur",
@@ -173,11 +177,12 @@ fn non_utf8() {
     let mut alt_buf = vec![];
     let mut center_buf = vec![];
     assert_eq!(post.generate(&("$ROOT/out/".to_string(), root.join("out")),
-                             Some(&mut alt_buf as &mut dyn Write),
-                             Some(("cen{data-post_content}ter", &mut center_buf as &mut dyn Write)),
+                             Some((&mut alt_buf as &mut dyn Write, false)),
+                             Some((&"cen{data-post_content}ter".parse().unwrap(), &mut center_buf as &mut dyn Write)),
                              Some("overriden-assets"),
-                             "header",
-                             "footer",
+                             Default::default(),
+                             &"header".parse().unwrap(),
+                             &"footer".parse().unwrap(),
                              "Блогг",
                              &LANGUAGE_EN_GB,
                              "autheur",
@@ -216,11 +221,12 @@ fn posts_directory() {
     let mut alt_buf = vec![];
     let mut center_buf = vec![];
     assert_eq!(post.generate(&("$ROOT/out/".to_string(), root.join("out")),
-                             Some(&mut alt_buf as &mut dyn Write),
-                             Some(("cen{data-post_content}ter", &mut center_buf as &mut dyn Write)),
+                             Some((&mut alt_buf as &mut dyn Write, false)),
+                             Some((&"cen{data-post_content}ter".parse().unwrap(), &mut center_buf as &mut dyn Write)),
                              Some("overriden-assets"),
-                             "header",
-                             "footer",
+                             Default::default(),
+                             &"header".parse().unwrap(),
+                             &"footer".parse().unwrap(),
                              "Блогг",
                              &LANGUAGE_EN_GB,
                              "autheur",
@@ -263,11 +269,12 @@ fn post_create() {
     let mut alt_buf = vec![];
     let mut center_buf = vec![];
     assert_eq!(post.generate(&("$ROOT/out/".to_string(), root.join("out")),
-                             Some(&mut alt_buf as &mut dyn Write),
-                             Some(("cen{data-post_content}ter", &mut center_buf as &mut dyn Write)),
+                             Some((&mut alt_buf as &mut dyn Write, false)),
+                             Some((&"cen{data-post_content}ter".parse().unwrap(), &mut center_buf as &mut dyn Write)),
                              Some("overriden-assets"),
-                             "header",
-                             "footer",
+                             Default::default(),
+                             &"header".parse().unwrap(),
+                             &"footer".parse().unwrap(),
                              "Блогг",
                              &LANGUAGE_EN_GB,
                              "autheur",
@@ -292,3 +299,60 @@ fn post_create() {
     assert!(alt_buf.is_empty());
     assert!(center_buf.is_empty());
 }
+
+#[test]
+fn nested_permalink() {
+    let root = temp_dir().join("bloguen-test").join("ops-post-generate-alt_center_asset_override-nested_permalink");
+    let _ = fs::remove_dir_all(&root);
+    let post_root = root.join("posts").join("1. 2018-01-08 16-52 Big speakers");
+    fs::create_dir_all(post_root.join("assets")).unwrap();
+    File::create(post_root.join("post.md")).unwrap().write_all("![img](assets/image.png)".as_bytes()).unwrap();
+    File::create(post_root.join("assets").join("image.png")).unwrap();
+
+    let mut post = BloguePost::new(("$ROOT/posts/1. 2018-01-08 16-52 Big speakers/".to_string(), post_root)).unwrap();
+    post.slug = "big-speakers".to_string();
+    post.apply_permalink_pattern("posts/{year}/{month}/{slug}/index.html");
+    let mut alt_buf = vec![];
+    let mut center_buf = vec![];
+    let mut paged_center_buf = vec![];
+    let mut tag_center_buf = vec![];
+    assert_eq!(post.generate(&("$ROOT/out/".to_string(), root.join("out")),
+                             Some((&mut alt_buf as &mut dyn Write, false)),
+                             Some((&"cen{data-post_content}ter".parse().unwrap(), &mut center_buf as &mut dyn Write)),
+                             Some("overriden-assets/"),
+                             PostRenderOptions {
+                                 paged_center_output: Some(&mut paged_center_buf as &mut dyn Write),
+                                 tag_center_output: Some((&"tag{data-post_content}ter".parse().unwrap(), &mut tag_center_buf as &mut dyn Write)),
+                                 ..Default::default()
+                             },
+                             &"header".parse().unwrap(),
+                             &"footer".parse().unwrap(),
+                             "Блогг",
+                             &LANGUAGE_EN_GB,
+                             "autheur",
+                             &[],
+                             &[],
+                             &Default::default(),
+                             &Default::default(),
+                             &[],
+                             &[],
+                             &[],
+                             &[]),
+               Ok(vec!["assets/image.png".to_string()]));
+
+    let mut read = String::new();
+    File::open(root.join("out").join("posts").join("2018").join("01").join("big-speakers").join("index.html"))
+        .unwrap()
+        .read_to_string(&mut read)
+        .unwrap();
+    assert_eq!(read,
+               "header<p><img src=\"../../../../overriden-assets/assets/image.png\" alt=\"img\" /></p>\nfooter");
+    assert_eq!(str::from_utf8(&alt_buf).unwrap(),
+               "<p><img src=\"../../../../overriden-assets/assets/image.png\" alt=\"img\" /></p>\n");
+    assert_eq!(str::from_utf8(&center_buf).unwrap(),
+               "cen<p><img src=\"overriden-assets/assets/image.png\" alt=\"img\" /></p>\nter");
+    assert_eq!(str::from_utf8(&paged_center_buf).unwrap(),
+               "cen<p><img src=\"../overriden-assets/assets/image.png\" alt=\"img\" /></p>\nter");
+    assert_eq!(str::from_utf8(&tag_center_buf).unwrap(),
+               "tag<p><img src=\"../overriden-assets/assets/image.png\" alt=\"img\" /></p>\nter");
+}
@@ -25,11 +25,12 @@ fn ok() {
     let mut alt_buf = vec![];
     let mut center_buf = vec![];
     assert_eq!(post.generate(&("$ROOT/out/".to_string(), root.join("out")),
-                             Some(&mut alt_buf as &mut dyn Write),
-                             Some(("cen{data-post_content}ter", &mut center_buf as &mut dyn Write)),
+                             Some((&mut alt_buf as &mut dyn Write, false)),
+                             Some((&"cen{data-post_content}ter".parse().unwrap(), &mut center_buf as &mut dyn Write)),
                              None,
-                             "header",
-                             "footer",
+                             Default::default(),
+                             &"header".parse().unwrap(),
+                             &"footer".parse().unwrap(),
                              "Блогг",
                              &LANGUAGE_EN_GB,
                              "autheur",
@@ -57,11 +58,12 @@ fn ok() {
     alt_buf.clear();
     center_buf.clear();
     assert_eq!(post.generate(&("$ROOT/out/".to_string(), root.join("out")),
-                             Some(&mut alt_buf as &mut dyn Write),
-                             Some(("cen{data-post_content}ter", &mut center_buf as &mut dyn Write)),
+                             Some((&mut alt_buf as &mut dyn Write, false)),
+                             Some((&"cen{data-post_content}ter".parse().unwrap(), &mut center_buf as &mut dyn Write)),
                              None,
-                             "header",
-                             "footer",
+                             Default::default(),
+                             &"header".parse().unwrap(),
+                             &"footer".parse().unwrap(),
                              "Блогг",
                              &LANGUAGE_EN_GB,
                              "autheur",
@@ -89,11 +91,12 @@ fn ok() {
     alt_buf.clear();
     center_buf.clear();
     assert_eq!(post.generate(&("$ROOT/out/".to_string(), root.join("out")),
-                             Some(&mut alt_buf as &mut dyn Write),
-                             Some(("cen{data-post_content}ter", &mut center_buf as &mut dyn Write)),
+                             Some((&mut alt_buf as &mut dyn Write, false)),
+                             Some((&"cen{data-post_content}ter".parse().unwrap(), &mut center_buf as &mut dyn Write)),
                              None,
-                             "header",
-                             "footer",
+                             Default::default(),
+                             &"header".parse().unwrap(),
+                             &"footer".parse().unwrap(),
                              "Блогг",
                              &LANGUAGE_EN_GB,
                              "autheur",
@@ -130,11 +133,12 @@ fn not_found() {
     let mut alt_buf = vec![];
     let mut center_buf = vec![];
     assert_eq!(post.generate(&("$ROOT/out/".to_string(), root.join("out")),
-                             Some(&mut alt_buf as &mut dyn Write),
-                             Some(("cen{data-post_content}ter", &mut center_buf as &mut dyn Write)),
+                             Some((&mut alt_buf as &mut dyn Write, false)),
+                             Some((&"cen{data-post_content}ter".parse().unwrap(), &mut center_buf as &mut dyn Write)),
                              None,
-                             "header",
-                             "footer",
+                             Default::default(),
+                             &"header".parse().unwrap(),
+                             &"footer".parse().unwrap(),
                              "Блогг",
                              &LANGUAGE_EN_GB,
                              "autheur",
@@ -173,11 +177,12 @@ fn non_utf8() {
     let mut alt_buf = vec![];
     let mut center_buf = vec![];
     assert_eq!(post.generate(&("$ROOT/out/".to_string(), root.join("out")),
-                             Some(&mut alt_buf as &mut dyn Write),
-                             Some(("cen{data-post_content}ter", &mut center_buf as &mut dyn Write)),
+                             Some((&mut alt_buf as &mut dyn Write, false)),
+                             Some((&"cen{data-post_content}ter".parse().unwrap(), &mut center_buf as &mut dyn Write)),
                              None,
-                             "header",
-                             "footer",
+                             Default::default(),
+                             &"header".parse().unwrap(),
+                             &"footer".parse().unwrap(),
                              "Блогг",
                              &LANGUAGE_EN_GB,
                              "autheur",
@@ -216,11 +221,12 @@ fn posts_directory() {
     let mut alt_buf = vec![];
     let mut center_buf = vec![];
     assert_eq!(post.generate(&("$ROOT/out/".to_string(), root.join("out")),
-                             Some(&mut alt_buf as &mut dyn Write),
-                             Some(("cen{data-post_content}ter", &mut center_buf as &mut dyn Write)),
+                             Some((&mut alt_buf as &mut dyn Write, false)),
+                             Some((&"cen{data-post_content}ter".parse().unwrap(), &mut center_buf as &mut dyn Write)),
                              None,
-                             "header",
-                             "footer",
+                             Default::default(),
+                             &"header".parse().unwrap(),
+                             &"footer".parse().unwrap(),
                              "Блогг",
                              &LANGUAGE_EN_GB,
                              "autheur",
@@ -263,11 +269,12 @@ fn post_create() {
     let mut alt_buf = vec![];
     let mut center_buf = vec![];
     assert_eq!(post.generate(&("$ROOT/out/".to_string(), root.join("out")),
-                             Some(&mut alt_buf as &mut dyn Write),
-                             Some(("cen{data-post_content}ter", &mut center_buf as &mut dyn Write)),
+                             Some((&mut alt_buf as &mut dyn Write, false)),
+                             Some((&"cen{data-post_content}ter".parse().unwrap(), &mut center_buf as &mut dyn Write)),
                              None,
-                             "header",
-                             "footer",
+                             Default::default(),
+                             &"header".parse().unwrap(),
+                             &"footer".parse().unwrap(),
                              "Блогг",
                              &LANGUAGE_EN_GB,
                              "autheur",
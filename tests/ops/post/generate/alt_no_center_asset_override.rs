@@ -24,11 +24,12 @@ fn ok() {
     let post = BloguePost::new(dir.clone()).unwrap();
     let mut alt_buf = vec![];
     assert_eq!(post.generate(&("$ROOT/out/".to_string(), root.join("out")),
-                             Some(&mut alt_buf as &mut dyn Write),
+                             Some((&mut alt_buf as &mut dyn Write, false)),
                              None,
                              Some("overriden-assets"),
-                             "header",
-                             "footer",
+                             Default::default(),
+                             &"header".parse().unwrap(),
+                             &"footer".parse().unwrap(),
                              "Блогг",
                              &LANGUAGE_EN_GB,
                              "autheur",
@@ -53,11 +54,12 @@ fn ok() {
     let post = BloguePost::new(dir.clone()).unwrap();
     alt_buf.clear();
     assert_eq!(post.generate(&("$ROOT/out/".to_string(), root.join("out")),
-                             Some(&mut alt_buf as &mut dyn Write),
+                             Some((&mut alt_buf as &mut dyn Write, false)),
                              None,
                              Some("overriden-assets"),
-                             "header",
-                             "footer",
+                             Default::default(),
+                             &"header".parse().unwrap(),
+                             &"footer".parse().unwrap(),
                              "Блогг",
                              &LANGUAGE_EN_GB,
                              "autheur",
@@ -82,11 +84,12 @@ fn ok() {
     let post = BloguePost::new(dir.clone()).unwrap();
     alt_buf.clear();
     assert_eq!(post.generate(&("$ROOT/out/".to_string(), root.join("out")),
-                             Some(&mut alt_buf as &mut dyn Write),
+                             Some((&mut alt_buf as &mut dyn Write, false)),
                              None,
                              Some("overriden-assets"),
-                             "header",
-                             "footer",
+                             Default::default(),
+                             &"header".parse().unwrap(),
+                             &"footer".parse().unwrap(),
                              "Блогг",
                              &LANGUAGE_EN_GB,
                              "autheur",
@@ -120,11 +123,12 @@ fn not_found() {
     let post = BloguePost::new(dir.clone()).unwrap();
     let mut alt_buf = vec![];
     assert_eq!(post.generate(&("$ROOT/out/".to_string(), root.join("out")),
-                             Some(&mut alt_buf as &mut dyn Write),
+                             Some((&mut alt_buf as &mut dyn Write, false)),
                              None,
                              Some("overriden-assets"),
-                             "header",
-                             "footer",
+                             Default::default(),
+                             &"header".parse().unwrap(),
+                             &"footer".parse().unwrap(),
                              "Блогг",
                              &LANGUAGE_EN_GB,
                              "autheur",
@@ -161,11 +165,12 @@ fn non_utf8() {
     let post = BloguePost::new(dir.clone()).unwrap();
     let mut alt_buf = vec![];
     assert_eq!(post.generate(&("$ROOT/out/".to_string(), root.join("out")),
-                             Some(&mut alt_buf as &mut dyn Write),
+                             Some((&mut alt_buf as &mut dyn Write, false)),
                              None,
                              Some("overriden-assets"),
-                             "header",
-                             "footer",
+                             Default::default(),
+                             &"header".parse().unwrap(),
+                             &"footer".parse().unwrap(),
                              "Блогг",
                              &LANGUAGE_EN_GB,
                              "autheur",
@@ -202,11 +207,12 @@ fn posts_directory() {
     File::create(root.join("out").join("posts")).unwrap().write_all("henlo".as_bytes()).unwrap();
     let mut alt_buf = vec![];
     assert_eq!(post.generate(&("$ROOT/out/".to_string(), root.join("out")),
-                             Some(&mut alt_buf as &mut dyn Write),
+                             Some((&mut alt_buf as &mut dyn Write, false)),
                              None,
                              Some("overriden-assets"),
-                             "header",
-                             "footer",
+                             Default::default(),
+                             &"header".parse().unwrap(),
+                             &"footer".parse().unwrap(),
                              "Блогг",
                              &LANGUAGE_EN_GB,
                              "autheur",
@@ -247,11 +253,12 @@ fn post_create() {
     fs::create_dir_all(root.join("out").join("posts").join("1. 2018-01-08 16-52-00 My first venture into crocheting, and what I've learned.html")).unwrap();
     let mut alt_buf = vec![];
     assert_eq!(post.generate(&("$ROOT/out/".to_string(), root.join("out")),
-                             Some(&mut alt_buf as &mut dyn Write),
+                             Some((&mut alt_buf as &mut dyn Write, false)),
                              None,
                              Some("overriden-assets"),
-                             "header",
-                             "footer",
+                             Default::default(),
+                             &"header".parse().unwrap(),
+                             &"footer".parse().unwrap(),
                              "Блогг",
                              &LANGUAGE_EN_GB,
                              "autheur",
@@ -0,0 +1,124 @@
+use bloguen::util::LANGUAGE_EN_GB;
+use bloguen::ops::BloguePost;
+use std::io::{Write, Read};
+use std::fs::{self, File};
+use std::env::temp_dir;
+use std::str;
+
+
+#[test]
+fn separator() {
+    let root = temp_dir().join("bloguen-test").join("ops-post-generate-excerpt-separator");
+    let _ = fs::remove_dir_all(&root);
+    let post_root = root.join("posts").join("1. 2018-01-08 16-52 Big speakers");
+    fs::create_dir_all(&post_root).unwrap();
+    File::create(post_root.join("post.md")).unwrap().write_all("Small speakers\n\nAre loud\n<!-- more -->\n\nBut not that loud".as_bytes()).unwrap();
+
+    let post = BloguePost::new(("$ROOT/posts/1. 2018-01-08 16-52 Big speakers/".to_string(), post_root)).unwrap();
+    let mut alt_buf = vec![];
+    let mut center_buf = vec![];
+    assert_eq!(post.generate(&("$ROOT/out/".to_string(), root.join("out")),
+                             Some((&mut alt_buf as &mut dyn Write, false)),
+                             Some((&"{excerpt}|{excerpt|html}".parse().unwrap(), &mut center_buf as &mut dyn Write)),
+                             None,
+                             Default::default(),
+                             &"{excerpt}|".parse().unwrap(),
+                             &"".parse().unwrap(),
+                             "Блогг",
+                             &LANGUAGE_EN_GB,
+                             "autheur",
+                             &[],
+                             &[],
+                             &Default::default(),
+                             &Default::default(),
+                             &[],
+                             &[],
+                             &[],
+                             &[]),
+               Ok(vec![]));
+
+    let mut read = String::new();
+    File::open(root.join("out").join("posts").join(post.normalised_name() + ".html")).unwrap().read_to_string(&mut read).unwrap();
+    assert_eq!(read,
+               "<p>Small speakers</p>\n<p>Are loud</p>\n|\
+                <p>Small speakers</p>\n<p>Are loud</p>\n<!-- more -->\n<p>But not that loud</p>\n");
+    assert_eq!(str::from_utf8(&alt_buf).unwrap(), "<p>Small speakers</p>\n<p>Are loud</p>\n<!-- more -->\n<p>But not that loud</p>\n");
+    assert_eq!(str::from_utf8(&center_buf).unwrap(),
+               "<p>Small speakers</p>\n<p>Are loud</p>\n|\
+                &lt;p&gt;Small speakers&lt;/p&gt;\n&lt;p&gt;Are loud&lt;/p&gt;\n");
+}
+
+#[test]
+fn fallback() {
+    let root = temp_dir().join("bloguen-test").join("ops-post-generate-excerpt-fallback");
+    let _ = fs::remove_dir_all(&root);
+    let post_root = root.join("posts").join("1. 2018-01-08 16-52 Big speakers");
+    fs::create_dir_all(&post_root).unwrap();
+    File::create(post_root.join("post.md")).unwrap().write_all("Small speakers\n\nAre loud".as_bytes()).unwrap();
+
+    let post = BloguePost::new(("$ROOT/posts/1. 2018-01-08 16-52 Big speakers/".to_string(), post_root)).unwrap();
+    let mut alt_buf = vec![];
+    let mut center_buf = vec![];
+    assert_eq!(post.generate(&("$ROOT/out/".to_string(), root.join("out")),
+                             Some((&mut alt_buf as &mut dyn Write, false)),
+                             Some((&"{excerpt}|{excerpt|html}".parse().unwrap(), &mut center_buf as &mut dyn Write)),
+                             None,
+                             Default::default(),
+                             &"{excerpt}|".parse().unwrap(),
+                             &"".parse().unwrap(),
+                             "Блогг",
+                             &LANGUAGE_EN_GB,
+                             "autheur",
+                             &[],
+                             &[],
+                             &Default::default(),
+                             &Default::default(),
+                             &[],
+                             &[],
+                             &[],
+                             &[]),
+               Ok(vec![]));
+
+    let mut read = String::new();
+    File::open(root.join("out").join("posts").join(post.normalised_name() + ".html")).unwrap().read_to_string(&mut read).unwrap();
+    assert_eq!(read, "<p>Small speakers</p>\n|<p>Small speakers</p>\n<p>Are loud</p>\n");
+    assert_eq!(str::from_utf8(&alt_buf).unwrap(), "<p>Small speakers</p>\n<p>Are loud</p>\n");
+    assert_eq!(str::from_utf8(&center_buf).unwrap(), "<p>Small speakers</p>\n|&lt;p&gt;Small speakers&lt;/p&gt;\n");
+}
+
+#[test]
+fn alt_excerpt() {
+    let root = temp_dir().join("bloguen-test").join("ops-post-generate-excerpt-alt_excerpt");
+    let _ = fs::remove_dir_all(&root);
+    let post_root = root.join("posts").join("1. 2018-01-08 16-52 Big speakers");
+    fs::create_dir_all(&post_root).unwrap();
+    File::create(post_root.join("post.md")).unwrap().write_all("Small speakers\n<!-- more -->\nAre loud".as_bytes()).unwrap();
+
+    let post = BloguePost::new(("$ROOT/posts/1. 2018-01-08 16-52 Big speakers/".to_string(), post_root)).unwrap();
+    let mut alt_buf = vec![];
+    let mut center_buf = vec![];
+    assert_eq!(post.generate(&("$ROOT/out/".to_string(), root.join("out")),
+                             Some((&mut alt_buf as &mut dyn Write, true)),
+                             Some((&"{excerpt}|{excerpt|html}".parse().unwrap(), &mut center_buf as &mut dyn Write)),
+                             None,
+                             Default::default(),
+                             &"{excerpt}|".parse().unwrap(),
+                             &"".parse().unwrap(),
+                             "Блогг",
+                             &LANGUAGE_EN_GB,
+                             "autheur",
+                             &[],
+                             &[],
+                             &Default::default(),
+                             &Default::default(),
+                             &[],
+                             &[],
+                             &[],
+                             &[]),
+               Ok(vec![]));
+
+    let mut read = String::new();
+    File::open(root.join("out").join("posts").join(post.normalised_name() + ".html")).unwrap().read_to_string(&mut read).unwrap();
+    assert_eq!(read, "<p>Small speakers</p>\n|<p>Small speakers</p>\n<!-- more -->\n<p>Are loud</p>\n");
+    assert_eq!(str::from_utf8(&alt_buf).unwrap(), "<p>Small speakers</p>\n");
+}
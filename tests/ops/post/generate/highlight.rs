@@ -0,0 +1,142 @@
+use bloguen::ops::{BlogueDescriptorHighlight, PostRenderOptions, BloguePost};
+use bloguen::util::LANGUAGE_EN_GB;
+use std::io::{Write, Read};
+use std::fs::{self, File};
+use std::env::temp_dir;
+use std::str;
+
+
+static POST_MD: &str = "```rust\nlet a = 1;\n```\n\n```\nplain\n```";
+
+
+#[test]
+fn none() {
+    let root = temp_dir().join("bloguen-test").join("ops-post-generate-highlight-none");
+    let _ = fs::remove_dir_all(&root);
+    let post_root = root.join("posts").join("1. 2018-01-08 16-52 Big speakers");
+    fs::create_dir_all(&post_root).unwrap();
+    File::create(post_root.join("post.md")).unwrap().write_all(POST_MD.as_bytes()).unwrap();
+
+    let post = BloguePost::new(("$ROOT/posts/1. 2018-01-08 16-52 Big speakers/".to_string(), post_root)).unwrap();
+    let mut alt_buf = vec![];
+    let mut center_buf = vec![];
+    assert_eq!(post.generate(&("$ROOT/out/".to_string(), root.join("out")),
+                             Some((&mut alt_buf as &mut dyn Write, false)),
+                             Some((&"{data-post_content}".parse().unwrap(), &mut center_buf as &mut dyn Write)),
+                             None,
+                             Default::default(),
+                             &"".parse().unwrap(),
+                             &"".parse().unwrap(),
+                             "Блогг",
+                             &LANGUAGE_EN_GB,
+                             "autheur",
+                             &[],
+                             &[],
+                             &Default::default(),
+                             &Default::default(),
+                             &[],
+                             &[],
+                             &[],
+                             &[]),
+               Ok(vec![]));
+
+    let mut read = String::new();
+    File::open(root.join("out").join("posts").join(post.normalised_name() + ".html")).unwrap().read_to_string(&mut read).unwrap();
+    assert_eq!(read, "<pre><code class=\"language-rust\">let a = 1;\n</code></pre>\n<pre><code>plain\n</code></pre>\n");
+    assert_eq!(str::from_utf8(&alt_buf).unwrap(), read);
+    assert_eq!(str::from_utf8(&center_buf).unwrap(), read);
+}
+
+#[test]
+fn inline() {
+    let root = temp_dir().join("bloguen-test").join("ops-post-generate-highlight-inline");
+    let _ = fs::remove_dir_all(&root);
+    let post_root = root.join("posts").join("1. 2018-01-08 16-52 Big speakers");
+    fs::create_dir_all(&post_root).unwrap();
+    File::create(post_root.join("post.md")).unwrap().write_all(POST_MD.as_bytes()).unwrap();
+
+    let post = BloguePost::new(("$ROOT/posts/1. 2018-01-08 16-52 Big speakers/".to_string(), post_root)).unwrap();
+    let highlight = BlogueDescriptorHighlight {
+        theme: "InspiredGitHub".to_string(),
+        classes: false,
+    };
+    let mut alt_buf = vec![];
+    let mut center_buf = vec![];
+    assert_eq!(post.generate(&("$ROOT/out/".to_string(), root.join("out")),
+                             Some((&mut alt_buf as &mut dyn Write, false)),
+                             Some((&"{data-post_content}".parse().unwrap(), &mut center_buf as &mut dyn Write)),
+                             None,
+                             PostRenderOptions { highlight: Some(&highlight), ..Default::default() },
+                             &"".parse().unwrap(),
+                             &"".parse().unwrap(),
+                             "Блогг",
+                             &LANGUAGE_EN_GB,
+                             "autheur",
+                             &[],
+                             &[],
+                             &Default::default(),
+                             &Default::default(),
+                             &[],
+                             &[],
+                             &[],
+                             &[]),
+               Ok(vec![]));
+
+    let mut read = String::new();
+    File::open(root.join("out").join("posts").join(post.normalised_name() + ".html")).unwrap().read_to_string(&mut read).unwrap();
+    assert_eq!(read,
+               "<pre style=\"background-color:#ffffff;\">\n\
+                <span style=\"font-weight:bold;color:#a71d5d;\">let</span><span style=\"color:#323232;\"> a </span>\
+                <span style=\"font-weight:bold;color:#a71d5d;\">= </span><span style=\"color:#0086b3;\">1</span>\
+                <span style=\"color:#323232;\">;\n</span></pre>\n\
+                <pre><code>plain\n</code></pre>\n");
+    assert_eq!(str::from_utf8(&alt_buf).unwrap(), read);
+    assert_eq!(str::from_utf8(&center_buf).unwrap(), read);
+}
+
+#[test]
+fn classes() {
+    let root = temp_dir().join("bloguen-test").join("ops-post-generate-highlight-classes");
+    let _ = fs::remove_dir_all(&root);
+    let post_root = root.join("posts").join("1. 2018-01-08 16-52 Big speakers");
+    fs::create_dir_all(&post_root).unwrap();
+    File::create(post_root.join("post.md")).unwrap().write_all(POST_MD.as_bytes()).unwrap();
+
+    let post = BloguePost::new(("$ROOT/posts/1. 2018-01-08 16-52 Big speakers/".to_string(), post_root)).unwrap();
+    let highlight = BlogueDescriptorHighlight {
+        theme: "InspiredGitHub".to_string(),
+        classes: true,
+    };
+    let mut alt_buf = vec![];
+    let mut center_buf = vec![];
+    assert_eq!(post.generate(&("$ROOT/out/".to_string(), root.join("out")),
+                             Some((&mut alt_buf as &mut dyn Write, false)),
+                             Some((&"{data-post_content}".parse().unwrap(), &mut center_buf as &mut dyn Write)),
+                             None,
+                             PostRenderOptions { highlight: Some(&highlight), ..Default::default() },
+                             &"".parse().unwrap(),
+                             &"".parse().unwrap(),
+                             "Блогг",
+                             &LANGUAGE_EN_GB,
+                             "autheur",
+                             &[],
+                             &[],
+                             &Default::default(),
+                             &Default::default(),
+                             &[],
+                             &[],
+                             &[],
+                             &[]),
+               Ok(vec![]));
+
+    let mut read = String::new();
+    File::open(root.join("out").join("posts").join(post.normalised_name() + ".html")).unwrap().read_to_string(&mut read).unwrap();
+    assert_eq!(read,
+               "<pre class=\"hl-code\"><code class=\"language-rust\"><span class=\"hl-source hl-rust\">\
+                <span class=\"hl-storage hl-type hl-rust\">let</span> a <span class=\"hl-keyword hl-operator hl-rust\">=</span> \
+                <span class=\"hl-constant hl-numeric hl-integer hl-decimal hl-rust\">1</span>\
+                <span class=\"hl-punctuation hl-terminator hl-rust\">;</span>\n</span></code></pre>\n\
+                <pre><code>plain\n</code></pre>\n");
+    assert_eq!(str::from_utf8(&alt_buf).unwrap(), read);
+    assert_eq!(str::from_utf8(&center_buf).unwrap(), read);
+}
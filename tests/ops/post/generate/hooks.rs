@@ -0,0 +1,208 @@
+use bloguen::ops::{BlogueDescriptorHooks, PostRenderOptions, BloguePost};
+use bloguen::util::LANGUAGE_EN_GB;
+use std::io::{Write, Read};
+use std::fs::{self, File};
+use std::env::temp_dir;
+use std::str;
+use bloguen::Error;
+
+
+static POST_MD: &str = "Small speakers\nare *louder*\n";
+
+
+#[test]
+fn none() {
+    let root = temp_dir().join("bloguen-test").join("ops-post-generate-hooks-none");
+    let _ = fs::remove_dir_all(&root);
+    let post_root = root.join("posts").join("1. 2018-01-08 16-52 Big speakers");
+    fs::create_dir_all(&post_root).unwrap();
+    File::create(post_root.join("post.md")).unwrap().write_all(POST_MD.as_bytes()).unwrap();
+
+    let post = BloguePost::new(("$ROOT/posts/1. 2018-01-08 16-52 Big speakers/".to_string(), post_root)).unwrap();
+    let mut alt_buf = vec![];
+    assert_eq!(post.generate(&("$ROOT/out/".to_string(), root.join("out")),
+                             Some((&mut alt_buf as &mut dyn Write, false)),
+                             None,
+                             None,
+                             Default::default(),
+                             &"<title>{title}</title>".parse().unwrap(),
+                             &"".parse().unwrap(),
+                             "Блогг",
+                             &LANGUAGE_EN_GB,
+                             "autheur",
+                             &["vodka".parse().unwrap()],
+                             &["depression".parse().unwrap()],
+                             &vec![("preferred-system".to_string(), "communism".to_string())].into_iter().collect(),
+                             &vec![("preferred-system".to_string(), "capitalism".to_string())].into_iter().collect(),
+                             &[],
+                             &[],
+                             &[],
+                             &[]),
+               Ok(vec![]));
+
+    let mut read = String::new();
+    File::open(root.join("out").join("posts").join(post.normalised_name() + ".html")).unwrap().read_to_string(&mut read).unwrap();
+    assert_eq!(read, "<title>Big speakers</title><p>Small speakers<br />\nare <em>louder</em></p>\n");
+    assert_eq!(str::from_utf8(&alt_buf).unwrap(), "<p>Small speakers<br />\nare <em>louder</em></p>\n");
+}
+
+#[test]
+fn pre() {
+    let root = temp_dir().join("bloguen-test").join("ops-post-generate-hooks-pre");
+    let _ = fs::remove_dir_all(&root);
+    let post_root = root.join("posts").join("1. 2018-01-08 16-52 Big speakers");
+    fs::create_dir_all(&post_root).unwrap();
+    File::create(post_root.join("post.md")).unwrap().write_all(POST_MD.as_bytes()).unwrap();
+
+    let post = BloguePost::new(("$ROOT/posts/1. 2018-01-08 16-52 Big speakers/".to_string(), post_root)).unwrap();
+    let hooks = BlogueDescriptorHooks {
+        pre: vec!["sed s/louder/quieter/".to_string(), "printf '%s\\n\\n' \"$BLOGUEN_TITLE\"; cat".to_string()],
+        post: vec![],
+    };
+    let mut alt_buf = vec![];
+    assert_eq!(post.generate(&("$ROOT/out/".to_string(), root.join("out")),
+                             Some((&mut alt_buf as &mut dyn Write, false)),
+                             None,
+                             None,
+                             PostRenderOptions { hooks: &hooks, ..Default::default() },
+                             &"<title>{title}</title>".parse().unwrap(),
+                             &"".parse().unwrap(),
+                             "Блогг",
+                             &LANGUAGE_EN_GB,
+                             "autheur",
+                             &["vodka".parse().unwrap()],
+                             &["depression".parse().unwrap()],
+                             &vec![("preferred-system".to_string(), "communism".to_string())].into_iter().collect(),
+                             &vec![("preferred-system".to_string(), "capitalism".to_string())].into_iter().collect(),
+                             &[],
+                             &[],
+                             &[],
+                             &[]),
+               Ok(vec![]));
+
+    let mut read = String::new();
+    File::open(root.join("out").join("posts").join(post.normalised_name() + ".html")).unwrap().read_to_string(&mut read).unwrap();
+    assert_eq!(read, "<title>Big speakers</title><p>Big speakers</p>\n<p>Small speakers<br />\nare <em>quieter</em></p>\n");
+    assert_eq!(str::from_utf8(&alt_buf).unwrap(), "<p>Big speakers</p>\n<p>Small speakers<br />\nare <em>quieter</em></p>\n");
+}
+
+#[test]
+fn post() {
+    let root = temp_dir().join("bloguen-test").join("ops-post-generate-hooks-post");
+    let _ = fs::remove_dir_all(&root);
+    let post_root = root.join("posts").join("1. 2018-01-08 16-52 Big speakers");
+    fs::create_dir_all(&post_root).unwrap();
+    File::create(post_root.join("post.md")).unwrap().write_all(POST_MD.as_bytes()).unwrap();
+
+    let post = BloguePost::new(("$ROOT/posts/1. 2018-01-08 16-52 Big speakers/".to_string(), post_root)).unwrap();
+    let hooks = BlogueDescriptorHooks {
+        pre: vec![],
+        post: vec!["tr a-z A-Z".to_string(), "cat; printf '|%s' \"$BLOGUEN_WORD_COUNT\"".to_string()],
+    };
+    let mut alt_buf = vec![];
+    assert_eq!(post.generate(&("$ROOT/out/".to_string(), root.join("out")),
+                             Some((&mut alt_buf as &mut dyn Write, false)),
+                             None,
+                             None,
+                             PostRenderOptions { hooks: &hooks, ..Default::default() },
+                             &"<title>{title}</title>".parse().unwrap(),
+                             &"".parse().unwrap(),
+                             "Блогг",
+                             &LANGUAGE_EN_GB,
+                             "autheur",
+                             &["vodka".parse().unwrap()],
+                             &["depression".parse().unwrap()],
+                             &vec![("preferred-system".to_string(), "communism".to_string())].into_iter().collect(),
+                             &vec![("preferred-system".to_string(), "capitalism".to_string())].into_iter().collect(),
+                             &[],
+                             &[],
+                             &[],
+                             &[]),
+               Ok(vec![]));
+
+    let mut read = String::new();
+    File::open(root.join("out").join("posts").join(post.normalised_name() + ".html")).unwrap().read_to_string(&mut read).unwrap();
+    assert_eq!(read, "<TITLE>BIG SPEAKERS</TITLE><P>SMALL SPEAKERS<BR />\nARE <EM>LOUDER</EM></P>\n|4");
+    assert_eq!(str::from_utf8(&alt_buf).unwrap(), "<p>Small speakers<br />\nare <em>louder</em></p>\n");
+}
+
+#[test]
+fn environment() {
+    let root = temp_dir().join("bloguen-test").join("ops-post-generate-hooks-environment");
+    let _ = fs::remove_dir_all(&root);
+    let post_root = root.join("posts").join("1. 2018-01-08 16-52 Big speakers");
+    fs::create_dir_all(&post_root).unwrap();
+    File::create(post_root.join("post.md")).unwrap().write_all(POST_MD.as_bytes()).unwrap();
+
+    let post = BloguePost::new(("$ROOT/posts/1. 2018-01-08 16-52 Big speakers/".to_string(), post_root)).unwrap();
+    let hooks = BlogueDescriptorHooks {
+        pre: vec!["cat; printf '%s|%s|%s' \"$BLOGUEN_NUMBER\" \"$BLOGUEN_DATA_PREFERRED_SYSTEM\" \"${BLOGUEN_WORD_COUNT-none}\""
+                      .to_string()],
+        post: vec!["printf '%s|%s|%s' \"$BLOGUEN_AUTHOR\" \"$BLOGUEN_DRAFT\" \"$BLOGUEN_TAGS\"".to_string()],
+    };
+    let mut alt_buf = vec![];
+    assert_eq!(post.generate(&("$ROOT/out/".to_string(), root.join("out")),
+                             Some((&mut alt_buf as &mut dyn Write, false)),
+                             None,
+                             None,
+                             PostRenderOptions { hooks: &hooks, ..Default::default() },
+                             &"<title>{title}</title>".parse().unwrap(),
+                             &"".parse().unwrap(),
+                             "Блогг",
+                             &LANGUAGE_EN_GB,
+                             "autheur",
+                             &["vodka".parse().unwrap()],
+                             &["depression".parse().unwrap()],
+                             &vec![("preferred-system".to_string(), "communism".to_string())].into_iter().collect(),
+                             &vec![("preferred-system".to_string(), "capitalism".to_string())].into_iter().collect(),
+                             &[],
+                             &[],
+                             &[],
+                             &[]),
+               Ok(vec![]));
+
+    let mut read = String::new();
+    File::open(root.join("out").join("posts").join(post.normalised_name() + ".html")).unwrap().read_to_string(&mut read).unwrap();
+    assert_eq!(read, "autheur||vodka\ndepression\n");
+    assert_eq!(str::from_utf8(&alt_buf).unwrap(), "<p>Small speakers<br />\nare <em>louder</em><br />\n1|communism|none</p>\n");
+}
+
+#[test]
+fn failed() {
+    let root = temp_dir().join("bloguen-test").join("ops-post-generate-hooks-failed");
+    let _ = fs::remove_dir_all(&root);
+    let post_root = root.join("posts").join("1. 2018-01-08 16-52 Big speakers");
+    fs::create_dir_all(&post_root).unwrap();
+    File::create(post_root.join("post.md")).unwrap().write_all(POST_MD.as_bytes()).unwrap();
+
+    let post = BloguePost::new(("$ROOT/posts/1. 2018-01-08 16-52 Big speakers/".to_string(), post_root)).unwrap();
+    let hooks = BlogueDescriptorHooks {
+        pre: vec!["cat".to_string(), "cat >/dev/null; exit 3".to_string()],
+        post: vec![],
+    };
+    let mut alt_buf = vec![];
+    assert_eq!(post.generate(&("$ROOT/out/".to_string(), root.join("out")),
+                             Some((&mut alt_buf as &mut dyn Write, false)),
+                             None,
+                             None,
+                             PostRenderOptions { hooks: &hooks, ..Default::default() },
+                             &"<title>{title}</title>".parse().unwrap(),
+                             &"".parse().unwrap(),
+                             "Блогг",
+                             &LANGUAGE_EN_GB,
+                             "autheur",
+                             &["vodka".parse().unwrap()],
+                             &["depression".parse().unwrap()],
+                             &vec![("preferred-system".to_string(), "communism".to_string())].into_iter().collect(),
+                             &vec![("preferred-system".to_string(), "capitalism".to_string())].into_iter().collect(),
+                             &[],
+                             &[],
+                             &[],
+                             &[]),
+               Err(Error::HookFailed {
+                   command: "cat >/dev/null; exit 3".into(),
+                   wher: "post source of 1. 2018-01-08 16-52-00 Big speakers".into(),
+                   more: "exited with code 3".into(),
+               }));
+
+}
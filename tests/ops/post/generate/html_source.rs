@@ -0,0 +1,99 @@
+use bloguen::util::LANGUAGE_EN_GB;
+use bloguen::ops::{PostRenderOptions, BloguePost};
+use std::io::{Write, Read};
+use std::fs::{self, File};
+use std::env::temp_dir;
+use std::str;
+
+
+static POST_HTML: &str = "<p>Small speakers are <a href=\"https://nabijaczleweli.xyz\">louder</a> than $5</p>\n\
+                          <!-- more -->\n\
+                          <h2 id=\"speakers\">Big <code>speakers</code></h2>\n\
+                          <figure>\n  <img src='assets/image.png' alt=\"img\">\n</figure>\n";
+
+
+#[test]
+fn no_asset_override() {
+    let root = temp_dir().join("bloguen-test").join("ops-post-generate-html_source-no_asset_override");
+    let _ = fs::remove_dir_all(&root);
+    let post_root = root.join("posts").join("1. 2018-01-08 16-52 Big speakers");
+    fs::create_dir_all(post_root.join("assets")).unwrap();
+    File::create(post_root.join("post.html")).unwrap().write_all(POST_HTML.as_bytes()).unwrap();
+    File::create(post_root.join("assets").join("image.png")).unwrap();
+
+    let post = BloguePost::new(("$ROOT/posts/1. 2018-01-08 16-52 Big speakers/".to_string(), post_root)).unwrap();
+    let mut alt_buf = vec![];
+    let mut center_buf = vec![];
+    let mut paged_center_buf = vec![];
+    assert_eq!(post.generate(&("$ROOT/out/".to_string(), root.join("out")),
+                             Some((&mut alt_buf as &mut dyn Write, true)),
+                             Some((&"{data-post_content}".parse().unwrap(), &mut center_buf as &mut dyn Write)),
+                             None,
+                             PostRenderOptions { paged_center_output: Some(&mut paged_center_buf), math: true, ..Default::default() },
+                             &"{word_count}|{toc}|".parse().unwrap(),
+                             &"".parse().unwrap(),
+                             "Блогг",
+                             &LANGUAGE_EN_GB,
+                             "autheur",
+                             &[],
+                             &[],
+                             &Default::default(),
+                             &Default::default(),
+                             &[],
+                             &[],
+                             &[],
+                             &[]),
+               Ok(vec!["https://nabijaczleweli.xyz".to_string(), "assets/image.png".to_string()]));
+
+    let mut read = String::new();
+    File::open(root.join("out").join("posts").join(post.normalised_name() + ".html")).unwrap().read_to_string(&mut read).unwrap();
+    assert_eq!(read, format!("7|<ul>\n<li><a href=\"#speakers\">Big speakers</a></li>\n</ul>|{}", POST_HTML));
+    assert_eq!(str::from_utf8(&alt_buf).unwrap(),
+               "<p>Small speakers are <a href=\"https://nabijaczleweli.xyz\">louder</a> than $5</p>\n");
+    assert_eq!(str::from_utf8(&center_buf).unwrap(), POST_HTML.replace("'assets/", "'posts/assets/"));
+    assert_eq!(str::from_utf8(&paged_center_buf).unwrap(), POST_HTML.replace("'assets/", "'../posts/assets/"));
+}
+
+#[test]
+fn asset_override() {
+    let root = temp_dir().join("bloguen-test").join("ops-post-generate-html_source-asset_override");
+    let _ = fs::remove_dir_all(&root);
+    let post_root = root.join("posts").join("1. 2018-01-08 16-52 Big speakers");
+    fs::create_dir_all(post_root.join("assets")).unwrap();
+    File::create(post_root.join("post.html")).unwrap().write_all(POST_HTML.as_bytes()).unwrap();
+    File::create(post_root.join("assets").join("image.png")).unwrap();
+
+    let post = BloguePost::new(("$ROOT/posts/1. 2018-01-08 16-52 Big speakers/".to_string(), post_root)).unwrap();
+    let mut alt_buf = vec![];
+    let mut center_buf = vec![];
+    let mut paged_center_buf = vec![];
+    assert_eq!(post.generate(&("$ROOT/out/".to_string(), root.join("out")),
+                             Some((&mut alt_buf as &mut dyn Write, true)),
+                             Some((&"{data-post_content}".parse().unwrap(), &mut center_buf as &mut dyn Write)),
+                             Some("overriden-assets/"),
+                             PostRenderOptions { paged_center_output: Some(&mut paged_center_buf), math: true, ..Default::default() },
+                             &"{word_count}|{toc}|".parse().unwrap(),
+                             &"".parse().unwrap(),
+                             "Блогг",
+                             &LANGUAGE_EN_GB,
+                             "autheur",
+                             &[],
+                             &[],
+                             &Default::default(),
+                             &Default::default(),
+                             &[],
+                             &[],
+                             &[],
+                             &[]),
+               Ok(vec!["https://nabijaczleweli.xyz".to_string(), "assets/image.png".to_string()]));
+
+    let mut read = String::new();
+    File::open(root.join("out").join("posts").join(post.normalised_name() + ".html")).unwrap().read_to_string(&mut read).unwrap();
+    assert_eq!(read,
+               format!("7|<ul>\n<li><a href=\"#speakers\">Big speakers</a></li>\n</ul>|{}",
+                       POST_HTML.replace("'assets/", "'../overriden-assets/assets/")));
+    assert_eq!(str::from_utf8(&alt_buf).unwrap(),
+               "<p>Small speakers are <a href=\"https://nabijaczleweli.xyz\">louder</a> than $5</p>\n");
+    assert_eq!(str::from_utf8(&center_buf).unwrap(), POST_HTML.replace("'assets/", "'overriden-assets/assets/"));
+    assert_eq!(str::from_utf8(&paged_center_buf).unwrap(), POST_HTML.replace("'assets/", "'../overriden-assets/assets/"));
+}
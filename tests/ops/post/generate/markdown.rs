@@ -0,0 +1,97 @@
+use bloguen::ops::{MarkdownOptions, PostRenderOptions, BloguePost};
+use bloguen::util::LANGUAGE_EN_GB;
+use std::io::{Write, Read};
+use std::fs::{self, File};
+use std::env::temp_dir;
+use std::str;
+
+
+static POST_MD: &str = "# Speakers\n\nSmall speakers\nare *louder*[^1]\n\n[^1]: Citation needed.\n";
+
+static DEFAULT_HTML: &str = "<h1><a href=\"#speakers\" aria-hidden=\"true\" class=\"anchor\" id=\"speakers\"></a>Speakers</h1>\n\
+                             <p>Small speakers<br />\nare <em>louder</em>[^1]</p>\n\
+                             <p>[^1]: Citation needed.</p>\n";
+
+static OVERRIDEN_HTML: &str = "<h1>Speakers</h1>\n\
+                               <p>Small speakers\nare <em>louder</em><sup class=\"footnote-ref\"><a href=\"#fn1\" id=\"fnref1\">1</a></sup></p>\n\
+                               <section class=\"footnotes\">\n<ol>\n<li id=\"fn1\">\n\
+                               <p>Citation needed. <a href=\"#fnref1\" class=\"footnote-backref\">↩</a></p>\n</li>\n</ol>\n</section>\n";
+
+
+#[test]
+fn default() {
+    let root = temp_dir().join("bloguen-test").join("ops-post-generate-markdown-default");
+    let _ = fs::remove_dir_all(&root);
+    let post_root = root.join("posts").join("1. 2018-01-08 16-52 Big speakers");
+    fs::create_dir_all(&post_root).unwrap();
+    File::create(post_root.join("post.md")).unwrap().write_all(POST_MD.as_bytes()).unwrap();
+
+    let post = BloguePost::new(("$ROOT/posts/1. 2018-01-08 16-52 Big speakers/".to_string(), post_root)).unwrap();
+    let mut alt_buf = vec![];
+    assert_eq!(post.generate(&("$ROOT/out/".to_string(), root.join("out")),
+                             Some((&mut alt_buf as &mut dyn Write, false)),
+                             None,
+                             None,
+                             Default::default(),
+                             &"{toc}|".parse().unwrap(),
+                             &"".parse().unwrap(),
+                             "Блогг",
+                             &LANGUAGE_EN_GB,
+                             "autheur",
+                             &[],
+                             &[],
+                             &Default::default(),
+                             &Default::default(),
+                             &[],
+                             &[],
+                             &[],
+                             &[]),
+               Ok(vec![]));
+
+    let mut read = String::new();
+    File::open(root.join("out").join("posts").join(post.normalised_name() + ".html")).unwrap().read_to_string(&mut read).unwrap();
+    assert_eq!(read, format!("<ul>\n<li><a href=\"#speakers\">Speakers</a></li>\n</ul>|{}", DEFAULT_HTML));
+    assert_eq!(str::from_utf8(&alt_buf).unwrap(), DEFAULT_HTML);
+}
+
+#[test]
+fn overriden() {
+    let root = temp_dir().join("bloguen-test").join("ops-post-generate-markdown-overriden");
+    let _ = fs::remove_dir_all(&root);
+    let post_root = root.join("posts").join("1. 2018-01-08 16-52 Big speakers");
+    fs::create_dir_all(&post_root).unwrap();
+    File::create(post_root.join("post.md")).unwrap().write_all(POST_MD.as_bytes()).unwrap();
+
+    let post = BloguePost::new(("$ROOT/posts/1. 2018-01-08 16-52 Big speakers/".to_string(), post_root)).unwrap();
+    let markdown = MarkdownOptions {
+        hardbreaks: Some(false),
+        header_ids: Some(false),
+        footnotes: Some(true),
+        ..Default::default()
+    };
+    let mut alt_buf = vec![];
+    assert_eq!(post.generate(&("$ROOT/out/".to_string(), root.join("out")),
+                             Some((&mut alt_buf as &mut dyn Write, false)),
+                             None,
+                             None,
+                             PostRenderOptions { markdown: markdown, ..Default::default() },
+                             &"{toc}|".parse().unwrap(),
+                             &"".parse().unwrap(),
+                             "Блогг",
+                             &LANGUAGE_EN_GB,
+                             "autheur",
+                             &[],
+                             &[],
+                             &Default::default(),
+                             &Default::default(),
+                             &[],
+                             &[],
+                             &[],
+                             &[]),
+               Ok(vec![]));
+
+    let mut read = String::new();
+    File::open(root.join("out").join("posts").join(post.normalised_name() + ".html")).unwrap().read_to_string(&mut read).unwrap();
+    assert_eq!(read, format!("|{}", OVERRIDEN_HTML));
+    assert_eq!(str::from_utf8(&alt_buf).unwrap(), OVERRIDEN_HTML);
+}
@@ -0,0 +1,91 @@
+use bloguen::util::LANGUAGE_EN_GB;
+use bloguen::ops::{PostRenderOptions, BloguePost};
+use std::io::{Write, Read};
+use std::fs::{self, File};
+use std::env::temp_dir;
+use std::str;
+
+
+static POST_MD: &str = "Small speakers are $\\sqrt{2}$ times *louder*\n\n$$a*b*c$$\n\n`$x$`";
+
+
+#[test]
+fn rendered_everywhere() {
+    let root = temp_dir().join("bloguen-test").join("ops-post-generate-math-rendered_everywhere");
+    let _ = fs::remove_dir_all(&root);
+    let post_root = root.join("posts").join("1. 2018-01-08 16-52 Big speakers");
+    fs::create_dir_all(&post_root).unwrap();
+    File::create(post_root.join("post.md")).unwrap().write_all(POST_MD.as_bytes()).unwrap();
+
+    let post = BloguePost::new(("$ROOT/posts/1. 2018-01-08 16-52 Big speakers/".to_string(), post_root)).unwrap();
+    let mut alt_buf = vec![];
+    let mut center_buf = vec![];
+    assert_eq!(post.generate(&("$ROOT/out/".to_string(), root.join("out")),
+                             Some((&mut alt_buf as &mut dyn Write, false)),
+                             Some((&"{data-post_content}".parse().unwrap(), &mut center_buf as &mut dyn Write)),
+                             None,
+                             PostRenderOptions { math: true, ..Default::default() },
+                             &"".parse().unwrap(),
+                             &"".parse().unwrap(),
+                             "Блогг",
+                             &LANGUAGE_EN_GB,
+                             "autheur",
+                             &[],
+                             &[],
+                             &Default::default(),
+                             &Default::default(),
+                             &[],
+                             &[],
+                             &[],
+                             &[]),
+               Ok(vec![]));
+
+    let mut read = String::new();
+    File::open(root.join("out").join("posts").join(post.normalised_name() + ".html")).unwrap().read_to_string(&mut read).unwrap();
+    assert_eq!(read,
+               "<p>Small speakers are <math xmlns=\"http://www.w3.org/1998/Math/MathML\"><semantics><msqrt><mn>2</mn></msqrt><annotation \
+                encoding=\"application/x-tex\">&#92;sqrt&#123;2&#125;</annotation></semantics></math> times <em>louder</em></p>\n<p><math \
+                xmlns=\"http://www.w3.org/1998/Math/MathML\" display=\"block\"><semantics><mrow><mi>a</mi><mo>∗</mo><mi>b</mi><mo>∗</mo>\
+                <mi>c</mi></mrow><annotation encoding=\"application/x-tex\">a&#42;b&#42;c</annotation></semantics></math></p>\n\
+                <p><code>$x$</code></p>\n");
+    assert_eq!(str::from_utf8(&alt_buf).unwrap(), read);
+    assert_eq!(str::from_utf8(&center_buf).unwrap(), read);
+}
+
+#[test]
+fn disabled() {
+    let root = temp_dir().join("bloguen-test").join("ops-post-generate-math-disabled");
+    let _ = fs::remove_dir_all(&root);
+    let post_root = root.join("posts").join("1. 2018-01-08 16-52 Big speakers");
+    fs::create_dir_all(&post_root).unwrap();
+    File::create(post_root.join("post.md")).unwrap().write_all(POST_MD.as_bytes()).unwrap();
+
+    let post = BloguePost::new(("$ROOT/posts/1. 2018-01-08 16-52 Big speakers/".to_string(), post_root)).unwrap();
+    let mut alt_buf = vec![];
+    let mut center_buf = vec![];
+    assert_eq!(post.generate(&("$ROOT/out/".to_string(), root.join("out")),
+                             Some((&mut alt_buf as &mut dyn Write, false)),
+                             Some((&"{data-post_content}".parse().unwrap(), &mut center_buf as &mut dyn Write)),
+                             None,
+                             Default::default(),
+                             &"".parse().unwrap(),
+                             &"".parse().unwrap(),
+                             "Блогг",
+                             &LANGUAGE_EN_GB,
+                             "autheur",
+                             &[],
+                             &[],
+                             &Default::default(),
+                             &Default::default(),
+                             &[],
+                             &[],
+                             &[],
+                             &[]),
+               Ok(vec![]));
+
+    let mut read = String::new();
+    File::open(root.join("out").join("posts").join(post.normalised_name() + ".html")).unwrap().read_to_string(&mut read).unwrap();
+    assert_eq!(read, "<p>Small speakers are $\\sqrt{2}$ times <em>louder</em></p>\n<p>$$a<em>b</em>c$$</p>\n<p><code>$x$</code></p>\n");
+    assert_eq!(str::from_utf8(&alt_buf).unwrap(), read);
+    assert_eq!(str::from_utf8(&center_buf).unwrap(), read);
+}
@@ -6,3 +6,17 @@ mod alt_center_no_asset_override;
 mod alt_no_center_asset_override;
 mod no_alt_center_asset_override;
 mod alt_center_asset_override;
+mod paged_center;
+mod tag_center;
+mod neighbours;
+mod related_posts;
+mod word_count;
+mod toc;
+mod excerpt;
+mod permalink;
+mod html_source;
+mod highlight;
+mod markdown;
+mod math;
+#[cfg(not(target_os = "windows"))]
+mod hooks;
@@ -0,0 +1,174 @@
+use bloguen::util::LANGUAGE_EN_GB;
+use bloguen::ops::{PostRenderOptions, FormatTemplate, BloguePost};
+use std::io::{Write, Read};
+use std::fs::{self, File};
+use std::env::temp_dir;
+use std::str;
+
+
+static FOOTER: &str = "{if prev_post_link}<a href=\"{prev_post_link}\">← {prev_post_title}</a>{end}|\
+                       {if next_post_link}<a href=\"{next_post_link}\">{next_post_title} ({date(next_post, \"%Y-%m-%d\")}) →</a>{end}|\
+                       {prev_post_name}|{next_post_name}";
+
+
+fn post(root: &str, name: &str) -> BloguePost {
+    let root = temp_dir().join("bloguen-test").join(root);
+    let post_root = root.join("posts").join(name);
+    fs::create_dir_all(&post_root).unwrap();
+    File::create(post_root.join("post.md")).unwrap().write_all("Блогг".as_bytes()).unwrap();
+
+    BloguePost::new((format!("$ROOT/posts/{}/", name), post_root)).unwrap()
+}
+
+
+#[test]
+fn both() {
+    let root = temp_dir().join("bloguen-test").join("ops-post-generate-neighbours-both");
+    let _ = fs::remove_dir_all(&root);
+    let previous = post("ops-post-generate-neighbours-both", "1. 2018-01-08 16-52 Big speakers");
+    let current = post("ops-post-generate-neighbours-both", "2. 2018-01-09 12-00 Small speakers");
+    let next = post("ops-post-generate-neighbours-both", "3. 2018-02-10 08-30 No speakers");
+
+    let mut center_buf = vec![];
+    assert_eq!(current.generate(&("$ROOT/out/".to_string(), root.join("out")),
+                                None,
+                                Some((&FOOTER.parse().unwrap(), &mut center_buf)),
+                                None,
+                                PostRenderOptions { previous_post: Some(&previous), next_post: Some(&next), ..Default::default() },
+                                &"".parse().unwrap(),
+                                &FOOTER.parse().unwrap(),
+                                "Блогг",
+                                &LANGUAGE_EN_GB,
+                                "autheur",
+                                &[],
+                                &[],
+                                &Default::default(),
+                                &Default::default(),
+                                &[],
+                                &[],
+                                &[],
+                                &[]),
+               Ok(vec![]));
+
+    let mut read = String::new();
+    File::open(root.join("out").join("posts").join("2. 2018-01-09 12-00-00 Small speakers.html")).unwrap().read_to_string(&mut read).unwrap();
+    assert_eq!(read,
+               "<p>Блогг</p>\n\
+                <a href=\"../posts/1.%202018-01-08%2016-52-00%20Big%20speakers.html\">← Big speakers</a>|\
+                <a href=\"../posts/3.%202018-02-10%2008-30-00%20No%20speakers.html\">No speakers (2018-02-10) →</a>|\
+                1. 2018-01-08 16-52-00 Big speakers|3. 2018-02-10 08-30-00 No speakers");
+    assert_eq!(str::from_utf8(&center_buf).unwrap(),
+               "<a href=\"posts/1.%202018-01-08%2016-52-00%20Big%20speakers.html\">← Big speakers</a>|\
+                <a href=\"posts/3.%202018-02-10%2008-30-00%20No%20speakers.html\">No speakers (2018-02-10) →</a>|\
+                1. 2018-01-08 16-52-00 Big speakers|3. 2018-02-10 08-30-00 No speakers");
+}
+
+#[test]
+fn none() {
+    let root = temp_dir().join("bloguen-test").join("ops-post-generate-neighbours-none");
+    let _ = fs::remove_dir_all(&root);
+    let current = post("ops-post-generate-neighbours-none", "1. 2018-01-08 16-52 Big speakers");
+
+    assert_eq!(current.generate(&("$ROOT/out/".to_string(), root.join("out")),
+                                None,
+                                None,
+                                None,
+                                PostRenderOptions { base_url: Some(&"https://nabijaczleweli.xyz/blogue/".parse().unwrap()), ..Default::default() },
+                                &"".parse().unwrap(),
+                                &FOOTER.parse().unwrap(),
+                                "Блогг",
+                                &LANGUAGE_EN_GB,
+                                "autheur",
+                                &[],
+                                &[],
+                                &Default::default(),
+                                &Default::default(),
+                                &[],
+                                &[],
+                                &[],
+                                &[]),
+               Ok(vec![]));
+
+    let mut read = String::new();
+    File::open(root.join("out").join("posts").join("1. 2018-01-08 16-52-00 Big speakers.html")).unwrap().read_to_string(&mut read).unwrap();
+    assert_eq!(read, "<p>Блогг</p>\n|||");
+}
+
+#[test]
+fn base_url() {
+    let root = temp_dir().join("bloguen-test").join("ops-post-generate-neighbours-base_url");
+    let _ = fs::remove_dir_all(&root);
+    let previous = post("ops-post-generate-neighbours-base_url", "1. 2018-01-08 16-52 Big speakers");
+    let current = post("ops-post-generate-neighbours-base_url", "2. 2018-01-09 12-00 Small speakers");
+
+    assert_eq!(current.generate(&("$ROOT/out/".to_string(), root.join("out")),
+                                None,
+                                None,
+                                None,
+                                PostRenderOptions {
+                                    base_url: Some(&"https://nabijaczleweli.xyz/blogue/".parse().unwrap()),
+                                    previous_post: Some(&previous),
+                                    ..Default::default()
+                                },
+                                &"".parse().unwrap(),
+                                &FOOTER.parse().unwrap(),
+                                "Блогг",
+                                &LANGUAGE_EN_GB,
+                                "autheur",
+                                &[],
+                                &[],
+                                &Default::default(),
+                                &Default::default(),
+                                &[],
+                                &[],
+                                &[],
+                                &[]),
+               Ok(vec![]));
+
+    let mut read = String::new();
+    File::open(root.join("out").join("posts").join("2. 2018-01-09 12-00-00 Small speakers.html")).unwrap().read_to_string(&mut read).unwrap();
+    assert_eq!(read,
+               "<p>Блогг</p>\n\
+                <a href=\"https://nabijaczleweli.xyz/blogue/posts/1.%202018-01-08%2016-52-00%20Big%20speakers.html\">← Big speakers</a>||\
+                1. 2018-01-08 16-52-00 Big speakers|");
+}
+
+#[test]
+fn escaped() {
+    let root = temp_dir().join("bloguen-test").join("ops-post-generate-neighbours-escaped");
+    let _ = fs::remove_dir_all(&root);
+    let mut previous = post("ops-post-generate-neighbours-escaped", "1. 2018-01-08 16-52 Loud speakers");
+    let current = post("ops-post-generate-neighbours-escaped", "2. 2018-01-09 12-00 Small speakers");
+    previous.name = "\"Loud\" & clear".to_string();
+    previous.slug = "\"Loud\" & 'clear'".to_string();
+    previous.apply_permalink_pattern("posts/{slug}.html");
+
+    let mut footer: FormatTemplate = FOOTER.parse().unwrap();
+    footer.escape_html_by_default();
+    assert_eq!(current.generate(&("$ROOT/out/".to_string(), root.join("out")),
+                                None,
+                                None,
+                                None,
+                                PostRenderOptions { previous_post: Some(&previous), ..Default::default() },
+                                &"".parse().unwrap(),
+                                &footer,
+                                "Блогг",
+                                &LANGUAGE_EN_GB,
+                                "autheur",
+                                &[],
+                                &[],
+                                &Default::default(),
+                                &Default::default(),
+                                &[],
+                                &[],
+                                &[],
+                                &[]),
+               Ok(vec![]));
+
+    let mut read = String::new();
+    File::open(root.join("out").join("posts").join("2. 2018-01-09 12-00-00 Small speakers.html")).unwrap().read_to_string(&mut read).unwrap();
+    assert_eq!(read,
+               "<p>Блогг</p>\n\
+                <a href=\"../posts/%22Loud%22%20&amp;%20'clear'.html\">← \"Loud\" &amp; clear</a>||\
+                1. 2018-01-08 16-52-00 \"Loud\" &amp; clear|");
+}
@@ -25,10 +25,11 @@ fn ok() {
     let mut center_buf = vec![];
     assert_eq!(post.generate(&("$ROOT/out/".to_string(), root.join("out")),
                              None,
-                             Some(("cen{data-post_content}ter", &mut center_buf as &mut dyn Write)),
+                             Some((&"cen{data-post_content}ter".parse().unwrap(), &mut center_buf as &mut dyn Write)),
                              None,
-                             "header",
-                             "footer",
+                             Default::default(),
+                             &"header".parse().unwrap(),
+                             &"footer".parse().unwrap(),
                              "Блогг",
                              &LANGUAGE_EN_GB,
                              "autheur",
@@ -54,10 +55,11 @@ fn ok() {
     center_buf.clear();
     assert_eq!(post.generate(&("$ROOT/out/".to_string(), root.join("out")),
                              None,
-                             Some(("cen{data-post_content}ter", &mut center_buf as &mut dyn Write)),
+                             Some((&"cen{data-post_content}ter".parse().unwrap(), &mut center_buf as &mut dyn Write)),
                              None,
-                             "header",
-                             "footer",
+                             Default::default(),
+                             &"header".parse().unwrap(),
+                             &"footer".parse().unwrap(),
                              "Блогг",
                              &LANGUAGE_EN_GB,
                              "autheur",
@@ -83,10 +85,11 @@ fn ok() {
     center_buf.clear();
     assert_eq!(post.generate(&("$ROOT/out/".to_string(), root.join("out")),
                              None,
-                             Some(("cen{data-post_content}ter", &mut center_buf as &mut dyn Write)),
+                             Some((&"cen{data-post_content}ter".parse().unwrap(), &mut center_buf as &mut dyn Write)),
                              None,
-                             "header",
-                             "footer",
+                             Default::default(),
+                             &"header".parse().unwrap(),
+                             &"footer".parse().unwrap(),
                              "Блогг",
                              &LANGUAGE_EN_GB,
                              "autheur",
@@ -121,10 +124,11 @@ fn not_found() {
     let mut center_buf = vec![];
     assert_eq!(post.generate(&("$ROOT/out/".to_string(), root.join("out")),
                              None,
-                             Some(("cen{data-post_content}ter", &mut center_buf as &mut dyn Write)),
+                             Some((&"cen{data-post_content}ter".parse().unwrap(), &mut center_buf as &mut dyn Write)),
                              None,
-                             "header",
-                             "footer",
+                             Default::default(),
+                             &"header".parse().unwrap(),
+                             &"footer".parse().unwrap(),
                              "Блогг",
                              &LANGUAGE_EN_GB,
                              "autheur",
@@ -162,10 +166,11 @@ fn non_utf8() {
     let mut center_buf = vec![];
     assert_eq!(post.generate(&("$ROOT/out/".to_string(), root.join("out")),
                              None,
-                             Some(("cen{data-post_content}ter", &mut center_buf as &mut dyn Write)),
+                             Some((&"cen{data-post_content}ter".parse().unwrap(), &mut center_buf as &mut dyn Write)),
                              None,
-                             "header",
-                             "footer",
+                             Default::default(),
+                             &"header".parse().unwrap(),
+                             &"footer".parse().unwrap(),
                              "Блогг",
                              &LANGUAGE_EN_GB,
                              "autheur",
@@ -203,10 +208,11 @@ fn posts_directory() {
     let mut center_buf = vec![];
     assert_eq!(post.generate(&("$ROOT/out/".to_string(), root.join("out")),
                              None,
-                             Some(("cen{data-post_content}ter", &mut center_buf as &mut dyn Write)),
+                             Some((&"cen{data-post_content}ter".parse().unwrap(), &mut center_buf as &mut dyn Write)),
                              None,
-                             "header",
-                             "footer",
+                             Default::default(),
+                             &"header".parse().unwrap(),
+                             &"footer".parse().unwrap(),
                              "Блогг",
                              &LANGUAGE_EN_GB,
                              "autheur",
@@ -248,10 +254,11 @@ fn post_create() {
     let mut center_buf = vec![];
     assert_eq!(post.generate(&("$ROOT/out/".to_string(), root.join("out")),
                              None,
-                             Some(("cen{data-post_content}ter", &mut center_buf as &mut dyn Write)),
+                             Some((&"cen{data-post_content}ter".parse().unwrap(), &mut center_buf as &mut dyn Write)),
                              None,
-                             "header",
-                             "footer",
+                             Default::default(),
+                             &"header".parse().unwrap(),
+                             &"footer".parse().unwrap(),
                              "Блогг",
                              &LANGUAGE_EN_GB,
                              "autheur",
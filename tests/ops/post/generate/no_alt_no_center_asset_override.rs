@@ -25,8 +25,9 @@ fn ok() {
                              None,
                              None,
                              Some("overriden-assets"),
-                             "header",
-                             "footer",
+                             Default::default(),
+                             &"header".parse().unwrap(),
+                             &"footer".parse().unwrap(),
                              "Блогг",
                              &LANGUAGE_EN_GB,
                              "autheur",
@@ -51,8 +52,9 @@ fn ok() {
                              None,
                              None,
                              Some("overriden-assets"),
-                             "header",
-                             "footer",
+                             Default::default(),
+                             &"header".parse().unwrap(),
+                             &"footer".parse().unwrap(),
                              "Блогг",
                              &LANGUAGE_EN_GB,
                              "autheur",
@@ -77,8 +79,9 @@ fn ok() {
                              None,
                              None,
                              Some("overriden-assets"),
-                             "header",
-                             "footer",
+                             Default::default(),
+                             &"header".parse().unwrap(),
+                             &"footer".parse().unwrap(),
                              "Блогг",
                              &LANGUAGE_EN_GB,
                              "autheur",
@@ -112,8 +115,9 @@ fn not_found() {
                              None,
                              None,
                              Some("overriden-assets"),
-                             "header",
-                             "footer",
+                             Default::default(),
+                             &"header".parse().unwrap(),
+                             &"footer".parse().unwrap(),
                              "Блогг",
                              &LANGUAGE_EN_GB,
                              "autheur",
@@ -151,8 +155,9 @@ fn non_utf8() {
                              None,
                              None,
                              Some("overriden-assets"),
-                             "header",
-                             "footer",
+                             Default::default(),
+                             &"header".parse().unwrap(),
+                             &"footer".parse().unwrap(),
                              "Блогг",
                              &LANGUAGE_EN_GB,
                              "autheur",
@@ -190,8 +195,9 @@ fn posts_directory() {
                              None,
                              None,
                              Some("overriden-assets"),
-                             "header",
-                             "footer",
+                             Default::default(),
+                             &"header".parse().unwrap(),
+                             &"footer".parse().unwrap(),
                              "Блогг",
                              &LANGUAGE_EN_GB,
                              "autheur",
@@ -233,8 +239,9 @@ fn post_create() {
                              None,
                              None,
                              Some("overriden-assets"),
-                             "header",
-                             "footer",
+                             Default::default(),
+                             &"header".parse().unwrap(),
+                             &"footer".parse().unwrap(),
                              "Блогг",
                              &LANGUAGE_EN_GB,
                              "autheur",
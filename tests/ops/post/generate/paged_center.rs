@@ -0,0 +1,130 @@
+use bloguen::util::LANGUAGE_EN_GB;
+use bloguen::ops::{PostRenderOptions, BloguePost};
+use std::env::temp_dir;
+use std::fs::{self, File};
+use std::io::Write;
+use std::str;
+
+
+#[test]
+fn no_asset_override() {
+    let root = temp_dir().join("bloguen-test").join("ops-post-generate-paged_center-no_asset_override");
+    let _ = fs::remove_dir_all(&root);
+    let post_root = root.join("posts").join("1. 2018-01-08 16-52 Big speakers");
+    fs::create_dir_all(post_root.join("assets")).unwrap();
+    File::create(post_root.join("post.md")).unwrap().write_all("![img](assets/image.png)".as_bytes()).unwrap();
+    File::create(post_root.join("assets").join("image.png")).unwrap();
+
+    let post = BloguePost::new(("$ROOT/posts/1. 2018-01-08 16-52 Big speakers/".to_string(), post_root)).unwrap();
+    let mut center_buf = vec![];
+    let mut paged_center_buf = vec![];
+    let mut tag_center_buf = vec![];
+    assert_eq!(post.generate(&("$ROOT/out/".to_string(), root.join("out")),
+                             None,
+                             Some((&"{permalink}|{tags}{data-post_content}".parse().unwrap(), &mut center_buf as &mut dyn Write)),
+                             None,
+                             PostRenderOptions {
+                                 paged_center_output: Some(&mut paged_center_buf),
+                                 tag_center_output: Some((&"{permalink}|".parse().unwrap(), &mut tag_center_buf as &mut dyn Write)),
+                                 ..Default::default()
+                             },
+                             &"".parse().unwrap(),
+                             &"".parse().unwrap(),
+                             "Блогг",
+                             &LANGUAGE_EN_GB,
+                             "autheur",
+                             &["vodka".parse().unwrap()],
+                             &[],
+                             &Default::default(),
+                             &Default::default(),
+                             &[],
+                             &[],
+                             &[],
+                             &[]),
+               Ok(vec!["assets/image.png".to_string()]));
+
+    assert_eq!(str::from_utf8(&center_buf).unwrap(),
+               "posts/1. 2018-01-08 16-52-00 Big speakers.html|<a href=\"tags/vodka.html\"><span class=\"post-tag\">vodka</span></a> \
+                <p><img src=\"posts/assets/image.png\" alt=\"img\" /></p>\n");
+    assert_eq!(str::from_utf8(&paged_center_buf).unwrap(),
+               "../posts/1. 2018-01-08 16-52-00 Big speakers.html|<a href=\"../tags/vodka.html\"><span class=\"post-tag\">vodka</span></a> \
+                <p><img src=\"../posts/assets/image.png\" alt=\"img\" /></p>\n");
+    assert_eq!(str::from_utf8(&tag_center_buf).unwrap(), "../posts/1. 2018-01-08 16-52-00 Big speakers.html|");
+}
+
+#[test]
+fn asset_override() {
+    let root = temp_dir().join("bloguen-test").join("ops-post-generate-paged_center-asset_override");
+    let _ = fs::remove_dir_all(&root);
+    let post_root = root.join("posts").join("1. 2018-01-08 16-52 Big speakers");
+    fs::create_dir_all(post_root.join("assets")).unwrap();
+    File::create(post_root.join("post.md")).unwrap().write_all("![img](assets/image.png)".as_bytes()).unwrap();
+    File::create(post_root.join("assets").join("image.png")).unwrap();
+
+    let post = BloguePost::new(("$ROOT/posts/1. 2018-01-08 16-52 Big speakers/".to_string(), post_root)).unwrap();
+    let mut center_buf = vec![];
+    let mut paged_center_buf = vec![];
+    assert_eq!(post.generate(&("$ROOT/out/".to_string(), root.join("out")),
+                             None,
+                             Some((&"{permalink}|{data-post_content}".parse().unwrap(), &mut center_buf as &mut dyn Write)),
+                             Some("overriden-assets/"),
+                             PostRenderOptions {
+                                 paged_center_output: Some(&mut paged_center_buf),
+                                 base_url: Some(&"https://nabijaczleweli.xyz/blogue/".parse().unwrap()),
+                                 ..Default::default()
+                             },
+                             &"".parse().unwrap(),
+                             &"".parse().unwrap(),
+                             "Блогг",
+                             &LANGUAGE_EN_GB,
+                             "autheur",
+                             &[],
+                             &[],
+                             &Default::default(),
+                             &Default::default(),
+                             &[],
+                             &[],
+                             &[],
+                             &[]),
+               Ok(vec!["assets/image.png".to_string()]));
+
+    assert_eq!(str::from_utf8(&center_buf).unwrap(),
+               "https://nabijaczleweli.xyz/blogue/posts/1.%202018-01-08%2016-52-00%20Big%20speakers.html|\
+                <p><img src=\"overriden-assets/assets/image.png\" alt=\"img\" /></p>\n");
+    assert_eq!(str::from_utf8(&paged_center_buf).unwrap(),
+               "https://nabijaczleweli.xyz/blogue/posts/1.%202018-01-08%2016-52-00%20Big%20speakers.html|\
+                <p><img src=\"../overriden-assets/assets/image.png\" alt=\"img\" /></p>\n");
+}
+
+#[test]
+fn no_center() {
+    let root = temp_dir().join("bloguen-test").join("ops-post-generate-paged_center-no_center");
+    let _ = fs::remove_dir_all(&root);
+    let post_root = root.join("posts").join("1. 2018-01-08 16-52 Big speakers");
+    fs::create_dir_all(&post_root).unwrap();
+    File::create(post_root.join("post.md")).unwrap().write_all("Блогг".as_bytes()).unwrap();
+
+    let post = BloguePost::new(("$ROOT/posts/1. 2018-01-08 16-52 Big speakers/".to_string(), post_root)).unwrap();
+    let mut paged_center_buf = vec![];
+    assert_eq!(post.generate(&("$ROOT/out/".to_string(), root.join("out")),
+                             None,
+                             None,
+                             None,
+                             PostRenderOptions { paged_center_output: Some(&mut paged_center_buf), ..Default::default() },
+                             &"".parse().unwrap(),
+                             &"".parse().unwrap(),
+                             "Блогг",
+                             &LANGUAGE_EN_GB,
+                             "autheur",
+                             &[],
+                             &[],
+                             &Default::default(),
+                             &Default::default(),
+                             &[],
+                             &[],
+                             &[],
+                             &[]),
+               Ok(vec![]));
+
+    assert!(paged_center_buf.is_empty());
+}
@@ -0,0 +1,100 @@
+use bloguen::ops::{PostRenderOptions, MachineDataKind, FeedType, BloguePost};
+use bloguen::util::LANGUAGE_EN_GB;
+use std::io::{Write, Read};
+use std::fs::{self, File};
+use std::env::temp_dir;
+use std::str;
+
+
+fn post(root: &str, name: &str, slug: &str) -> BloguePost {
+    let root = temp_dir().join("bloguen-test").join(root);
+    let post_root = root.join("posts").join(name);
+    fs::create_dir_all(post_root.join("assets")).unwrap();
+    File::create(post_root.join("post.md")).unwrap().write_all("![img](assets/img.png)".as_bytes()).unwrap();
+    File::create(post_root.join("assets").join("img.png")).unwrap();
+
+    let mut post = BloguePost::new((format!("$ROOT/posts/{}/", name), post_root)).unwrap();
+    post.slug = slug.to_string();
+    post.apply_permalink_pattern("posts/{year}/{month}/{slug}/index.html");
+    post
+}
+
+
+#[test]
+fn nested() {
+    let root = temp_dir().join("bloguen-test").join("ops-post-generate-permalink-nested");
+    let _ = fs::remove_dir_all(&root);
+    let previous = post("ops-post-generate-permalink-nested", "1. 2018-01-08 16-52 Big speakers", "big-speakers");
+    let current = post("ops-post-generate-permalink-nested", "2. 2018-02-10 12-00 Small speakers", "small-speakers");
+    let out = ("$ROOT/out/".to_string(), root.join("out"));
+
+    let mut center_buf = vec![];
+    let mut tag_center_buf = vec![];
+    assert_eq!(current.generate(&out,
+                                None,
+                                Some((&"{permalink}|{data-post_content}".parse().unwrap(), &mut center_buf)),
+                                None,
+                                PostRenderOptions {
+                                    tag_center_output: Some((&"{prev_post_link}|{data-post_content}".parse().unwrap(), &mut tag_center_buf)),
+                                    previous_post: Some(&previous),
+                                    ..Default::default()
+                                },
+                                &"".parse().unwrap(),
+                                &"{prev_post_link}|{tag_index}|{permalink}".parse().unwrap(),
+                                "Блогг",
+                                &LANGUAGE_EN_GB,
+                                "autheur",
+                                &[],
+                                &[],
+                                &Default::default(),
+                                &Default::default(),
+                                &[],
+                                &[],
+                                &[],
+                                &[]),
+               Ok(vec!["assets/img.png".to_string()]));
+    assert_eq!(current.copy_asset(&out, None, "assets/img.png"), Ok(true));
+
+    let mut read = String::new();
+    File::open(root.join("out").join("posts").join("2018").join("02").join("small-speakers").join("index.html"))
+        .unwrap()
+        .read_to_string(&mut read)
+        .unwrap();
+    assert_eq!(read,
+               "<p><img src=\"assets/img.png\" alt=\"img\" /></p>\n\
+                ../../../../posts/2018/01/big-speakers/index.html|../../../../tags/|../../../../posts/2018/02/small-speakers/index.html");
+    assert_eq!(str::from_utf8(&center_buf).unwrap(),
+               "posts/2018/02/small-speakers/index.html|<p><img src=\"posts/2018/02/small-speakers/assets/img.png\" alt=\"img\" /></p>\n");
+    assert_eq!(str::from_utf8(&tag_center_buf).unwrap(),
+               "../posts/2018/01/big-speakers/index.html|<p><img src=\"../posts/2018/02/small-speakers/assets/img.png\" alt=\"img\" /></p>\n");
+    assert!(root.join("out").join("posts").join("2018").join("02").join("small-speakers").join("assets").join("img.png").is_file());
+}
+
+#[test]
+fn machine_output() {
+    let root = temp_dir().join("bloguen-test").join("ops-post-generate-permalink-machine_output");
+    let _ = fs::remove_dir_all(&root);
+    let post = post("ops-post-generate-permalink-machine_output", "1. 2018-01-08 16-52 Big speakers", "big-speakers");
+
+    post.create_machine_output(&("$ROOT/out/".to_string(), root.join("out")), "machine/", &MachineDataKind::Json).unwrap();
+    assert!(root.join("out").join("machine").join("big-speakers.json").is_file());
+}
+
+#[test]
+fn feed() {
+    let post = post("ops-post-generate-permalink-feed", "1. 2018-01-08 16-52 Big speakers", "big-speakers");
+
+    let mut out = vec![];
+    post.generate_feed_head(&mut out, &FeedType::Atom, "feeds/atom.xml", None, &LANGUAGE_EN_GB, "autheur", false).unwrap();
+    let out = String::from_utf8(out).unwrap();
+    assert!(out.contains("<link rel=\"alternate\" href=\"../posts/2018/01/big-speakers/index.html\" />"));
+    assert!(out.contains("xml:base=\"../posts/2018/01/big-speakers/\""));
+
+    let mut out = vec![];
+    post.generate_feed_head(&mut out, &FeedType::Atom, "feeds/atom.xml", Some(&"https://nabijaczleweli.xyz/blogue/".parse().unwrap()),
+                            &LANGUAGE_EN_GB, "autheur", false)
+        .unwrap();
+    let out = String::from_utf8(out).unwrap();
+    assert!(out.contains("<link rel=\"alternate\" href=\"https://nabijaczleweli.xyz/blogue/posts/2018/01/big-speakers/index.html\" />"));
+    assert!(out.contains("xml:base=\"https://nabijaczleweli.xyz/blogue/posts/2018/01/big-speakers/\""));
+}
@@ -0,0 +1,169 @@
+use bloguen::util::LANGUAGE_EN_GB;
+use bloguen::ops::{PostRenderOptions, BloguePost};
+use std::io::{Write, Read};
+use std::fs::{self, File};
+use std::env::temp_dir;
+use std::str;
+
+
+fn post(root: &str, name: &str) -> BloguePost {
+    let root = temp_dir().join("bloguen-test").join(root);
+    let post_root = root.join("posts").join(name);
+    fs::create_dir_all(&post_root).unwrap();
+    File::create(post_root.join("post.md")).unwrap().write_all("Блогг".as_bytes()).unwrap();
+
+    BloguePost::new((format!("$ROOT/posts/{}/", name), post_root)).unwrap()
+}
+
+
+#[test]
+fn relative() {
+    let root = temp_dir().join("bloguen-test").join("ops-post-generate-related_posts-relative");
+    let _ = fs::remove_dir_all(&root);
+    let current = post("ops-post-generate-related_posts-relative", "1. 2018-01-08 16-52 Big speakers");
+    let first = post("ops-post-generate-related_posts-relative", "2. 2018-01-09 12-00 Speakers & amps");
+    let second = post("ops-post-generate-related_posts-relative", "3. 2018-02-10 08-30 No speakers");
+
+    let mut center_buf = vec![];
+    assert_eq!(current.generate(&("$ROOT/out/".to_string(), root.join("out")),
+                                None,
+                                Some((&"{related_posts(1)}".parse().unwrap(), &mut center_buf)),
+                                None,
+                                PostRenderOptions { related_posts: &[&first, &second], ..Default::default() },
+                                &"".parse().unwrap(),
+                                &"{if related_posts(2)}<nav>{related_posts(2)}</nav>{end}|{related_posts(0)}".parse().unwrap(),
+                                "Блогг",
+                                &LANGUAGE_EN_GB,
+                                "autheur",
+                                &[],
+                                &[],
+                                &Default::default(),
+                                &Default::default(),
+                                &[],
+                                &[],
+                                &[],
+                                &[]),
+               Ok(vec![]));
+
+    let mut read = String::new();
+    File::open(root.join("out").join("posts").join("1. 2018-01-08 16-52-00 Big speakers.html")).unwrap().read_to_string(&mut read).unwrap();
+    assert_eq!(read,
+               "<p>Блогг</p>\n\
+                <nav><a class=\"related-post\" href=\"../posts/2.%202018-01-09%2012-00-00%20Speakers%20&amp;%20amps.html\">Speakers &amp; amps</a> \
+                <a class=\"related-post\" href=\"../posts/3.%202018-02-10%2008-30-00%20No%20speakers.html\">No speakers</a></nav>|");
+    assert_eq!(str::from_utf8(&center_buf).unwrap(),
+               "<a class=\"related-post\" href=\"posts/2.%202018-01-09%2012-00-00%20Speakers%20&amp;%20amps.html\">Speakers &amp; amps</a>");
+}
+
+#[test]
+fn none() {
+    let root = temp_dir().join("bloguen-test").join("ops-post-generate-related_posts-none");
+    let _ = fs::remove_dir_all(&root);
+    let current = post("ops-post-generate-related_posts-none", "1. 2018-01-08 16-52 Big speakers");
+
+    assert_eq!(current.generate(&("$ROOT/out/".to_string(), root.join("out")),
+                                None,
+                                None,
+                                None,
+                                Default::default(),
+                                &"".parse().unwrap(),
+                                &"{if related_posts(3)}<nav>{related_posts(3)}</nav>{else}Nothing related{end}".parse().unwrap(),
+                                "Блогг",
+                                &LANGUAGE_EN_GB,
+                                "autheur",
+                                &[],
+                                &[],
+                                &Default::default(),
+                                &Default::default(),
+                                &[],
+                                &[],
+                                &[],
+                                &[]),
+               Ok(vec![]));
+
+    let mut read = String::new();
+    File::open(root.join("out").join("posts").join("1. 2018-01-08 16-52-00 Big speakers.html")).unwrap().read_to_string(&mut read).unwrap();
+    assert_eq!(read, "<p>Блогг</p>\nNothing related");
+}
+
+#[test]
+fn base_url() {
+    let root = temp_dir().join("bloguen-test").join("ops-post-generate-related_posts-base_url");
+    let _ = fs::remove_dir_all(&root);
+    let current = post("ops-post-generate-related_posts-base_url", "1. 2018-01-08 16-52 Big speakers");
+    let related = post("ops-post-generate-related_posts-base_url", "2. 2018-01-09 12-00 Small speakers");
+
+    assert_eq!(current.generate(&("$ROOT/out/".to_string(), root.join("out")),
+                                None,
+                                None,
+                                None,
+                                PostRenderOptions {
+                                    base_url: Some(&"https://nabijaczleweli.xyz/blogue/".parse().unwrap()),
+                                    related_posts: &[&related],
+                                    ..Default::default()
+                                },
+                                &"".parse().unwrap(),
+                                &"{related_posts(5)}".parse().unwrap(),
+                                "Блогг",
+                                &LANGUAGE_EN_GB,
+                                "autheur",
+                                &[],
+                                &[],
+                                &Default::default(),
+                                &Default::default(),
+                                &[],
+                                &[],
+                                &[],
+                                &[]),
+               Ok(vec![]));
+
+    let mut read = String::new();
+    File::open(root.join("out").join("posts").join("1. 2018-01-08 16-52-00 Big speakers.html")).unwrap().read_to_string(&mut read).unwrap();
+    assert_eq!(read,
+               "<p>Блогг</p>\n\
+                <a class=\"related-post\" href=\"https://nabijaczleweli.xyz/blogue/posts/2.%202018-01-09%2012-00-00%20Small%20speakers.html\">\
+                Small speakers</a>");
+}
+
+#[test]
+fn escaped() {
+    let root = temp_dir().join("bloguen-test").join("ops-post-generate-related_posts-escaped");
+    let _ = fs::remove_dir_all(&root);
+    let current = post("ops-post-generate-related_posts-escaped", "1. 2018-01-08 16-52 Big speakers");
+    let mut related = post("ops-post-generate-related_posts-escaped", "2. 2018-01-09 12-00 Loud speakers");
+    related.name = "\"Loud\" & clear".to_string();
+    related.slug = "\"Loud\" & 'clear'".to_string();
+    related.apply_permalink_pattern("posts/{slug}.html");
+
+    for &(ref base_url, link) in &[(None, "../posts/%22Loud%22%20&amp;%20&#39;clear&#39;.html"),
+                                   (Some("https://nabijaczleweli.xyz/blogue/".parse().unwrap()),
+                                    "https://nabijaczleweli.xyz/blogue/posts/%22Loud%22%20&amp;%20&#39;clear&#39;.html")] {
+        assert_eq!(current.generate(&("$ROOT/out/".to_string(), root.join("out")),
+                                    None,
+                                    None,
+                                    None,
+                                    PostRenderOptions {
+                                        base_url: base_url.as_ref(),
+                                        related_posts: &[&related],
+                                        ..Default::default()
+                                    },
+                                    &"".parse().unwrap(),
+                                    &"{related_posts(1)}".parse().unwrap(),
+                                    "Блогг",
+                                    &LANGUAGE_EN_GB,
+                                    "autheur",
+                                    &[],
+                                    &[],
+                                    &Default::default(),
+                                    &Default::default(),
+                                    &[],
+                                    &[],
+                                    &[],
+                                    &[]),
+                   Ok(vec![]));
+
+        let mut read = String::new();
+        File::open(root.join("out").join("posts").join("1. 2018-01-08 16-52-00 Big speakers.html")).unwrap().read_to_string(&mut read).unwrap();
+        assert_eq!(read, format!("<p>Блогг</p>\n<a class=\"related-post\" href=\"{}\">\"Loud\" &amp; clear</a>", link));
+    }
+}
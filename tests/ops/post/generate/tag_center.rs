@@ -0,0 +1,104 @@
+use bloguen::util::LANGUAGE_EN_GB;
+use bloguen::ops::{PostRenderOptions, BloguePost};
+use std::io::{Write, Read};
+use std::fs::{self, File};
+use std::env::temp_dir;
+use std::str;
+
+
+#[test]
+fn no_asset_override() {
+    let root = temp_dir().join("bloguen-test").join("ops-post-generate-tag_center-no_asset_override");
+    let _ = fs::remove_dir_all(&root);
+    let post_root = root.join("posts").join("1. 2018-01-08 16-52 Big speakers");
+    fs::create_dir_all(post_root.join("assets")).unwrap();
+    File::create(post_root.join("post.md")).unwrap().write_all("![img](assets/image.png)".as_bytes()).unwrap();
+    File::create(post_root.join("assets").join("image.png")).unwrap();
+
+    let post = BloguePost::new(("$ROOT/posts/1. 2018-01-08 16-52 Big speakers/".to_string(), post_root)).unwrap();
+    let mut center_buf = vec![];
+    let mut tag_center_buf = vec![];
+    assert_eq!(post.generate(&("$ROOT/out/".to_string(), root.join("out")),
+                             None,
+                             Some((&"{tags}{data-post_content}".parse().unwrap(), &mut center_buf as &mut dyn Write)),
+                             None,
+                             PostRenderOptions {
+                                 tag_center_output: Some((&"{tag_index}|{tags}{data-post_content}".parse().unwrap(), &mut tag_center_buf as &mut dyn Write)),
+                                 ..Default::default()
+                             },
+                             &"{tag_index}|{tags}".parse().unwrap(),
+                             &"".parse().unwrap(),
+                             "Блогг",
+                             &LANGUAGE_EN_GB,
+                             "autheur",
+                             &["vodka".parse().unwrap()],
+                             &["C/C++".parse().unwrap()],
+                             &Default::default(),
+                             &Default::default(),
+                             &[],
+                             &[],
+                             &[],
+                             &[]),
+               Ok(vec!["assets/image.png".to_string()]));
+
+    let mut read = String::new();
+    File::open(root.join("out").join("posts").join(post.normalised_name() + ".html")).unwrap().read_to_string(&mut read).unwrap();
+    assert_eq!(read,
+               "../tags/|<a href=\"../tags/vodka.html\"><span class=\"post-tag\">vodka</span></a> \
+                <a href=\"../tags/C_C++.html\"><span class=\"post-tag\">C/C++</span></a>\
+                <p><img src=\"assets/image.png\" alt=\"img\" /></p>\n");
+    assert_eq!(str::from_utf8(&center_buf).unwrap(),
+               "<a href=\"tags/vodka.html\"><span class=\"post-tag\">vodka</span></a> \
+                <a href=\"tags/C_C++.html\"><span class=\"post-tag\">C/C++</span></a>\
+                <p><img src=\"posts/assets/image.png\" alt=\"img\" /></p>\n");
+    assert_eq!(str::from_utf8(&tag_center_buf).unwrap(),
+               "|<a href=\"vodka.html\"><span class=\"post-tag\">vodka</span></a> \
+                <a href=\"C_C++.html\"><span class=\"post-tag\">C/C++</span></a>\
+                <p><img src=\"../posts/assets/image.png\" alt=\"img\" /></p>\n");
+}
+
+#[test]
+fn asset_override() {
+    let root = temp_dir().join("bloguen-test").join("ops-post-generate-tag_center-asset_override");
+    let _ = fs::remove_dir_all(&root);
+    let post_root = root.join("posts").join("1. 2018-01-08 16-52 Big speakers");
+    fs::create_dir_all(post_root.join("assets")).unwrap();
+    File::create(post_root.join("post.md")).unwrap().write_all("![img](assets/image.png)".as_bytes()).unwrap();
+    File::create(post_root.join("assets").join("image.png")).unwrap();
+
+    let post = BloguePost::new(("$ROOT/posts/1. 2018-01-08 16-52 Big speakers/".to_string(), post_root)).unwrap();
+    let mut center_buf = vec![];
+    let mut tag_center_buf = vec![];
+    assert_eq!(post.generate(&("$ROOT/out/".to_string(), root.join("out")),
+                             None,
+                             Some((&"{data-post_content}".parse().unwrap(), &mut center_buf as &mut dyn Write)),
+                             Some("overriden-assets/"),
+                             PostRenderOptions {
+                                 tag_center_output: Some((&"{tag_index}|{data-post_content}".parse().unwrap(), &mut tag_center_buf as &mut dyn Write)),
+                                 base_url: Some(&"https://nabijaczleweli.xyz/blogue/".parse().unwrap()),
+                                 ..Default::default()
+                             },
+                             &"{tag_index}|".parse().unwrap(),
+                             &"".parse().unwrap(),
+                             "Блогг",
+                             &LANGUAGE_EN_GB,
+                             "autheur",
+                             &[],
+                             &[],
+                             &Default::default(),
+                             &Default::default(),
+                             &[],
+                             &[],
+                             &[],
+                             &[]),
+               Ok(vec!["assets/image.png".to_string()]));
+
+    let mut read = String::new();
+    File::open(root.join("out").join("posts").join(post.normalised_name() + ".html")).unwrap().read_to_string(&mut read).unwrap();
+    assert_eq!(read,
+               "https://nabijaczleweli.xyz/blogue/tags/|<p><img src=\"../overriden-assets/assets/image.png\" alt=\"img\" /></p>\n");
+    assert_eq!(str::from_utf8(&center_buf).unwrap(),
+               "<p><img src=\"overriden-assets/assets/image.png\" alt=\"img\" /></p>\n");
+    assert_eq!(str::from_utf8(&tag_center_buf).unwrap(),
+               "https://nabijaczleweli.xyz/blogue/tags/|<p><img src=\"../overriden-assets/assets/image.png\" alt=\"img\" /></p>\n");
+}
@@ -0,0 +1,177 @@
+use bloguen::util::LANGUAGE_EN_GB;
+use bloguen::ops::{PostRenderOptions, BloguePost};
+use std::io::{Write, Read};
+use std::fs::{self, File};
+use std::env::temp_dir;
+use std::str;
+
+
+#[test]
+fn nested() {
+    let root = temp_dir().join("bloguen-test").join("ops-post-generate-toc-nested");
+    let _ = fs::remove_dir_all(&root);
+    let post_root = root.join("posts").join("1. 2018-01-08 16-52 Big speakers");
+    fs::create_dir_all(&post_root).unwrap();
+    File::create(post_root.join("post.md")).unwrap().write_all("# Speakers\n\n## Drivers\n\n### Tweeters\n\n## Boxes\n\n# Speakers\n".as_bytes()).unwrap();
+
+    let post = BloguePost::new(("$ROOT/posts/1. 2018-01-08 16-52 Big speakers/".to_string(), post_root)).unwrap();
+    let mut center_buf = vec![];
+    assert_eq!(post.generate(&("$ROOT/out/".to_string(), root.join("out")),
+                             None,
+                             Some((&"{if toc}<nav>{toc}</nav>{else}No headings{end}|".parse().unwrap(), &mut center_buf as &mut dyn Write)),
+                             None,
+                             PostRenderOptions { toc: true, ..Default::default() },
+                             &"{toc}|".parse().unwrap(),
+                             &"".parse().unwrap(),
+                             "Блогг",
+                             &LANGUAGE_EN_GB,
+                             "autheur",
+                             &[],
+                             &[],
+                             &Default::default(),
+                             &Default::default(),
+                             &[],
+                             &[],
+                             &[],
+                             &[]),
+               Ok(vec![]));
+
+    let mut read = String::new();
+    File::open(root.join("out").join("posts").join(post.normalised_name() + ".html")).unwrap().read_to_string(&mut read).unwrap();
+    let center = str::from_utf8(&center_buf).unwrap();
+    let toc = "<ul>\n\
+                   <li><a href=\"#speakers\">Speakers</a>\n\
+                   <ul>\n\
+                   <li><a href=\"#drivers\">Drivers</a>\n\
+                   <ul>\n\
+                   <li><a href=\"#tweeters\">Tweeters</a></li>\n\
+                   </ul>\n\
+                   </li>\n\
+                   <li><a href=\"#boxes\">Boxes</a></li>\n\
+                   </ul>\n\
+                   </li>\n\
+                   <li><a href=\"#speakers-1\">Speakers</a></li>\n\
+                   </ul>";
+    assert!(read.starts_with(&format!("{}|", toc)), "{}", read);
+    assert!(read.contains("id=\"speakers-1\""), "{}", read);
+    assert!(center.starts_with(&format!("<nav>{}</nav>|", toc)), "{}", center);
+}
+
+#[test]
+fn skipped_levels() {
+    let root = temp_dir().join("bloguen-test").join("ops-post-generate-toc-skipped_levels");
+    let _ = fs::remove_dir_all(&root);
+    let post_root = root.join("posts").join("1. 2018-01-08 16-52 Big speakers");
+    fs::create_dir_all(&post_root).unwrap();
+    File::create(post_root.join("post.md")).unwrap().write_all("### Deep\n\n# Shallow\n\n### Deeper\n\n## Middle\n".as_bytes()).unwrap();
+
+    let post = BloguePost::new(("$ROOT/posts/1. 2018-01-08 16-52 Big speakers/".to_string(), post_root)).unwrap();
+    let mut center_buf = vec![];
+    assert_eq!(post.generate(&("$ROOT/out/".to_string(), root.join("out")),
+                             None,
+                             Some((&"{if toc}<nav>{toc}</nav>{else}No headings{end}|".parse().unwrap(), &mut center_buf as &mut dyn Write)),
+                             None,
+                             PostRenderOptions { toc: true, ..Default::default() },
+                             &"{toc}|".parse().unwrap(),
+                             &"".parse().unwrap(),
+                             "Блогг",
+                             &LANGUAGE_EN_GB,
+                             "autheur",
+                             &[],
+                             &[],
+                             &Default::default(),
+                             &Default::default(),
+                             &[],
+                             &[],
+                             &[],
+                             &[]),
+               Ok(vec![]));
+
+    let mut read = String::new();
+    File::open(root.join("out").join("posts").join(post.normalised_name() + ".html")).unwrap().read_to_string(&mut read).unwrap();
+    assert!(read.starts_with("<ul>\n\
+                              <li><a href=\"#deep\">Deep</a></li>\n\
+                              <li><a href=\"#shallow\">Shallow</a>\n\
+                              <ul>\n\
+                              <li><a href=\"#deeper\">Deeper</a></li>\n\
+                              <li><a href=\"#middle\">Middle</a></li>\n\
+                              </ul>\n\
+                              </li>\n\
+                              </ul>|"),
+            "{}",
+            read);
+}
+
+#[test]
+fn no_headings() {
+    let root = temp_dir().join("bloguen-test").join("ops-post-generate-toc-no_headings");
+    let _ = fs::remove_dir_all(&root);
+    let post_root = root.join("posts").join("1. 2018-01-08 16-52 Big speakers");
+    fs::create_dir_all(&post_root).unwrap();
+    File::create(post_root.join("post.md")).unwrap().write_all("Just a paragraph.\n".as_bytes()).unwrap();
+
+    let post = BloguePost::new(("$ROOT/posts/1. 2018-01-08 16-52 Big speakers/".to_string(), post_root)).unwrap();
+    let mut center_buf = vec![];
+    assert_eq!(post.generate(&("$ROOT/out/".to_string(), root.join("out")),
+                             None,
+                             Some((&"{if toc}<nav>{toc}</nav>{else}No headings{end}|".parse().unwrap(), &mut center_buf as &mut dyn Write)),
+                             None,
+                             PostRenderOptions { toc: true, ..Default::default() },
+                             &"{toc}|".parse().unwrap(),
+                             &"".parse().unwrap(),
+                             "Блогг",
+                             &LANGUAGE_EN_GB,
+                             "autheur",
+                             &[],
+                             &[],
+                             &Default::default(),
+                             &Default::default(),
+                             &[],
+                             &[],
+                             &[],
+                             &[]),
+               Ok(vec![]));
+
+    let mut read = String::new();
+    File::open(root.join("out").join("posts").join(post.normalised_name() + ".html")).unwrap().read_to_string(&mut read).unwrap();
+    let center = str::from_utf8(&center_buf).unwrap();
+    assert!(read.starts_with("|<p>"), "{}", read);
+    assert!(center.starts_with("No headings|"), "{}", center);
+}
+
+#[test]
+fn disabled() {
+    let root = temp_dir().join("bloguen-test").join("ops-post-generate-toc-disabled");
+    let _ = fs::remove_dir_all(&root);
+    let post_root = root.join("posts").join("1. 2018-01-08 16-52 Big speakers");
+    fs::create_dir_all(&post_root).unwrap();
+    File::create(post_root.join("post.md")).unwrap().write_all("# Speakers\n\n## Drivers\n".as_bytes()).unwrap();
+
+    let post = BloguePost::new(("$ROOT/posts/1. 2018-01-08 16-52 Big speakers/".to_string(), post_root)).unwrap();
+    let mut center_buf = vec![];
+    assert_eq!(post.generate(&("$ROOT/out/".to_string(), root.join("out")),
+                             None,
+                             Some((&"{if toc}<nav>{toc}</nav>{else}No headings{end}|".parse().unwrap(), &mut center_buf as &mut dyn Write)),
+                             None,
+                             PostRenderOptions { toc: false, ..Default::default() },
+                             &"{toc}|".parse().unwrap(),
+                             &"".parse().unwrap(),
+                             "Блогг",
+                             &LANGUAGE_EN_GB,
+                             "autheur",
+                             &[],
+                             &[],
+                             &Default::default(),
+                             &Default::default(),
+                             &[],
+                             &[],
+                             &[],
+                             &[]),
+               Ok(vec![]));
+
+    let mut read = String::new();
+    File::open(root.join("out").join("posts").join(post.normalised_name() + ".html")).unwrap().read_to_string(&mut read).unwrap();
+    let center = str::from_utf8(&center_buf).unwrap();
+    assert!(read.starts_with("|<h1>"), "{}", read);
+    assert!(center.starts_with("No headings|"), "{}", center);
+}
@@ -0,0 +1,116 @@
+use bloguen::util::LANGUAGE_EN_GB;
+use bloguen::ops::{BlogueDescriptorHooks, PostRenderOptions, MachineDataKind, MarkdownOptions, BloguePost};
+use std::io::{Write, Read};
+use std::fs::{self, File};
+use std::env::temp_dir;
+use std::str;
+
+
+#[test]
+fn variables() {
+    let root = temp_dir().join("bloguen-test").join("ops-post-generate-word_count-variables");
+    let _ = fs::remove_dir_all(&root);
+    let post_root = root.join("posts").join("1. 2018-01-08 16-52 Big speakers");
+    fs::create_dir_all(&post_root).unwrap();
+    File::create(post_root.join("post.md"))
+        .unwrap()
+        .write_all("Five words in this paragraph.\n\n```\nnot counted at all\n```\n\nAnd `code` three.".as_bytes())
+        .unwrap();
+
+    let post = BloguePost::new(("$ROOT/posts/1. 2018-01-08 16-52 Big speakers/".to_string(), post_root)).unwrap();
+    let mut center_buf = vec![];
+    assert_eq!(post.generate(&("$ROOT/out/".to_string(), root.join("out")),
+                             None,
+                             Some((&"{word_count}|{reading_time(3)}".parse().unwrap(), &mut center_buf)),
+                             None,
+                             Default::default(),
+                             &"{word_count}|{reading_time}|{reading_time()}|{reading_time(2)}|".parse().unwrap(),
+                             &"".parse().unwrap(),
+                             "Блогг",
+                             &LANGUAGE_EN_GB,
+                             "autheur",
+                             &[],
+                             &[],
+                             &Default::default(),
+                             &Default::default(),
+                             &[],
+                             &[],
+                             &[],
+                             &[]),
+               Ok(vec![]));
+
+    let mut read = String::new();
+    File::open(root.join("out").join("posts").join("1. 2018-01-08 16-52-00 Big speakers.html")).unwrap().read_to_string(&mut read).unwrap();
+    assert!(read.starts_with("7|1|1|4|<p>"), "{}", read);
+    assert_eq!(str::from_utf8(&center_buf).unwrap(), "7|3");
+
+    let mut machine = vec![];
+    post.generate_machine(&mut machine,
+                          &MachineDataKind::Json,
+                          &Default::default(),
+                          "Блогг",
+                          &LANGUAGE_EN_GB,
+                          "autheur",
+                          &[],
+                          &[],
+                          &Default::default(),
+                          &Default::default(),
+                          &[],
+                          &[],
+                          &[],
+                          &[])
+        .unwrap();
+    let machine = str::from_utf8(&machine).unwrap();
+    assert!(machine.contains("\n    \"word_count\": 7,\n    \"reading_time\": 1,\n"), "{}", machine);
+}
+
+#[test]
+fn machine_render_options() {
+    let root = temp_dir().join("bloguen-test").join("ops-post-generate-word_count-machine_render_options");
+    let _ = fs::remove_dir_all(&root);
+    let post_root = root.join("posts").join("1. 2018-01-08 16-52 Big speakers");
+    fs::create_dir_all(&post_root).unwrap();
+    File::create(post_root.join("post.md")).unwrap().write_all("Words[^1] about $a + b$ here.\n\n[^1]: A note.".as_bytes()).unwrap();
+    let post = BloguePost::new(("$ROOT/posts/1. 2018-01-08 16-52 Big speakers/".to_string(), post_root)).unwrap();
+
+    let machine_word_count = |render: &PostRenderOptions| {
+        let mut machine = vec![];
+        post.generate_machine(&mut machine,
+                              &MachineDataKind::Json,
+                              render,
+                              "Блогг",
+                              &LANGUAGE_EN_GB,
+                              "autheur",
+                              &[],
+                              &[],
+                              &Default::default(),
+                              &Default::default(),
+                              &[],
+                              &[],
+                              &[],
+                              &[])
+            .unwrap();
+        let machine = String::from_utf8(machine).unwrap();
+        let word_count = &machine[machine.find("\"word_count\": ").unwrap() + "\"word_count\": ".len()..];
+        word_count[..word_count.find(',').unwrap()].parse::<usize>().unwrap()
+    };
+
+    assert_eq!(machine_word_count(&Default::default()), 6 + 3);
+    assert_eq!(machine_word_count(&PostRenderOptions { math: true, ..Default::default() }), 3 + 3);
+    assert_eq!(machine_word_count(&PostRenderOptions {
+                   math: true,
+                   markdown: MarkdownOptions { footnotes: Some(true), ..Default::default() },
+                   ..Default::default()
+               }),
+               3 + 2);
+    if cfg!(not(target_os = "windows")) {
+        assert_eq!(machine_word_count(&PostRenderOptions {
+                       hooks: &BlogueDescriptorHooks {
+                           pre: vec!["printf 'Only \\$%s$ words' \"$BLOGUEN_NUMBER\"".to_string()],
+                           post: vec![],
+                       },
+                       ..Default::default()
+                   }),
+                   3);
+    }
+}
@@ -0,0 +1,62 @@
+use chrono::{Duration, Offset, Utc};
+use bloguen::ops::BloguePost;
+use std::env::temp_dir;
+use std::fs;
+
+
+fn post(root: &str, name: &str) -> BloguePost {
+    let root = temp_dir().join("bloguen-test").join(root);
+    let _ = fs::remove_dir_all(&root);
+    fs::create_dir_all(root.join("posts").join(name)).unwrap();
+    BloguePost::new((format!("$ROOT/posts/{}/", name), root.join("posts").join(name))).unwrap()
+}
+
+
+#[test]
+fn published() {
+    let post = post("ops-post-is_published-published", "1. 2018-01-08 16-52 Speakers");
+    let now = post.datetime.with_timezone(&post.datetime.offset().fix()) + Duration::days(1);
+    for &(drafts, future) in &[(false, false), (true, false), (false, true), (true, true)] {
+        assert!(post.is_published(&now, drafts, future), "{} {}", drafts, future);
+    }
+}
+
+#[test]
+fn draft() {
+    let post = post("ops-post-is_published-draft", "1. 2018-01-08 16-52 Speakers_draft");
+    let now = post.datetime.with_timezone(&post.datetime.offset().fix()) + Duration::days(1);
+    assert!(!post.is_published(&now, false, false));
+    assert!(post.is_published(&now, true, false));
+    assert!(!post.is_published(&now, false, true));
+    assert!(post.is_published(&now, true, true));
+}
+
+#[test]
+fn future() {
+    let post = post("ops-post-is_published-future", "1. 2018-01-08 16-52 Speakers");
+    let now = post.datetime.with_timezone(&post.datetime.offset().fix()) - Duration::days(1);
+    assert!(!post.is_published(&now, false, false));
+    assert!(!post.is_published(&now, true, false));
+    assert!(post.is_published(&now, false, true));
+    assert!(post.is_published(&now, true, true));
+}
+
+#[test]
+fn future_draft() {
+    let post = post("ops-post-is_published-future_draft", "1. 2018-01-08 16-52 Speakers_draft");
+    let now = post.datetime.with_timezone(&post.datetime.offset().fix()) - Duration::days(1);
+    assert!(!post.is_published(&now, false, false));
+    assert!(!post.is_published(&now, true, false));
+    assert!(!post.is_published(&now, false, true));
+    assert!(post.is_published(&now, true, true));
+}
+
+#[test]
+fn now_boundary() {
+    let post = post("ops-post-is_published-now_boundary", "1. 2018-01-08 16-52 Speakers");
+    let now = post.datetime.with_timezone(&post.datetime.offset().fix());
+    assert!(post.is_published(&now, false, false));
+    assert!(post.is_published(&now.with_timezone(&Utc), false, false));
+    assert!(!post.is_published(&(now - Duration::seconds(1)), false, false));
+    assert!(post.is_published(&(now + Duration::seconds(1)), false, false));
+}
@@ -2,10 +2,14 @@ use bloguen::ops::BloguePost;
 use std::env::temp_dir;
 use std::fs;
 
+mod check_unique_paths;
 mod copy_asset;
 mod generate;
+mod is_published;
 mod list;
 mod new;
+mod related_posts;
+mod source;
 
 
 #[test]
@@ -23,7 +23,10 @@ fn ok() {
                    source_dir: dir,
                    number: (1, "1".to_string()),
                    name: "My first venture into crocheting, and what I've learned".to_string(),
+                   slug: "1. 2018-01-08 16-52-00 My first venture into crocheting, and what I've learned".to_string(),
+                   path: "posts/1. 2018-01-08 16-52-00 My first venture into crocheting, and what I've learned.html".to_string(),
                    datetime: LocalOffset.ymd(2018, 01, 08).and_hms(16, 52, 00),
+                   draft: false,
                }));
 
     let dir = ("$ROOT/posts/03. 2018-02-05 release-front - a generic release front-end, like Patchwork's".to_string(),
@@ -33,7 +36,10 @@ fn ok() {
                    source_dir: dir,
                    number: (3, "03".to_string()),
                    name: "release-front - a generic release front-end, like Patchwork's".to_string(),
+                   slug: "03. 2018-02-05 12-33-05 release-front - a generic release front-end, like Patchwork's".to_string(),
+                   path: "posts/03. 2018-02-05 12-33-05 release-front - a generic release front-end, like Patchwork's.html".to_string(),
                    datetime: LocalOffset.ymd(2018, 02, 05).and_hms(12, 33, 05),
+                   draft: false,
                }));
 
     let dir = ("$ROOT/posts/005. 2018-04-19 23-19-21 cursed device chain".to_string(), root.join("posts").join("005. 2018-04-19 23-19-21 cursed device chain"));
@@ -42,7 +48,44 @@ fn ok() {
                    source_dir: dir,
                    number: (5, "005".to_string()),
                    name: "cursed device chain".to_string(),
+                   slug: "005. 2018-04-19 23-19-21 cursed device chain".to_string(),
+                   path: "posts/005. 2018-04-19 23-19-21 cursed device chain.html".to_string(),
                    datetime: LocalOffset.ymd(2018, 04, 19).and_hms(23, 19, 21),
+                   draft: false,
+               }));
+}
+
+#[test]
+fn ok_draft() {
+    let root = temp_dir().join("bloguen-test").join("ops-post-new-ok_draft");
+    let _ = fs::remove_dir_all(&root);
+    for d in &["1. 2018-01-08 16-52 Big speakers_draft", "2. 2018-02-05 23-24-43 Small speakers _draft"] {
+        fs::create_dir_all(root.join("posts").join(d)).unwrap();
+    }
+
+    let dir = ("$ROOT/posts/1. 2018-01-08 16-52 Big speakers_draft".to_string(), root.join("posts").join("1. 2018-01-08 16-52 Big speakers_draft"));
+    assert_eq!(BloguePost::new(dir.clone()),
+               Ok(BloguePost {
+                   source_dir: dir,
+                   number: (1, "1".to_string()),
+                   name: "Big speakers".to_string(),
+                   slug: "1. 2018-01-08 16-52-00 Big speakers".to_string(),
+                   path: "posts/1. 2018-01-08 16-52-00 Big speakers.html".to_string(),
+                   datetime: LocalOffset.ymd(2018, 01, 08).and_hms(16, 52, 00),
+                   draft: true,
+               }));
+
+    let dir = ("$ROOT/posts/2. 2018-02-05 23-24-43 Small speakers _draft".to_string(),
+               root.join("posts").join("2. 2018-02-05 23-24-43 Small speakers _draft"));
+    assert_eq!(BloguePost::new(dir.clone()),
+               Ok(BloguePost {
+                   source_dir: dir,
+                   number: (2, "2".to_string()),
+                   name: "Small speakers".to_string(),
+                   slug: "2. 2018-02-05 23-24-43 Small speakers".to_string(),
+                   path: "posts/2. 2018-02-05 23-24-43 Small speakers.html".to_string(),
+                   datetime: LocalOffset.ymd(2018, 02, 05).and_hms(23, 24, 43),
+                   draft: true,
                }));
 }
 
@@ -0,0 +1,61 @@
+use bloguen::ops::{BloguePost, TagName};
+use std::env::temp_dir;
+use std::fs;
+
+
+fn posts(root: &str, names: &[&str]) -> Vec<BloguePost> {
+    let root = temp_dir().join("bloguen-test").join(root);
+    let _ = fs::remove_dir_all(&root);
+    names.iter()
+        .map(|name| {
+            fs::create_dir_all(root.join("posts").join(name)).unwrap();
+            BloguePost::new((format!("$ROOT/posts/{}/", name), root.join("posts").join(name))).unwrap()
+        })
+        .collect()
+}
+
+fn tags(tags: &[&str]) -> Vec<TagName> {
+    tags.iter().map(|t| t.parse().unwrap()).collect()
+}
+
+
+#[test]
+fn shared_tags_first() {
+    let posts = posts("ops-post-related_posts-shared_tags_first",
+                      &["1. 2018-01-08 Speakers", "2. 2018-01-09 Amps", "3. 2018-06-01 Cables", "4. 2018-06-02 Crochet"]);
+    let tags = vec![tags(&["audio", "diy"]), tags(&["audio"]), tags(&["audio", "diy"]), tags(&["diy"])];
+    let all: Vec<_> = posts.iter().zip(&tags).map(|(p, t)| (p, &t[..])).collect();
+
+    assert_eq!(posts[0].related_posts(&tags[0], &all), vec![&posts[2], &posts[1], &posts[3]]);
+    assert_eq!(posts[1].related_posts(&tags[1], &all), vec![&posts[0], &posts[2]]);
+    assert_eq!(posts[3].related_posts(&tags[3], &all), vec![&posts[2], &posts[0]]);
+}
+
+#[test]
+fn closest_date() {
+    let posts = posts("ops-post-related_posts-closest_date",
+                      &["1. 2018-01-01 Before", "2. 2018-01-10 Current", "3. 2018-01-12 After", "4. 2018-03-01 Much after"]);
+    let tags = vec![tags(&["audio"]), tags(&["audio"]), tags(&["audio"]), tags(&["audio"])];
+    let all: Vec<_> = posts.iter().zip(&tags).map(|(p, t)| (p, &t[..])).collect();
+
+    assert_eq!(posts[1].related_posts(&tags[1], &all), vec![&posts[2], &posts[0], &posts[3]]);
+}
+
+#[test]
+fn duplicate_tags() {
+    let posts = posts("ops-post-related_posts-duplicate_tags", &["1. 2018-01-01 Current", "2. 2018-01-02 Repeated", "3. 2018-02-01 Different"]);
+    let tags = vec![tags(&["audio", "diy"]), tags(&["audio", "audio", "audio"]), tags(&["audio", "diy"])];
+    let all: Vec<_> = posts.iter().zip(&tags).map(|(p, t)| (p, &t[..])).collect();
+
+    assert_eq!(posts[0].related_posts(&tags[0], &all), vec![&posts[2], &posts[1]]);
+}
+
+#[test]
+fn unrelated() {
+    let posts = posts("ops-post-related_posts-unrelated", &["1. 2018-01-01 Speakers", "2. 2018-01-02 Crochet"]);
+    let tags = vec![tags(&["audio"]), tags(&["diy"])];
+    let all: Vec<_> = posts.iter().zip(&tags).map(|(p, t)| (p, &t[..])).collect();
+
+    assert_eq!(posts[0].related_posts(&tags[0], &all), Vec::<&BloguePost>::new());
+    assert_eq!(posts[0].related_posts(&tags[0], &all[..1]), Vec::<&BloguePost>::new());
+}
@@ -0,0 +1,49 @@
+use bloguen::ops::BloguePost;
+use std::fs::{self, File};
+use std::env::temp_dir;
+
+
+fn post(test: &str, files: &[&str]) -> BloguePost {
+    let root = temp_dir().join("bloguen-test").join(format!("ops-post-source-{}", test));
+    let _ = fs::remove_dir_all(&root);
+    let post_root = root.join("posts").join("1. 2018-01-08 16-52 Big speakers");
+    fs::create_dir_all(&post_root).unwrap();
+    for f in files {
+        File::create(post_root.join(f)).unwrap();
+    }
+
+    BloguePost::new(("$ROOT/posts/1. 2018-01-08 16-52 Big speakers/".to_string(), post_root)).unwrap()
+}
+
+
+#[test]
+fn markdown() {
+    let post = post("markdown", &["post.md", "post.rst"]);
+    let (format, file) = post.source();
+    assert_eq!(format.extension(), "md");
+    assert_eq!(file, ("$ROOT/posts/1. 2018-01-08 16-52 Big speakers/post.md".to_string(), post.source_dir.1.join("post.md")));
+}
+
+#[test]
+fn html() {
+    let post = post("html", &["post.html", "post.rst"]);
+    let (format, file) = post.source();
+    assert_eq!(format.extension(), "html");
+    assert_eq!(file, ("$ROOT/posts/1. 2018-01-08 16-52 Big speakers/post.html".to_string(), post.source_dir.1.join("post.html")));
+}
+
+#[test]
+fn markdown_precedence() {
+    let post = post("markdown_precedence", &["post.html", "post.md"]);
+    let (format, file) = post.source();
+    assert_eq!(format.extension(), "md");
+    assert_eq!(file, ("$ROOT/posts/1. 2018-01-08 16-52 Big speakers/post.md".to_string(), post.source_dir.1.join("post.md")));
+}
+
+#[test]
+fn none() {
+    let post = post("none", &["post.rst"]);
+    let (format, file) = post.source();
+    assert_eq!(format.extension(), "md");
+    assert_eq!(file, ("$ROOT/posts/1. 2018-01-08 16-52 Big speakers/post.md".to_string(), post.source_dir.1.join("post.md")));
+}
@@ -0,0 +1,26 @@
+use bloguen::ops::mime_type;
+
+
+#[test]
+fn text() {
+    assert_eq!(mime_type("index.html"), "text/html; charset=utf-8");
+    assert_eq!(mime_type("posts/01. 2018-01-08 16-52-00 The venture into crocheting.html"), "text/html; charset=utf-8");
+    assert_eq!(mime_type("assets/common.css"), "text/css; charset=utf-8");
+    assert_eq!(mime_type("feeds/rss.xml"), "text/xml; charset=utf-8");
+    assert_eq!(mime_type("assets/notes.txt"), "text/plain; charset=utf-8");
+    assert_eq!(mime_type("assets/syllable.js"), "text/javascript; charset=utf-8");
+}
+
+#[test]
+fn binary() {
+    assert_eq!(mime_type("assets/image.png"), "image/png");
+    assert_eq!(mime_type("assets/image.jpg"), "image/jpeg");
+    assert_eq!(mime_type("assets/image.svg"), "image/svg+xml");
+    assert_eq!(mime_type("machine/01. 2018-01-08 16-52-00 The venture into crocheting.json"), "application/json");
+}
+
+#[test]
+fn unknown() {
+    assert_eq!(mime_type("assets/data.unknown-extension"), "application/octet-stream");
+    assert_eq!(mime_type("assets/README"), "application/octet-stream");
+}
@@ -0,0 +1,3 @@
+mod resolve_request;
+mod mime_type;
+mod serve;
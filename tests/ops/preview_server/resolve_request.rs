@@ -0,0 +1,73 @@
+use bloguen::ops::{RequestTarget, resolve_request};
+use std::fs::{self, File};
+use std::env::temp_dir;
+use std::path::PathBuf;
+
+
+fn make_root(name: &str) -> PathBuf {
+    let root = temp_dir().join("bloguen-test").join(format!("ops-preview_server-resolve_request-{}", name));
+    let _ = fs::remove_dir_all(&root);
+    fs::create_dir_all(root.join("posts").join("assets")).unwrap();
+    fs::create_dir_all(root.join("feeds")).unwrap();
+    File::create(root.join("index.html")).unwrap();
+    File::create(root.join("posts").join("index.html")).unwrap();
+    File::create(root.join("posts").join("01. 2018-01-08 16-52-00 The venture into crocheting.html")).unwrap();
+    File::create(root.join("posts").join("assets").join("image.png")).unwrap();
+    root
+}
+
+
+#[test]
+fn index() {
+    let root = make_root("index");
+
+    assert_eq!(resolve_request(&root, "/"), RequestTarget::File(root.join("index.html")));
+    assert_eq!(resolve_request(&root, "/posts/"), RequestTarget::File(root.join("posts").join("index.html")));
+    assert_eq!(resolve_request(&root, "/posts/?page=2"), RequestTarget::File(root.join("posts").join("index.html")));
+}
+
+#[test]
+fn index_missing() {
+    let root = make_root("index_missing");
+
+    assert_eq!(resolve_request(&root, "/feeds/"), RequestTarget::NotFound);
+    assert_eq!(resolve_request(&root, "/posts/assets/"), RequestTarget::NotFound);
+}
+
+#[test]
+fn directory_redirect() {
+    let root = make_root("directory_redirect");
+
+    assert_eq!(resolve_request(&root, "/posts"), RequestTarget::Redirect("/posts/".to_string()));
+    assert_eq!(resolve_request(&root, "/posts/assets?q#frag"), RequestTarget::Redirect("/posts/assets/".to_string()));
+}
+
+#[test]
+fn file() {
+    let root = make_root("file");
+
+    assert_eq!(resolve_request(&root, "/posts/assets/image.png"),
+               RequestTarget::File(root.join("posts").join("assets").join("image.png")));
+    assert_eq!(resolve_request(&root, "/posts/./assets/image.png#top"),
+               RequestTarget::File(root.join("posts").join("assets").join("image.png")));
+    assert_eq!(resolve_request(&root, "/posts/01.%202018-01-08%2016-52-00%20The%20venture%20into%20crocheting.html"),
+               RequestTarget::File(root.join("posts").join("01. 2018-01-08 16-52-00 The venture into crocheting.html")));
+}
+
+#[test]
+fn not_found() {
+    let root = make_root("not_found");
+
+    assert_eq!(resolve_request(&root, "/posts/02.html"), RequestTarget::NotFound);
+    assert_eq!(resolve_request(&root, "/index.html/"), RequestTarget::NotFound);
+    assert_eq!(resolve_request(&root, "/%FF"), RequestTarget::NotFound);
+}
+
+#[test]
+fn escape() {
+    let root = make_root("escape");
+
+    assert_eq!(resolve_request(&root, "/../index.html"), RequestTarget::NotFound);
+    assert_eq!(resolve_request(&root, "/posts/%2E%2E/%2E%2E/index.html"), RequestTarget::NotFound);
+    assert_eq!(resolve_request(&root, "//etc/passwd"), RequestTarget::NotFound);
+}
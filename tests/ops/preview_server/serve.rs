@@ -0,0 +1,46 @@
+use std::io::{Write, Read};
+use bloguen::ops::PreviewServer;
+use std::fs::{self, File};
+use std::env::temp_dir;
+use std::net::TcpStream;
+use std::thread;
+
+
+fn request(port: u16, path: &str) -> String {
+    let mut stream = TcpStream::connect(("127.0.0.1", port)).unwrap();
+    stream.write_all(format!("GET {} HTTP/1.1\r\nHost: localhost\r\nConnection: close\r\n\r\n", path).as_bytes()).unwrap();
+
+    let mut response = vec![];
+    stream.read_to_end(&mut response).unwrap();
+    String::from_utf8_lossy(&response).into_owned()
+}
+
+
+#[test]
+fn responses() {
+    let root = temp_dir().join("bloguen-test").join("ops-preview_server-serve-responses");
+    let _ = fs::remove_dir_all(&root);
+    fs::create_dir_all(root.join("posts")).unwrap();
+    File::create(root.join("index.html")).unwrap().write_all("<p>Блогг</p>".as_bytes()).unwrap();
+    File::create(root.join("posts").join("image.png")).unwrap().write_all(b"\x89PNG").unwrap();
+
+    let server = PreviewServer::bind("127.0.0.1:0").unwrap();
+    let port = server.address().port();
+    thread::spawn(move || server.serve(&root));
+
+    let index = request(port, "/");
+    assert!(index.starts_with("HTTP/1.1 200"), "{}", index);
+    assert!(index.contains("Content-Type: text/html; charset=utf-8"), "{}", index);
+    assert!(index.ends_with("<p>Блогг</p>"), "{}", index);
+
+    let image = request(port, "/posts/image.png");
+    assert!(image.starts_with("HTTP/1.1 200"), "{}", image);
+    assert!(image.contains("Content-Type: image/png"), "{}", image);
+
+    let redirect = request(port, "/posts");
+    assert!(redirect.starts_with("HTTP/1.1 301"), "{}", redirect);
+    assert!(redirect.contains("Location: /posts/"), "{}", redirect);
+
+    assert!(request(port, "/posts/").starts_with("HTTP/1.1 404"));
+    assert!(request(port, "/../index.html").starts_with("HTTP/1.1 404"));
+}
@@ -0,0 +1,46 @@
+use std::time::{SystemTime, Duration};
+use bloguen::ops::SourceSnapshot;
+use std::path::Path;
+
+
+#[test]
+fn same() {
+    let now = SystemTime::now();
+    let snapshot = SourceSnapshot { files: vec![(Path::new("blogue.toml").to_path_buf(), Some((now, 12)))].into_iter().collect() };
+
+    assert_eq!(snapshot.differences(&snapshot.clone()), Vec::<&Path>::new());
+}
+
+#[test]
+fn modified() {
+    let now = SystemTime::now();
+    let old = SourceSnapshot {
+        files: vec![(Path::new("blogue.toml").to_path_buf(), Some((now, 12))), (Path::new("header.html").to_path_buf(), Some((now, 0)))]
+            .into_iter()
+            .collect(),
+    };
+    let new = SourceSnapshot {
+        files: vec![(Path::new("blogue.toml").to_path_buf(), Some((now + Duration::from_secs(1), 12))),
+                    (Path::new("header.html").to_path_buf(), Some((now, 1)))]
+            .into_iter()
+            .collect(),
+    };
+
+    assert_eq!(old.differences(&new), vec![Path::new("blogue.toml"), Path::new("header.html")]);
+}
+
+#[test]
+fn created_removed() {
+    let now = SystemTime::now();
+    let old = SourceSnapshot {
+        files: vec![(Path::new("blogue.toml").to_path_buf(), None), (Path::new("header.html").to_path_buf(), Some((now, 0)))].into_iter().collect(),
+    };
+    let new = SourceSnapshot {
+        files: vec![(Path::new("blogue.toml").to_path_buf(), Some((now, 12))), (Path::new("footer.html").to_path_buf(), Some((now, 0)))]
+            .into_iter()
+            .collect(),
+    };
+
+    assert_eq!(old.differences(&new), vec![Path::new("blogue.toml"), Path::new("footer.html"), Path::new("header.html")]);
+    assert_eq!(new.differences(&old), vec![Path::new("blogue.toml"), Path::new("footer.html"), Path::new("header.html")]);
+}
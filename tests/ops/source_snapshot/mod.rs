@@ -0,0 +1,2 @@
+mod differences;
+mod take;
@@ -0,0 +1,156 @@
+use bloguen::ops::{SourceSnapshot, FormatTemplate, BuildManifest};
+use bloguen::util::content_hash;
+use std::fs::{self, File};
+use std::env::temp_dir;
+use std::io::Write;
+
+
+#[test]
+fn all_watched() {
+    let root = temp_dir().join("bloguen-test").join("ops-source_snapshot-take-all_watched");
+    let _ = fs::remove_dir_all(&root);
+    fs::create_dir_all(root.join("templates")).unwrap();
+    fs::create_dir_all(root.join("01. 2018-01-08 16-52 The venture into crocheting").join("assets")).unwrap();
+    fs::create_dir_all(root.join("temp")).unwrap();
+
+    File::create(root.join("blogue.toml"))
+        .unwrap()
+        .write_all("name = \"Блогг\"\n\
+                    header = \"templates/head\"\n\
+                    footer = \"templates/foot\"\n\
+                    styles = [\"file:common.css\", \"link://nabijaczleweli.xyz/kaschism/assets/column.css\"]\n\
+                    \n\
+                    [index]\n\
+                    generate = true\n\
+                    header = \"templates/idx_head\"\n\
+                    center = \"templates/idx_center\"\n\
+                    footer = \"templates/idx_foot\"\n\
+                    scripts = [\"file:index.js\"]\n\
+                    \n\
+                    [tag_index]\n\
+                    header = \"templates/tag_head\"\n\
+                    center = \"templates/tag_center\"\n\
+                    cloud_center = \"templates/tag_cloud\"\n\
+                    footer = \"templates/tag_foot\"\n\
+                    styles = [\"file:tags.css\"]\n"
+            .as_bytes())
+        .unwrap();
+    for f in &["head", "foot", "idx_head", "idx_center", "idx_foot", "tag_head", "tag_center", "tag_cloud", "tag_foot"] {
+        File::create(root.join("templates").join(f)).unwrap();
+    }
+    File::create(root.join("common.css")).unwrap();
+    File::create(root.join("temp").join("unrelated.md")).unwrap();
+
+    let post_root = root.join("01. 2018-01-08 16-52 The venture into crocheting");
+    File::create(post_root.join("post.md")).unwrap();
+    File::create(post_root.join("assets").join("img.png")).unwrap();
+    File::create(post_root.join("metadata.toml")).unwrap().write_all("styles = [\"file:../shared.css\"]\n".as_bytes()).unwrap();
+
+    let snapshot = SourceSnapshot::take(&("$ROOT/".to_string(), root.clone())).unwrap();
+    assert_eq!(snapshot.files.keys().collect::<Vec<_>>(),
+               {
+                   let mut expected = vec![root.join("blogue.toml"),
+                                           root.join("templates").join("head"),
+                                           root.join("templates").join("foot"),
+                                           root.join("common.css"),
+                                           root.join("templates").join("idx_head"),
+                                           root.join("templates").join("idx_center"),
+                                           root.join("templates").join("idx_foot"),
+                                           root.join("index.js"),
+                                           root.join("templates").join("tag_head"),
+                                           root.join("templates").join("tag_center"),
+                                           root.join("templates").join("tag_cloud"),
+                                           root.join("templates").join("tag_foot"),
+                                           root.join("tags.css"),
+                                           post_root.clone(),
+                                           post_root.join("post.md"),
+                                           post_root.join("assets"),
+                                           post_root.join("assets").join("img.png"),
+                                           post_root.join("metadata.toml"),
+                                           root.join("shared.css")];
+                   expected.sort();
+                   expected
+               }
+               .iter()
+               .collect::<Vec<_>>());
+
+    assert!(snapshot.files[&root.join("common.css")].is_some());
+    assert_eq!(snapshot.files[&root.join("index.js")], None);
+    assert_eq!(snapshot.files[&root.join("shared.css")], None);
+}
+
+#[test]
+fn invalid_descriptor() {
+    let root = temp_dir().join("bloguen-test").join("ops-source_snapshot-take-invalid_descriptor");
+    let _ = fs::remove_dir_all(&root);
+    fs::create_dir_all(root.join("01. 2018-01-08 16-52 The venture into crocheting")).unwrap();
+
+    File::create(root.join("blogue.toml")).unwrap().write_all("[description\n".as_bytes()).unwrap();
+    File::create(root.join("header.html")).unwrap();
+    File::create(root.join("01. 2018-01-08 16-52 The venture into crocheting").join("post.md")).unwrap();
+
+    let snapshot = SourceSnapshot::take(&("$ROOT/".to_string(), root.clone())).unwrap();
+    assert_eq!(snapshot.files.keys().collect::<Vec<_>>(),
+               vec![&root.join("01. 2018-01-08 16-52 The venture into crocheting"),
+                    &root.join("01. 2018-01-08 16-52 The venture into crocheting").join("post.md"),
+                    &root.join("blogue.toml")]);
+}
+
+#[test]
+fn changes_detected() {
+    let root = temp_dir().join("bloguen-test").join("ops-source_snapshot-take-changes_detected");
+    let _ = fs::remove_dir_all(&root);
+    fs::create_dir_all(root.join("01. 2018-01-08 16-52 The venture into crocheting")).unwrap();
+
+    File::create(root.join("blogue.toml")).unwrap().write_all("name = \"Блогг\"\n".as_bytes()).unwrap();
+    File::create(root.join("header.html")).unwrap();
+    File::create(root.join("footer.html")).unwrap();
+    File::create(root.join("01. 2018-01-08 16-52 The venture into crocheting").join("post.md")).unwrap();
+
+    let snapshot = SourceSnapshot::take(&("$ROOT/".to_string(), root.clone())).unwrap();
+    assert_eq!(SourceSnapshot::take(&("$ROOT/".to_string(), root.clone())).unwrap(), snapshot);
+
+    File::create(root.join("01. 2018-01-08 16-52 The venture into crocheting").join("post.md")).unwrap().write_all("Блогг".as_bytes()).unwrap();
+    let modified = SourceSnapshot::take(&("$ROOT/".to_string(), root.clone())).unwrap();
+    assert_eq!(snapshot.differences(&modified),
+               vec![root.join("01. 2018-01-08 16-52 The venture into crocheting").join("post.md").as_path()]);
+
+    fs::create_dir_all(root.join("02. 2018-01-09 Hooked")).unwrap();
+    let created = SourceSnapshot::take(&("$ROOT/".to_string(), root.clone())).unwrap();
+    assert_eq!(modified.differences(&created), vec![root.join("02. 2018-01-09 Hooked").as_path()]);
+}
+
+#[test]
+fn includes_watched() {
+    let root = temp_dir().join("bloguen-test").join("ops-source_snapshot-take-includes_watched");
+    let _ = fs::remove_dir_all(&root);
+    fs::create_dir_all(root.join("partials")).unwrap();
+
+    File::create(root.join("blogue.toml")).unwrap().write_all("name = \"Блогг\"\n".as_bytes()).unwrap();
+    File::create(root.join("header.html")).unwrap().write_all(b"<html>{include(partials/nav.html)}").unwrap();
+    File::create(root.join("footer.html")).unwrap().write_all(b"{include(partials/missing.html)}</html>").unwrap();
+    File::create(root.join("partials").join("nav.html")).unwrap().write_all(b"<nav>{include(partials/logo.html)}</nav>").unwrap();
+    File::create(root.join("partials").join("logo.html")).unwrap().write_all(b"<img src=\"logo.png\">").unwrap();
+
+    let source_dir = ("$ROOT/".to_string(), root.clone());
+    let manifest = || {
+        let header = FormatTemplate::parse_with_includes("<html>{include(partials/nav.html)}", &source_dir, "post header").unwrap();
+        BuildManifest::new(0, vec![("post_header".to_string(), content_hash(&header))].into_iter().collect())
+    };
+
+    let snapshot = SourceSnapshot::take(&source_dir).unwrap();
+    let before = manifest();
+    assert_eq!(snapshot.files.keys().collect::<Vec<_>>(),
+               vec![&root.join("blogue.toml"),
+                    &root.join("footer.html"),
+                    &root.join("header.html"),
+                    &root.join("partials").join("logo.html"),
+                    &root.join("partials").join("missing.html"),
+                    &root.join("partials").join("nav.html")]);
+    assert_eq!(snapshot.files[&root.join("partials").join("missing.html")], None);
+
+    File::create(root.join("partials").join("logo.html")).unwrap().write_all(b"<img src=\"logo.svg\">").unwrap();
+    let modified = SourceSnapshot::take(&source_dir).unwrap();
+    assert_eq!(snapshot.differences(&modified), vec![root.join("partials").join("logo.html").as_path()]);
+    assert!(!manifest().globals_match(&before));
+}
@@ -0,0 +1,39 @@
+use bloguen::ops::TagName;
+use bloguen::Error;
+
+
+fn tags(names: &[&str]) -> Vec<TagName> {
+    names.iter().map(|n| n.parse().unwrap()).collect()
+}
+
+
+#[test]
+fn unique() {
+    assert_eq!(TagName::check_unique_file_names(&tags(&["vodka", "depression", "коммунизм", "C/C++", "C++", "indices"])), Ok(()));
+}
+
+#[test]
+fn sanitised_collision() {
+    assert_eq!(TagName::check_unique_file_names(&tags(&["vodka", "a/b", "a_b"])),
+               Err(Error::Parse {
+                   tp: "path chunk",
+                   wher: "blogue tags".into(),
+                   more: "tags \"a/b\" and \"a_b\" both output to \"a_b.html\"".into(),
+               }));
+    assert_eq!(TagName::check_unique_file_names(&tags(&["100%?", "100??"])),
+               Err(Error::Parse {
+                   tp: "path chunk",
+                   wher: "blogue tags".into(),
+                   more: "tags \"100%?\" and \"100??\" both output to \"100__.html\"".into(),
+               }));
+}
+
+#[test]
+fn index() {
+    assert_eq!(TagName::check_unique_file_names(&tags(&["vodka", "index"])),
+               Err(Error::Parse {
+                   tp: "path chunk",
+                   wher: "blogue tags".into(),
+                   more: "tag \"index\" outputs to the tag cloud's \"index.html\"".into(),
+               }));
+}
@@ -0,0 +1,2 @@
+mod check_unique_file_names;
+mod permalink;
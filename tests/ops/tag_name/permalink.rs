@@ -0,0 +1,20 @@
+use bloguen::ops::TagName;
+
+
+#[test]
+fn no_base_url() {
+    assert_eq!(TagName::cloud_permalink(None), "index.html");
+    assert_eq!("vodka".parse::<TagName>().unwrap().permalink(None), "vodka.html");
+    assert_eq!("C/C++".parse::<TagName>().unwrap().permalink(None), "C_C++.html");
+    assert_eq!("коммунизм".parse::<TagName>().unwrap().permalink(None), "коммунизм.html");
+}
+
+#[test]
+fn base_url() {
+    let base_url = "https://nabijaczleweli.xyz/blogue/".parse().unwrap();
+    assert_eq!(TagName::cloud_permalink(Some(&base_url)), "https://nabijaczleweli.xyz/blogue/tags/index.html");
+    assert_eq!("vodka".parse::<TagName>().unwrap().permalink(Some(&base_url)), "https://nabijaczleweli.xyz/blogue/tags/vodka.html");
+    assert_eq!("C/C++".parse::<TagName>().unwrap().permalink(Some(&base_url)), "https://nabijaczleweli.xyz/blogue/tags/C_C++.html");
+    assert_eq!("коммунизм".parse::<TagName>().unwrap().permalink(Some(&base_url)),
+               "https://nabijaczleweli.xyz/blogue/tags/%D0%BA%D0%BE%D0%BC%D0%BC%D1%83%D0%BD%D0%B8%D0%B7%D0%BC.html");
+}
@@ -8,7 +8,10 @@ use std::str;
 
 mod parse_date_format_specifier;
 mod parse_function_notation;
+mod sanitise_file_name;
 mod uppercase_first;
+mod tex_to_mathml;
+mod render_math;
 mod is_asset_link;
 mod read_file;
 mod bcp_47;
@@ -21,6 +24,13 @@ fn name_based_post_time() {
     assert_eq!(util::name_based_post_time("cursed device chain"), NaiveTime::from_hms(19, 03, 09));
 }
 
+#[test]
+fn content_hash() {
+    assert_eq!(util::content_hash("abolish"), util::content_hash("abolish"));
+    assert_ne!(util::content_hash("abolish"), util::content_hash("abolisH"));
+    assert_ne!(util::content_hash(&("abolish", "capitalism")), util::content_hash(&("abolishcapitalism", "")));
+}
+
 /// Not quite sure how to test the non-UTF-8 error case, since the document is parsed from a UTF-8 string
 #[test]
 fn extract_links() {
@@ -56,4 +66,129 @@ fn extract_actual_assets() {
     }
 }
 
+/// Not quite sure how to test the non-UTF-8 error case, since the document is parsed from a UTF-8 string
+#[test]
+fn extract_actual_assets_html() {
+    let root = temp_dir().join("bloguen-test").join("util-extract_actual_assets_html");
+    fs::create_dir_all(root.join("images")).unwrap();
+    File::create(root.join("images").join("i mage.png")).unwrap().write_all("images/i mage.png".as_bytes()).unwrap();
+    File::create(root.join("link.html")).unwrap().write_all("link.html".as_bytes()).unwrap();
+
+    let doc_arena = ComrakArena::new();
+    let ast = util::parse_html(&doc_arena,
+                               r#"<a href="not-link.html">not-link</a>
+                                  <a class="link" HREF = 'link.html'>link</a>
+                                  <img src="images/i%20mage.png" data-src="link.html">
+                                  <a href="https://nabijaczleweli.xyz">наб</a>"#);
+
+    let actual_asset_links = util::extract_actual_assets(&root, &ast).unwrap();
+    assert_eq!(actual_asset_links.len(), 2);
+    for (link, &expected) in actual_asset_links.into_iter().zip(["link.html", "images/i%20mage.png"].iter()) {
+        assert_eq!(str::from_utf8(&link[..]), Ok(expected));
+    }
+}
+
+#[test]
+fn parse_html() {
+    let doc_arena = ComrakArena::new();
+    for &(html, links, excerpt) in &[("<p>Intro</p>\n<p>More <a href=\"link.html\">intro</a></p>\n<!--more-->\n<p>Body</p>\n<!-- more -->\n<p>More body</p>",
+                                      &["link.html"][..],
+                                      "<p>Intro</p>\n<p>More <a href=\"link.html\">intro</a></p>\n"),
+                                     ("<p>Intro <!-- more --> inline</p>\n<p>Body</p>", &[][..], "<p>Intro <!-- more --> inline</p>\n"),
+                                     ("<div>\n<img src=image.png>\n</div>\n<p>Intro</p><p>Body</p>",
+                                      &[][..],
+                                      "<div>\n<img src=image.png>\n</div>\n<p>Intro</p>"),
+                                     ("<!-- more -->\n<p>Body</p>", &[][..], ""),
+                                     ("", &[][..], "")] {
+        let ast = util::parse_html(&doc_arena, html);
+        assert_eq!(util::extract_links(ast), Ok(links.iter().map(|l| l.to_string()).collect()));
+
+        let mut out = vec![];
+        comrak::format_html(ast, &util::MARKDOWN_OPTIONS, &mut out).unwrap();
+        assert_eq!(str::from_utf8(&out), Ok(html));
+
+        let mut out = vec![];
+        util::write_excerpt(ast, &util::MARKDOWN_OPTIONS, &mut out).unwrap();
+        assert_eq!(str::from_utf8(&out), Ok(excerpt));
+    }
+}
+
+#[test]
+fn count_words() {
+    let doc_arena = ComrakArena::new();
+    let ast = comrak::parse_document(&doc_arena,
+                                     "# Заголовок\n\
+                                      \n\
+                                      Some *emphasised* text with `inline code` and [a link](link.html).\n\
+                                      \n\
+                                      ```rust\n\
+                                      fn main() { println!(\"not counted\"); }\n\
+                                      ```\n\
+                                      \n\
+                                      <div>raw HTML</div>\n\
+                                      \n\
+                                      | one | two |\n\
+                                      |-----|-----|\n\
+                                      | a b | c   |\n\
+                                      \n\
+                                      * list\n\
+                                      * items",
+                                     &util::MARKDOWN_OPTIONS);
+    assert_eq!(util::count_words(ast), 1 + 7 + 5 + 2);
+}
+
+#[test]
+fn count_html_words() {
+    assert_eq!(util::count_html_words("<h1>Заголовок</h1>\n\
+                                       <p>Some <em>emph</em>asised text with <code>inline code</code> and <a href=\"link.html\">a link</a>.</p>\n\
+                                       <pre><code class=\"language-rust\">fn main() { println!(\"not counted\"); }</code></pre>\n\
+                                       <!-- not counted -->\n\
+                                       <script>not counted</script><style>not { counted: true; }</style>\n\
+                                       <table><tr><td>a b</td><td>c</td></tr></table>\n\
+                                       <ul><li>list</li><li>items</li></ul>"),
+               1 + 7 + 3 + 2);
+}
+
+#[test]
+fn html_table_of_contents() {
+    assert_eq!(util::html_table_of_contents("<h2 id=\"cotton\">Cotton</h2>\n\
+                                             <H3 ID=\"acrylic\">Acrylic <code>&amp;</code>\n<em>wool</em></H3>\n\
+                                             <h4>No ID</h4>\n\
+                                             <h2 data-id=\"no-id\">Data ID</h2>\n\
+                                             <h3 id='quote\"d'>Quoted</h3>"),
+               "<ul>\n\
+                <li><a href=\"#cotton\">Cotton</a>\n\
+                <ul>\n\
+                <li><a href=\"#acrylic\">Acrylic &amp; wool</a></li>\n\
+                <li><a href=\"#quote&quot;d\">Quoted</a></li>\n\
+                </ul>\n\
+                </li>\n\
+                </ul>");
+    assert_eq!(util::html_table_of_contents("<p>No headings</p>"), "");
+}
+
+#[test]
+fn write_excerpt() {
+    let doc_arena = ComrakArena::new();
+    for &(md, expected) in &[("Intro\n\nMore intro\n<!--more-->\nBody\n\n<!-- more -->\n\nMore body", "<p>Intro</p>\n<p>More intro</p>\n"),
+                             ("Intro <!-- more --> inline\n\nBody", "<p>Intro <!-- more --> inline</p>\n"),
+                             ("<!-- less -->\n\nIntro\n\nBody", "<!-- less -->\n<p>Intro</p>\n"),
+                             ("<!-- more -->\n\nBody", ""),
+                             ("", "")] {
+        let ast = comrak::parse_document(&doc_arena, md, &util::MARKDOWN_OPTIONS);
+        let mut out = vec![];
+        util::write_excerpt(ast, &util::MARKDOWN_OPTIONS, &mut out).unwrap();
+        assert_eq!(str::from_utf8(&out), Ok(expected));
+    }
+}
+
+#[test]
+fn reading_time() {
+    assert_eq!(util::reading_time(0, util::DEFAULT_READING_SPEED), 0);
+    assert_eq!(util::reading_time(1, util::DEFAULT_READING_SPEED), 1);
+    assert_eq!(util::reading_time(util::DEFAULT_READING_SPEED, util::DEFAULT_READING_SPEED), 1);
+    assert_eq!(util::reading_time(util::DEFAULT_READING_SPEED + 1, util::DEFAULT_READING_SPEED), 2);
+    assert_eq!(util::reading_time(1000, 150), 7);
+}
+
 // default_language() is untestable :v
@@ -0,0 +1,86 @@
+use bloguen::util::{MARKDOWN_OPTIONS, render_math};
+use comrak::{self, Arena};
+use bloguen::Error;
+
+
+fn render(md: &str) -> Result<String, Error> {
+    let arena = Arena::new();
+    let ast = render_math(&arena, md, &MARKDOWN_OPTIONS)?;
+
+    let mut out = vec![];
+    comrak::format_html(ast, &MARKDOWN_OPTIONS, &mut out).unwrap();
+    Ok(String::from_utf8(out).unwrap())
+}
+
+
+#[test]
+fn inline_and_display() {
+    assert_eq!(render("Let $x$ be\n\n$$\nx^2\n$$"),
+               Ok("<p>Let <math xmlns=\"http://www.w3.org/1998/Math/MathML\"><semantics><mi>x</mi><annotation \
+                   encoding=\"application/x-tex\">x</annotation></semantics></math> be</p>\n<p><math xmlns=\"http://www.w3.org/1998/Math/MathML\" \
+                   display=\"block\"><semantics><msup><mi>x</mi><mn>2</mn></msup><annotation \
+                   encoding=\"application/x-tex\">x&#94;2</annotation></semantics></math></p>\n"
+                   .to_string()));
+}
+
+#[test]
+fn split_by_emphasis() {
+    assert_eq!(render("$a*b*c$"),
+               Ok("<p><math xmlns=\"http://www.w3.org/1998/Math/MathML\"><semantics><mrow><mi>a</mi><mo>∗</mo><mi>b</mi><mo>∗</mo><mi>c</mi></mrow><annotation \
+                   encoding=\"application/x-tex\">a&#42;b&#42;c</annotation></semantics></math></p>\n"
+                   .to_string()));
+}
+
+#[test]
+fn escapes() {
+    assert_eq!(render("$\\{a\\}$ and \\$x\\$"),
+               Ok("<p><math xmlns=\"http://www.w3.org/1998/Math/MathML\"><semantics><mrow><mo>&#123;</mo><mi>a</mi><mo>&#125;</mo></mrow><annotation \
+                   encoding=\"application/x-tex\">&#92;&#123;a&#92;&#125;</annotation></semantics></math> and $x$</p>\n"
+                   .to_string()));
+}
+
+#[test]
+fn links() {
+    assert_eq!(render("[a](http://x/$a$b)"), Ok("<p><a href=\"http://x/$a$b\">a</a></p>\n".to_string()));
+    assert_eq!(render("[$a$](http://x/$a$b \"$a$\")"),
+               Ok("<p><a href=\"http://x/$a$b\" title=\"$a$\"><math xmlns=\"http://www.w3.org/1998/Math/MathML\"><semantics><mi>a</mi><annotation \
+                   encoding=\"application/x-tex\">a</annotation></semantics></math></a></p>\n"
+                   .to_string()));
+    assert_eq!(render("$a [b](c) d$"), Ok("<p>$a <a href=\"c\">b</a> d$</p>\n".to_string()));
+}
+
+#[test]
+fn inline_html() {
+    assert_eq!(render("<span title=\"$a$\">$b$</span>"),
+               Ok("<p><span title=\"$a$\"><math xmlns=\"http://www.w3.org/1998/Math/MathML\"><semantics><mi>b</mi><annotation \
+                   encoding=\"application/x-tex\">b</annotation></semantics></math></span></p>\n"
+                   .to_string()));
+}
+
+#[test]
+fn html_block() {
+    assert_eq!(render("<div data-x=\"$a$\">\n$b$\n</div>\n"), Ok("<div data-x=\"$a$\">\n$b$\n</div>\n".to_string()));
+}
+
+#[test]
+fn not_math() {
+    for &(md, html) in &[("It costs $5, or $10 on weekends", "<p>It costs $5, or $10 on weekends</p>\n"),
+                         ("a $ b $ c", "<p>a $ b $ c</p>\n"),
+                         ("$x$5", "<p>$x$5</p>\n"),
+                         ("$a`b$", "<p>$a`b$</p>\n"),
+                         ("$x\n\ny$", "<p>$x</p>\n<p>y$</p>\n"),
+                         ("$$\n\n$$", "<p>$$</p>\n<p>$$</p>\n")] {
+        assert_eq!(render(md), Ok(html.to_string()));
+    }
+}
+
+#[test]
+fn code() {
+    assert_eq!(render("`$x$` and `\\$y$`\n\n```\n$x$\n```\n\n    $x$\n"),
+               Ok("<p><code>$x$</code> and <code>\\$y$</code></p>\n<pre><code>$x$\n</code></pre>\n<pre><code>$x$\n</code></pre>\n".to_string()));
+}
+
+#[test]
+fn invalid() {
+    assert!(render("$\\frac{a}$").is_err());
+}
@@ -0,0 +1,23 @@
+use bloguen::util::sanitise_file_name;
+
+
+#[test]
+fn unchanged() {
+    assert_eq!(sanitise_file_name("vodka"), "vodka");
+    assert_eq!(sanitise_file_name("коммунизм"), "коммунизм");
+    assert_eq!(sanitise_file_name("en-GB"), "en-GB");
+    assert_eq!(sanitise_file_name("C++"), "C++");
+}
+
+#[test]
+fn replaced() {
+    assert_eq!(sanitise_file_name("C/C++"), "C_C++");
+    assert_eq!(sanitise_file_name("C:\\Windows"), "C__Windows");
+    assert_eq!(sanitise_file_name("*?\"<>|"), "______");
+    assert_eq!(sanitise_file_name("#100%"), "_100_");
+}
+
+#[test]
+fn empty() {
+    assert!(sanitise_file_name("").is_empty());
+}
@@ -0,0 +1,73 @@
+use bloguen::util::tex_to_mathml;
+use bloguen::Error;
+
+
+fn body(tex: &str, display: bool) -> String {
+    let mathml = tex_to_mathml(tex, display).unwrap();
+    mathml[mathml.find("<semantics>").unwrap() + "<semantics>".len()..mathml.find("<annotation").unwrap()].to_string()
+}
+
+
+#[test]
+fn scripts() {
+    assert_eq!(body("x_1^2", false), "<msubsup><mi>x</mi><mn>1</mn><mn>2</mn></msubsup>");
+    assert_eq!(body("x^10", false), "<mrow><msup><mi>x</mi><mn>1</mn></msup><mn>0</mn></mrow>");
+    assert_eq!(body("e^{i\\pi}", false), "<msup><mi>e</mi><mrow><mi>i</mi><mi>π</mi></mrow></msup>");
+}
+
+#[test]
+fn limits() {
+    assert_eq!(body("\\sum_{i=0}^n", false),
+               "<msubsup><mo largeop=\"true\" movablelimits=\"true\">∑</mo><mrow><mi>i</mi><mo>&#61;</mo><mn>0</mn></mrow><mi>n</mi></msubsup>");
+    assert_eq!(body("\\sum_{i=0}^n", true),
+               "<munderover><mo largeop=\"true\" movablelimits=\"true\">∑</mo><mrow><mi>i</mi><mo>&#61;</mo><mn>0</mn></mrow><mi>n</mi></munderover>");
+    assert_eq!(body("\\int_0^1", true), "<msubsup><mo largeop=\"true\" movablelimits=\"true\">∫</mo><mn>0</mn><mn>1</mn></msubsup>");
+}
+
+#[test]
+fn commands() {
+    assert_eq!(body("\\sqrt[3]{x}", false), "<mroot><mi>x</mi><mn>3</mn></mroot>");
+    assert_eq!(body("\\sqrt[\\sqrt[3]{2}]{x}", false), "<mroot><mi>x</mi><mroot><mn>2</mn><mn>3</mn></mroot></mroot>");
+    assert_eq!(body("\\mathbf{v} \\cdot \\vec{w}", false),
+               "<mrow><mi mathvariant=\"bold\">v</mi><mo>⋅</mo><mover accent=\"true\"><mi>w</mi><mo stretchy=\"true\">→</mo></mover></mrow>");
+    assert_eq!(body("\\left[ \\frac{a}{b} \\right.", false),
+               "<mrow><mo fence=\"true\" stretchy=\"true\">&#91;</mo><mfrac><mi>a</mi><mi>b</mi></mfrac></mrow>");
+    assert_eq!(body("\\text{if } x", false), "<mrow><mtext>if </mtext><mi>x</mi></mrow>");
+}
+
+#[test]
+fn environments() {
+    assert_eq!(body("\\begin{pmatrix} a & b \\\\ c & d \\end{pmatrix}", true),
+               "<mrow><mo fence=\"true\" stretchy=\"true\">&#40;</mo><mtable><mtr><mtd><mi>a</mi></mtd><mtd><mi>b</mi></mtd></mtr>\
+                <mtr><mtd><mi>c</mi></mtd><mtd><mi>d</mi></mtd></mtr></mtable><mo fence=\"true\" stretchy=\"true\">&#41;</mo></mrow>");
+    assert_eq!(body("\\begin{cases} 1 & x \\\\ 0 \\\\ \\end{cases}", true),
+               "<mrow><mo fence=\"true\" stretchy=\"true\">&#123;</mo><mtable columnalign=\"left left\"><mtr><mtd><mn>1</mn></mtd><mtd><mi>x</mi></mtd></mtr>\
+                <mtr><mtd><mn>0</mn></mtd></mtr></mtable></mrow>");
+}
+
+#[test]
+fn invalid() {
+    assert_eq!(tex_to_mathml("\\foo", false),
+               Err(Error::Parse {
+                   tp: "TeX",
+                   wher: "math".into(),
+                   more: "\"\\\\foo\": unknown command \\foo at position 0".into(),
+               }));
+    assert_eq!(tex_to_mathml("{a", false),
+               Err(Error::Parse {
+                   tp: "TeX",
+                   wher: "math".into(),
+                   more: "\"{a\": expected '}', found end of formula".into(),
+               }));
+    for &tex in &["x\\", "\\left\\"] {
+        assert_eq!(tex_to_mathml(tex, false),
+                   Err(Error::Parse {
+                       tp: "TeX",
+                       wher: "math".into(),
+                       more: format!("{:?}: unexpected end of formula after \\", tex).into(),
+                   }));
+    }
+    assert!(tex_to_mathml("a}", false).is_err());
+    assert!(tex_to_mathml("a \\\\ b", false).is_err());
+    assert!(tex_to_mathml("\\begin{matrix} a \\end{pmatrix}", false).is_err());
+}